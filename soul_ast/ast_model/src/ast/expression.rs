@@ -2,7 +2,7 @@ use soul_utils::{
     Ident,
     ids::FunctionId,
     soul_import_path::SoulImportPath,
-    soul_names::KeyWord,
+    soul_names::{KeyWord, PrimitiveTypes},
     span::{Span, Spanned},
 };
 
@@ -33,8 +33,12 @@ pub enum ExpressionKind {
     Null(Option<NodeId>),
     /// A `default` literal or default value e.g., '()'.
     Default(Option<NodeId>),
-    /// A literal value (number, string, etc.).
-    Literal((Option<NodeId>, Literal)),
+    /// A literal value (number, string, etc.), numbers can have a type suffix e.g. `10u8`.
+    Literal {
+        id: Option<NodeId>,
+        literal: Literal,
+        suffix: Option<PrimitiveTypes>,
+    },
 
     /// Indexing into a collection, e.g., `arr[i]`.
     Index(Index),
@@ -56,6 +60,10 @@ pub enum ExpressionKind {
     Unary(Unary),
     /// A binary operation (addition, multiplication, comparison, etc.) `1 + 2`.
     Binary(Binary),
+    /// A half-open range `start..end`, both ends can be left open e.g., `start..`, `..end` or `..`.
+    Range(Range),
+    /// A format string `f"x: {x}"`, gives the text as a heap string `[*]char`.
+    FormatString(FormatString),
     Array(Array),
     ArrayContructor(ArrayContructor),
     StructConstructor(StructConstructor),
@@ -74,11 +82,26 @@ pub enum ExpressionKind {
         is_mutable: bool,
         expression: BoxExpression,
     },
+    /// An explicit copy of a value, e.g., `copy x`.
+    Copy {
+        id: Option<NodeId>,
+        inner: BoxExpression,
+    },
     As(Box<AsTypeCast>),
+    /// A heap allocation, e.g., `new Point{x: 1, y: 2}` or `new:[for len => 0]`.
+    New(New),
     /// A block of statements, returning the last expression `{/*stuff*/}`.
     Block(Block),
+    /// A block in which raw pointers can be dereferenced and extern functions called
+    /// `unsafe{*ptr}`.
+    Unsafe(Block),
     /// Return-like expressions (`return`, `break`) `return 1`.
     ReturnLike(ReturnLike),
+    /// Runs `inner` when the enclosing block exits `defer Close(file)`.
+    Defer {
+        id: Option<NodeId>,
+        inner: BoxExpression,
+    },
 
     /// `i32.sizeof // returns 4`
     Sizeof(SoulType),
@@ -91,6 +114,48 @@ pub struct AsTypeCast {
     pub type_cast: SoulType,
 }
 
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct New {
+    pub id: Option<NodeId>,
+    pub kind: NewKind,
+    /// `new?` gives `null` when the allocator runs out of memory instead of panicking.
+    pub fallible: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum NewKind {
+    /// `new value` allocates a single value and gives `*T`.
+    Value(BoxExpression),
+    /// `new:[for len => value]` allocates `len` copies of `value` and gives `[*]T`.
+    Array(Box<ArrayContructor>),
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Range {
+    pub id: Option<NodeId>,
+    /// Inclusive start, `None` starts at `0`.
+    pub start: Option<BoxExpression>,
+    /// Exclusive end, `None` runs to the end of the indexed collection.
+    pub end: Option<BoxExpression>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FormatString {
+    pub id: Option<NodeId>,
+    pub pieces: Vec<FormatPiece>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum FormatPiece {
+    /// Plain text.
+    Text(String),
+    /// An embedded `{value}`, padded with spaces to `width` chars for `{value:width}`.
+    Argument {
+        value: BoxExpression,
+        width: Option<usize>,
+    },
+}
+
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Index {
     pub id: Option<NodeId>,
@@ -118,6 +183,12 @@ pub struct ExternalRef {
 pub enum Intrinsic {
     InFile,
     InLine,
+    /// `panic(msg)`, stops the program and has type `never`.
+    Panic,
+    /// `target_os()`, the name of the target operating system as a comptime string.
+    TargetOs,
+    /// `target_arch()`, the name of the target architecture as a comptime string.
+    TargetArch,
 }
 
 impl Intrinsic {
@@ -125,6 +196,9 @@ impl Intrinsic {
         match name {
             "InFile" => Some(Intrinsic::InFile),
             "InLine" => Some(Intrinsic::InLine),
+            "panic" => Some(Intrinsic::Panic),
+            "target_os" => Some(Intrinsic::TargetOs),
+            "target_arch" => Some(Intrinsic::TargetArch),
             _ => None,
         }
     }
@@ -146,7 +220,7 @@ pub struct FunctionCall {
     pub external_ref: Option<ExternalRef>,
     /// Compiler intrinsic if this is an intrinsic call
     pub intrinsic: Option<Intrinsic>,
-    /// Computed intrinsic value (e.g., file path for InFile)
+    /// Computed intrinsic value (e.g., file path for InFile, os name for TargetOs)
     pub intrinsic_value: Option<String>,
 }
 
@@ -324,8 +398,30 @@ impl Expression {
         Expression::new(ExpressionKind::Binary(binary), span)
     }
 
+    pub fn new_range(start: Option<Expression>, end: Option<Expression>, span: Span) -> Expression {
+        let range = Range {
+            id: None,
+            start: start.map(Box::new),
+            end: end.map(Box::new),
+        };
+        Expression::new(ExpressionKind::Range(range), span)
+    }
+
     pub fn new_literal(literal: Literal, span: Span) -> Expression {
-        Expression::new(ExpressionKind::Literal((None, literal)), span)
+        Expression::new_typed_literal(literal, None, span)
+    }
+
+    pub fn new_typed_literal(
+        literal: Literal,
+        suffix: Option<PrimitiveTypes>,
+        span: Span,
+    ) -> Expression {
+        let kind = ExpressionKind::Literal {
+            id: None,
+            literal,
+            suffix,
+        };
+        Expression::new(kind, span)
     }
 
     pub fn from_function_call(function_call: Spanned<FunctionCall>) -> Expression {
@@ -371,6 +467,22 @@ impl Expression {
         Expression::new(new_ref, new_span)
     }
 
+    pub fn new_copy(expression: Expression, new_span: Span) -> Expression {
+        let copy = ExpressionKind::Copy {
+            id: None,
+            inner: Box::new(expression),
+        };
+        Expression::new(copy, new_span)
+    }
+
+    pub fn new_defer(expression: Expression, new_span: Span) -> Expression {
+        let defer = ExpressionKind::Defer {
+            id: None,
+            inner: Box::new(expression),
+        };
+        Expression::new(defer, new_span)
+    }
+
     pub fn new_deref(expression: Expression, new_span: Span) -> Expression {
         let Expression { node, span } = expression;
         let deref = ExpressionKind::Deref {
@@ -389,9 +501,11 @@ impl ExpressionKind {
             ExpressionKind::FieldAccess(_) => "Field",
             ExpressionKind::Null(_) => "Null",
             ExpressionKind::Default(_) => "Default",
-            ExpressionKind::Literal(_) => "Literal",
+            ExpressionKind::Literal { .. } => "Literal",
+            ExpressionKind::FormatString(_) => "FormatString",
 
             ExpressionKind::Index(_) => "Index",
+            ExpressionKind::Range(_) => "Range",
             ExpressionKind::FunctionCall(_) => "FunctionCall",
 
             ExpressionKind::Variable { .. } => "Variable",
@@ -405,9 +519,13 @@ impl ExpressionKind {
             ExpressionKind::While(_) => "While",
             ExpressionKind::Deref { .. } => "Deref",
             ExpressionKind::Ref { .. } => "Ref",
+            ExpressionKind::Copy { .. } => "Copy",
             ExpressionKind::As(_) => "As",
+            ExpressionKind::New(_) => "New",
             ExpressionKind::Block(_) => "Block",
+            ExpressionKind::Unsafe(_) => "Unsafe",
             ExpressionKind::ReturnLike(_) => "ReturnLike",
+            ExpressionKind::Defer { .. } => "Defer",
         }
     }
 }
@@ -3,7 +3,7 @@ use std::fmt::Debug;
 use enum_variant_name_const::EnumVariantNameConst;
 use soul_utils::ids::FunctionId;
 use soul_utils::soul_names::TypeModifier;
-use soul_utils::span::{ItemMetaData, ModuleId, Span};
+use soul_utils::span::{ItemMetaData, ModuleId, Span, Visibility};
 use soul_utils::{Ident, soul_import_path::SoulImportPath, span::Spanned};
 
 use crate::Generic;
@@ -47,6 +47,23 @@ pub enum StatementKind {
     Struct(Struct),
     Enum(Enum),
 }
+impl StatementKind {
+    /// The visibility of the item this statement declares, `None` if it is not an item.
+    pub fn visibility_mut(&mut self) -> Option<&mut Visibility> {
+        match self {
+            StatementKind::Function(function) | StatementKind::ExternalFunction(function) => {
+                Some(&mut function.signature.node.visibility)
+            }
+            StatementKind::Variable(variable) => Some(&mut variable.visibility),
+            StatementKind::Struct(obj) => Some(&mut obj.visibility),
+            StatementKind::Enum(obj) => Some(&mut obj.visibility),
+            StatementKind::Import(import) => Some(&mut import.visibility),
+            StatementKind::Expression { .. }
+            | StatementKind::Assignment(_)
+            | StatementKind::UseBlock(_) => None,
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct UseBlock {
@@ -69,6 +86,7 @@ pub struct Struct {
     pub generics: Vec<Generic>,
     pub fields: Vec<Field>,
     pub defined_in: Option<ModuleId>,
+    pub visibility: Visibility,
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -76,6 +94,7 @@ pub struct Enum {
     pub name: Ident,
     pub id: Option<NodeId>,
     pub variants: Vec<Ident>,
+    pub visibility: Visibility,
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -83,6 +102,8 @@ pub struct Field {
     pub id: Option<NodeId>,
     pub name: Ident,
     pub ty: SoulType,
+    pub meta_data: ItemMetaData,
+    pub visibility: Visibility,
 }
 
 /// Imported paths
@@ -90,6 +111,8 @@ pub struct Field {
 pub struct Import {
     pub id: Option<NodeId>,
     pub paths: Vec<ImportPath>,
+    /// `pub import` re-exports the imported items from this module.
+    pub visibility: Visibility,
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -167,6 +190,9 @@ pub struct FunctionSignature {
     /// Return type, if specified.
     pub return_type: SoulType,
     pub external: Option<ExternLanguage>,
+    /// Takes C variadic arguments after `parameters`, written `...`.
+    pub variadic: bool,
+    pub visibility: Visibility,
 }
 
 /// Optional `this` parameter type.
@@ -203,6 +229,8 @@ pub struct Variable {
     pub initialize_value: Option<Expression>,
 
     pub node_id: Option<NodeId>,
+    /// Only `pub` for globals.
+    pub visibility: Visibility,
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use soul_utils::{
     Ident,
     ids::{FunctionId, IdGenerator},
-    span::ModuleId,
+    span::{ModuleId, Visibility},
     vec_map::VecMap,
     vec_set::VecSet,
 };
@@ -79,16 +79,10 @@ impl CustomType {
 #[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct EntryKind<T> {
     pub value: T,
-    pub is_public: bool,
+    pub visibility: Visibility,
 }
 impl<T: Copy> Copy for EntryKind<T> {}
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub enum Visibility {
-    Public,
-    Private,
-}
-
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct AstModuleStore {
     map: VecMap<ModuleId, Module>,
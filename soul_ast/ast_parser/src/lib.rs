@@ -1,18 +1,35 @@
 use ast::Module;
 use soul_tokenizer::TokenStream;
-use soul_utils::{crate_store::CrateContext, span::ModuleId};
+use soul_utils::{compile_options::TargetInfo, crate_store::CrateContext, span::ModuleId};
 use std::path::PathBuf;
 
 use crate::parser::Parser;
 mod parser;
+mod strip_cfg;
 
+/// Parses a module and strips the items whose `#[cfg(...)]` does not match `target`.
+///
+/// `crate_folder` is the folder of the crate root, `crate.` import paths are resolved from it.
+#[allow(clippy::too_many_arguments)]
 pub fn parse_module<'a, 'f>(
     tokens: TokenStream<'a>,
     id: ModuleId,
     name: String,
     parent: Option<ModuleId>,
     context: &'f mut CrateContext,
+    target: &TargetInfo,
     source_folder: PathBuf,
+    crate_folder: PathBuf,
 ) -> Module {
-    Parser::parse(tokens, id, name, parent, context, source_folder)
+    let mut module = Parser::parse(
+        tokens,
+        id,
+        name,
+        parent,
+        context,
+        source_folder,
+        crate_folder,
+    );
+    strip_cfg::strip_cfg(&mut module.global.statements, target);
+    module
 }
@@ -1,13 +1,17 @@
 use std::path::PathBuf;
 use std::{collections::HashMap, path::Path};
 
-use ast::{Block, Module, SoulType, Visibility};
+use ast::{Block, Module, SoulType};
 #[cfg(debug_assertions)]
 use soul_tokenizer::Token;
 use soul_tokenizer::{TokenKind, TokenStream};
 use soul_utils::{
-    crate_store::CrateContext, error::SoulError, sementic_level::SementicFault,
-    soul_names::TypeModifier, span::ModuleId, vec_set::VecSet,
+    crate_store::CrateContext,
+    error::SoulError,
+    sementic_level::SementicFault,
+    soul_names::TypeModifier,
+    span::{ModuleId, Visibility},
+    vec_set::VecSet,
 };
 
 use crate::parser::parse_utils::SEMI_COLON;
@@ -40,20 +44,33 @@ pub(crate) struct Parser<'a, 'f> {
     current_this: Option<SoulType>,
     context: &'f mut CrateContext,
     source_path: PathBuf,
+    /// The folder of the crate root, `crate.` import paths start here.
+    crate_folder: PathBuf,
 }
 impl<'a, 'f> Parser<'a, 'f> {
     #[cfg(not(debug_assertions))]
-    fn new(tokens: TokenStream<'a>, faults: &'f mut CrateContext, path: PathBuf) -> Self {
+    fn new(
+        tokens: TokenStream<'a>,
+        faults: &'f mut CrateContext,
+        path: PathBuf,
+        crate_folder: PathBuf,
+    ) -> Self {
         Self {
             tokens,
             context: faults,
             source_path: path,
+            crate_folder,
             current_this: None,
         }
     }
 
     #[cfg(debug_assertions)]
-    fn new(tokens: TokenStream<'a>, faults: &'f mut CrateContext, path: PathBuf) -> Self {
+    fn new(
+        tokens: TokenStream<'a>,
+        faults: &'f mut CrateContext,
+        path: PathBuf,
+        crate_folder: PathBuf,
+    ) -> Self {
         use soul_tokenizer::TokenKind;
         use soul_utils::span::Span;
 
@@ -67,6 +84,7 @@ impl<'a, 'f> Parser<'a, 'f> {
             tokens,
             context: faults,
             source_path: path,
+            crate_folder,
             current_this: None,
         }
     }
@@ -78,15 +96,11 @@ impl<'a, 'f> Parser<'a, 'f> {
         parent: Option<ModuleId>,
         context: &'f mut CrateContext,
         source_folder: PathBuf,
+        crate_folder: PathBuf,
     ) -> Module {
-        let is_capital = name.chars().next().map_or(false, char::is_uppercase);
-        let visibility = if is_capital {
-            Visibility::Public
-        } else {
-            Visibility::Private
-        };
+        let visibility = Visibility::of_module_name(&name);
 
-        let mut this = Self::new(tokens, context, source_folder);
+        let mut this = Self::new(tokens, context, source_folder, crate_folder);
         if let Err(err) = this.tokens.initialize() {
             this.log_error(err);
             return Module {
@@ -138,6 +152,10 @@ impl<'a, 'f> Parser<'a, 'f> {
         self.context.faults.push(SementicFault::error(err));
     }
 
+    pub(super) fn log_warning(&mut self, err: SoulError) {
+        self.context.faults.push(SementicFault::warning(err));
+    }
+
     /// checked if node is end of line and ends with a semicolon
     fn ends_semicolon(&mut self) -> bool {
         self.current_is(&SEMI_COLON) && self.peek().kind == TokenKind::EndLine
@@ -1,6 +1,6 @@
 use ast::{
-    Array, AsTypeCast, BinaryOperator, BinaryOperatorKind, Expression, ExpressionKind, Literal,
-    SoulType, UnaryOperator, UnaryOperatorKind,
+    AnyArray, Array, AsTypeCast, BinaryOperator, BinaryOperatorKind, Expression, ExpressionKind,
+    ExternalExpression, Literal, New, NewKind, SoulType, UnaryOperator, UnaryOperatorKind,
 };
 use soul_tokenizer::{Number, Token, TokenKind};
 use soul_utils::{
@@ -8,6 +8,7 @@ use soul_utils::{
     error::{SoulError, SoulErrorKind, SoulResult},
     precedence::Precedence,
     soul_error_internal,
+    soul_import_path::SoulImportPath,
     soul_names::{AccessType, KeyWord, Operator, TypeModifier},
     span::{Span, Spanned},
     symbool_kind::SymbolKind,
@@ -17,13 +18,14 @@ use soul_utils::{
 use crate::parser::{
     Parser,
     parse_utils::{
-        ARRAY, ARROW_LEFT, COLON, CURLY_OPEN, DECREMENT, INCREMENT, ROUND_OPEN, SQUARE_CLOSE,
-        SQUARE_OPEN,
+        ARRAY, ARROW_LEFT, COLON, COMMA, CURLY_OPEN, DECREMENT, INCREMENT, OPTIONAL, ROUND_CLOSE,
+        ROUND_OPEN, SQUARE_CLOSE, SQUARE_OPEN,
     },
 };
 
 mod parse_condition;
 mod parse_expression_group;
+mod parse_format_string;
 
 impl<'a, 'f> Parser<'a, 'f> {
     pub(crate) fn parse_expression(&mut self, end_tokens: &[TokenKind]) -> SoulResult<Expression> {
@@ -75,6 +77,12 @@ impl<'a, 'f> Parser<'a, 'f> {
                         self.span_combine(start_span),
                     )
                 }
+                ExpressionOperator::Binary(operator)
+                    if operator.node == BinaryOperatorKind::Range =>
+                {
+                    let end = self.parse_range_end(precedence, end_tokens)?;
+                    left = Expression::new_range(Some(left), end, self.span_combine(start_span))
+                }
                 ExpressionOperator::Binary(operator) => {
                     let next_min_precedence = precedence.next();
                     let right = self.pratt_parse_expression(next_min_precedence, end_tokens)?;
@@ -137,7 +145,15 @@ impl<'a, 'f> Parser<'a, 'f> {
                 Expression::from_any_array(array)
             }
             &ROUND_OPEN => {
-                return Err(soul_error_internal!("tuple not yet impl", Some(start_span)));
+                self.bump();
+                let mut inner = self.parse_expression(&[ROUND_CLOSE, COMMA])?;
+                if self.current_is(&COMMA) {
+                    return Err(soul_error_internal!("tuple not yet impl", Some(start_span)));
+                }
+
+                self.expect(&ROUND_CLOSE)?;
+                inner.span = self.span_combine(start_span);
+                inner
             }
             &ARRAY => {
                 self.bump();
@@ -149,6 +165,11 @@ impl<'a, 'f> Parser<'a, 'f> {
                 };
                 Expression::from_array(Spanned::new(arr, start_span))
             }
+            TokenKind::Symbol(SymbolKind::DoubleDot) => {
+                self.bump();
+                let end = self.parse_range_end(Operator::Range.precedence(), &[])?;
+                Expression::new_range(None, end, self.span_combine(start_span))
+            }
             TokenKind::Symbol(symbol) => {
                 let unary = self.expect_unary_kind(start_span, *symbol)?;
                 self.bump();
@@ -180,16 +201,18 @@ impl<'a, 'f> Parser<'a, 'f> {
                     StringLiteral::Normal(string) => {
                         Expression::new_literal(Literal::Str(string), token.span)
                     }
+                    StringLiteral::Fmt(pieces) => self.parse_format_string(pieces, token.span)?,
                 }
             }
-            TokenKind::Number(num) => {
+            TokenKind::Number(num, suffix) => {
+                let suffix = *suffix;
                 let number = match num {
-                    Number::Int(val) => Literal::Int(*val as i128),
-                    Number::Uint(val) => Literal::Uint(*val as u128),
+                    Number::Int(val) => Literal::Int(*val),
+                    Number::Uint(val) => Literal::Uint(*val),
                     Number::Float(val) => Literal::Float(*val),
                 };
                 self.bump();
-                Expression::new_literal(number, start_span)
+                Expression::new_typed_literal(number, suffix, start_span)
             }
             other => {
                 return Err(SoulError::new(
@@ -228,10 +251,33 @@ impl<'a, 'f> Parser<'a, 'f> {
         Ok(expression)
     }
 
+    /// Parses the end of a range after `..`, which is left open when the range is closed
+    /// by a delimiter, e.g., `array[start..]`.
+    fn parse_range_end(
+        &mut self,
+        precedence: Precedence,
+        end_tokens: &[TokenKind],
+    ) -> SoulResult<Option<Expression>> {
+        const OPEN_RANGE_END: &[TokenKind] = &[
+            SQUARE_CLOSE,
+            ROUND_CLOSE,
+            COMMA,
+            TokenKind::EndLine,
+            TokenKind::EndFile,
+        ];
+
+        if self.current_is_any(end_tokens) || self.current_is_any(OPEN_RANGE_END) {
+            return Ok(None);
+        }
+
+        self.pratt_parse_expression(precedence.next(), end_tokens)
+            .map(Some)
+    }
+
     fn parse_primary_ident(&mut self, start_span: Span) -> SoulResult<Expression> {
         let str = self.try_token_as_ident_str()?;
 
-        match KeyWord::from_str(str) {
+        match KeyWord::try_from_str(str) {
             Some(KeyWord::If) => return self.parse_if(),
             Some(KeyWord::While) => return self.parse_while(),
 
@@ -244,6 +290,26 @@ impl<'a, 'f> Parser<'a, 'f> {
                 ));
             }
 
+            Some(KeyWord::Copy) => {
+                self.bump();
+                let inner = self.parse_primary()?;
+                return Ok(Expression::new_copy(inner, self.span_combine(start_span)));
+            }
+
+            Some(KeyWord::New) => {
+                self.bump();
+                return self.parse_new(start_span);
+            }
+
+            Some(KeyWord::Unsafe) => {
+                self.bump();
+                let block = self.parse_block(TypeModifier::Mut)?;
+                return Ok(Expression::new(
+                    ExpressionKind::Unsafe(block),
+                    self.span_combine(start_span),
+                ));
+            }
+
             Some(KeyWord::Null) => {
                 self.bump();
                 return Ok(Expression::new(
@@ -252,8 +318,14 @@ impl<'a, 'f> Parser<'a, 'f> {
                 ));
             }
 
+            Some(KeyWord::Crate) => {
+                self.bump();
+                return self.parse_external_expression(start_span);
+            }
+
             Some(KeyWord::Fall)
             | Some(KeyWord::Break)
+            | Some(KeyWord::Defer)
             | Some(KeyWord::Return)
             | Some(KeyWord::Continue) => {
                 return Err(SoulError::new(
@@ -272,13 +344,6 @@ impl<'a, 'f> Parser<'a, 'f> {
         let peek = self.peek();
         match &self.token().kind {
             &COLON if peek.kind == SQUARE_OPEN => {
-                if ident.as_str() == KeyWord::New.as_str() {
-                    return Err(soul_error_internal!(
-                        "heap array alloc not yet impl",
-                        Some(span)
-                    ));
-                }
-
                 return Err(soul_error_internal!(
                     "collectionType array not yet impl",
                     Some(span)
@@ -319,6 +384,76 @@ impl<'a, 'f> Parser<'a, 'f> {
         ))
     }
 
+    /// `crate.Module.item`, every segment but the last goes in the path, the resolver
+    /// decides which of them are modules.
+    fn parse_external_expression(&mut self, start_span: Span) -> SoulResult<Expression> {
+        const SEPARATOR: TokenKind = TokenKind::Symbol(SymbolKind::Dot);
+
+        let mut path = SoulImportPath::new();
+        self.expect(&SEPARATOR)?;
+        let mut ident = self.try_bump_consume_ident()?;
+        while self.current_is(&SEPARATOR) {
+            self.bump();
+            path.push(ident.as_str());
+            ident = self.try_bump_consume_ident()?;
+        }
+
+        let expr = match self.try_parse_function_call(ident.span, None, &ident) {
+            Ok(call) => Expression::from_function_call(call),
+            Err(TryError::IsNotValue(_)) => Expression::new_variable(ident),
+            Err(TryError::IsErr(err)) => return Err(err),
+        };
+
+        Ok(Expression::new(
+            ExpressionKind::ExternalExpression(ExternalExpression {
+                id: None,
+                path,
+                expr: Box::new(expr),
+            }),
+            self.span_combine(start_span),
+        ))
+    }
+
+    fn parse_new(&mut self, start_span: Span) -> SoulResult<Expression> {
+        let fallible = self.current_is(&OPTIONAL);
+        if fallible {
+            self.bump();
+        }
+
+        let kind = if self.current_is(&COLON) && self.peek().kind == SQUARE_OPEN {
+            self.bump();
+            let array = self.parse_array(None)?;
+            match array.node {
+                AnyArray::ArrayConstructor(ctor) => NewKind::Array(Box::new(ctor)),
+                AnyArray::ArrayLiteral(_) => {
+                    return Err(SoulError::new(
+                        "heap array literals are not supported, use 'new:[for len => value]'",
+                        SoulErrorKind::InvalidContext,
+                        Some(array.span),
+                    ));
+                }
+            }
+        } else if self.current_is(&ROUND_OPEN) {
+            self.bump();
+            self.skip_end_lines();
+            let value = self.parse_expression(&[ROUND_CLOSE])?;
+            self.skip_end_lines();
+            self.expect(&ROUND_CLOSE)?;
+            NewKind::Value(Box::new(value))
+        } else {
+            NewKind::Value(Box::new(self.parse_primary()?))
+        };
+
+        Ok(Expression::new(
+            ExpressionKind::New(New {
+                id: None,
+                kind,
+                fallible,
+            }),
+            self.span_combine(start_span),
+        ))
+    }
+
     fn parse_as_typecast(&mut self, left: Expression, start_span: Span) -> SoulResult<Expression> {
         self.expect_ident(KeyWord::As.as_str())?;
 
@@ -367,7 +502,7 @@ impl<'a, 'f> Parser<'a, 'f> {
     fn current_precedence(&mut self) -> Precedence {
         match &self.token().kind {
             TokenKind::Ident(ident) => {
-                if let Some(keyword) = KeyWord::from_str(ident) {
+                if let Some(keyword) = KeyWord::try_from_str(ident) {
                     keyword.precedence()
                 } else {
                     Precedence::MIN
@@ -399,7 +534,7 @@ impl<'a, 'f> Parser<'a, 'f> {
         }
 
         match &self.token().kind {
-            TokenKind::Ident(ident) => match KeyWord::from_str(ident.as_str()) {
+            TokenKind::Ident(ident) => match KeyWord::try_from_str(ident.as_str()) {
                 Some(KeyWord::As) => {
                     self.bump();
                     let type_cast = self.try_parse_type().merge_to_result()?;
@@ -463,7 +598,7 @@ impl<'a, 'f> Parser<'a, 'f> {
     }
 
     fn parse_field_access(&mut self, left: Expression, ident: Ident) -> SoulResult<Expression> {
-        match KeyWord::from_str(ident.as_str()) {
+        match KeyWord::try_from_str(ident.as_str()) {
             Some(KeyWord::Sizeof) => self.parse_sizeof(left, ident),
             _ => Ok(Expression::new_field(left, ident)),
         }
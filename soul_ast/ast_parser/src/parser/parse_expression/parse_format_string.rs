@@ -0,0 +1,63 @@
+use ast::{Expression, ExpressionKind, FormatPiece, FormatString};
+use soul_tokenizer::{TokenKind, TokenStream};
+use soul_utils::{
+    FmtArgument, FmtPiece,
+    error::{SoulError, SoulErrorKind, SoulResult},
+    span::Span,
+};
+
+use crate::parser::Parser;
+
+impl<'a, 'f> Parser<'a, 'f> {
+    pub(crate) fn parse_format_string(
+        &mut self,
+        pieces: Vec<FmtPiece>,
+        span: Span,
+    ) -> SoulResult<Expression> {
+        let mut format_pieces = Vec::with_capacity(pieces.len());
+        for piece in pieces {
+            format_pieces.push(match piece {
+                FmtPiece::Text(text) => FormatPiece::Text(text),
+                FmtPiece::Argument(argument) => FormatPiece::Argument {
+                    value: Box::new(self.parse_format_argument(&argument)?),
+                    width: argument.width,
+                },
+            });
+        }
+
+        let format = FormatString {
+            id: None,
+            pieces: format_pieces,
+        };
+        Ok(Expression::new(ExpressionKind::FormatString(format), span))
+    }
+
+    /// Parses the value of `{value}` with a token stream of its own that keeps the spans of
+    /// the format string.
+    fn parse_format_argument(&mut self, argument: &FmtArgument) -> SoulResult<Expression> {
+        let mut tokens = TokenStream::new_at(&argument.source, argument.span);
+        tokens.initialize()?;
+
+        let mut parser = Parser::new(
+            tokens,
+            self.context,
+            self.source_path.clone(),
+            self.crate_folder.clone(),
+        );
+        parser.current_this = self.current_this.clone();
+
+        let value = parser.parse_expression(&[TokenKind::EndFile])?;
+        if !parser.current_is(&TokenKind::EndFile) {
+            return Err(SoulError::new(
+                format!(
+                    "unexpected '{}' after format string argument",
+                    parser.token().kind.display()
+                ),
+                SoulErrorKind::InvalidTokenKind,
+                Some(parser.token().span),
+            ));
+        }
+
+        Ok(value)
+    }
+}
@@ -7,7 +7,7 @@ use soul_utils::{
     Ident,
     error::{SoulError, SoulErrorKind, SoulResult},
     soul_names::{KeyWord, TypeModifier},
-    span::{Span, Spanned},
+    span::{Span, Spanned, Visibility},
     try_result::{ResultTryErr, ToResult, TryErr, TryError, TryNotValue, TryOk, TryResult},
 };
 
@@ -22,7 +22,7 @@ type FuncResult<T> = TryResult<T, (Ident, Box<SoulError>)>;
 impl<'a, 'f> Parser<'a, 'f> {
     pub(crate) fn parse_any_function(&mut self) -> SoulResult<Statement> {
         let mut ident = self.try_bump_consume_ident()?;
-        let modifier = match TypeModifier::from_str(ident.as_str()) {
+        let modifier = match TypeModifier::try_from_str(ident.as_str()) {
             Some(modifer) => {
                 ident = self.try_bump_consume_ident()?;
                 modifer
@@ -98,11 +98,39 @@ impl<'a, 'f> Parser<'a, 'f> {
         let name = self.try_bump_consume_ident()?;
 
         let span = self.token().span;
-        match self.try_parse_function_signature(span, SoulType::none(span), name, Some(external)) {
-            Ok(signature) => Ok(Statement::from_external_function(signature)),
-            Err(TryError::IsErr(err)) => Err(err),
-            Err(TryError::IsNotValue((_, err))) => Err(*err),
+        let signature = match self.try_parse_function_signature(
+            span,
+            SoulType::none(span),
+            name,
+            Some(external),
+        ) {
+            Ok(signature) => signature,
+            Err(TryError::IsErr(err)) => return Err(err),
+            Err(TryError::IsNotValue((_, err))) => return Err(*err),
+        };
+
+        // `extern "C" name(..) { .. }` defines a function callable from C
+        if !self.current_is(&CURLY_OPEN) {
+            return Ok(Statement::from_external_function(signature));
+        }
+
+        if signature.node.variadic {
+            return Err(SoulError::new(
+                format!(
+                    "variadic function '{}' can only be declared, not defined",
+                    signature.node.name.as_str()
+                ),
+                SoulErrorKind::InvalidContext,
+                Some(self.token().span),
+            ));
         }
+
+        let block = self.parse_block(TypeModifier::Mut)?;
+        let span = signature.span;
+        Ok(Statement::from_function(Spanned::new(
+            Function { block, signature },
+            self.span_combine(span),
+        )))
     }
 
     pub(crate) fn try_parse_function_declaration(
@@ -285,12 +313,23 @@ impl<'a, 'f> Parser<'a, 'f> {
             return TryErr(self.get_expect_error(&ROUND_OPEN));
         }
 
-        let (parameters, function_kind) = match self.try_parse_parameters() {
+        let (parameters, function_kind, variadic) = match self.try_parse_parameters() {
             Ok(val) => val,
             Err(TryError::IsErr(err)) => return TryErr(err),
             Err(TryError::IsNotValue(err)) => return TryNotValue((name, Box::new(err))),
         };
 
+        if variadic && external.is_none() {
+            return TryErr(SoulError::new(
+                format!(
+                    "function '{}' can not take variadic arguments '...', only an extern \"C\" declaration can",
+                    name.as_str()
+                ),
+                SoulErrorKind::InvalidContext,
+                Some(name.span),
+            ));
+        }
+
         let return_type = match self.current_is(&COLON) {
             true => {
                 self.bump();
@@ -308,10 +347,12 @@ impl<'a, 'f> Parser<'a, 'f> {
             id: None,
             generics,
             external,
+            variadic,
             parameters,
             return_type,
             methode_type,
             function_kind,
+            visibility: Visibility::Private,
         };
 
         TryOk(Spanned::new(signature, self.span_combine(start_span)))
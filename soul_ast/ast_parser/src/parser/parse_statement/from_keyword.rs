@@ -20,11 +20,15 @@ impl<'a, 'f> Parser<'a, 'f> {
     ) -> TryResult<Statement, SoulError> {
         let kind = match keyword {
             KeyWord::If
+            | KeyWord::Copy
+            | KeyWord::Crate
+            | KeyWord::New
             | KeyWord::True
             | KeyWord::Null
             | KeyWord::Else
             | KeyWord::False
             | KeyWord::Sizeof
+            | KeyWord::Unsafe
             | KeyWord::While => {
                 let value = self.parse_expression(STAMENT_END_TOKENS).try_err()?;
                 Statement::from_expression(value, self.current_is(&SEMI_COLON))
@@ -56,6 +60,15 @@ impl<'a, 'f> Parser<'a, 'f> {
                 )
             }
 
+            KeyWord::Defer => {
+                self.bump();
+                let value = self.parse_expression(STAMENT_END_TOKENS).try_err()?;
+                Statement::from_expression(
+                    Expression::new_defer(value, self.span_combine(start_span)),
+                    self.current_is(&SEMI_COLON),
+                )
+            }
+
             KeyWord::Import => self.parse_import().try_err()?,
             KeyWord::Extern => self.parse_extern_function().try_err()?,
 
@@ -63,20 +76,11 @@ impl<'a, 'f> Parser<'a, 'f> {
 
             KeyWord::Use => self.parse_use().try_err()?,
 
-            KeyWord::Crate => {
-                return TryErr(soul_error_internal!(
-                    "'crate' outside of import unstable",
-                    Some(self.token().span)
-                ));
-            }
-
             KeyWord::Enum => self.parse_enum().try_err()?,
 
-            KeyWord::New
-            | KeyWord::For
+            KeyWord::For
             | KeyWord::Dyn
             | KeyWord::Fall
-            | KeyWord::Copy
             | KeyWord::Impl
             | KeyWord::Trait
             | KeyWord::Class
@@ -93,6 +97,17 @@ impl<'a, 'f> Parser<'a, 'f> {
                 ));
             }
 
+            KeyWord::Pub => {
+                return TryErr(SoulError::new(
+                    format!(
+                        "'{}' can only be written once, before an item",
+                        keyword.as_str()
+                    ),
+                    SoulErrorKind::InvalidContext,
+                    Some(self.token().span),
+                ));
+            }
+
             KeyWord::As => {
                 return TryErr(soul_error_internal!(
                     format!(
@@ -8,7 +8,7 @@ use soul_utils::{
     Ident,
     error::{SoulError, SoulErrorKind, SoulResult},
     soul_names::{AssignType, KeyWord, TypeModifier},
-    span::Span,
+    span::{Span, Visibility},
     try_result::{ResultMapNotValue, ResultTryErr, ToResult, TryErr, TryError, TryOk, TryResult},
 };
 
@@ -67,6 +67,7 @@ impl<'a, 'f> Parser<'a, 'f> {
                 ty,
                 node_id: None,
                 initialize_value: None,
+                visibility: Visibility::Private,
             };
             return TryOk(Statement::new_variable(variable, span));
         }
@@ -89,6 +90,7 @@ impl<'a, 'f> Parser<'a, 'f> {
             ty,
             node_id: None,
             initialize_value: Some(value),
+            visibility: Visibility::Private,
         };
 
         TryOk(Statement::new_variable(
@@ -5,7 +5,7 @@ use soul_tokenizer::TokenKind;
 use soul_utils::{
     error::{SoulError, SoulErrorKind, SoulResult},
     soul_names::{KeyWord, TypeModifier},
-    span::Span,
+    span::{Span, Visibility},
     symbool_kind::SymbolKind,
     try_result::{
         ResultMapNotValue, ResultTryErr, ResultTryNotValue, ToResult, TryErr, TryError,
@@ -24,9 +24,11 @@ use crate::parser::{
 mod from_keyword;
 mod from_modifier;
 mod parse_assign;
+mod parse_attribute;
 mod parse_import;
 mod parse_objects;
 mod parse_variable;
+mod parse_visibility;
 
 impl<'a, 'f> Parser<'a, 'f> {
     pub(crate) fn parse_global_statments(&mut self) -> Vec<Statement> {
@@ -65,7 +67,10 @@ impl<'a, 'f> Parser<'a, 'f> {
             }
 
             match self.parse_statement() {
-                Ok(statement) => statements.push(statement),
+                Ok(mut statement) => {
+                    self.check_local_visibility(&mut statement);
+                    statements.push(statement)
+                }
                 Err(err) => {
                     self.log_error(err);
                     self.skip_over_statement();
@@ -86,7 +91,15 @@ impl<'a, 'f> Parser<'a, 'f> {
     }
 
     pub(crate) fn parse_statement(&mut self) -> SoulResult<Statement> {
-        let statement = self.inner_parse_statement()?;
+        let meta_data = self.parse_attributes()?;
+        let visibility_span = self.token().span;
+        let visibility = self.parse_visibility();
+        let mut statement = self.inner_parse_statement()?;
+        self.set_visibility(&mut statement, visibility, visibility_span);
+        let target = parse_attribute::attribute_target(&statement);
+        self.check_attributes(&meta_data.attributes, target);
+        statement.meta_data = meta_data;
+
         if !matches!(statement.node, StatementKind::Expression { .. }) && self.ends_semicolon() {
             self.log_error(SoulError::new(
                 format!("'{}' at the end of a line can only be used for expressions at the end of a block", SymbolKind::SemiColon.as_str()),
@@ -159,11 +172,11 @@ impl<'a, 'f> Parser<'a, 'f> {
     fn try_parse_from_ident(&mut self, start_span: Span) -> TryResult<Statement, SoulError> {
         let ident = self.try_token_as_ident_str().try_err()?;
 
-        if let Some(modifier) = TypeModifier::from_str(ident) {
+        if let Some(modifier) = TypeModifier::try_from_str(ident) {
             return self.try_parse_from_modifier(start_span, modifier);
         }
 
-        if let Some(keyword) = KeyWord::from_str(ident) {
+        if let Some(keyword) = KeyWord::try_from_str(ident) {
             return self.try_parse_from_keyword(start_span, keyword);
         }
 
@@ -234,8 +247,9 @@ impl<'a, 'f> Parser<'a, 'f> {
                 continue;
             }
 
+            let visibility = self.parse_visibility();
             let mut name = self.try_bump_consume_ident()?;
-            let modifier = match TypeModifier::from_str(name.as_str()) {
+            let modifier = match TypeModifier::try_from_str(name.as_str()) {
                 Some(modifer) => {
                     name = self.try_bump_consume_ident()?;
                     modifer
@@ -243,7 +257,7 @@ impl<'a, 'f> Parser<'a, 'f> {
                 None => TypeModifier::Mut,
             };
 
-            let methode = self
+            let mut methode = self
                 .try_parse_function_declaration(
                     name.span,
                     self.default_methode_type(modifier, name.span),
@@ -252,6 +266,7 @@ impl<'a, 'f> Parser<'a, 'f> {
                 .map_try_not_value(|(_, err)| *err)
                 .merge_to_result()?;
 
+            methode.node.signature.node.visibility = visibility;
             methodes.push(methode.node);
         }
         self.expect(&CURLY_CLOSE)?;
@@ -277,7 +292,7 @@ impl<'a, 'f> Parser<'a, 'f> {
             }
 
             let mut name = self.try_bump_consume_ident()?;
-            let modifier = match TypeModifier::from_str(name.as_str()) {
+            let modifier = match TypeModifier::try_from_str(name.as_str()) {
                 Some(modifer) => {
                     name = self.try_bump_consume_ident()?;
                     modifer
@@ -285,7 +300,7 @@ impl<'a, 'f> Parser<'a, 'f> {
                 None => TypeModifier::Mut,
             };
 
-            let methode = self
+            let mut methode = self
                 .try_parse_function_declaration(
                     name.span,
                     self.default_methode_type(modifier, name.span),
@@ -294,6 +309,8 @@ impl<'a, 'f> Parser<'a, 'f> {
                 .map_try_not_value(|(_, err)| *err)
                 .merge_to_result()?;
 
+            // the methodes of a trait are as visible as the trait itself
+            methode.node.signature.node.visibility = Visibility::Public;
             methodes.push(methode.node);
         }
         self.expect(&CURLY_CLOSE)?;
@@ -0,0 +1,211 @@
+use ast::{Statement, StatementKind};
+use soul_tokenizer::TokenKind;
+use soul_utils::{
+    StringLiteral,
+    compile_options::{Arch, CfgPredicate, Os},
+    error::{SoulError, SoulErrorKind, SoulResult},
+    soul_names::{AttributeKind, AttributeTarget},
+    span::{Attribute, ItemMetaData},
+    symbool_kind::SymbolKind,
+};
+
+use crate::parser::{
+    Parser,
+    parse_utils::{ASSIGN, COMMA, ROUND_CLOSE, ROUND_OPEN, SQUARE_CLOSE, SQUARE_OPEN},
+};
+
+const HASH: TokenKind = TokenKind::Symbol(SymbolKind::Hash);
+
+impl<'a, 'f> Parser<'a, 'f> {
+    /// Parses the `#[name]`, `#[name(value, ...)]` and `#[cfg(...)]` lines in front of an item.
+    pub(crate) fn parse_attributes(&mut self) -> SoulResult<ItemMetaData> {
+        let mut meta_data = ItemMetaData::default_const();
+        while self.current_is(&HASH) {
+            self.bump();
+            self.expect(&SQUARE_OPEN)?;
+            let name = self.try_bump_consume_ident()?;
+
+            if AttributeKind::try_from_str(name.as_str()) == Some(AttributeKind::Cfg) {
+                let mut predicates = self.parse_cfg_list()?;
+                let predicate = match predicates.len() {
+                    1 => predicates.remove(0),
+                    _ => CfgPredicate::All(predicates),
+                };
+                meta_data.cfg = Some(match meta_data.cfg.take() {
+                    Some(previous) => CfgPredicate::All(vec![previous, predicate]),
+                    None => predicate,
+                });
+
+                self.expect(&SQUARE_CLOSE)?;
+                self.skip_end_lines();
+                continue;
+            }
+
+            let mut values = vec![];
+            if self.current_is(&ROUND_OPEN) {
+                self.bump();
+                while !self.current_is(&ROUND_CLOSE) {
+                    values.push(self.try_bump_consume_ident()?);
+                    if !self.current_is(&COMMA) {
+                        break;
+                    }
+                    self.bump();
+                }
+                self.expect(&ROUND_CLOSE)?;
+            }
+            self.expect(&SQUARE_CLOSE)?;
+
+            meta_data.attributes.push(Attribute { name, values });
+            self.skip_end_lines();
+        }
+        Ok(meta_data)
+    }
+
+    /// Parses `(predicate, ...)`, a predicate is `os = "linux"`, `arch = "x86_64"`,
+    /// `any(...)`, `all(...)` or `not(predicate)`.
+    fn parse_cfg_list(&mut self) -> SoulResult<Vec<CfgPredicate>> {
+        self.expect(&ROUND_OPEN)?;
+        let mut predicates = vec![];
+        while !self.current_is(&ROUND_CLOSE) {
+            predicates.push(self.parse_cfg_predicate()?);
+            if !self.current_is(&COMMA) {
+                break;
+            }
+            self.bump();
+        }
+        self.expect(&ROUND_CLOSE)?;
+        Ok(predicates)
+    }
+
+    fn parse_cfg_predicate(&mut self) -> SoulResult<CfgPredicate> {
+        let key = self.try_bump_consume_ident()?;
+        match key.as_str() {
+            "any" => return Ok(CfgPredicate::Any(self.parse_cfg_list()?)),
+            "all" => return Ok(CfgPredicate::All(self.parse_cfg_list()?)),
+            "not" => {
+                let mut predicates = self.parse_cfg_list()?;
+                if predicates.len() != 1 {
+                    return Err(SoulError::new(
+                        format!("'not' takes one predicate but got {}", predicates.len()),
+                        SoulErrorKind::InvalidAttribute,
+                        Some(key.span),
+                    ));
+                }
+                return Ok(CfgPredicate::Not(Box::new(predicates.remove(0))));
+            }
+            "os" | "arch" => (),
+            _ => {
+                return Err(SoulError::new(
+                    format!(
+                        "unknown cfg predicate '{}', expected one of: os, arch, any, all, not",
+                        key.as_str()
+                    ),
+                    SoulErrorKind::InvalidAttribute,
+                    Some(key.span),
+                ));
+            }
+        }
+
+        self.expect(&ASSIGN)?;
+        let value = match &self.token().kind {
+            TokenKind::StringLiteral(StringLiteral::Normal(value)) => value.clone(),
+            other => {
+                return Err(SoulError::new(
+                    format!(
+                        "expected a string after '{} =' but found: '{}'",
+                        key.as_str(),
+                        other.display()
+                    ),
+                    SoulErrorKind::InvalidTokenKind,
+                    Some(self.token().span),
+                ));
+            }
+        };
+        let span = self.token().span;
+        self.bump();
+
+        let (predicate, expected) = match key.as_str() {
+            "os" => (Os::try_from_str(&value).map(CfgPredicate::Os), Os::VALUES),
+            _ => (
+                Arch::try_from_str(&value).map(CfgPredicate::Arch),
+                Arch::VALUES,
+            ),
+        };
+        match predicate {
+            Some(predicate) => Ok(predicate),
+            None => {
+                self.log_warning(SoulError::new(
+                    format!(
+                        "unknown {} \"{value}\", expected one of: {}",
+                        key.as_str(),
+                        expected.join(", ")
+                    ),
+                    SoulErrorKind::InvalidAttribute,
+                    Some(span),
+                ));
+                // a target the compiler does not know is never the one it builds for,
+                // `any()` without predicates never matches
+                Ok(CfgPredicate::Any(vec![]))
+            }
+        }
+    }
+
+    /// Warns about attributes that are unknown or have no effect on `target`.
+    pub(crate) fn check_attributes(&mut self, attributes: &[Attribute], target: AttributeTarget) {
+        for attribute in attributes {
+            let Some(kind) = attribute.kind() else {
+                self.log_warning(SoulError::new(
+                    format!("unknown attribute '{}'", attribute.name.as_str()),
+                    SoulErrorKind::InvalidAttribute,
+                    Some(attribute.name.span),
+                ));
+                continue;
+            };
+
+            if !kind.applies_to(target) {
+                self.log_warning(SoulError::new(
+                    format!(
+                        "attribute '{}' has no effect on a {}",
+                        kind.as_str(),
+                        target.as_str()
+                    ),
+                    SoulErrorKind::InvalidAttribute,
+                    Some(attribute.name.span),
+                ));
+                continue;
+            }
+
+            let (valid, expected) = match kind {
+                AttributeKind::Repr => (
+                    matches!(attribute.values.as_slice(), [value] if value.as_str() == "C"),
+                    format!("#[{}(C)]", kind.as_str()),
+                ),
+                _ => (attribute.values.is_empty(), format!("#[{}]", kind.as_str())),
+            };
+            if !valid {
+                self.log_warning(SoulError::new(
+                    format!(
+                        "attribute '{}' is ignored, expected {expected}",
+                        kind.as_str()
+                    ),
+                    SoulErrorKind::InvalidAttribute,
+                    Some(attribute.name.span),
+                ));
+            }
+        }
+    }
+}
+
+/// The kind of item `statement` declares, for checking its attributes.
+pub(crate) fn attribute_target(statement: &Statement) -> AttributeTarget {
+    match &statement.node {
+        // attributes on a `use` block apply to each of its methodes
+        StatementKind::Function(_)
+        | StatementKind::ExternalFunction(_)
+        | StatementKind::UseBlock(_) => AttributeTarget::Function,
+        StatementKind::Struct(_) => AttributeTarget::Struct,
+        StatementKind::Enum(_) => AttributeTarget::Enum,
+        StatementKind::Variable(_) => AttributeTarget::Variable,
+        _ => AttributeTarget::Statement,
+    }
+}
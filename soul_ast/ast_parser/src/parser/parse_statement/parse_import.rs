@@ -2,6 +2,7 @@ use ast::{Import, ImportKind, ImportPath, Statement};
 use ast::{ImportItem, StatementKind};
 use soul_tokenizer::TokenKind;
 use soul_utils::Ident;
+use soul_utils::span::Visibility;
 use soul_utils::symbool_kind::SymbolKind;
 use soul_utils::{
     error::{SoulError, SoulErrorKind, SoulResult},
@@ -35,7 +36,11 @@ impl<'a, 'f> Parser<'a, 'f> {
             paths.push(self.inner_parse_import()?);
         }
 
-        let import = Import { id: None, paths };
+        let import = Import {
+            id: None,
+            paths,
+            visibility: Visibility::Private,
+        };
 
         self.expect(&TokenKind::EndLine)?;
 
@@ -128,8 +133,7 @@ impl<'a, 'f> Parser<'a, 'f> {
         let mut lib_name = None;
         let mut path = SoulImportPath::new();
         if self.current_is_ident(THIS_PORJECT) {
-            let current_path = self.source_path.clone();
-            path = SoulImportPath::from(current_path);
+            path = SoulImportPath::from(self.crate_folder.clone());
             self.bump();
             self.expect(&SEPARATOR)?;
         } else if self.current_is(&SEPARATOR) {
@@ -1,7 +1,8 @@
 use ast::{Enum, Field, Statement, Struct};
 use soul_utils::{
     error::{SoulError, SoulResult},
-    soul_names::{KeyWord, TypeModifier},
+    soul_names::{AttributeTarget, KeyWord, TypeModifier},
+    span::Visibility,
     try_result::{ResultTryErr, ResultTryNotValue, ToResult, TryErr, TryOk, TryResult},
 };
 
@@ -52,6 +53,7 @@ impl<'f, 'a> Parser<'f, 'a> {
                 id: None,
                 name,
                 variants: variant,
+                visibility: Visibility::Private,
             }),
             self.span_combine(start_span),
         ))
@@ -94,6 +96,7 @@ impl<'f, 'a> Parser<'f, 'a> {
             fields,
             generics,
             defined_in: None,
+            visibility: Visibility::Private,
         };
 
         Ok(Statement::new(
@@ -103,8 +106,12 @@ impl<'f, 'a> Parser<'f, 'a> {
     }
 
     fn parse_field(&mut self) -> TryResult<Field, SoulError> {
+        let meta_data = self.parse_attributes().try_err()?;
+        self.check_attributes(&meta_data.attributes, AttributeTarget::Field);
+        let visibility = self.parse_visibility();
+
         let mut name = self.try_bump_consume_ident().try_err()?;
-        let modifier = TypeModifier::from_str(name.as_str());
+        let modifier = TypeModifier::try_from_str(name.as_str());
         if modifier.is_some() {
             name = self.try_bump_consume_ident().try_err()?;
         }
@@ -117,6 +124,12 @@ impl<'f, 'a> Parser<'f, 'a> {
             return TryErr(self.get_expect_any_error(STAMENT_END_TOKENS));
         }
 
-        TryOk(Field { id: None, name, ty })
+        TryOk(Field {
+            id: None,
+            name,
+            ty,
+            meta_data,
+            visibility,
+        })
     }
 }
@@ -3,6 +3,7 @@ use soul_tokenizer::TokenKind;
 use soul_utils::{
     error::{SoulError, SoulErrorKind, SoulResult},
     soul_names::{AssignType, TypeModifier},
+    span::Visibility,
     try_result::ToResult,
 };
 
@@ -47,6 +48,7 @@ impl<'a, 'f> Parser<'a, 'f> {
                         name,
                         node_id: None,
                         initialize_value: None,
+                        visibility: Visibility::Private,
                     },
                     self.span_combine(name_span),
                 ));
@@ -71,6 +73,7 @@ impl<'a, 'f> Parser<'a, 'f> {
                 name,
                 node_id: None,
                 initialize_value: Some(self.parse_expression(STAMENT_END_TOKENS)?),
+                visibility: Visibility::Private,
             },
             self.span_combine(name_span),
         ))
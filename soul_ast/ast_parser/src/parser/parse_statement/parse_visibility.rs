@@ -0,0 +1,63 @@
+use ast::Statement;
+use soul_utils::{
+    error::{SoulError, SoulErrorKind},
+    soul_names::KeyWord,
+    span::{Span, Visibility},
+};
+
+use crate::parser::Parser;
+
+impl<'a, 'f> Parser<'a, 'f> {
+    /// Parses the optional `pub` in front of an item or field.
+    pub(crate) fn parse_visibility(&mut self) -> Visibility {
+        if !self.current_is_keyword(KeyWord::Pub) {
+            return Visibility::Private;
+        }
+
+        self.bump();
+        Visibility::Public
+    }
+
+    /// Puts the `pub` written before `statement` on the item it declares.
+    pub(super) fn set_visibility(
+        &mut self,
+        statement: &mut Statement,
+        visibility: Visibility,
+        span: Span,
+    ) {
+        if !visibility.is_public() {
+            return;
+        }
+
+        match statement.node.visibility_mut() {
+            Some(item_visibility) => *item_visibility = visibility,
+            None => self.log_error(SoulError::new(
+                format!(
+                    "'{}' can only be used on functions, structs, enums, globals and imports",
+                    KeyWord::Pub.as_str()
+                ),
+                SoulErrorKind::InvalidContext,
+                Some(span),
+            )),
+        }
+    }
+
+    /// `pub` only means something for the items at the top of a module.
+    pub(super) fn check_local_visibility(&mut self, statement: &mut Statement) {
+        let is_public = statement
+            .node
+            .visibility_mut()
+            .is_some_and(|visibility| visibility.is_public());
+
+        if is_public {
+            self.log_error(SoulError::new(
+                format!(
+                    "'{}' can only be used on items in the global scope of a module",
+                    KeyWord::Pub.as_str()
+                ),
+                SoulErrorKind::InvalidContext,
+                Some(statement.span),
+            ));
+        }
+    }
+}
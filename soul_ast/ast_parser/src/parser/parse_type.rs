@@ -16,8 +16,8 @@ use soul_utils::{
 use crate::parser::{
     Parser,
     parse_utils::{
-        ARRAY, ARROW_LEFT, ASSIGN, COLON, COMMA, CONST_REF, CURLY_OPEN, MUT_REF, OPTIONAL, POINTER,
-        ROUND_CLOSE, ROUND_OPEN, SQUARE_CLOSE, SQUARE_OPEN,
+        ARRAY, ARROW_LEFT, ASSIGN, COLON, COMMA, CONST_REF, CURLY_OPEN, ELLIPSIS, MUT_REF,
+        OPTIONAL, POINTER, ROUND_CLOSE, ROUND_OPEN, SQUARE_CLOSE, SQUARE_OPEN,
     },
 };
 
@@ -38,7 +38,7 @@ impl<'a, 'f> Parser<'a, 'f> {
             return SoulType::none(self.token().span);
         };
 
-        if let Some(prim) = PrimitiveTypes::from_str(ident.as_str()) {
+        if let Some(prim) = PrimitiveTypes::try_from_str(ident.as_str()) {
             let span = self.token().span;
             return SoulType::new(None, TypeKind::Primitive(prim), span);
         }
@@ -53,9 +53,10 @@ impl<'a, 'f> Parser<'a, 'f> {
         )
     }
 
+    /// Parses `(this, name: T, ...)`, the `bool` is whether it ends in variadic `...`.
     pub(crate) fn try_parse_parameters(
         &mut self,
-    ) -> TryResult<(NamedTupleType, FunctionKind), SoulError> {
+    ) -> TryResult<(NamedTupleType, FunctionKind, bool), SoulError> {
         let begin = self.current_position();
 
         let result = self.inner_parse_named_tuple_kinds(NamedTupleKinds::Function);
@@ -76,14 +77,16 @@ impl<'a, 'f> Parser<'a, 'f> {
             let span = self.token().span.combine(ty.span);
 
             ty = match wrap {
-                ParseWrappers::ConstRef => SoulType::new(
+                ParseWrappers::ConstRef(lifetime) => SoulType::new(
                     None,
-                    TypeKind::Reference(ReferenceType::new(ty, CONST)),
+                    TypeKind::Reference(ReferenceType::new_lifetime(ty, lifetime, CONST)),
+                    span,
+                ),
+                ParseWrappers::MutRef(lifetime) => SoulType::new(
+                    None,
+                    TypeKind::Reference(ReferenceType::new_lifetime(ty, lifetime, MUT)),
                     span,
                 ),
-                ParseWrappers::MutRef => {
-                    SoulType::new(None, TypeKind::Reference(ReferenceType::new(ty, MUT)), span)
-                }
                 ParseWrappers::Pointer => {
                     SoulType::new(None, TypeKind::Pointer(Box::new(ty)), span)
                 }
@@ -106,8 +109,8 @@ impl<'a, 'f> Parser<'a, 'f> {
         let mut wrappers = vec![];
         loop {
             let possible_wrap = match self.token().kind {
-                CONST_REF => Some(ParseWrappers::ConstRef),
-                MUT_REF => Some(ParseWrappers::MutRef),
+                CONST_REF => Some(ParseWrappers::ConstRef(None)),
+                MUT_REF => Some(ParseWrappers::MutRef(None)),
                 POINTER => Some(ParseWrappers::Pointer),
                 OPTIONAL => Some(ParseWrappers::Option),
                 SQUARE_OPEN => Some(ParseWrappers::Array(self.get_array_type_wrapper()?)),
@@ -127,6 +130,11 @@ impl<'a, 'f> Parser<'a, 'f> {
             };
 
             self.bump();
+            let wrap = match wrap {
+                ParseWrappers::ConstRef(_) => ParseWrappers::ConstRef(self.parse_lifetime()),
+                ParseWrappers::MutRef(_) => ParseWrappers::MutRef(self.parse_lifetime()),
+                other => other,
+            };
             wrappers.push(wrap);
         }
 
@@ -134,13 +142,26 @@ impl<'a, 'f> Parser<'a, 'f> {
         TryOk(wrappers)
     }
 
+    /// Parses the optional lifetime of a reference (so `'a` in `@'a int`).
+    fn parse_lifetime(&mut self) -> Option<Ident> {
+        let TokenKind::Lifetime(lifetime) = &self.token().kind else {
+            return None;
+        };
+
+        let lifetime = Ident::new(lifetime.clone(), self.token().span);
+        self.bump();
+        Some(lifetime)
+    }
+
     fn get_array_type_wrapper(&mut self) -> TryResult<ArrayKind, SoulError> {
         self.bump();
         let kind = match &self.token().kind {
             &CONST_REF => ArrayKind::ConstSlice,
             &MUT_REF => ArrayKind::MutSlice,
             &POINTER => ArrayKind::HeapArray,
-            TokenKind::Number(Number::Uint(size)) => ArrayKind::StackArray(*size),
+            TokenKind::Number(Number::Uint(size), None) if u64::try_from(*size).is_ok() => {
+                ArrayKind::StackArray(*size as u64)
+            }
             other => {
                 return TryNotValue(SoulError::new(
                     format!(
@@ -185,7 +206,7 @@ impl<'a, 'f> Parser<'a, 'f> {
         };
 
         let ident = self.try_bump_consume_ident().try_not_value()?;
-        if let Some(keyword) = KeyWord::from_str(ident.as_str()) {
+        if let Some(keyword) = KeyWord::try_from_str(ident.as_str()) {
             return TryNotValue(SoulError::new(
                 format!("keyword '{}' can not be type", keyword.as_str()),
                 SoulErrorKind::TypeNotFound,
@@ -193,7 +214,7 @@ impl<'a, 'f> Parser<'a, 'f> {
             ));
         }
 
-        if let Some(prim) = PrimitiveTypes::from_str(ident.as_str()) {
+        if let Some(prim) = PrimitiveTypes::try_from_str(ident.as_str()) {
             let span = self.token().span;
             return TryOk(SoulType::new(None, TypeKind::Primitive(prim), span));
         }
@@ -217,7 +238,7 @@ impl<'a, 'f> Parser<'a, 'f> {
     fn inner_parse_named_tuple_kinds(
         &mut self,
         kind: NamedTupleKinds,
-    ) -> TryResult<(NamedTupleType, FunctionKind), SoulError> {
+    ) -> TryResult<(NamedTupleType, FunctionKind, bool), SoulError> {
         let (open, close, can_have_this) = match kind {
             NamedTupleKinds::Function => (&ROUND_OPEN, &ROUND_CLOSE, true),
         };
@@ -228,19 +249,30 @@ impl<'a, 'f> Parser<'a, 'f> {
         let mut function_kind = FunctionKind::Static;
 
         let mut has_default = false;
+        let mut variadic = false;
         loop {
             self.skip_end_lines();
             if self.current_is(close) {
                 break;
             }
 
+            if self.current_is(&ELLIPSIS) {
+                self.bump();
+                self.skip_end_lines();
+                variadic = true;
+                break;
+            }
+
             match self.inner_parse_named_this(&mut function_kind, can_have_this)? {
                 Loop::None => (),
                 Loop::Break => break,
                 Loop::Continue => continue,
             }
 
-            let modifier = match self.try_token_as_ident_str().map(TypeModifier::from_str) {
+            let modifier = match self
+                .try_token_as_ident_str()
+                .map(TypeModifier::try_from_str)
+            {
                 Ok(Some(modifier)) => {
                     self.bump();
                     Some(modifier)
@@ -294,7 +326,7 @@ impl<'a, 'f> Parser<'a, 'f> {
 
         self.expect(close).try_err()?;
 
-        Ok((types, function_kind))
+        Ok((types, function_kind, variadic))
     }
 
     fn inner_parse_named_this(
@@ -350,8 +382,8 @@ impl<'a, 'f> Parser<'a, 'f> {
 }
 
 enum ParseWrappers {
-    ConstRef,
-    MutRef,
+    ConstRef(Option<Ident>),
+    MutRef(Option<Ident>),
     Pointer,
     Option,
     Array(ArrayKind),
@@ -15,6 +15,7 @@ pub const MUT_REF: TokenKind = TokenKind::Symbol(SymbolKind::And);
 pub const COMMA: TokenKind = TokenKind::Symbol(SymbolKind::Comma);
 pub const ARRAY: TokenKind = TokenKind::Symbol(SymbolKind::Array);
 pub const COLON: TokenKind = TokenKind::Symbol(SymbolKind::Colon);
+pub const ELLIPSIS: TokenKind = TokenKind::Symbol(SymbolKind::Ellipsis);
 pub const ASSIGN: TokenKind = TokenKind::Symbol(SymbolKind::Assign);
 pub const POINTER: TokenKind = TokenKind::Symbol(SymbolKind::Star);
 pub const OPTIONAL: TokenKind = TokenKind::Symbol(SymbolKind::Question);
@@ -70,7 +71,7 @@ impl<'a, 'f> Parser<'a, 'f> {
 
     pub(crate) fn current_is_keyword(&self, expected: KeyWord) -> bool {
         match &self.token().kind {
-            TokenKind::Ident(ident) => KeyWord::from_str(ident.as_str()) == Some(expected),
+            TokenKind::Ident(ident) => KeyWord::try_from_str(ident.as_str()) == Some(expected),
             _ => false,
         }
     }
@@ -0,0 +1,33 @@
+use ast::{ExpressionKind, Statement, StatementKind};
+use soul_utils::compile_options::TargetInfo;
+
+/// Removes the statements and fields whose `#[cfg(...)]` does not match `target`, so the
+/// name resolver never sees them.
+pub(crate) fn strip_cfg(statements: &mut Vec<Statement>, target: &TargetInfo) {
+    statements.retain(|statement| statement.meta_data.is_cfg_enabled(target));
+
+    for statement in statements {
+        match &mut statement.node {
+            StatementKind::Struct(object) => {
+                object
+                    .fields
+                    .retain(|field| field.meta_data.is_cfg_enabled(target));
+            }
+            StatementKind::Function(function) => {
+                strip_cfg(&mut function.block.statements, target);
+            }
+            StatementKind::UseBlock(use_block) => {
+                let impl_methodes = use_block.impls.iter_mut().flat_map(|i| &mut i.methodes);
+                for methode in use_block.methodes.iter_mut().chain(impl_methodes) {
+                    strip_cfg(&mut methode.block.statements, target);
+                }
+            }
+            StatementKind::Expression { expression, .. } => {
+                if let ExpressionKind::Block(block) = &mut expression.node {
+                    strip_cfg(&mut block.statements, target);
+                }
+            }
+            _ => (),
+        }
+    }
+}
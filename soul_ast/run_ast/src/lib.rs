@@ -12,7 +12,7 @@ const ENTRY_MOD_NAME: &str = "crate";
 
 pub fn to_ast<'a>(
     token_stream: TokenStream<'a>,
-    _options: &CompilerOptions,
+    options: &CompilerOptions,
     module_store: &mut ModuleStore,
     context: &mut CrateContext,
     crates: &'a CrateStore,
@@ -28,10 +28,20 @@ pub fn to_ast<'a>(
         name,
         None,
         context,
+        options.target_info(),
+        source_folder.clone(),
         source_folder.clone(),
     );
     ast.modules.insert(root, module);
 
-    name_resolve(root, module_store, context, &mut ast, crates, source_folder);
+    name_resolve(
+        root,
+        module_store,
+        context,
+        &mut ast,
+        crates,
+        options.target_info(),
+        source_folder,
+    );
     ast
 }
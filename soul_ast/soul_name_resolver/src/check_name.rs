@@ -1,6 +1,8 @@
 use soul_utils::{
     Ident,
     error::{SoulError, SoulErrorKind, SoulResult},
+    soul_names::KeyWord,
+    span::Visibility,
 };
 
 use crate::NameResolver;
@@ -18,6 +20,22 @@ impl<'a> NameResolver<'a> {
         }
     }
 
+    /// A capitalized name used to export a function, warn for the ones that are no longer exported.
+    pub(crate) fn check_function_visibility(&mut self, name: &Ident, visibility: Visibility) {
+        let is_capital = name.as_str().chars().next().is_some_and(char::is_uppercase);
+        if is_capital && !visibility.is_public() {
+            self.log_warning(SoulError::new(
+                format!(
+                    "function '{}' starts with a capital letter but is not '{}', it can only be used in this module",
+                    name.as_str(),
+                    KeyWord::Pub.as_str(),
+                ),
+                SoulErrorKind::InvalidFunctionName,
+                Some(name.span),
+            ));
+        }
+    }
+
     fn inner_check_variable_name(&mut self, name: &Ident) -> SoulResult<()> {
         let mut chars = name.as_str().chars();
         let first = chars.next().ok_or(SoulError::new(
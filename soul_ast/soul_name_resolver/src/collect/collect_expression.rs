@@ -1,8 +1,9 @@
 use crate::NameResolver;
-use ast::{ElseKind, Expression, ExpressionKind, If};
+use ast::{ElseKind, Expression, ExpressionKind, FormatPiece, If, NewKind};
 
 impl<'a> NameResolver<'a> {
     pub(super) fn collect_expression(&mut self, expression: &mut Expression) {
+        let span = expression.span;
         match &mut expression.node {
             ExpressionKind::Sizeof(ty) => self.collect_type(ty),
             ExpressionKind::ArrayContructor(ctor) => {
@@ -16,6 +17,28 @@ impl<'a> NameResolver<'a> {
                 self.collect_expression(&mut ctor.amount);
                 self.collect_expression(&mut ctor.element);
             }
+            ExpressionKind::New(new) => {
+                new.id = Some(self.alloc_node());
+                match &mut new.kind {
+                    NewKind::Value(value) => self.collect_expression(value),
+                    NewKind::Array(ctor) => {
+                        ctor.id = Some(self.alloc_node());
+                        if let Some(ty) = ctor.element_type.as_mut() {
+                            self.collect_type(ty)
+                        }
+                        self.collect_expression(&mut ctor.amount);
+                        self.collect_expression(&mut ctor.element);
+                    }
+                }
+            }
+            ExpressionKind::FormatString(format) => {
+                format.id = Some(self.alloc_node());
+                for piece in &mut format.pieces {
+                    if let FormatPiece::Argument { value, .. } = piece {
+                        self.collect_expression(value);
+                    }
+                }
+            }
             ExpressionKind::FieldAccess(field) => {
                 self.collect_expression(&mut field.object);
             }
@@ -53,7 +76,7 @@ impl<'a> NameResolver<'a> {
                 unary.id = Some(self.alloc_node());
                 self.collect_expression(&mut unary.expression);
             }
-            ExpressionKind::Block(block) => {
+            ExpressionKind::Block(block) | ExpressionKind::Unsafe(block) => {
                 block.node_id = Some(self.alloc_node());
 
                 let prev = self.current.in_global;
@@ -66,7 +89,18 @@ impl<'a> NameResolver<'a> {
                 self.collect_expression(&mut binary.left);
                 self.collect_expression(&mut binary.right);
             }
-            ExpressionKind::Deref { inner, id } => {
+            ExpressionKind::Range(range) => {
+                range.id = Some(self.alloc_node());
+                if let Some(start) = &mut range.start {
+                    self.collect_expression(start);
+                }
+                if let Some(end) = &mut range.end {
+                    self.collect_expression(end);
+                }
+            }
+            ExpressionKind::Deref { inner, id }
+            | ExpressionKind::Copy { inner, id }
+            | ExpressionKind::Defer { inner, id } => {
                 *id = Some(self.alloc_node());
                 self.collect_expression(inner);
             }
@@ -89,9 +123,13 @@ impl<'a> NameResolver<'a> {
                 *id = Some(self.alloc_node());
                 self.collect_expression(expression);
             }
-            ExpressionKind::ExternalExpression(_) => todo!("impl external expressions"),
+            ExpressionKind::ExternalExpression(external) => {
+                external.id = Some(self.alloc_node());
+                self.collect_external_path(&external.path, span);
+                self.collect_expression(&mut external.expr);
+            }
             ExpressionKind::Default(id) => *id = Some(self.alloc_node()),
-            ExpressionKind::Literal((id, _)) => *id = Some(self.alloc_node()),
+            ExpressionKind::Literal { id, .. } => *id = Some(self.alloc_node()),
             ExpressionKind::Variable { id, .. } => {
                 *id = Some(self.alloc_node());
             }
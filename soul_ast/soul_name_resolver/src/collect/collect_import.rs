@@ -1,17 +1,24 @@
 use std::path::{Path, PathBuf};
 
-use ast::{EntryKind, ImportItem, ImportPath};
+use ast::{EntryKind, HeaderEntry, ImportItem, ImportKind, ImportPath, scope::ScopeTypeEntryKind};
 use soul_utils::{
     error::{SoulError, SoulErrorKind},
     ids::IdAlloc,
     soul_error_internal,
-    span::{ModuleId, Span},
+    soul_import_path::SoulImportPath,
+    soul_names::KeyWord,
+    span::{ModuleId, Span, Visibility},
 };
 
-use crate::NameResolver;
+use crate::{NameResolver, collect::module_file};
 
 impl<'a> NameResolver<'a> {
-    pub(crate) fn collect_import_path(&mut self, path: &ImportPath, span: Span) {
+    pub(crate) fn collect_import_path(
+        &mut self,
+        path: &ImportPath,
+        visibility: Visibility,
+        span: Span,
+    ) {
         let module_name = match path.module.get_module_name() {
             Some(val) => val,
             None => {
@@ -53,11 +60,114 @@ impl<'a> NameResolver<'a> {
         );
 
         // For external crates, we don't collect items from AST (the module_id is error)
-        if module_id == ModuleId::error() && path.lib_name.is_some() {
+        if path.lib_name.is_some() {
+            if visibility.is_public() {
+                self.log_error(SoulError::new(
+                    format!(
+                        "'{} {}' can not re-export items of other crates",
+                        KeyWord::Pub.as_str(),
+                        KeyWord::Import.as_str(),
+                    ),
+                    SoulErrorKind::InvalidContext,
+                    Some(span),
+                ));
+            }
             return;
         }
 
-        self.collect_items(module_id, module_name, &imported_items, span)
+        self.collect_items(module_id, module_name, &imported_items, span);
+        if visibility.is_public() {
+            self.reexport_items(module_id, &path.kind, span);
+        }
+    }
+
+    /// `pub import` adds the public items it imports to the header of the current module,
+    /// so other modules can import them from here. Items of the current module win over
+    /// re-exported ones with the same name.
+    fn reexport_items(&mut self, module_id: ModuleId, kind: &ImportKind, span: Span) {
+        let Some(module) = self.modules.get(module_id) else {
+            self.log_error(soul_error_internal!(
+                format!("module {:?} not found", module_id),
+                Some(span)
+            ));
+            return;
+        };
+
+        let entries: Vec<(String, HeaderEntry)> = match kind {
+            ImportKind::Glob => module
+                .header
+                .iter()
+                .map(|(name, entry)| (name.clone(), entry.clone()))
+                .collect(),
+            ImportKind::Items { items, .. } => items
+                .iter()
+                .filter_map(|item| {
+                    let (name, alias) = match item {
+                        ImportItem::Alias { name, alias } => (name, alias),
+                        ImportItem::Normal(name) => (name, name),
+                    };
+                    let entry = module.header.get(name.as_str())?;
+                    Some((alias.to_string(), entry.clone()))
+                })
+                .collect(),
+            ImportKind::This | ImportKind::Module | ImportKind::Alias(_) => {
+                self.log_error(SoulError::new(
+                    format!(
+                        "'{} {}' can only re-export items, import them with '.{{..}}' or '.*'",
+                        KeyWord::Pub.as_str(),
+                        KeyWord::Import.as_str(),
+                    ),
+                    SoulErrorKind::InvalidContext,
+                    Some(span),
+                ));
+                return;
+            }
+        };
+
+        let header = &mut self.modules[self.current.module].header;
+        for (name, entry) in entries {
+            let reexport = header.entry(name).or_default();
+            if let Some(function) = entry.function.filter(|kind| kind.visibility.is_public()) {
+                reexport.function.get_or_insert(function);
+            }
+
+            if let Some(variable) = entry.variable.filter(|kind| kind.visibility.is_public()) {
+                reexport.variable.get_or_insert(variable);
+            }
+
+            if let Some(obj) = entry.struct_type.filter(|kind| kind.visibility.is_public()) {
+                reexport.struct_type.get_or_insert(obj);
+            }
+        }
+    }
+
+    /// Loads the deepest module a `crate.Module.item` path names, so the path can be resolved
+    /// without importing the module first.
+    pub(super) fn collect_external_path(&mut self, path: &SoulImportPath, span: Span) {
+        let mut module_path = self.current.source_folder.clone();
+        let mut deepest = None;
+        for segment in path.as_path() {
+            module_path.push(segment);
+            if module_file(&module_path).is_none() {
+                break;
+            }
+            deepest = Some(module_path.clone());
+        }
+
+        let Some(module_path) = deepest else {
+            return;
+        };
+
+        let import_path = ImportPath {
+            lib_name: None,
+            module: SoulImportPath::from(module_path),
+            kind: ImportKind::Module,
+        };
+        let Some(module_name) = import_path.module.get_module_name().map(str::to_string) else {
+            return;
+        };
+
+        self.collect_internal_module(&import_path, &module_name, span);
     }
 
     fn collect_external_lib(&mut self, lib_name: &String, span: Span) -> ModuleId {
@@ -125,7 +235,7 @@ impl<'a> NameResolver<'a> {
             return;
         };
 
-        let is_public = self.is_name_public(name);
+        let is_public = Visibility::of_module_name(name).is_public();
         if !is_public {
             self.log_error(SoulError::new(
                 format!("module '{}' is private", name),
@@ -214,14 +324,19 @@ impl<'a> NameResolver<'a> {
             let entry_function = entry.function;
             if let Some(EntryKind {
                 value: obj,
-                is_public,
+                visibility,
             }) = &entry.struct_type
             {
-                if !is_public {
+                let kind_name = match obj {
+                    ast::CustomType::Struct(_) => "struct",
+                    ast::CustomType::Enum(_) => "enum",
+                };
+
+                if !visibility.is_public() {
                     Self::static_log_error(
                         &mut self.context,
                         SoulError::new(
-                            format!("struct {} is private", alias_name.as_str()),
+                            format!("{kind_name} {} is private", alias_name.as_str()),
                             SoulErrorKind::AlreadyFoundInScope,
                             Some(alias_name.span),
                         ),
@@ -232,24 +347,30 @@ impl<'a> NameResolver<'a> {
                     Some(val) => val,
                     None => {
                         self.log_error(soul_error_internal!(
-                            format!("Struct: '{}' node_id is None", obj.name().as_str()),
+                            format!("{kind_name}: '{}' node_id is None", obj.name().as_str()),
                             None
                         ));
                         return;
                     }
                 };
 
-                if !Self::insert_struct_alias(
+                let scope_kind = match obj {
+                    ast::CustomType::Struct(_) => ScopeTypeEntryKind::Struct,
+                    ast::CustomType::Enum(_) => ScopeTypeEntryKind::Enum,
+                };
+
+                if !Self::insert_type_alias(
                     &mut self.info.scopes,
                     alias_name,
                     span,
                     id,
+                    scope_kind,
                     self.current.module,
                 ) {
                     Self::static_log_error(
                         self.context,
                         SoulError::new(
-                            format!("struct {} already exists", alias_name.as_str()),
+                            format!("{kind_name} {} already exists", alias_name.as_str()),
                             SoulErrorKind::AlreadyFoundInScope,
                             Some(alias_name.span),
                         ),
@@ -260,16 +381,18 @@ impl<'a> NameResolver<'a> {
                     ast::CustomType::Struct(struct_) => {
                         Self::resolve_struct(self.context, self.store, &self.current, struct_)
                     }
-                    ast::CustomType::Enum(_) => todo!("impl enum in collect items"),
+                    ast::CustomType::Enum(obj) => {
+                        Self::resolve_enum(self.context, self.store, &self.current, obj)
+                    }
                 }
             }
 
             if let Some(EntryKind {
                 value: id,
-                is_public,
+                visibility,
             }) = entry_variable
             {
-                if !is_public {
+                if !visibility.is_public() {
                     self.log_error(SoulError::new(
                         format!("variable '{}' is private", alias_name.as_str()),
                         SoulErrorKind::AlreadyFoundInScope,
@@ -288,10 +411,10 @@ impl<'a> NameResolver<'a> {
 
             if let Some(EntryKind {
                 value: id,
-                is_public,
+                visibility,
             }) = entry_function
             {
-                if !is_public {
+                if !visibility.is_public() {
                     self.log_error(SoulError::new(
                         format!("function '{}' is private", alias_name.as_str()),
                         SoulErrorKind::AlreadyFoundInScope,
@@ -42,14 +42,17 @@ impl<'a> NameResolver<'a> {
                     self.collect_function(methode);
                 }
 
-                if !impls.is_empty() {
-                    todo!()
+                for impl_block in impls {
+                    for methode in &mut impl_block.methodes {
+                        self.check_function_name(&methode.signature.node.name);
+                        self.collect_function(methode);
+                    }
                 }
                 self.current.in_global = prev;
             }
             StatementKind::Import(import) => {
                 for path in &import.paths {
-                    self.collect_import_path(path, statement.span)
+                    self.collect_import_path(path, import.visibility, statement.span)
                 }
             }
             StatementKind::Struct(obj) => {
@@ -195,7 +198,11 @@ fn owner_hint_from_expression(
     // Without this default, literals would have no type and couldn't participate in
     // type inference at all.
     match &init.node {
-        ExpressionKind::Literal((_, lit)) => Some(TypeKind::Primitive(match lit {
+        ExpressionKind::Literal {
+            suffix: Some(suffix),
+            ..
+        } => Some(TypeKind::Primitive(*suffix)),
+        ExpressionKind::Literal { literal: lit, .. } => Some(TypeKind::Primitive(match lit {
             Literal::Int(_) | Literal::Uint(_) => PrimitiveTypes::Int,
             Literal::Float(_) => PrimitiveTypes::Float64,
             Literal::Bool(_) => PrimitiveTypes::Boolean,
@@ -207,6 +214,8 @@ fn owner_hint_from_expression(
                 return match intrinsic {
                     ast::Intrinsic::InFile => Some(TypeKind::Primitive(PrimitiveTypes::CStr)),
                     ast::Intrinsic::InLine => Some(TypeKind::Primitive(PrimitiveTypes::Int)),
+                    ast::Intrinsic::Panic => Some(TypeKind::Primitive(PrimitiveTypes::Never)),
+                    ast::Intrinsic::TargetOs | ast::Intrinsic::TargetArch => None,
                 };
             }
 
@@ -236,7 +245,7 @@ fn parse_callee_type(
 ) -> Option<TypeKind> {
     match &callee.node {
         ExpressionKind::Variable { ident, .. } => {
-            if let Some(primitive) = PrimitiveTypes::from_str(ident.as_str()) {
+            if let Some(primitive) = PrimitiveTypes::try_from_str(ident.as_str()) {
                 return Some(TypeKind::Primitive(primitive));
             }
             if scopes.lookup_type(ident, module).is_some() {
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use ast::{
     Block, Enum, FunctionSignature, NamedTupleElement, NamedTupleType, Struct, VarTypeKind,
@@ -229,11 +229,12 @@ impl<'a> NameResolver<'a> {
         true
     }
 
-    fn insert_struct_alias(
+    fn insert_type_alias(
         scopes: &mut ScopeBuilder,
         name: &Ident,
         span: Span,
         id: NodeId,
+        kind: ScopeTypeEntryKind,
         module: ModuleId,
     ) -> bool {
         if scopes.flat_lookup_type(name, module).is_some() {
@@ -246,7 +247,7 @@ impl<'a> NameResolver<'a> {
                 span,
                 node_id: id,
                 trait_parent: None,
-                kind: ScopeTypeEntryKind::Struct,
+                kind,
             },
         );
 
@@ -312,7 +313,16 @@ impl<'a> NameResolver<'a> {
         name: String,
     ) {
         let tokens = to_token_stream(source, module_id);
-        let module = parse_module(tokens, module_id, name, Some(parent), self.context, path);
+        let module = parse_module(
+            tokens,
+            module_id,
+            name,
+            Some(parent),
+            self.context,
+            self.target,
+            path,
+            self.current.source_folder.clone(),
+        );
 
         if let Some(module) = self.modules.get_mut(parent) {
             module.modules.insert(module_id);
@@ -345,3 +355,14 @@ impl<'a> NameResolver<'a> {
             .expect("resolver has no scope")
     }
 }
+
+/// The file of the module at `path`, a folder module keeps its items in `mod.soul`.
+pub(crate) fn module_file(path: &Path) -> Option<PathBuf> {
+    if path.is_dir() {
+        let file = path.join("mod.soul");
+        return file.is_file().then_some(file);
+    }
+
+    let file = path.with_added_extension("soul");
+    file.is_file().then_some(file)
+}
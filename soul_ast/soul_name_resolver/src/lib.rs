@@ -8,6 +8,7 @@ use ast::{
 };
 use soul_utils::{
     CrateStore, Ident,
+    compile_options::TargetInfo,
     crate_store::CrateContext,
     error::{SoulError, SoulErrorKind},
     ids::{FunctionId, IdAlloc, IdGenerator},
@@ -26,6 +27,7 @@ pub fn name_resolve(
     context: &mut CrateContext,
     ast_context: &mut AbtractSyntaxTree,
     crates: &CrateStore,
+    target: &TargetInfo,
     source_folder: PathBuf,
 ) {
     let mut resolver = NameResolver::new(
@@ -34,6 +36,7 @@ pub fn name_resolve(
         context,
         ast_context,
         crates,
+        target,
         source_folder,
     );
 
@@ -77,6 +80,7 @@ struct NameResolver<'a> {
     node_generator: IdGenerator<NodeId>,
     function_generator: &'a mut IdGenerator<FunctionId>,
     crates: &'a CrateStore,
+    target: &'a TargetInfo,
 }
 impl<'a> NameResolver<'a> {
     fn new(
@@ -85,10 +89,12 @@ impl<'a> NameResolver<'a> {
         context: &'a mut CrateContext,
         ast_context: &'a mut AbtractSyntaxTree,
         crates: &'a CrateStore,
+        target: &'a TargetInfo,
         source_folder: PathBuf,
     ) -> Self {
         Self {
             crates,
+            target,
             context,
             module_store,
             current: Current {
@@ -108,7 +114,8 @@ impl<'a> NameResolver<'a> {
 
     fn header_insert_function(&mut self, function: &Function) -> Option<EntryKind<FunctionId>> {
         let signature = &function.signature.node;
-        let is_public = self.is_name_public(signature.name.as_str());
+        let visibility = signature.visibility;
+        self.check_function_visibility(&signature.name, visibility);
         let header = &mut self.modules[self.current.module].header;
         let entry = match header.get_mut(signature.name.as_str()) {
             Some(val) => val,
@@ -117,12 +124,12 @@ impl<'a> NameResolver<'a> {
 
         entry.function.replace(EntryKind {
             value: signature.id?,
-            is_public,
+            visibility,
         })
     }
 
     fn header_insert_variable(&mut self, variable: &Variable) -> Option<EntryKind<NodeId>> {
-        let is_public = self.is_name_public(variable.name.as_str());
+        let visibility = variable.visibility;
         let header = &mut self.modules[self.current.module].header;
         let entry = match header.get_mut(variable.name.as_str()) {
             Some(val) => val,
@@ -131,12 +138,12 @@ impl<'a> NameResolver<'a> {
 
         entry.variable.replace(EntryKind {
             value: variable.node_id?,
-            is_public,
+            visibility,
         })
     }
 
     fn header_insert_enum(&mut self, obj: Enum) -> Option<EntryKind<CustomType>> {
-        let is_public = self.is_name_public(obj.name.as_str());
+        let visibility = obj.visibility;
         let header = &mut self.modules[self.current.module].header;
         let entry = match header.get_mut(obj.name.as_str()) {
             Some(val) => val,
@@ -145,12 +152,12 @@ impl<'a> NameResolver<'a> {
 
         entry.struct_type.replace(EntryKind {
             value: ast::CustomType::Enum(obj),
-            is_public,
+            visibility,
         })
     }
 
     fn header_insert_struct(&mut self, obj: Struct) -> Option<EntryKind<CustomType>> {
-        let is_public = self.is_name_public(obj.name.as_str());
+        let visibility = obj.visibility;
         let header = &mut self.modules[self.current.module].header;
         let entry = match header.get_mut(obj.name.as_str()) {
             Some(val) => val,
@@ -159,7 +166,7 @@ impl<'a> NameResolver<'a> {
 
         entry.struct_type.replace(EntryKind {
             value: ast::CustomType::Struct(obj),
-            is_public,
+            visibility,
         })
     }
 
@@ -213,6 +220,10 @@ impl<'a> NameResolver<'a> {
         self.context.faults.push(SementicFault::error(error));
     }
 
+    fn log_warning(&mut self, warning: SoulError) {
+        self.context.faults.push(SementicFault::warning(warning));
+    }
+
     fn static_log_error(context: &mut CrateContext, error: SoulError) {
         context.faults.push(SementicFault::error(error));
     }
@@ -271,7 +282,7 @@ impl<'a> NameResolver<'a> {
 
         let header = &self.modules.get(module_id)?.header;
         let entry = header.get(function_name)?.function?;
-        if !entry.is_public {
+        if !entry.visibility.is_public() {
             self.log_error(SoulError::new(
                 format!("'{function_name}' is private"),
                 SoulErrorKind::InvalidModuleAccess,
@@ -302,7 +313,7 @@ impl<'a> NameResolver<'a> {
 
         let header = &self.modules.get(module_id)?.header;
         let entry = header.get(variable_name)?.variable?;
-        if !entry.is_public {
+        if !entry.visibility.is_public() {
             self.log_error(SoulError::new(
                 format!("'{variable_name}' is private"),
                 SoulErrorKind::InvalidModuleAccess,
@@ -313,6 +324,24 @@ impl<'a> NameResolver<'a> {
         Some(entry.value)
     }
 
+    /// The declare store holds the functions of every module, only `pub` ones can be called
+    /// from another module.
+    fn check_function_access(&mut self, id: FunctionId, span: Span) {
+        let Some((signature, module)) = self.store.get_function(id) else {
+            return;
+        };
+
+        if *module == self.current.module || signature.visibility.is_public() {
+            return;
+        }
+
+        self.log_error(SoulError::new(
+            format!("'{}' is private", signature.name.as_str()),
+            SoulErrorKind::InvalidModuleAccess,
+            Some(span),
+        ));
+    }
+
     fn resolve_alias(&self, module_name: &str, function_name: &str) -> Option<String> {
         let module_entry = match self
             .info
@@ -345,11 +374,4 @@ impl<'a> NameResolver<'a> {
             .scopes
             .lookup_value(name, ScopeValue::Variable, self.current.module)
     }
-
-    fn is_name_public(&self, name: &str) -> bool {
-        name.chars()
-            .next()
-            .map(|ch| ch.is_uppercase())
-            .unwrap_or(false)
-    }
 }
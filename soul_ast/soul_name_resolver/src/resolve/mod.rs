@@ -4,6 +4,8 @@ use soul_utils::{ids::FunctionId, soul_error_internal, span::ModuleId};
 use crate::NameResolver;
 mod resolve_expression;
 mod resolve_function_call;
+mod resolve_glob;
+mod resolve_path;
 mod resolve_statement;
 
 impl<'a> NameResolver<'a> {
@@ -1,10 +1,19 @@
-use ast::{ElseKind, Expression, ExpressionKind, FieldAccess};
+use ast::{ElseKind, Expression, ExpressionKind, FieldAccess, FormatPiece, NewKind};
 use soul_utils::error::{SoulError, SoulErrorKind};
 
 use crate::NameResolver;
 
 impl<'a> NameResolver<'a> {
     pub(super) fn resolve_expression(&mut self, expression: &mut Expression) {
+        if matches!(expression.node, ExpressionKind::ExternalExpression(_)) {
+            self.resolve_external_expression(expression);
+            return;
+        }
+
+        if self.try_resolve_module_path(expression) {
+            return;
+        }
+
         let span = expression.span;
         match &mut expression.node {
             ExpressionKind::Sizeof(_) => (),
@@ -12,6 +21,20 @@ impl<'a> NameResolver<'a> {
                 self.resolve_expression(&mut ctor.amount);
                 self.resolve_expression(&mut ctor.element);
             }
+            ExpressionKind::New(new) => match &mut new.kind {
+                NewKind::Value(value) => self.resolve_expression(value),
+                NewKind::Array(ctor) => {
+                    self.resolve_expression(&mut ctor.amount);
+                    self.resolve_expression(&mut ctor.element);
+                }
+            },
+            ExpressionKind::FormatString(format) => {
+                for piece in &mut format.pieces {
+                    if let FormatPiece::Argument { value, .. } = piece {
+                        self.resolve_expression(value);
+                    }
+                }
+            }
             ExpressionKind::FieldAccess(field_access) => {
                 self.resolve_field_access(field_access);
             }
@@ -44,6 +67,14 @@ impl<'a> NameResolver<'a> {
                 self.resolve_expression(&mut binary.left);
                 self.resolve_expression(&mut binary.right);
             }
+            ExpressionKind::Range(range) => {
+                if let Some(start) = &mut range.start {
+                    self.resolve_expression(start);
+                }
+                if let Some(end) = &mut range.end {
+                    self.resolve_expression(end);
+                }
+            }
             ExpressionKind::If(r#if) => {
                 self.resolve_expression(&mut r#if.condition);
                 self.resolve_block(&mut r#if.block);
@@ -69,13 +100,15 @@ impl<'a> NameResolver<'a> {
                 }
                 self.resolve_block(&mut r#while.block);
             }
-            ExpressionKind::Deref { id: _, inner } => {
+            ExpressionKind::Deref { id: _, inner }
+            | ExpressionKind::Copy { id: _, inner }
+            | ExpressionKind::Defer { id: _, inner } => {
                 self.resolve_expression(inner);
             }
             ExpressionKind::Ref { expression, .. } => {
                 self.resolve_expression(expression);
             }
-            ExpressionKind::Block(block) => {
+            ExpressionKind::Block(block) | ExpressionKind::Unsafe(block) => {
                 self.resolve_block(block);
             }
             ExpressionKind::ReturnLike(return_like) => {
@@ -92,7 +125,7 @@ impl<'a> NameResolver<'a> {
 
             ExpressionKind::Null(_)
             | ExpressionKind::Default(_)
-            | ExpressionKind::Literal(_)
+            | ExpressionKind::Literal { .. }
             | ExpressionKind::ExternalExpression(_) => (),
         }
     }
@@ -22,7 +22,7 @@ impl<'a> NameResolver<'a> {
         }
     }
 
-    fn check_if_valid(&mut self, function_call: &mut FunctionCall, span: Span) {
+    pub(super) fn check_if_valid(&mut self, function_call: &mut FunctionCall, span: Span) {
         if function_call.intrinsic.is_some() {
             return;
         }
@@ -85,6 +85,10 @@ impl<'a> NameResolver<'a> {
                 } else if intrinsic == ast::Intrinsic::InLine {
                     function_call.intrinsic_value =
                         Some(function_call.name.span.start_line.to_string());
+                } else if intrinsic == ast::Intrinsic::TargetOs {
+                    function_call.intrinsic_value = Some(self.target.os.as_str().to_string());
+                } else if intrinsic == ast::Intrinsic::TargetArch {
+                    function_call.intrinsic_value = Some(self.target.arch.as_str().to_string());
                 }
                 return;
             }
@@ -169,13 +173,28 @@ impl<'a> NameResolver<'a> {
         }
 
         if has_module_with_this && !can_use_store {
-            function_call.resolved = self.lookup_function(function_call.name.as_str());
+            function_call.resolved = match self.lookup_function(function_call.name.as_str()) {
+                Some(id) => Some(id),
+                None => {
+                    self.lookup_glob_function(&function_call.name, &mut function_call.external_ref)
+                }
+            };
             return;
         }
 
         let type_qualifier = self.parse_owner_type(function_call.callee.as_deref());
         let is_type_qualifier = type_qualifier.is_some();
 
+        if !is_type_qualifier
+            && function_call.callee.is_none()
+            && self.lookup_function(function_call.name.as_str()).is_none()
+            && let Some(id) =
+                self.lookup_glob_function(&function_call.name, &mut function_call.external_ref)
+        {
+            function_call.resolved = Some(id);
+            return;
+        }
+
         if is_type_qualifier {
             function_call.callee = None;
         } else if let Some(callee) = &mut function_call.callee {
@@ -188,8 +207,9 @@ impl<'a> NameResolver<'a> {
             .store
             .find_function(function_call.name.as_str(), owner_kind);
 
-        if function_call.resolved.is_none() {
-            function_call.resolved = self.lookup_function(function_call.name.as_str());
+        match function_call.resolved {
+            Some(id) => self.check_function_access(id, function_call.name.span),
+            None => function_call.resolved = self.lookup_function(function_call.name.as_str()),
         }
     }
 
@@ -246,7 +266,7 @@ impl<'a> NameResolver<'a> {
             _ => return None,
         };
 
-        if let Some(primitive) = PrimitiveTypes::from_str(ident.as_str()) {
+        if let Some(primitive) = PrimitiveTypes::try_from_str(ident.as_str()) {
             return Some(SoulType::new(
                 None,
                 TypeKind::Primitive(primitive),
@@ -254,12 +274,13 @@ impl<'a> NameResolver<'a> {
             ));
         }
 
-        if self
+        let is_type = self
             .info
             .scopes
             .lookup_type(ident, self.current.module)
-            .is_some()
-        {
+            .is_some();
+
+        if is_type || self.lookup_glob_type(ident).is_some() {
             return Some(SoulType::new(
                 None,
                 TypeKind::Stub(ast::Stub {
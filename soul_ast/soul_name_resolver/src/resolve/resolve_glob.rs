@@ -0,0 +1,134 @@
+use ast::{EntryKind, ExternalRef, HeaderEntry, ImportKind, TypeKind, scope::NodeId};
+use soul_utils::{
+    Ident,
+    error::{SoulError, SoulErrorKind},
+    ids::{FunctionId, IdAlloc},
+};
+
+use crate::NameResolver;
+
+/// An item found through one of the `import Module.*` of the current module.
+enum GlobItem<T> {
+    Internal(T),
+    External(ExternalRef),
+}
+
+impl<'a> NameResolver<'a> {
+    /// Finds a public free function in the modules imported with `*`, for a function of another
+    /// crate the call gets an `external_ref` and a `FunctionId::error()`.
+    pub(super) fn lookup_glob_function(
+        &mut self,
+        name: &Ident,
+        external_ref: &mut Option<ExternalRef>,
+    ) -> Option<FunctionId> {
+        let get_function = |this: &Self, entry: &HeaderEntry| {
+            let function = entry.function?;
+            let (signature, _) = this.store.get_function(function.value)?;
+            matches!(signature.methode_type.kind, TypeKind::None).then_some(function)
+        };
+
+        match self.lookup_glob(name, true, get_function)? {
+            GlobItem::Internal(id) => Some(id),
+            GlobItem::External(reference) => {
+                *external_ref = Some(reference);
+                Some(FunctionId::error())
+            }
+        }
+    }
+
+    pub(super) fn lookup_glob_variable(&mut self, name: &Ident) -> Option<NodeId> {
+        match self.lookup_glob(name, false, |_, entry| entry.variable)? {
+            GlobItem::Internal(id) => Some(id),
+            GlobItem::External(_) => None,
+        }
+    }
+
+    pub(super) fn lookup_glob_type(&mut self, name: &Ident) -> Option<NodeId> {
+        let get_type = |_: &Self, entry: &HeaderEntry| {
+            let obj = entry.struct_type.as_ref()?;
+            Some(EntryKind {
+                value: obj.value.id()?,
+                visibility: obj.visibility,
+            })
+        };
+
+        match self.lookup_glob(name, false, get_type)? {
+            GlobItem::Internal(id) => Some(id),
+            GlobItem::External(_) => None,
+        }
+    }
+
+    /// Globs only fill in names that are not declared or imported by name, two globs
+    /// bringing in different items with the same name is an error once that name is used.
+    fn lookup_glob<T: Copy + PartialEq>(
+        &mut self,
+        name: &Ident,
+        find_external: bool,
+        get: impl Fn(&Self, &HeaderEntry) -> Option<EntryKind<T>>,
+    ) -> Option<GlobItem<T>> {
+        let modules = self.info.scopes.iter_modules(self.current.module)?;
+
+        let mut found: Vec<(String, GlobItem<T>)> = vec![];
+        for (import_name, entry) in modules {
+            if !matches!(entry.import_kind, ImportKind::Glob) {
+                continue;
+            }
+
+            let item = match &entry.crate_name {
+                Some(crate_name) => {
+                    let module_path = format!("{}.{}", entry.module_name, name.as_str());
+                    if !find_external
+                        || self
+                            .crates
+                            .resolve_function(crate_name, &module_path)
+                            .is_none()
+                    {
+                        continue;
+                    }
+
+                    GlobItem::External(ExternalRef {
+                        crate_name: crate_name.clone(),
+                        module_path,
+                    })
+                }
+                None => {
+                    let Some(module) = self.modules.get(entry.module_id) else {
+                        continue;
+                    };
+
+                    let entry = module.header.get(name.as_str());
+                    match entry.and_then(|entry| get(self, entry)) {
+                        Some(kind) if kind.visibility.is_public() => GlobItem::Internal(kind.value),
+                        _ => continue,
+                    }
+                }
+            };
+
+            let is_same = found.iter().any(|(_, other)| match (other, &item) {
+                (GlobItem::Internal(a), GlobItem::Internal(b)) => a == b,
+                (GlobItem::External(a), GlobItem::External(b)) => a == b,
+                _ => false,
+            });
+
+            if !is_same {
+                found.push((import_name, item));
+            }
+        }
+
+        if found.len() > 1 {
+            let mut modules: Vec<String> = found.iter().map(|(module, _)| module.clone()).collect();
+            modules.sort();
+            self.log_error(SoulError::new(
+                format!(
+                    "'{}' is ambiguous, it is imported by the globs of '{}'",
+                    name.as_str(),
+                    modules.join("', '"),
+                ),
+                SoulErrorKind::AlreadyFoundInScope,
+                Some(name.span),
+            ));
+        }
+
+        found.into_iter().next().map(|(_, item)| item)
+    }
+}
@@ -0,0 +1,444 @@
+use std::ffi::OsStr;
+
+use ast::{
+    CustomType, EntryKind, Expression, ExpressionKind, FieldAccess, FunctionCall, HeaderEntry,
+    Stub, TypeKind,
+};
+use soul_utils::{
+    Ident,
+    error::{SoulError, SoulErrorKind},
+    ids::{FunctionId, IdAlloc},
+    soul_error_internal,
+    span::{ModuleId, Span, Visibility},
+};
+
+use crate::{NameResolver, collect::module_file};
+
+impl<'a> NameResolver<'a> {
+    /// `crate.Module.item`, the path starts at the root module of the crate.
+    pub(super) fn resolve_external_expression(&mut self, expression: &mut Expression) {
+        let ExpressionKind::ExternalExpression(external) = &expression.node else {
+            return;
+        };
+
+        let segments: Vec<Ident> = external
+            .path
+            .as_path()
+            .iter()
+            .map(|segment| Ident::new(segment.to_string_lossy().to_string(), expression.span))
+            .collect();
+        let inner = external.expr.as_ref().clone();
+
+        let root = self.module_store.get_root_id();
+        if let Some(node) = self.resolve_path(root, &segments, inner) {
+            expression.node = node;
+        }
+    }
+
+    /// `Module.item`, `Module.Sub.item` or `Module.Type.Variant` through an imported module
+    /// of this crate, modules of other crates are resolved by `lookup_module_function`.
+    pub(super) fn try_resolve_module_path(&mut self, expression: &mut Expression) -> bool {
+        let (object, inner) = match &expression.node {
+            ExpressionKind::FieldAccess(field_access) => (
+                field_access.object.as_ref(),
+                Expression::new_variable(field_access.field.clone()),
+            ),
+            ExpressionKind::FunctionCall(function_call) => {
+                let Some(callee) = &function_call.callee else {
+                    return false;
+                };
+
+                let call = FunctionCall {
+                    callee: None,
+                    ..function_call.clone()
+                };
+                (
+                    callee.as_ref(),
+                    Expression::new(ExpressionKind::FunctionCall(call), expression.span),
+                )
+            }
+            _ => return false,
+        };
+
+        let Some((root, segments)) = flatten_path(object) else {
+            return false;
+        };
+
+        // a variable shadows the module
+        if segments.is_empty() && self.check_variable(&root).is_some() {
+            return false;
+        }
+
+        let module_id = match self.lookup_module(root.as_str()) {
+            Some(entry) if entry.crate_name.is_none() => entry.module_id,
+            _ => return false,
+        };
+
+        match self.resolve_path(module_id, &segments, inner) {
+            Some(node) => expression.node = node,
+            None => self.unresolved_path(expression, root),
+        }
+        true
+    }
+
+    /// Replaces a path whose error is already logged, so it is not reported again.
+    fn unresolved_path(&mut self, expression: &mut Expression, root: Ident) {
+        match &mut expression.node {
+            ExpressionKind::FunctionCall(function_call) => {
+                function_call.callee = None;
+                function_call.resolved = Some(FunctionId::error());
+                for arg in &mut function_call.arguments {
+                    self.resolve_expression(&mut arg.value);
+                }
+            }
+            node => {
+                *node = ExpressionKind::Variable {
+                    id: None,
+                    ident: root,
+                    resolved: None,
+                }
+            }
+        }
+    }
+
+    fn resolve_path(
+        &mut self,
+        module: ModuleId,
+        segments: &[Ident],
+        inner: Expression,
+    ) -> Option<ExpressionKind> {
+        let (module, rest) = self.walk_submodules(module, segments)?;
+        let Some((item, rest)) = rest.split_first() else {
+            return self.resolve_path_item(module, inner);
+        };
+
+        let entry = self.module_item(module, item)?;
+        if let Some(obj) = &entry.struct_type {
+            return self.resolve_path_type(module, item, obj, rest, inner);
+        }
+
+        let Some(global) = entry.variable else {
+            self.log_error(SoulError::new(
+                format!(
+                    "'{}' in module '{}' is not a module, type or global",
+                    item.as_str(),
+                    self.module_name(module),
+                ),
+                SoulErrorKind::InvalidContext,
+                Some(item.span),
+            ));
+            return None;
+        };
+
+        self.check_item_access(module, item, global.visibility);
+        let mut object = Expression::new(
+            ExpressionKind::Variable {
+                id: None,
+                ident: item.clone(),
+                resolved: Some(global.value),
+            },
+            item.span,
+        );
+        for field in rest {
+            object = Expression::new_field(object, field.clone());
+        }
+
+        match inner.node {
+            ExpressionKind::Variable { ident, .. } => {
+                Some(Expression::new_field(object, ident).node)
+            }
+            _ => {
+                self.log_error(SoulError::new(
+                    format!(
+                        "methods can not be called on '{}' through a path, import it first",
+                        item.as_str(),
+                    ),
+                    SoulErrorKind::InvalidContext,
+                    Some(inner.span),
+                ));
+                None
+            }
+        }
+    }
+
+    /// The last segment of a path, a global or a function of `module`.
+    fn resolve_path_item(&mut self, module: ModuleId, inner: Expression) -> Option<ExpressionKind> {
+        let span = inner.span;
+        match inner.node {
+            ExpressionKind::Variable { ident, id, .. } => {
+                let entry = self.module_item(module, &ident)?;
+                let Some(global) = entry.variable else {
+                    self.log_error(SoulError::new(
+                        format!(
+                            "'{}' in module '{}' is not a global",
+                            ident.as_str(),
+                            self.module_name(module),
+                        ),
+                        SoulErrorKind::InvalidContext,
+                        Some(ident.span),
+                    ));
+                    return None;
+                };
+
+                self.check_item_access(module, &ident, global.visibility);
+                Some(ExpressionKind::Variable {
+                    id,
+                    ident,
+                    resolved: Some(global.value),
+                })
+            }
+            ExpressionKind::FunctionCall(mut function_call) => {
+                let entry = self.module_item(module, &function_call.name)?;
+                let Some(function) = entry.function else {
+                    self.log_error(SoulError::new(
+                        format!(
+                            "'{}' in module '{}' is not a function",
+                            function_call.name.as_str(),
+                            self.module_name(module),
+                        ),
+                        SoulErrorKind::InvalidContext,
+                        Some(function_call.name.span),
+                    ));
+                    return None;
+                };
+
+                self.check_item_access(module, &function_call.name, function.visibility);
+                function_call.resolved = Some(function.value);
+                self.resolve_path_call(&mut function_call, span);
+                Some(ExpressionKind::FunctionCall(function_call))
+            }
+            _ => {
+                self.log_error(soul_error_internal!(
+                    format!(
+                        "unexpected '{}' at the end of a path",
+                        inner.node.variant_str()
+                    ),
+                    Some(span)
+                ));
+                None
+            }
+        }
+    }
+
+    /// `Type.Variant` or `Type.StaticMethode()` where `Type` is an item of `module`.
+    fn resolve_path_type(
+        &mut self,
+        module: ModuleId,
+        type_name: &Ident,
+        obj: &EntryKind<CustomType>,
+        rest: &[Ident],
+        inner: Expression,
+    ) -> Option<ExpressionKind> {
+        if let Some(segment) = rest.first() {
+            self.log_error(SoulError::new(
+                format!(
+                    "no item '{}' in type '{}'",
+                    segment.as_str(),
+                    type_name.as_str()
+                ),
+                SoulErrorKind::NotFoundInScope,
+                Some(segment.span),
+            ));
+            return None;
+        }
+
+        self.check_item_access(module, type_name, obj.visibility);
+        let span = inner.span;
+        match (&obj.value, inner.node) {
+            (CustomType::Enum(enum_def), ExpressionKind::Variable { ident, .. }) => {
+                if !enum_def
+                    .variants
+                    .iter()
+                    .any(|v| v.as_str() == ident.as_str())
+                {
+                    self.log_error(SoulError::new(
+                        format!(
+                            "variant '{}' not found in enum '{}'",
+                            ident.as_str(),
+                            type_name.as_str()
+                        ),
+                        SoulErrorKind::NotFoundInScope,
+                        Some(ident.span),
+                    ));
+                    return None;
+                }
+
+                Some(ExpressionKind::FieldAccess(FieldAccess {
+                    id: enum_def.id,
+                    object: Box::new(Expression::new_variable(type_name.clone())),
+                    field: ident,
+                    is_enum_variant: true,
+                }))
+            }
+            (_, ExpressionKind::FunctionCall(mut function_call)) => {
+                let owner = TypeKind::Stub(Stub {
+                    name: type_name.to_string(),
+                    generics: vec![],
+                });
+
+                function_call.resolved = self
+                    .store
+                    .find_function(function_call.name.as_str(), Some(&owner));
+                if let Some(id) = function_call.resolved {
+                    self.check_function_access(id, function_call.name.span);
+                } else {
+                    self.log_error(SoulError::new(
+                        format!(
+                            "no function '{}' in type '{}'",
+                            function_call.name.as_str(),
+                            type_name.as_str()
+                        ),
+                        SoulErrorKind::NotFoundInScope,
+                        Some(function_call.name.span),
+                    ));
+                    return None;
+                }
+
+                self.resolve_path_call(&mut function_call, span);
+                Some(ExpressionKind::FunctionCall(function_call))
+            }
+            (CustomType::Struct(_), ExpressionKind::Variable { ident, .. }) => {
+                self.log_error(SoulError::new(
+                    format!(
+                        "'{}' is a struct, '{}' is not one of its variants",
+                        type_name.as_str(),
+                        ident.as_str()
+                    ),
+                    SoulErrorKind::InvalidContext,
+                    Some(ident.span),
+                ));
+                None
+            }
+            (_, node) => {
+                self.log_error(soul_error_internal!(
+                    format!("unexpected '{}' at the end of a path", node.variant_str()),
+                    Some(span)
+                ));
+                None
+            }
+        }
+    }
+
+    fn resolve_path_call(&mut self, function_call: &mut FunctionCall, span: Span) {
+        self.check_if_valid(function_call, span);
+        for arg in &mut function_call.arguments {
+            self.resolve_expression(&mut arg.value);
+        }
+    }
+
+    /// Follows the segments that name submodules of `module`, the rest names an item.
+    fn walk_submodules<'s>(
+        &mut self,
+        mut module: ModuleId,
+        segments: &'s [Ident],
+    ) -> Option<(ModuleId, &'s [Ident])> {
+        let mut rest = segments;
+        while let Some((segment, tail)) = rest.split_first() {
+            let Some(submodule) = self.find_submodule(module, segment.as_str()) else {
+                break;
+            };
+
+            if !Visibility::of_module_name(segment.as_str()).is_public()
+                && !self.is_module_visible(submodule)
+            {
+                self.log_error(SoulError::new(
+                    format!("module '{}' is private", segment.as_str()),
+                    SoulErrorKind::InvalidModuleAccess,
+                    Some(segment.span),
+                ));
+                return None;
+            }
+
+            module = submodule;
+            rest = tail;
+        }
+
+        Some((module, rest))
+    }
+
+    /// Only loaded modules are found, `crate.` paths load theirs while collecting.
+    fn find_submodule(&self, module: ModuleId, name: &str) -> Option<ModuleId> {
+        let mut folder = if module == self.module_store.get_root_id() {
+            self.current.source_folder.clone()
+        } else {
+            let path = self.module_store.get_path(module)?;
+            if path.file_name() == Some(OsStr::new("mod.soul")) {
+                path.parent()?.to_path_buf()
+            } else {
+                path.with_extension("")
+            }
+        };
+
+        folder.push(name);
+        self.module_store.get_id(&module_file(&folder)?)
+    }
+
+    /// A private module can be used by the modules next to it and inside of them.
+    fn is_module_visible(&self, module: ModuleId) -> bool {
+        let Some(parent) = self.modules.get(module).and_then(|module| module.parent) else {
+            return true;
+        };
+
+        let mut current = Some(self.current.module);
+        while let Some(id) = current {
+            if id == parent {
+                return true;
+            }
+            current = self.modules.get(id).and_then(|module| module.parent);
+        }
+        false
+    }
+
+    fn module_item(&mut self, module: ModuleId, name: &Ident) -> Option<HeaderEntry> {
+        let entry = self
+            .modules
+            .get(module)
+            .and_then(|module| module.header.get(name.as_str()))
+            .cloned();
+
+        if entry.is_none() {
+            self.log_error(SoulError::new(
+                format!(
+                    "no item '{}' in module '{}'",
+                    name.as_str(),
+                    self.module_name(module)
+                ),
+                SoulErrorKind::NotFoundInScope,
+                Some(name.span),
+            ));
+        }
+        entry
+    }
+
+    fn check_item_access(&mut self, module: ModuleId, name: &Ident, visibility: Visibility) {
+        if module == self.current.module || visibility.is_public() {
+            return;
+        }
+
+        self.log_error(SoulError::new(
+            format!("'{}' is private", name.as_str()),
+            SoulErrorKind::InvalidModuleAccess,
+            Some(name.span),
+        ));
+    }
+
+    fn module_name(&self, module: ModuleId) -> String {
+        self.modules
+            .get(module)
+            .map(|module| module.name.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// `a.b.c` as `a` and `[b, c]`.
+fn flatten_path(expression: &Expression) -> Option<(Ident, Vec<Ident>)> {
+    match &expression.node {
+        ExpressionKind::Variable { ident, .. } => Some((ident.clone(), vec![])),
+        ExpressionKind::FieldAccess(field_access) => {
+            let (root, mut segments) = flatten_path(&field_access.object)?;
+            segments.push(field_access.field.clone());
+            Some((root, segments))
+        }
+        _ => None,
+    }
+}
@@ -40,8 +40,10 @@ impl<'a> NameResolver<'a> {
                     self.resolves_function(methode);
                 }
 
-                if !impls.is_empty() {
-                    todo!()
+                for impl_block in impls {
+                    for methode in &mut impl_block.methodes {
+                        self.resolves_function(methode);
+                    }
                 }
             }
             StatementKind::Struct(obj) => {
@@ -87,7 +89,12 @@ impl<'a> NameResolver<'a> {
         resolved: &mut Option<NodeId>,
         span: Span,
     ) {
-        match self.check_variable(name) {
+        let id = match self.check_variable(name) {
+            Some(id) => Some(id),
+            None => self.lookup_glob_variable(name),
+        };
+
+        match id {
             Some(id) => *resolved = Some(id),
             None => self.log_error(SoulError::new(
                 format!("variable '{}' is undefined in scope", name.as_str()),
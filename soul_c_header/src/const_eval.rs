@@ -0,0 +1,159 @@
+use crate::lexer::CToken;
+
+/// Evaluates an integer constant expression, like the value of a `#define`,
+/// an `#if` condition or an enum value. `lookup` resolves the identifiers in it.
+pub(crate) fn const_eval(tokens: &[CToken], lookup: &dyn Fn(&str) -> Option<i128>) -> Option<i128> {
+    let mut eval = ConstEval {
+        tokens,
+        position: 0,
+        lookup,
+    };
+
+    let value = eval.ternary()?;
+    match eval.position == tokens.len() {
+        true => Some(value),
+        false => None,
+    }
+}
+
+struct ConstEval<'t, 'l> {
+    tokens: &'t [CToken],
+    position: usize,
+    lookup: &'l dyn Fn(&str) -> Option<i128>,
+}
+impl ConstEval<'_, '_> {
+    fn current(&self) -> Option<&CToken> {
+        self.tokens.get(self.position)
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        let found = self.current().is_some_and(|token| token.is_punct(punct));
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn ternary(&mut self) -> Option<i128> {
+        let condition = self.binary(1)?;
+        if !self.eat("?") {
+            return Some(condition);
+        }
+
+        let then = self.ternary()?;
+        if !self.eat(":") {
+            return None;
+        }
+        let otherwise = self.ternary()?;
+        Some(if condition != 0 { then } else { otherwise })
+    }
+
+    fn binary(&mut self, min_precedence: u8) -> Option<i128> {
+        let mut left = self.unary()?;
+        loop {
+            let Some(CToken::Punct(operator)) = self.current() else {
+                return Some(left);
+            };
+            let operator = *operator;
+            let Some(precedence) = precedence(operator) else {
+                return Some(left);
+            };
+            if precedence < min_precedence {
+                return Some(left);
+            }
+
+            self.position += 1;
+            let right = self.binary(precedence + 1)?;
+            left = apply_binary(operator, left, right)?;
+        }
+    }
+
+    fn unary(&mut self) -> Option<i128> {
+        let token = self.current()?.clone();
+        self.position += 1;
+        match token {
+            CToken::Int(value) | CToken::Char(value) => Some(value),
+            CToken::Ident(ident) => (self.lookup)(&ident),
+            CToken::Punct("-") => self.unary()?.checked_neg(),
+            CToken::Punct("+") => self.unary(),
+            CToken::Punct("!") => Some((self.unary()? == 0) as i128),
+            CToken::Punct("~") => Some(!self.unary()?),
+            CToken::Punct("(") => {
+                if self.skip_cast() {
+                    return self.unary();
+                }
+
+                let value = self.ternary()?;
+                match self.eat(")") {
+                    true => Some(value),
+                    false => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Skips `int)` of a cast like `(int)1`, the value is kept as is.
+    fn skip_cast(&mut self) -> bool {
+        let start = self.position;
+        while let Some(CToken::Ident(ident)) = self.current() {
+            if !is_integer_type_word(ident) {
+                break;
+            }
+            self.position += 1;
+        }
+
+        if self.position > start && self.eat(")") {
+            return true;
+        }
+        self.position = start;
+        false
+    }
+}
+
+fn is_integer_type_word(word: &str) -> bool {
+    matches!(
+        word,
+        "int" | "unsigned" | "signed" | "long" | "short" | "char" | "const"
+    ) || word.ends_with("_t")
+}
+
+fn precedence(operator: &str) -> Option<u8> {
+    Some(match operator {
+        "||" => 1,
+        "&&" => 2,
+        "|" => 3,
+        "^" => 4,
+        "&" => 5,
+        "==" | "!=" => 6,
+        "<" | ">" | "<=" | ">=" => 7,
+        "<<" | ">>" => 8,
+        "+" | "-" => 9,
+        "*" | "/" | "%" => 10,
+        _ => return None,
+    })
+}
+
+fn apply_binary(operator: &str, left: i128, right: i128) -> Option<i128> {
+    Some(match operator {
+        "||" => (left != 0 || right != 0) as i128,
+        "&&" => (left != 0 && right != 0) as i128,
+        "|" => left | right,
+        "^" => left ^ right,
+        "&" => left & right,
+        "==" => (left == right) as i128,
+        "!=" => (left != right) as i128,
+        "<" => (left < right) as i128,
+        ">" => (left > right) as i128,
+        "<=" => (left <= right) as i128,
+        ">=" => (left >= right) as i128,
+        "<<" => left.checked_shl(u32::try_from(right).ok()?)?,
+        ">>" => left.checked_shr(u32::try_from(right).ok()?)?,
+        "+" => left.checked_add(right)?,
+        "-" => left.checked_sub(right)?,
+        "*" => left.checked_mul(right)?,
+        "/" => left.checked_div(right)?,
+        "%" => left.checked_rem(right)?,
+        _ => return None,
+    })
+}
@@ -0,0 +1,89 @@
+/// A C type as written in a declaration, typedef names are resolved when emitting.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CType {
+    Void,
+    Bool,
+    /// `char` without `signed` or `unsigned`.
+    Char,
+    Integer {
+        kind: IntegerKind,
+        unsigned: bool,
+    },
+    Float,
+    Double,
+    LongDouble,
+    /// A typedef name, like `size_t` or a typedef of the header.
+    Named(String),
+    Struct(String),
+    Union(String),
+    Enum(String),
+    Pointer {
+        pointee: Box<CType>,
+        /// Whether the pointee is `const`, `const char*` is a `c_str`.
+        pointee_const: bool,
+    },
+    Array(Box<CType>, u64),
+    Function(Box<CFunctionType>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegerKind {
+    Char,
+    Short,
+    Int,
+    Long,
+    LongLong,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CFunctionType {
+    pub return_type: CType,
+    pub parameters: Vec<CParameter>,
+    pub variadic: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CParameter {
+    pub name: Option<String>,
+    pub ty: CType,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CField {
+    pub name: String,
+    pub ty: CType,
+}
+
+/// A top level declaration of a C header.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CDeclaration {
+    Typedef {
+        name: String,
+        ty: CType,
+    },
+    Struct {
+        name: String,
+        fields: Vec<CField>,
+        is_union: bool,
+    },
+    Enum {
+        name: String,
+        variants: Vec<(String, i128)>,
+        /// Declared without a tag and not named by a typedef or field, like `enum { A, B };`.
+        is_anonymous: bool,
+    },
+    Function {
+        name: String,
+        function: CFunctionType,
+    },
+    /// An integer `#define`.
+    Constant {
+        name: String,
+        value: i128,
+    },
+    /// A declaration that has no Soul equivalent, kept to explain why it is missing.
+    Skipped {
+        name: String,
+        reason: String,
+    },
+}
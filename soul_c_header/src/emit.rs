@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+
+use soul_utils::{
+    compile_options::{Os, TargetInfo},
+    soul_names::{KeyWord, PrimitiveTypes},
+};
+
+use crate::declaration::{CDeclaration, CField, CFunctionType, CType, IntegerKind};
+
+/// Typedefs nested deeper than this are assumed to refer to themselves.
+const MAX_TYPEDEF_DEPTH: usize = 64;
+
+/// The Soul type of the typedefs of the standard headers, which are not read.
+pub(crate) fn builtin_typedef(name: &str, target: &TargetInfo) -> Option<&'static str> {
+    Some(match name {
+        "size_t" | "uintptr_t" => "uint",
+        "ssize_t" | "ptrdiff_t" | "intptr_t" => "int",
+        "int8_t" => "i8",
+        "int16_t" => "i16",
+        "int32_t" => "i32",
+        "int64_t" | "intmax_t" | "time_t" => "i64",
+        "uint8_t" => "u8",
+        "uint16_t" => "u16",
+        "uint32_t" => "u32",
+        "uint64_t" | "uintmax_t" => "u64",
+        "char16_t" => "char16",
+        "char32_t" => "u32",
+        "wchar_t" if target.os == Os::Windows => "char16",
+        "wchar_t" => "i32",
+        "va_list" => "*none",
+        _ => return None,
+    })
+}
+
+/// Writes the declarations of a C header as Soul source.
+///
+/// Structs become `#[repr(C)]` structs, functions `extern "C"` declarations and
+/// integer `#define`s `literal`s. Soul enums have no variant values, so only C enums
+/// counting up from 0 become `#[repr(C)]` enums, the others become a `literal` per
+/// constant and `c_int` where used.
+pub(crate) struct SoulEmitter<'a> {
+    target: &'a TargetInfo,
+    typedefs: HashMap<&'a str, &'a CType>,
+    /// Structs with a body by tag, with the name they get in Soul.
+    struct_names: HashMap<&'a str, String>,
+    /// Enums emitted as Soul enums by tag, with the name they get in Soul.
+    enum_names: HashMap<&'a str, String>,
+    /// Structs that can not be emitted with the reason.
+    skipped_structs: HashMap<&'a str, String>,
+    /// Declarations that could not be parsed with the reason.
+    skipped: HashMap<&'a str, &'a str>,
+    out: String,
+}
+impl<'a> SoulEmitter<'a> {
+    pub(crate) fn new(target: &'a TargetInfo, declarations: &'a [CDeclaration]) -> Self {
+        let mut this = Self {
+            target,
+            typedefs: HashMap::new(),
+            struct_names: HashMap::new(),
+            enum_names: HashMap::new(),
+            skipped_structs: HashMap::new(),
+            skipped: HashMap::new(),
+            out: String::new(),
+        };
+
+        for declaration in declarations {
+            match declaration {
+                CDeclaration::Typedef { name, ty } => {
+                    this.typedefs.insert(name, ty);
+                }
+                CDeclaration::Struct { name, is_union, .. } => {
+                    if *is_union {
+                        let reason = format!("union '{name}' is not supported, Soul has no unions");
+                        this.skipped_structs.insert(name, reason);
+                    }
+                    this.struct_names.insert(name, name.clone());
+                }
+                CDeclaration::Enum {
+                    name,
+                    variants,
+                    is_anonymous,
+                } if !is_anonymous && counts_from_zero(variants) => {
+                    this.enum_names.insert(name, name.clone());
+                }
+                CDeclaration::Skipped { name, reason } => {
+                    this.skipped.insert(name, reason);
+                }
+                _ => (),
+            }
+        }
+
+        // `typedef struct Tag_s { ... } Tag;` is emitted as `struct Tag`, enums alike
+        for declaration in declarations {
+            let CDeclaration::Typedef { name, ty } = declaration else {
+                continue;
+            };
+            let names = match ty {
+                CType::Struct(_) => &mut this.struct_names,
+                CType::Enum(_) => &mut this.enum_names,
+                _ => continue,
+            };
+            if let CType::Struct(tag) | CType::Enum(tag) = ty
+                && names.get(tag.as_str()) == Some(tag)
+                && !names.contains_key(name.as_str())
+            {
+                names.insert(tag, name.clone());
+            }
+        }
+
+        this.check_structs(declarations);
+        this
+    }
+
+    pub(crate) fn emit(mut self, declarations: &[CDeclaration], header_name: &str) -> String {
+        self.out.push_str(&format!(
+            "// generated from '{header_name}' by soul_c_header\n"
+        ));
+
+        for declaration in declarations {
+            match declaration {
+                CDeclaration::Constant { name, value } => {
+                    self.out
+                        .push_str(&format!("\npub literal {} = {value}\n", soul_name(name)));
+                }
+                CDeclaration::Enum { name, variants, .. } => self.emit_enum(name, variants),
+                CDeclaration::Struct { name, fields, .. } => self.emit_struct(name, fields),
+                CDeclaration::Function { name, function } => self.emit_function(name, function),
+                CDeclaration::Skipped { name, reason } => self.skip(name, reason),
+                // typedefs are replaced by the type they name
+                CDeclaration::Typedef { .. } => (),
+            }
+        }
+        self.out
+    }
+
+    fn emit_struct(&mut self, name: &str, fields: &[CField]) {
+        if let Some(reason) = self.skipped_structs.get(name).cloned() {
+            return self.skip(name, &reason);
+        }
+
+        let mut text = format!(
+            "\n#[repr(C)]\npub struct {} {{\n",
+            soul_name(&self.struct_names[name])
+        );
+        for field in fields {
+            // checked by `check_structs`
+            let ty = self.soul_type(&field.ty).unwrap_or_default();
+            text.push_str(&format!("    pub mut {}: {ty}\n", soul_name(&field.name)));
+        }
+        text.push_str("}\n");
+        self.out.push_str(&text);
+    }
+
+    fn emit_enum(&mut self, name: &str, variants: &[(String, i128)]) {
+        let Some(soul) = self.enum_names.get(name) else {
+            self.out.push_str(&format!("\n// enum {name}\n"));
+            for (variant, value) in variants {
+                self.out
+                    .push_str(&format!("pub literal {} = {value}\n", soul_name(variant)));
+            }
+            return;
+        };
+
+        let mut text = format!("\n#[repr(C)]\npub enum {} {{\n", soul_name(soul));
+        for (variant, _) in variants {
+            text.push_str(&format!("    {},\n", soul_name(variant)));
+        }
+        text.push_str("}\n");
+        self.out.push_str(&text);
+    }
+
+    fn emit_function(&mut self, name: &str, function: &CFunctionType) {
+        if soul_name(name) != name {
+            return self.skip(
+                name,
+                "the name is a Soul keyword and the symbol can not be renamed",
+            );
+        }
+
+        let mut parameters = vec![];
+        for (i, parameter) in function.parameters.iter().enumerate() {
+            let parameter_name = match &parameter.name {
+                Some(name) => soul_name(name),
+                None => format!("arg{i}"),
+            };
+
+            match self.value_type(&parameter.ty) {
+                Ok(ty) => parameters.push(format!("{parameter_name}: {ty}")),
+                Err(reason) => {
+                    return self.skip(name, &format!("parameter '{parameter_name}': {reason}"));
+                }
+            }
+        }
+        if function.variadic {
+            parameters.push("...".into());
+        }
+
+        let return_type = match &function.return_type {
+            ty if self.resolve(ty) == Some(&CType::Void) => String::new(),
+            ty => match self.value_type(ty) {
+                Ok(ty) => format!(": {ty}"),
+                Err(reason) => return self.skip(name, &format!("return type: {reason}")),
+            },
+        };
+
+        self.out.push_str(&format!(
+            "\npub extern \"C\" {name}({}){return_type}\n",
+            parameters.join(", ")
+        ));
+    }
+
+    fn skip(&mut self, name: &str, reason: &str) {
+        self.out
+            .push_str(&format!("\n// skipped '{name}': {reason}\n"));
+    }
+
+    /// Removes the structs with a field that has no Soul type, and the structs containing them.
+    fn check_structs(&mut self, declarations: &'a [CDeclaration]) {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for declaration in declarations {
+                let CDeclaration::Struct { name, fields, .. } = declaration else {
+                    continue;
+                };
+                if self.skipped_structs.contains_key(name.as_str()) {
+                    continue;
+                }
+
+                let invalid = fields.iter().find_map(|field| {
+                    let reason = self.value_type(&field.ty).err()?;
+                    Some(format!("field '{}': {reason}", field.name))
+                });
+                if let Some(reason) = invalid {
+                    self.skipped_structs.insert(name, reason);
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    /// The Soul type of a parameter, field or return value.
+    fn value_type(&self, ty: &CType) -> Result<String, String> {
+        match self.resolve(ty) {
+            Some(CType::Void) => Err("'void' is not a value".into()),
+            _ => self.soul_type(ty),
+        }
+    }
+
+    fn soul_type(&self, ty: &CType) -> Result<String, String> {
+        let long_is_32_bit = self.target.os == Os::Windows || self.target.ptr_bit_size == 32;
+        let integer = |signed: &str, unsigned: &str, is_unsigned: bool| match is_unsigned {
+            true => unsigned.to_string(),
+            false => signed.to_string(),
+        };
+
+        Ok(match ty {
+            CType::Void => "none".into(),
+            CType::Bool => "bool".into(),
+            CType::Char => "char".into(),
+            CType::Integer { kind, unsigned } => match kind {
+                IntegerKind::Char => integer("i8", "u8", *unsigned),
+                IntegerKind::Short => integer("i16", "u16", *unsigned),
+                IntegerKind::Int => integer("c_int", "c_uint", *unsigned),
+                IntegerKind::Long if long_is_32_bit => integer("i32", "u32", *unsigned),
+                IntegerKind::Long | IntegerKind::LongLong => integer("i64", "u64", *unsigned),
+            },
+            CType::Float => "f32".into(),
+            CType::Double => "f64".into(),
+            CType::LongDouble => return Err("'long double' has no Soul type".into()),
+            CType::Named(name) => match builtin_typedef(name, self.target) {
+                Some(ty) => ty.into(),
+                None => match self.typedefs.get(name.as_str()) {
+                    Some(ty) => self.soul_type(ty)?,
+                    None => match self.skipped.get(name.as_str()) {
+                        Some(reason) => return Err(format!("'{name}' is skipped, {reason}")),
+                        None => return Err(format!("unknown type '{name}'")),
+                    },
+                },
+            },
+            CType::Struct(tag) | CType::Union(tag) => {
+                if let Some(reason) = self.skipped_structs.get(tag.as_str()) {
+                    return Err(format!("'{tag}' is skipped, {reason}"));
+                }
+                match self.struct_names.get(tag.as_str()) {
+                    Some(name) => soul_name(name),
+                    None => {
+                        return Err(format!(
+                            "'{tag}' is only declared, it can only be used behind a pointer"
+                        ));
+                    }
+                }
+            }
+            CType::Enum(tag) => match self.enum_names.get(tag.as_str()) {
+                Some(name) => soul_name(name),
+                // emitted as constants, C enums are `int` sized
+                None => "c_int".into(),
+            },
+            CType::Pointer {
+                pointee,
+                pointee_const,
+            } => match self.resolve(pointee) {
+                Some(CType::Char) if *pointee_const => "c_str".into(),
+                Some(CType::Void | CType::Function(_)) | None => "*none".into(),
+                Some(_) => match self.soul_type(pointee) {
+                    Ok(ty) => format!("*{ty}"),
+                    // opaque structs and the like
+                    Err(_) => "*none".into(),
+                },
+            },
+            CType::Array(element, size) => format!("[{size}]{}", self.value_type(element)?),
+            CType::Function(_) => {
+                return Err("function types can only be used behind a pointer".into());
+            }
+        })
+    }
+
+    /// Follows typedefs to the type they name, `None` for unknown names.
+    fn resolve<'t>(&'t self, mut ty: &'t CType) -> Option<&'t CType> {
+        for _ in 0..MAX_TYPEDEF_DEPTH {
+            let CType::Named(name) = ty else {
+                return Some(ty);
+            };
+            if builtin_typedef(name, self.target).is_some() {
+                return Some(ty);
+            }
+            ty = self.typedefs.get(name.as_str())?;
+        }
+        None
+    }
+}
+
+/// Whether the values of `variants` are `0, 1, 2, ...` like the tags of a Soul enum.
+fn counts_from_zero(variants: &[(String, i128)]) -> bool {
+    variants
+        .iter()
+        .enumerate()
+        .all(|(i, (_, value))| *value == i as i128)
+}
+
+/// Adds a `_` to C names that are Soul keywords or type names.
+fn soul_name(name: &str) -> String {
+    match KeyWord::try_from_str(name).is_some() || PrimitiveTypes::try_from_str(name).is_some() {
+        true => format!("{name}_"),
+        false => name.to_string(),
+    }
+}
@@ -0,0 +1,220 @@
+use soul_utils::error::{SoulError, SoulErrorKind, SoulResult};
+
+/// A token of preprocessed C source.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum CToken {
+    Ident(String),
+    Int(i128),
+    Float(f64),
+    Str(String),
+    Char(i128),
+    Punct(&'static str),
+}
+impl CToken {
+    pub(crate) fn is_punct(&self, punct: &str) -> bool {
+        matches!(self, CToken::Punct(this) if *this == punct)
+    }
+
+    pub(crate) fn is_ident(&self, ident: &str) -> bool {
+        matches!(self, CToken::Ident(this) if this == ident)
+    }
+
+    pub(crate) fn display(&self) -> String {
+        match self {
+            CToken::Ident(ident) => ident.clone(),
+            CToken::Int(value) | CToken::Char(value) => value.to_string(),
+            CToken::Float(value) => value.to_string(),
+            CToken::Str(text) => format!("\"{text}\""),
+            CToken::Punct(punct) => punct.to_string(),
+        }
+    }
+}
+
+/// Longest first so that `<<=` is not lexed as `<` `<=`.
+const PUNCTUATORS: &[&str] = &[
+    "...", "<<=", ">>=", "->", "++", "--", "<<", ">>", "<=", ">=", "==", "!=", "&&", "||", "+=",
+    "-=", "*=", "/=", "%=", "&=", "|=", "^=", "##", "(", ")", "[", "]", "{", "}", ";", ",", ".",
+    "+", "-", "*", "/", "%", "&", "|", "^", "!", "~", "<", ">", "=", "?", ":", "#",
+];
+
+/// Splits comment free C source into tokens.
+pub(crate) fn lex(source: &str) -> SoulResult<Vec<CToken>> {
+    let chars = source.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        let char = chars[i];
+        if char.is_whitespace() {
+            i += 1;
+        } else if is_ident_start(char) {
+            let start = i;
+            while i < chars.len() && is_ident_continue(chars[i]) {
+                i += 1;
+            }
+
+            let ident = chars[start..i].iter().collect::<String>();
+            // `L"text"` and `u8"text"` prefixes
+            if i < chars.len() && matches!(chars[i], '"' | '\'') && is_literal_prefix(&ident) {
+                continue;
+            }
+            tokens.push(CToken::Ident(ident));
+        } else if char.is_ascii_digit()
+            || (char == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit))
+        {
+            let start = i;
+            while i < chars.len() && is_number_continue(&chars, i) {
+                i += 1;
+            }
+            let text = chars[start..i].iter().collect::<String>();
+            tokens.push(lex_number(&text)?);
+        } else if char == '"' {
+            let (text, end) = lex_quoted(&chars, i, '"')?;
+            tokens.push(CToken::Str(text));
+            i = end;
+        } else if char == '\'' {
+            let (text, end) = lex_quoted(&chars, i, '\'')?;
+            tokens.push(CToken::Char(char_value(&text)?));
+            i = end;
+        } else {
+            let punct = PUNCTUATORS
+                .iter()
+                .find(|punct| {
+                    punct
+                        .chars()
+                        .enumerate()
+                        .all(|(j, c)| chars.get(i + j) == Some(&c))
+                })
+                .ok_or_else(|| {
+                    SoulError::new(
+                        format!("unexpected character '{char}' in C header"),
+                        SoulErrorKind::UnexpectedCharacter,
+                        None,
+                    )
+                })?;
+            tokens.push(CToken::Punct(punct));
+            i += punct.len();
+        }
+    }
+
+    Ok(tokens)
+}
+
+pub(crate) fn is_ident_start(char: char) -> bool {
+    char.is_ascii_alphabetic() || char == '_' || char == '$'
+}
+
+pub(crate) fn is_ident_continue(char: char) -> bool {
+    char.is_ascii_alphanumeric() || char == '_' || char == '$'
+}
+
+fn is_literal_prefix(ident: &str) -> bool {
+    matches!(ident, "L" | "u" | "U" | "u8")
+}
+
+fn is_number_continue(chars: &[char], i: usize) -> bool {
+    let char = chars[i];
+    if char.is_ascii_alphanumeric() || char == '.' || char == '_' {
+        return true;
+    }
+
+    // exponent sign, `1e-3` but not the hex digit in `0xe-1`
+    let is_hex = chars[..i]
+        .iter()
+        .rev()
+        .take_while(|c| c.is_ascii_alphanumeric() || **c == '.')
+        .any(|c| matches!(c, 'x' | 'X'));
+    matches!(char, '+' | '-') && !is_hex && i > 0 && matches!(chars[i - 1], 'e' | 'E')
+}
+
+fn lex_number(text: &str) -> SoulResult<CToken> {
+    let invalid = || {
+        SoulError::new(
+            format!("invalid number '{text}' in C header"),
+            SoulErrorKind::InvalidNumber,
+            None,
+        )
+    };
+
+    let lower = text.to_ascii_lowercase();
+    let (digits, radix) = if let Some(hex) = lower.strip_prefix("0x") {
+        (hex, 16)
+    } else if let Some(binary) = lower.strip_prefix("0b") {
+        (binary, 2)
+    } else if lower.contains(['.', 'e']) {
+        let float = lower.trim_end_matches(['f', 'l']);
+        return float.parse().map(CToken::Float).map_err(|_| invalid());
+    } else if lower.len() > 1 && lower.starts_with('0') {
+        (&lower[1..], 8)
+    } else {
+        (lower.as_str(), 10)
+    };
+
+    let digits = digits.trim_end_matches(['u', 'l']);
+    i128::from_str_radix(digits, radix)
+        .map(CToken::Int)
+        .map_err(|_| invalid())
+}
+
+/// Returns the text between the quotes, escapes left as written, and the index after the closing quote.
+fn lex_quoted(chars: &[char], start: usize, quote: char) -> SoulResult<(String, usize)> {
+    let mut i = start + 1;
+    let mut text = String::new();
+    while i < chars.len() && chars[i] != quote {
+        if chars[i] == '\\' && i + 1 < chars.len() {
+            text.push(chars[i]);
+            i += 1;
+        }
+        text.push(chars[i]);
+        i += 1;
+    }
+
+    if i >= chars.len() {
+        return Err(SoulError::new(
+            format!("unterminated {quote} literal in C header"),
+            SoulErrorKind::UnexpecedFileEnd,
+            None,
+        ));
+    }
+    Ok((text, i + 1))
+}
+
+fn char_value(text: &str) -> SoulResult<i128> {
+    let invalid = || {
+        SoulError::new(
+            format!("invalid character literal '{text}' in C header"),
+            SoulErrorKind::InvalidEscapeSequence,
+            None,
+        )
+    };
+
+    let mut chars = text.chars();
+    let value = match chars.next().ok_or_else(invalid)? {
+        '\\' => match chars.next().ok_or_else(invalid)? {
+            'n' => '\n' as i128,
+            't' => '\t' as i128,
+            'r' => '\r' as i128,
+            'a' => 0x07,
+            'b' => 0x08,
+            'f' => 0x0c,
+            'v' => 0x0b,
+            'x' => {
+                let hex = chars.by_ref().collect::<String>();
+                return i128::from_str_radix(&hex, 16).map_err(|_| invalid());
+            }
+            digit @ '0'..='7' => {
+                let octal = std::iter::once(digit)
+                    .chain(chars.by_ref())
+                    .collect::<String>();
+                return i128::from_str_radix(&octal, 8).map_err(|_| invalid());
+            }
+            other => other as i128,
+        },
+        char => char as i128,
+    };
+
+    match chars.next() {
+        None => Ok(value),
+        Some(_) => Err(invalid()),
+    }
+}
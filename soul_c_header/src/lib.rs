@@ -0,0 +1,52 @@
+use std::path::Path;
+
+use soul_utils::{compile_options::TargetInfo, error::SoulResult};
+
+use crate::{
+    declaration::CDeclaration, emit::SoulEmitter, lexer::lex, parser::CParser,
+    preprocess::Preprocessor,
+};
+
+mod const_eval;
+mod declaration;
+mod emit;
+mod lexer;
+mod parser;
+mod preprocess;
+
+#[cfg(test)]
+mod tests;
+
+/// Reads the C header at `path` and returns Soul source declaring its functions,
+/// structs, enums and integer `#define`s, for `target`.
+pub fn import_c_header(path: &Path, target: &TargetInfo) -> SoulResult<String> {
+    let mut preprocessor = Preprocessor::new(target);
+    preprocessor.process_file(path)?;
+    to_soul(&preprocessor, path, target)
+}
+
+/// Like [`import_c_header`] for header source that is already read,
+/// `path` is used to resolve `#include "..."`.
+pub fn import_c_source(source: &str, path: &Path, target: &TargetInfo) -> SoulResult<String> {
+    let mut preprocessor = Preprocessor::new(target);
+    preprocessor.process_source(source, path)?;
+    to_soul(&preprocessor, path, target)
+}
+
+fn to_soul(preprocessor: &Preprocessor, path: &Path, target: &TargetInfo) -> SoulResult<String> {
+    let tokens = lex(preprocessor.output())?;
+    let is_builtin_type = |name: &str| emit::builtin_typedef(name, target).is_some();
+
+    let mut declarations = preprocessor
+        .constants()
+        .into_iter()
+        .map(|(name, value)| CDeclaration::Constant { name, value })
+        .collect::<Vec<_>>();
+    declarations.extend(CParser::new(&tokens, &is_builtin_type).parse());
+
+    let header_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    Ok(SoulEmitter::new(target, &declarations).emit(&declarations, &header_name))
+}
@@ -0,0 +1,72 @@
+use std::{
+    env::consts::{ARCH, OS},
+    fs,
+    path::Path,
+    process::ExitCode,
+};
+
+use soul_c_header::import_c_header;
+use soul_utils::compile_options::{Arch, Os, TargetInfo};
+
+const USAGE: &str = "usage: soul_c_header <header.h> [out.soul] [--os <linux|windows|macos>] [--arch <x86_64|x86|aarch64|armv7|riscv64>]
+--os and --arch default to the os and arch soul_c_header runs on";
+
+fn main() -> ExitCode {
+    match run(std::env::args().skip(1).collect()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(arguments: Vec<String>) -> Result<(), String> {
+    let mut os = Os::try_from_str(OS);
+    // Rust calls every 32-bit ARM `arm`
+    let mut arch = Arch::try_from_str(if ARCH == "arm" { "armv7" } else { ARCH });
+    let mut files = vec![];
+
+    let mut arguments = arguments.into_iter();
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "--os" => {
+                let value = arguments.next().ok_or(USAGE)?;
+                os =
+                    Some(Os::try_from_str(&value).ok_or(format!("unknown os '{value}'\n{USAGE}"))?);
+            }
+            "--arch" => {
+                let value = arguments.next().ok_or(USAGE)?;
+                arch = Some(
+                    Arch::try_from_str(&value).ok_or(format!("unknown arch '{value}'\n{USAGE}"))?,
+                );
+            }
+            _ => files.push(argument),
+        }
+    }
+
+    let (header, output) = match files.as_slice() {
+        [header] => (header, None),
+        [header, output] => (header, Some(output)),
+        _ => return Err(USAGE.into()),
+    };
+    let os = os.ok_or(format!(
+        "can not target the host os '{OS}', pass --os\n{USAGE}"
+    ))?;
+    let arch = arch.ok_or(format!(
+        "can not target the host arch '{ARCH}', pass --arch\n{USAGE}"
+    ))?;
+
+    let source = import_c_header(Path::new(header), &TargetInfo::new(arch, os))
+        .map_err(|err| format!("{}: {}", err.kind.display(), err.message))?;
+
+    match output {
+        Some(output) => {
+            fs::write(output, source).map_err(|err| format!("could not write '{output}': {err}"))
+        }
+        None => {
+            print!("{source}");
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,768 @@
+use std::collections::{HashMap, HashSet};
+
+use soul_utils::error::{SoulError, SoulErrorKind, SoulResult};
+
+use crate::{
+    const_eval::const_eval,
+    declaration::{CDeclaration, CField, CFunctionType, CParameter, CType, IntegerKind},
+    lexer::CToken,
+};
+
+/// Keywords and compiler extensions that do not change the declared type.
+const IGNORED_WORDS: &[&str] = &[
+    "inline",
+    "__inline",
+    "__inline__",
+    "__forceinline",
+    "_Noreturn",
+    "__extension__",
+    "register",
+    "auto",
+    "_Thread_local",
+    "volatile",
+    "__volatile__",
+    "restrict",
+    "__restrict",
+    "__restrict__",
+    "__cdecl",
+    "__stdcall",
+    "__fastcall",
+    "__vectorcall",
+    "__ptr32",
+    "__ptr64",
+    "__unaligned",
+];
+
+/// Compiler extensions followed by parenthesized arguments that do not change the declared type.
+const IGNORED_CALLS: &[&str] = &[
+    "__attribute__",
+    "__attribute",
+    "__declspec",
+    "_Alignas",
+    "alignas",
+    "__asm__",
+    "__asm",
+    "asm",
+];
+
+const TYPE_KEYWORDS: &[&str] = &[
+    "void", "char", "short", "int", "long", "signed", "unsigned", "float", "double", "_Bool",
+    "bool", "struct", "union", "enum", "const",
+];
+
+struct Specifiers {
+    ty: CType,
+    is_const: bool,
+    is_typedef: bool,
+    is_static: bool,
+    /// The index of the struct, union or enum without a tag these specifiers define,
+    /// it is named after the typedef or field that uses it.
+    anonymous: Option<usize>,
+}
+
+enum Suffix {
+    Array(Option<u64>),
+    Function(Vec<CParameter>, bool),
+}
+
+/// Parses the declarations of preprocessed C source.
+///
+/// Declarations that can not be parsed, like bit fields, do not fail the header,
+/// they are kept as [`CDeclaration::Skipped`] with the reason.
+pub(crate) struct CParser<'a> {
+    tokens: &'a [CToken],
+    position: usize,
+    is_builtin_type: &'a dyn Fn(&str) -> bool,
+    typedefs: HashSet<String>,
+    enum_constants: HashMap<String, i128>,
+    anonymous_count: usize,
+    declarations: Vec<CDeclaration>,
+}
+impl<'a> CParser<'a> {
+    pub(crate) fn new(tokens: &'a [CToken], is_builtin_type: &'a dyn Fn(&str) -> bool) -> Self {
+        Self {
+            tokens,
+            position: 0,
+            is_builtin_type,
+            typedefs: HashSet::new(),
+            enum_constants: HashMap::new(),
+            anonymous_count: 0,
+            declarations: vec![],
+        }
+    }
+
+    pub(crate) fn parse(mut self) -> Vec<CDeclaration> {
+        let mut extern_blocks = 0usize;
+        while self.position < self.tokens.len() {
+            if self.eat(";") {
+                continue;
+            }
+
+            // `extern "C" { ... }` of headers that are also included from C++
+            if self.current_is_ident("extern")
+                && matches!(self.peek(1), Some(CToken::Str(_)))
+                && self.peek(2).is_some_and(|token| token.is_punct("{"))
+            {
+                self.position += 3;
+                extern_blocks += 1;
+                continue;
+            }
+            if extern_blocks > 0 && self.eat("}") {
+                extern_blocks -= 1;
+                continue;
+            }
+
+            let start = self.position;
+            if let Err(err) = self.declaration() {
+                self.position = start;
+                let name = self.skip_declaration();
+                self.declarations.push(CDeclaration::Skipped {
+                    name,
+                    reason: err.message,
+                });
+            }
+        }
+        self.declarations
+    }
+
+    fn declaration(&mut self) -> SoulResult<()> {
+        let specifiers = self.specifiers()?;
+        if self.eat(";") {
+            return Ok(());
+        }
+
+        loop {
+            let (name, ty) = self.declarator(specifiers.ty.clone(), specifiers.is_const, false)?;
+            let name = name.ok_or_else(|| self.error("expected a name in declaration"))?;
+            self.skip_ignored();
+
+            if specifiers.is_typedef {
+                self.typedef(name, ty, &specifiers);
+            } else if let CType::Function(function) = ty {
+                let has_body = self.current().is_some_and(|token| token.is_punct("{"));
+                if has_body {
+                    self.skip_balanced()?;
+                }
+
+                let declaration = match specifiers.is_static {
+                    true => CDeclaration::Skipped {
+                        name,
+                        reason: "static functions are not exported".into(),
+                    },
+                    false => CDeclaration::Function {
+                        name,
+                        function: *function,
+                    },
+                };
+                self.declarations.push(declaration);
+                if has_body {
+                    return Ok(());
+                }
+            } else {
+                self.declarations.push(CDeclaration::Skipped {
+                    name,
+                    reason: "global variables are not supported, only functions".into(),
+                });
+                if self.eat("=") {
+                    self.skip_initializer();
+                }
+            }
+
+            if self.eat(",") {
+                continue;
+            }
+            return self.expect(";");
+        }
+    }
+
+    fn typedef(&mut self, name: String, ty: CType, specifiers: &Specifiers) {
+        self.typedefs.insert(name.clone());
+        let ty = match specifiers.anonymous {
+            // `typedef struct { ... } Name;` names the struct itself
+            Some(index) if ty == specifiers.ty => self.name_anonymous(index, name.clone()),
+            _ => ty,
+        };
+        self.declarations.push(CDeclaration::Typedef { name, ty });
+    }
+
+    fn specifiers(&mut self) -> SoulResult<Specifiers> {
+        let mut specifiers = Specifiers {
+            ty: CType::Void,
+            is_const: false,
+            is_typedef: false,
+            is_static: false,
+            anonymous: None,
+        };
+
+        let mut named: Option<CType> = None;
+        let mut keywords: Vec<String> = vec![];
+        loop {
+            self.skip_ignored();
+            let Some(CToken::Ident(ident)) = self.current().cloned() else {
+                break;
+            };
+
+            match ident.as_str() {
+                "typedef" => specifiers.is_typedef = true,
+                "static" => specifiers.is_static = true,
+                "extern" => {
+                    // `extern "C" int f(void);`
+                    if matches!(self.peek(1), Some(CToken::Str(_))) {
+                        self.position += 1;
+                    }
+                }
+                "const" | "__const" => specifiers.is_const = true,
+                "struct" | "union" | "enum" => {
+                    let (ty, anonymous) = self.tagged()?;
+                    named = Some(ty);
+                    specifiers.anonymous = anonymous;
+                    continue;
+                }
+                "void" | "char" | "short" | "int" | "long" | "signed" | "unsigned" | "float"
+                | "double" | "_Bool" | "bool" => {
+                    // an unknown macro before the type, like `API int f(void);`
+                    if matches!(named, Some(CType::Named(_))) {
+                        named = None;
+                    }
+                    keywords.push(ident);
+                }
+                _ if named.is_none() && keywords.is_empty() => {
+                    named = Some(CType::Named(ident));
+                }
+                _ if matches!(&named, Some(CType::Named(unknown)) if !self.is_type_name(unknown))
+                    && self.is_type_name(&ident) =>
+                {
+                    named = Some(CType::Named(ident));
+                }
+                _ => break,
+            }
+            self.position += 1;
+        }
+
+        specifiers.ty = match named {
+            Some(ty) => ty,
+            None => self.keyword_type(&keywords)?,
+        };
+        Ok(specifiers)
+    }
+
+    fn keyword_type(&self, keywords: &[String]) -> SoulResult<CType> {
+        let has = |keyword: &str| keywords.iter().any(|word| word == keyword);
+        let longs = keywords.iter().filter(|word| *word == "long").count();
+        let unsigned = has("unsigned");
+
+        let integer = |kind| CType::Integer { kind, unsigned };
+        Ok(match () {
+            _ if keywords.is_empty() => return Err(self.error("expected a type")),
+            _ if has("void") => CType::Void,
+            _ if has("_Bool") || has("bool") => CType::Bool,
+            _ if has("float") => CType::Float,
+            _ if has("double") && longs > 0 => CType::LongDouble,
+            _ if has("double") => CType::Double,
+            _ if has("char") && !unsigned && !has("signed") => CType::Char,
+            _ if has("char") => integer(IntegerKind::Char),
+            _ if has("short") => integer(IntegerKind::Short),
+            _ if longs == 1 => integer(IntegerKind::Long),
+            _ if longs >= 2 => integer(IntegerKind::LongLong),
+            _ => integer(IntegerKind::Int),
+        })
+    }
+
+    /// Parses `struct Tag`, `enum { A, B }` and the like.
+    fn tagged(&mut self) -> SoulResult<(CType, Option<usize>)> {
+        let keyword = self.expect_ident()?;
+        self.skip_ignored();
+
+        let tag = match self.current() {
+            Some(CToken::Ident(tag)) => {
+                let tag = tag.clone();
+                self.position += 1;
+                Some(tag)
+            }
+            _ => None,
+        };
+        self.skip_ignored();
+
+        // `enum Name : int { ... }`
+        if keyword == "enum" && self.eat(":") {
+            self.specifiers()?;
+        }
+
+        let to_type = |name: String| match keyword.as_str() {
+            "struct" => CType::Struct(name),
+            "union" => CType::Union(name),
+            _ => CType::Enum(name),
+        };
+
+        if !self.current().is_some_and(|token| token.is_punct("{")) {
+            let tag =
+                tag.ok_or_else(|| self.error(format!("expected a name after '{keyword}'")))?;
+            return Ok((to_type(tag), None));
+        }
+
+        let is_anonymous = tag.is_none();
+        let name = tag.unwrap_or_else(|| {
+            self.anonymous_count += 1;
+            format!("anonymous{}", self.anonymous_count)
+        });
+
+        let declaration = match keyword.as_str() {
+            "enum" => CDeclaration::Enum {
+                name: name.clone(),
+                variants: self.enum_body()?,
+                is_anonymous,
+            },
+            _ => CDeclaration::Struct {
+                name: name.clone(),
+                fields: self.struct_body(&name)?,
+                is_union: keyword == "union",
+            },
+        };
+
+        self.declarations.push(declaration);
+        let anonymous = is_anonymous.then_some(self.declarations.len() - 1);
+        Ok((to_type(name), anonymous))
+    }
+
+    fn struct_body(&mut self, struct_name: &str) -> SoulResult<Vec<CField>> {
+        self.expect("{")?;
+
+        let mut fields = vec![];
+        while !self.eat("}") {
+            if self.eat(";") {
+                continue;
+            }
+
+            let specifiers = self.specifiers()?;
+            if self.current().is_some_and(|token| token.is_punct(";")) {
+                return Err(unsupported(format!(
+                    "anonymous struct or union members in '{struct_name}' are not supported"
+                )));
+            }
+
+            loop {
+                let (name, mut ty) =
+                    self.declarator(specifiers.ty.clone(), specifiers.is_const, false)?;
+                let name = name.ok_or_else(|| self.error("expected a field name"))?;
+                if self.current().is_some_and(|token| token.is_punct(":")) {
+                    return Err(unsupported(format!(
+                        "bit field '{name}' in '{struct_name}' is not supported"
+                    )));
+                }
+
+                if let Some(index) = specifiers.anonymous
+                    && ty == specifiers.ty
+                {
+                    ty = self.name_anonymous(index, format!("{struct_name}_{name}"));
+                }
+
+                fields.push(CField { name, ty });
+                self.skip_ignored();
+                if self.eat(",") {
+                    continue;
+                }
+                self.expect(";")?;
+                break;
+            }
+        }
+        Ok(fields)
+    }
+
+    fn enum_body(&mut self) -> SoulResult<Vec<(String, i128)>> {
+        self.expect("{")?;
+
+        let mut variants = vec![];
+        let mut next = 0;
+        while !self.eat("}") {
+            let name = self.expect_ident()?;
+            self.skip_ignored();
+
+            let value = match self.eat("=") {
+                true => {
+                    let start = self.position;
+                    self.skip_initializer();
+                    let constants = &self.enum_constants;
+                    const_eval(&self.tokens[start..self.position], &|name| {
+                        constants.get(name).copied()
+                    })
+                    .ok_or_else(|| {
+                        self.error(format!(
+                            "can not evaluate the value of enum constant '{name}'"
+                        ))
+                    })?
+                }
+                false => next,
+            };
+
+            self.enum_constants.insert(name.clone(), value);
+            variants.push((name, value));
+            next = value + 1;
+
+            if !self.eat(",") {
+                self.expect("}")?;
+                break;
+            }
+        }
+        Ok(variants)
+    }
+
+    /// Parses a declarator like `*name`, `name[4]` or `(*name)(int)`, returns
+    /// the declared name, `None` for abstract declarators, and the type of it.
+    fn declarator(
+        &mut self,
+        base: CType,
+        base_const: bool,
+        is_parameter: bool,
+    ) -> SoulResult<(Option<String>, CType)> {
+        let mut ty = base;
+        let mut pointee_const = base_const;
+        loop {
+            self.skip_ignored();
+            if self.eat("*") {
+                ty = CType::Pointer {
+                    pointee: Box::new(ty),
+                    pointee_const,
+                };
+                pointee_const = false;
+            } else if self.current_is_ident("const") {
+                // `* const` makes the pointer itself const, which the next pointer points to
+                self.position += 1;
+                pointee_const = true;
+            } else {
+                break;
+            }
+        }
+
+        let mut name = None;
+        let mut nested = None;
+        match self.current() {
+            Some(CToken::Ident(ident)) if !self.is_type_name(ident) => {
+                name = Some(ident.clone());
+                self.position += 1;
+            }
+            Some(token) if token.is_punct("(") && self.is_nested_declarator() => {
+                let start = self.position + 1;
+                self.skip_balanced()?;
+                nested = Some((start, self.position - 1));
+            }
+            _ => (),
+        }
+        self.skip_ignored();
+
+        let mut suffixes = vec![];
+        loop {
+            if self.eat("[") {
+                let start = self.position;
+                while !self.current().is_none_or(|token| token.is_punct("]")) {
+                    self.position += 1;
+                }
+                let tokens = self.tokens;
+                let size_tokens = &tokens[start..self.position];
+                self.expect("]")?;
+
+                let size = match size_tokens.is_empty() {
+                    true => None,
+                    false => Some(
+                        const_eval(size_tokens, &|name| self.enum_constants.get(name).copied())
+                            .and_then(|size| u64::try_from(size).ok())
+                            .ok_or_else(|| self.error("can not evaluate the size of an array"))?,
+                    ),
+                };
+                suffixes.push(Suffix::Array(size));
+            } else if self.current().is_some_and(|token| token.is_punct("(")) {
+                let (parameters, variadic) = self.parameters()?;
+                suffixes.push(Suffix::Function(parameters, variadic));
+            } else {
+                break;
+            }
+        }
+
+        for suffix in suffixes.into_iter().rev() {
+            ty = match suffix {
+                Suffix::Array(Some(size)) => CType::Array(Box::new(ty), size),
+                Suffix::Array(None) if is_parameter => CType::Pointer {
+                    pointee: Box::new(ty),
+                    pointee_const: false,
+                },
+                Suffix::Array(None) => {
+                    return Err(unsupported("arrays without a size are not supported"));
+                }
+                Suffix::Function(parameters, variadic) => {
+                    CType::Function(Box::new(CFunctionType {
+                        return_type: ty,
+                        parameters,
+                        variadic,
+                    }))
+                }
+            };
+        }
+
+        let Some((start, end)) = nested else {
+            return Ok((name, ty));
+        };
+
+        // `(*name)(int)`, the nested declarator applies to the type declared around it
+        let after = self.position;
+        self.position = start;
+        let declared = self.declarator(ty, false, is_parameter)?;
+        if self.position != end {
+            return Err(self.error("unexpected token in declarator"));
+        }
+        self.position = after;
+        Ok(declared)
+    }
+
+    fn parameters(&mut self) -> SoulResult<(Vec<CParameter>, bool)> {
+        self.expect("(")?;
+        if self.eat(")") {
+            return Ok((vec![], false));
+        }
+        if self.current_is_ident("void") && self.peek(1).is_some_and(|token| token.is_punct(")")) {
+            self.position += 2;
+            return Ok((vec![], false));
+        }
+
+        let mut parameters = vec![];
+        loop {
+            if self.eat("...") {
+                self.expect(")")?;
+                return Ok((parameters, true));
+            }
+
+            let specifiers = self.specifiers()?;
+            let (name, ty) = self.declarator(specifiers.ty, specifiers.is_const, true)?;
+            let ty = match ty {
+                CType::Array(element, _) => CType::Pointer {
+                    pointee: element,
+                    pointee_const: specifiers.is_const,
+                },
+                CType::Function(_) => CType::Pointer {
+                    pointee: Box::new(ty),
+                    pointee_const: false,
+                },
+                ty => ty,
+            };
+            parameters.push(CParameter { name, ty });
+
+            if self.eat(",") {
+                continue;
+            }
+            self.expect(")")?;
+            return Ok((parameters, false));
+        }
+    }
+
+    /// Renames the tagless declaration at `index`, returns the type that refers to it.
+    ///
+    /// Tagless structs declared in its fields were named after it, they are renamed too.
+    fn name_anonymous(&mut self, index: usize, new_name: String) -> CType {
+        let (old_name, ty) = match &mut self.declarations[index] {
+            CDeclaration::Struct { name, is_union, .. } => {
+                let ty = match is_union {
+                    true => CType::Union(new_name.clone()),
+                    false => CType::Struct(new_name.clone()),
+                };
+                (std::mem::replace(name, new_name.clone()), ty)
+            }
+            CDeclaration::Enum {
+                name, is_anonymous, ..
+            } => {
+                *is_anonymous = false;
+                (
+                    std::mem::replace(name, new_name.clone()),
+                    CType::Enum(new_name.clone()),
+                )
+            }
+            _ => return CType::Named(new_name),
+        };
+
+        let prefix = format!("{old_name}_");
+        let rename = |name: &mut String| {
+            if let Some(rest) = name.strip_prefix(&prefix) {
+                *name = format!("{new_name}_{rest}");
+            }
+        };
+        for declaration in &mut self.declarations {
+            match declaration {
+                CDeclaration::Struct { name, fields, .. } => {
+                    rename(name);
+                    for field in fields {
+                        if let CType::Struct(name) | CType::Union(name) | CType::Enum(name) =
+                            &mut field.ty
+                        {
+                            rename(name);
+                        }
+                    }
+                }
+                CDeclaration::Enum { name, .. } => rename(name),
+                _ => (),
+            }
+        }
+        ty
+    }
+
+    /// Skips to the end of the current declaration, returns the name it probably declares.
+    fn skip_declaration(&mut self) -> String {
+        let is_typedef = self.current_is_ident("typedef");
+        let mut name = String::new();
+        let mut depth = 0usize;
+        let mut name_done = false;
+        while let Some(token) = self.current().cloned() {
+            self.position += 1;
+            match token {
+                CToken::Ident(ident) if depth == 0 && !name_done => name = ident,
+                CToken::Punct("(" | "[" | "{") => {
+                    // a function body ends the declaration without `;`
+                    let is_body = token.is_punct("{")
+                        && depth == 0
+                        && self.position >= 2
+                        && self.tokens[self.position - 2].is_punct(")");
+                    if is_body {
+                        let _ = self.skip_balanced_from(self.position - 1);
+                        break;
+                    }
+                    name_done |= depth == 0 && token.is_punct("(");
+                    depth += 1;
+                }
+                CToken::Punct(")" | "]" | "}") => depth = depth.saturating_sub(1),
+                CToken::Punct("=" | ":") if depth == 0 => name_done = true,
+                CToken::Punct(";") if depth == 0 => break,
+                _ => (),
+            }
+        }
+
+        if is_typedef && !name.is_empty() {
+            self.typedefs.insert(name.clone());
+        }
+        name
+    }
+
+    fn skip_initializer(&mut self) {
+        let mut depth = 0usize;
+        while let Some(token) = self.current() {
+            match token {
+                CToken::Punct("(" | "[" | "{") => depth += 1,
+                CToken::Punct(")" | "]" | "}") if depth > 0 => depth -= 1,
+                CToken::Punct("," | ";" | "}") if depth == 0 => return,
+                _ => (),
+            }
+            self.position += 1;
+        }
+    }
+
+    fn skip_balanced(&mut self) -> SoulResult<()> {
+        self.skip_balanced_from(self.position)
+    }
+
+    /// Skips the bracketed tokens starting at the opening bracket at `start`.
+    fn skip_balanced_from(&mut self, start: usize) -> SoulResult<()> {
+        self.position = start;
+        let mut depth = 0usize;
+        while let Some(token) = self.current() {
+            self.position += 1;
+            match token {
+                CToken::Punct("(" | "[" | "{") => depth += 1,
+                CToken::Punct(")" | "]" | "}") => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                _ => (),
+            }
+        }
+        Err(SoulError::new(
+            "unclosed bracket in C header",
+            SoulErrorKind::UnexpecedFileEnd,
+            None,
+        ))
+    }
+
+    fn skip_ignored(&mut self) {
+        while let Some(CToken::Ident(ident)) = self.current() {
+            if IGNORED_WORDS.contains(&ident.as_str()) {
+                self.position += 1;
+            } else if IGNORED_CALLS.contains(&ident.as_str()) {
+                self.position += 1;
+                if self.current().is_some_and(|token| token.is_punct("(")) {
+                    let _ = self.skip_balanced();
+                }
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn is_nested_declarator(&self) -> bool {
+        match self.peek(1) {
+            Some(CToken::Punct("*" | "^" | "(" | "[")) => true,
+            Some(CToken::Ident(ident)) => {
+                !self.is_type_name(ident)
+                    && !IGNORED_WORDS.contains(&ident.as_str())
+                    && !IGNORED_CALLS.contains(&ident.as_str())
+                    || matches!(ident.as_str(), "__cdecl" | "__stdcall" | "__fastcall")
+            }
+            _ => false,
+        }
+    }
+
+    fn is_type_name(&self, name: &str) -> bool {
+        TYPE_KEYWORDS.contains(&name)
+            || self.typedefs.contains(name)
+            || (self.is_builtin_type)(name)
+    }
+
+    fn current(&self) -> Option<&'a CToken> {
+        self.tokens.get(self.position)
+    }
+
+    fn peek(&self, offset: usize) -> Option<&'a CToken> {
+        self.tokens.get(self.position + offset)
+    }
+
+    fn current_is_ident(&self, ident: &str) -> bool {
+        self.current().is_some_and(|token| token.is_ident(ident))
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        let found = self.current().is_some_and(|token| token.is_punct(punct));
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, punct: &str) -> SoulResult<()> {
+        match self.eat(punct) {
+            true => Ok(()),
+            false => Err(self.error(format!("expected '{punct}'"))),
+        }
+    }
+
+    fn expect_ident(&mut self) -> SoulResult<String> {
+        match self.current() {
+            Some(CToken::Ident(ident)) => {
+                let ident = ident.clone();
+                self.position += 1;
+                Ok(ident)
+            }
+            _ => Err(self.error("expected an identifier")),
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> SoulError {
+        let found = match self.current() {
+            Some(token) => token.display(),
+            None => "end of file".into(),
+        };
+        SoulError::new(
+            format!("{} but found '{found}'", message.into()),
+            SoulErrorKind::InvalidTokenKind,
+            None,
+        )
+    }
+}
+
+fn unsupported(message: impl Into<String>) -> SoulError {
+    SoulError::new(message, SoulErrorKind::InvalidType, None)
+}
@@ -0,0 +1,645 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use soul_utils::{
+    compile_options::{Arch, Os, TargetInfo},
+    error::{SoulError, SoulErrorKind, SoulResult},
+};
+
+use crate::{
+    const_eval::const_eval,
+    lexer::{is_ident_continue, is_ident_start, lex},
+};
+
+/// Nested `#include`s deeper than this are assumed to be an include cycle.
+const MAX_INCLUDE_DEPTH: usize = 64;
+/// Stops macros that expand to each other through arguments from expanding forever.
+const MAX_EXPANSION_DEPTH: usize = 256;
+
+#[derive(Debug, Clone)]
+struct Macro {
+    /// `None` for object like macros, the parameters of function like macros.
+    parameters: Option<Vec<String>>,
+    body: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Condition {
+    /// Whether the lines in the current branch are kept.
+    active: bool,
+    /// Whether one of the branches was already taken.
+    taken: bool,
+    /// Whether the enclosing branch is kept.
+    parent_active: bool,
+}
+
+/// The subset of the C preprocessor headers need to be read as declarations.
+///
+/// Supports `#include "..."` (system `<...>` includes are skipped), `#define`/`#undef`
+/// of object and function like macros, `#if`/`#ifdef`/`#ifndef`/`#elif`/`#else`/`#endif`
+/// and `#pragma once`. The target decides which platform macros are predefined.
+pub(crate) struct Preprocessor {
+    macros: HashMap<String, Macro>,
+    /// Object like macros defined by the headers, in definition order.
+    defined: Vec<String>,
+    include_once: HashSet<PathBuf>,
+    include_depth: usize,
+    output: String,
+}
+impl Preprocessor {
+    pub(crate) fn new(target: &TargetInfo) -> Self {
+        let mut this = Self {
+            macros: HashMap::new(),
+            defined: vec![],
+            include_once: HashSet::new(),
+            include_depth: 0,
+            output: String::new(),
+        };
+        this.predefine(target);
+        this
+    }
+
+    /// The preprocessed source of everything processed so far.
+    pub(crate) fn output(&self) -> &str {
+        &self.output
+    }
+
+    pub(crate) fn process_file(&mut self, path: &Path) -> SoulResult<()> {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        if self.include_once.contains(&path) {
+            return Ok(());
+        }
+
+        let source = fs::read_to_string(&path).map_err(|err| {
+            SoulError::new(
+                format!("could not read C header '{}': {err}", path.display()),
+                SoulErrorKind::PathNotFound,
+                None,
+            )
+        })?;
+        self.process_source(&source, &path)
+    }
+
+    pub(crate) fn process_source(&mut self, source: &str, path: &Path) -> SoulResult<()> {
+        let directory = path.parent().unwrap_or(Path::new("."));
+        let source = strip_comments(&source.replace("\\\r\n", "").replace("\\\n", ""));
+
+        let mut conditions: Vec<Condition> = vec![];
+        let mut pending = String::new();
+        for (line_index, line) in source.lines().enumerate() {
+            let is_active = conditions.last().is_none_or(|condition| condition.active);
+            let Some(directive) = line.trim_start().strip_prefix('#') else {
+                if is_active {
+                    pending.push_str(line);
+                    pending.push('\n');
+                }
+                continue;
+            };
+
+            self.flush(&mut pending);
+            let directive = directive.trim();
+            let name_end = directive
+                .find(|char: char| !is_ident_continue(char))
+                .unwrap_or(directive.len());
+            let (name, arguments) = (&directive[..name_end], directive[name_end..].trim());
+
+            let at_line =
+                |message: String| format!("{}:{}: {message}", path.display(), line_index + 1);
+            let unmatched = || {
+                SoulError::new(
+                    at_line(format!("'#{name}' without '#if'")),
+                    SoulErrorKind::InvalidContext,
+                    None,
+                )
+            };
+
+            match name {
+                "if" | "ifdef" | "ifndef" => {
+                    let is_true = is_active
+                        && match name {
+                            "ifdef" => self.macros.contains_key(first_ident(arguments)),
+                            "ifndef" => !self.macros.contains_key(first_ident(arguments)),
+                            _ => self.condition(arguments).map_err(|err| {
+                                SoulError::new(at_line(err.message), err.kind, None)
+                            })?,
+                        };
+                    conditions.push(Condition {
+                        active: is_true,
+                        taken: is_true,
+                        parent_active: is_active,
+                    });
+                }
+                "elif" => {
+                    let condition = conditions.last().copied().ok_or_else(unmatched)?;
+                    let is_true = condition.parent_active
+                        && !condition.taken
+                        && self
+                            .condition(arguments)
+                            .map_err(|err| SoulError::new(at_line(err.message), err.kind, None))?;
+
+                    let last = conditions.last_mut().ok_or_else(unmatched)?;
+                    last.active = is_true;
+                    last.taken |= is_true;
+                }
+                "else" => {
+                    let last = conditions.last_mut().ok_or_else(unmatched)?;
+                    last.active = last.parent_active && !last.taken;
+                    last.taken = true;
+                }
+                "endif" => {
+                    conditions.pop().ok_or_else(unmatched)?;
+                }
+                _ if !is_active => (),
+                "define" => self.define(arguments),
+                "undef" => {
+                    let name = first_ident(arguments);
+                    self.macros.remove(name);
+                    self.defined.retain(|defined| defined != name);
+                }
+                "include" => self.include(arguments, directory)?,
+                "pragma" if arguments == "once" => {
+                    self.include_once.insert(path.to_path_buf());
+                }
+                "error" => {
+                    return Err(SoulError::new(
+                        at_line(format!("#error {arguments}")),
+                        SoulErrorKind::InvalidContext,
+                        None,
+                    ));
+                }
+                // `#pragma`, `#warning`, `#line` and unknown directives do not change declarations
+                _ => (),
+            }
+        }
+
+        self.flush(&mut pending);
+        if !conditions.is_empty() {
+            return Err(SoulError::new(
+                format!("{}: '#if' without '#endif'", path.display()),
+                SoulErrorKind::UnexpecedFileEnd,
+                None,
+            ));
+        }
+        Ok(())
+    }
+
+    /// The integer constants of the object like macros the headers defined, in definition order.
+    pub(crate) fn constants(&self) -> Vec<(String, i128)> {
+        self.defined
+            .iter()
+            .filter_map(|name| {
+                let body = &self.macros.get(name)?.body;
+                if body.is_empty() {
+                    return None;
+                }
+
+                let expanded = self.expand(body, &mut vec![name.clone()]);
+                let tokens = lex(&expanded).ok()?;
+                let value = const_eval(&tokens, &|_| None)?;
+                Some((name.clone(), value))
+            })
+            .collect()
+    }
+
+    fn flush(&mut self, pending: &mut String) {
+        if pending.is_empty() {
+            return;
+        }
+
+        let expanded = self.expand(pending, &mut vec![]);
+        self.output.push_str(&expanded);
+        pending.clear();
+    }
+
+    fn define(&mut self, arguments: &str) {
+        let name = first_ident(arguments);
+        if name.is_empty() {
+            return;
+        }
+
+        let rest = &arguments[name.len()..];
+        let (parameters, body) = match rest.strip_prefix('(') {
+            Some(rest) => {
+                let end = rest.find(')').unwrap_or(rest.len());
+                let parameters = rest[..end]
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|parameter| !parameter.is_empty())
+                    .map(|parameter| match parameter {
+                        "..." => "__VA_ARGS__".to_string(),
+                        _ => parameter.to_string(),
+                    })
+                    .collect();
+                (Some(parameters), rest.get(end + 1..).unwrap_or_default())
+            }
+            None => (None, rest),
+        };
+
+        if parameters.is_none() && !self.defined.iter().any(|defined| defined == name) {
+            self.defined.push(name.to_string());
+        }
+        self.macros.insert(
+            name.to_string(),
+            Macro {
+                parameters,
+                body: body.trim().to_string(),
+            },
+        );
+    }
+
+    fn include(&mut self, arguments: &str, directory: &Path) -> SoulResult<()> {
+        // system headers are not read, the types they define are known by name
+        let Some(quoted) = arguments.strip_prefix('"') else {
+            return Ok(());
+        };
+
+        let file = &quoted[..quoted.find('"').unwrap_or(quoted.len())];
+        let path = directory.join(file);
+        if !path.is_file() {
+            return Err(SoulError::new(
+                format!("included C header '{}' not found", path.display()),
+                SoulErrorKind::PathNotFound,
+                None,
+            ));
+        }
+
+        if self.include_depth >= MAX_INCLUDE_DEPTH {
+            return Err(SoulError::new(
+                format!(
+                    "'#include \"{file}\"' nested more than {MAX_INCLUDE_DEPTH} times, the headers include each other"
+                ),
+                SoulErrorKind::InvalidContext,
+                None,
+            ));
+        }
+
+        self.include_depth += 1;
+        let result = self.process_file(&path);
+        self.include_depth -= 1;
+        result
+    }
+
+    /// Evaluates the condition of an `#if` or `#elif`.
+    fn condition(&self, arguments: &str) -> SoulResult<bool> {
+        let replaced = self.replace_defined(arguments);
+        let expanded = self.expand(&replaced, &mut vec![]);
+        let tokens = lex(&expanded)?;
+
+        // identifiers that are not macros are `0` in preprocessor conditions
+        const_eval(&tokens, &|_| Some(0))
+            .map(|value| value != 0)
+            .ok_or_else(|| {
+                SoulError::new(
+                    format!("can not evaluate preprocessor condition '{arguments}'"),
+                    SoulErrorKind::NeedsToBeLiteralError,
+                    None,
+                )
+            })
+    }
+
+    /// Replaces `defined(NAME)` and `__has_include(...)` like checks by `1` or `0`.
+    fn replace_defined(&self, text: &str) -> String {
+        let chars = text.chars().collect::<Vec<_>>();
+        let mut out = String::new();
+        let mut i = 0;
+        while i < chars.len() {
+            if !is_ident_start(chars[i]) {
+                out.push(chars[i]);
+                i += 1;
+                continue;
+            }
+
+            let ident = take_ident(&chars, &mut i);
+            if ident == "defined" {
+                skip_whitespace(&chars, &mut i);
+                let parenthesized = chars.get(i) == Some(&'(');
+                if parenthesized {
+                    i += 1;
+                    skip_whitespace(&chars, &mut i);
+                }
+
+                let name = take_ident(&chars, &mut i);
+                if parenthesized {
+                    skip_whitespace(&chars, &mut i);
+                    i += (chars.get(i) == Some(&')')) as usize;
+                }
+                out.push_str(if self.macros.contains_key(&name) {
+                    " 1 "
+                } else {
+                    " 0 "
+                });
+            } else if ident.starts_with("__has_") {
+                let mut end = i;
+                skip_whitespace(&chars, &mut end);
+                if collect_arguments(&chars, &mut end).is_some() {
+                    i = end;
+                }
+                out.push_str(" 0 ");
+            } else {
+                out.push_str(&ident);
+            }
+        }
+        out
+    }
+
+    /// Expands the macros in `text`, `disabled` holds the macros currently being expanded.
+    fn expand(&self, text: &str, disabled: &mut Vec<String>) -> String {
+        let chars = text.chars().collect::<Vec<_>>();
+        let mut out = String::with_capacity(text.len());
+        let mut i = 0;
+
+        while i < chars.len() {
+            let char = chars[i];
+            if char == '"' || char == '\'' {
+                let start = i;
+                skip_quoted(&chars, &mut i);
+                out.extend(&chars[start..i]);
+                continue;
+            }
+
+            if char.is_ascii_digit() {
+                while i < chars.len() && (is_ident_continue(chars[i]) || chars[i] == '.') {
+                    out.push(chars[i]);
+                    i += 1;
+                }
+                continue;
+            }
+
+            if !is_ident_start(char) {
+                out.push(char);
+                i += 1;
+                continue;
+            }
+
+            let ident = take_ident(&chars, &mut i);
+            let expandable = disabled.len() < MAX_EXPANSION_DEPTH && !disabled.contains(&ident);
+            let Some(definition) = self.macros.get(&ident).filter(|_| expandable) else {
+                out.push_str(&ident);
+                continue;
+            };
+
+            let body = match &definition.parameters {
+                None => definition.body.clone(),
+                Some(parameters) => {
+                    let mut end = i;
+                    skip_whitespace(&chars, &mut end);
+                    let Some(arguments) = collect_arguments(&chars, &mut end) else {
+                        // a function like macro name without arguments is not expanded
+                        out.push_str(&ident);
+                        continue;
+                    };
+
+                    i = end;
+                    let arguments = arguments
+                        .iter()
+                        .map(|argument| self.expand(argument, disabled))
+                        .collect::<Vec<_>>();
+                    substitute(&definition.body, parameters, &arguments)
+                }
+            };
+
+            disabled.push(ident);
+            out.push(' ');
+            out.push_str(&self.expand(&body, disabled));
+            out.push(' ');
+            disabled.pop();
+        }
+        out
+    }
+
+    fn predefine(&mut self, target: &TargetInfo) {
+        let mut define = |name: &str, value: &str| {
+            self.macros.insert(
+                name.to_string(),
+                Macro {
+                    parameters: None,
+                    body: value.to_string(),
+                },
+            );
+        };
+
+        let is_64_bit = target.ptr_bit_size == 64;
+        let long_bit_size = match (target.os, is_64_bit) {
+            (Os::Windows, _) | (_, false) => 32,
+            _ => 64,
+        };
+
+        define("__STDC__", "1");
+        define("__STDC_VERSION__", "201112L");
+        define("__CHAR_BIT__", "8");
+        define("__SIZEOF_INT__", &(target.c_int_bit_size / 8).to_string());
+        define("__SIZEOF_LONG__", &(long_bit_size / 8).to_string());
+        define("__SIZEOF_POINTER__", &(target.ptr_bit_size / 8).to_string());
+
+        match target.os {
+            Os::Windows => {
+                define("_WIN32", "1");
+                if is_64_bit {
+                    define("_WIN64", "1");
+                }
+                match target.arch {
+                    Arch::X86_64 => {
+                        define("_M_X64", "100");
+                        define("_M_AMD64", "100");
+                    }
+                    Arch::X86 => define("_M_IX86", "600"),
+                    Arch::AArch64 => define("_M_ARM64", "1"),
+                    Arch::Armv7 => define("_M_ARM", "7"),
+                    Arch::Riscv64 => (),
+                }
+            }
+            Os::Linux => {
+                define("__linux__", "1");
+                define("__linux", "1");
+                define("__unix__", "1");
+                define("__unix", "1");
+            }
+            Os::Macos => {
+                define("__APPLE__", "1");
+                define("__MACH__", "1");
+            }
+        }
+
+        if is_64_bit && target.os != Os::Windows {
+            define("__LP64__", "1");
+            define("_LP64", "1");
+        }
+
+        match target.arch {
+            Arch::X86_64 => {
+                define("__x86_64__", "1");
+                define("__x86_64", "1");
+                define("__amd64__", "1");
+            }
+            Arch::X86 => define("__i386__", "1"),
+            Arch::AArch64 => define("__aarch64__", "1"),
+            Arch::Armv7 => define("__arm__", "1"),
+            Arch::Riscv64 => {
+                define("__riscv", "1");
+                define("__riscv_xlen", "64");
+            }
+        }
+    }
+}
+
+/// Replaces the comments by whitespace, keeping the newlines of block comments.
+fn strip_comments(source: &str) -> String {
+    let chars = source.chars().collect::<Vec<_>>();
+    let mut out = String::with_capacity(source.len());
+    let mut i = 0;
+    while i < chars.len() {
+        match (chars[i], chars.get(i + 1)) {
+            ('"' | '\'', _) => {
+                let start = i;
+                skip_quoted(&chars, &mut i);
+                out.extend(&chars[start..i]);
+            }
+            ('/', Some('/')) => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            ('/', Some('*')) => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    if chars[i] == '\n' {
+                        out.push('\n');
+                    }
+                    i += 1;
+                }
+                i += 2;
+                out.push(' ');
+            }
+            (char, _) => {
+                out.push(char);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Replaces the parameters in the body of a function like macro, handles `#param` and `a ## b`.
+fn substitute(body: &str, parameters: &[String], arguments: &[String]) -> String {
+    let argument = |name: &str| -> Option<String> {
+        if name == "__VA_ARGS__" {
+            let start = parameters.len().saturating_sub(1);
+            return Some(arguments.get(start..).unwrap_or_default().join(", "));
+        }
+
+        let index = parameters.iter().position(|parameter| parameter == name)?;
+        Some(arguments.get(index).cloned().unwrap_or_default())
+    };
+
+    let chars = body.chars().collect::<Vec<_>>();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let char = chars[i];
+        if char == '#' && chars.get(i + 1) == Some(&'#') {
+            while out.ends_with(char::is_whitespace) {
+                out.pop();
+            }
+            i += 2;
+            skip_whitespace(&chars, &mut i);
+        } else if char == '#' {
+            i += 1;
+            skip_whitespace(&chars, &mut i);
+            let name = take_ident(&chars, &mut i);
+            let text = argument(&name).unwrap_or(name);
+            out.push_str(&format!(
+                "\"{}\"",
+                text.replace('\\', "\\\\").replace('"', "\\\"")
+            ));
+        } else if char == '"' || char == '\'' {
+            let start = i;
+            skip_quoted(&chars, &mut i);
+            out.extend(&chars[start..i]);
+        } else if is_ident_start(char) {
+            let name = take_ident(&chars, &mut i);
+            out.push_str(&argument(&name).unwrap_or(name));
+        } else {
+            out.push(char);
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Collects the comma separated arguments of a macro call, `chars[*i]` has to be the `(`.
+fn collect_arguments(chars: &[char], i: &mut usize) -> Option<Vec<String>> {
+    if chars.get(*i) != Some(&'(') {
+        return None;
+    }
+    *i += 1;
+
+    let mut arguments = vec![];
+    let mut current = String::new();
+    let mut depth = 0usize;
+    while *i < chars.len() {
+        let char = chars[*i];
+        match char {
+            '"' | '\'' => {
+                let start = *i;
+                skip_quoted(chars, i);
+                current.extend(&chars[start..*i]);
+                continue;
+            }
+            '(' => depth += 1,
+            ')' if depth == 0 => {
+                *i += 1;
+                if !current.trim().is_empty() || !arguments.is_empty() {
+                    arguments.push(current.trim().to_string());
+                }
+                return Some(arguments);
+            }
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                arguments.push(current.trim().to_string());
+                current.clear();
+                *i += 1;
+                continue;
+            }
+            _ => (),
+        }
+        current.push(char);
+        *i += 1;
+    }
+    None
+}
+
+fn skip_quoted(chars: &[char], i: &mut usize) {
+    let quote = chars[*i];
+    *i += 1;
+    while *i < chars.len() && chars[*i] != quote && chars[*i] != '\n' {
+        if chars[*i] == '\\' {
+            *i += 1;
+        }
+        *i += 1;
+    }
+    *i = (*i + 1).min(chars.len());
+}
+
+fn skip_whitespace(chars: &[char], i: &mut usize) {
+    while *i < chars.len() && chars[*i].is_whitespace() {
+        *i += 1;
+    }
+}
+
+fn take_ident(chars: &[char], i: &mut usize) -> String {
+    let start = *i;
+    while *i < chars.len() && is_ident_continue(chars[*i]) {
+        *i += 1;
+    }
+    chars[start..*i].iter().collect()
+}
+
+fn first_ident(text: &str) -> &str {
+    let end = text
+        .find(|char: char| !is_ident_continue(char))
+        .unwrap_or(text.len());
+    &text[..end]
+}
@@ -0,0 +1,119 @@
+use std::path::Path;
+
+use soul_utils::compile_options::{Arch, Os, TargetInfo};
+
+use crate::import_c_source;
+
+fn import(source: &str, os: Os) -> String {
+    let target = TargetInfo::new(Arch::X86_64, os);
+    import_c_source(source, Path::new("test.h"), &target).expect("import error")
+}
+
+fn import_windows(source: &str) -> String {
+    import(source, Os::Windows)
+}
+
+#[test]
+fn import_function_prototypes() {
+    let soul = import_windows(
+        "int add(int a, int b);\nvoid log_message(const char *message, ...);\nvoid *alloc(size_t size);",
+    );
+
+    assert!(soul.contains("pub extern \"C\" add(a: c_int, b: c_int): c_int"));
+    assert!(soul.contains("extern \"C\" log_message(message: c_str, ...)"));
+    assert!(soul.contains("extern \"C\" alloc(size: uint): *none"));
+}
+
+#[test]
+fn import_typedef_struct() {
+    let soul = import_windows(
+        "typedef unsigned long long u64;\ntypedef struct Duration_s { u64 sec; unsigned nano; } Duration;\nDuration now(void);",
+    );
+
+    assert!(soul.contains(
+        "#[repr(C)]\npub struct Duration {\n    pub mut sec: u64\n    pub mut nano: c_uint\n}"
+    ));
+    assert!(soul.contains("extern \"C\" now(): Duration"));
+}
+
+#[test]
+fn import_enum() {
+    let soul = import_windows(
+        "typedef enum Color_e { RED, GREEN, BLUE } Color;\ntypedef struct { Color fill; enum { SOLID, DASHED } line; } Style;\nvoid paint(Color color);",
+    );
+
+    assert!(soul.contains("#[repr(C)]\npub enum Color {\n    RED,\n    GREEN,\n    BLUE,\n}"));
+    assert!(soul.contains("#[repr(C)]\npub enum Style_line {\n    SOLID,\n    DASHED,\n}"));
+    assert!(soul.contains("    pub mut fill: Color\n    pub mut line: Style_line\n"));
+    assert!(soul.contains("extern \"C\" paint(color: Color)"));
+}
+
+#[test]
+fn import_enum_with_values_as_constants() {
+    let soul = import_windows(
+        "typedef enum { LOW = 1, HIGH } Level;\nenum { FIRST, SECOND };\nvoid set(Level level);",
+    );
+
+    assert!(soul.contains("pub literal LOW = 1\npub literal HIGH = 2"));
+    assert!(soul.contains("extern \"C\" set(level: c_int)"));
+    // without a name there is no type to declare
+    assert!(soul.contains("pub literal FIRST = 0\npub literal SECOND = 1"));
+    assert!(!soul.contains("pub enum"));
+}
+
+#[test]
+fn import_define_constants() {
+    let soul = import_windows(
+        "#define MAJOR 2\n#define VERSION ((MAJOR << 8) | 1)\n#define NAME \"name\"\n#define SQUARE(x) ((x) * (x))\n#define AREA SQUARE(3)",
+    );
+
+    assert!(soul.contains("pub literal MAJOR = 2"));
+    assert!(soul.contains("literal VERSION = 513"));
+    assert!(soul.contains("literal AREA = 9"));
+    assert!(!soul.contains("NAME"));
+}
+
+#[test]
+fn import_target_conditions() {
+    let source = "#if defined(_WIN32)\nint windows_only(void);\n#elif __linux__\nint linux_only(void);\n#endif\nlong size(void);";
+
+    let windows = import(source, Os::Windows);
+    assert!(windows.contains("windows_only"));
+    assert!(!windows.contains("linux_only"));
+    assert!(windows.contains("extern \"C\" size(): i32"));
+
+    let linux = import(source, Os::Linux);
+    assert!(linux.contains("linux_only"));
+    assert!(!linux.contains("windows_only"));
+    assert!(linux.contains("extern \"C\" size(): i64"));
+}
+
+#[test]
+fn import_pointers() {
+    let soul = import_windows(
+        "struct Opaque;\ntypedef void (*Callback)(int);\nvoid run(struct Opaque *handle, Callback callback, char **lines, int values[4]);",
+    );
+
+    assert!(soul.contains(
+        "extern \"C\" run(handle: *none, callback: *none, lines: **char, values: *c_int)"
+    ));
+}
+
+#[test]
+fn import_skips_unsupported() {
+    let soul = import_windows(
+        "typedef struct { int flag : 1; } Bits;\nvoid use_bits(Bits bits);\nstatic inline int helper(int x) { return x; }\nint after(void);",
+    );
+
+    assert!(soul.contains("// skipped 'Bits': bit field 'flag'"));
+    assert!(soul.contains("// skipped 'use_bits': parameter 'bits': 'Bits' is skipped"));
+    assert!(soul.contains("// skipped 'helper': static functions are not exported"));
+    assert!(soul.contains("extern \"C\" after(): c_int"));
+}
+
+#[test]
+fn import_escapes_soul_keywords() {
+    let soul = import_windows("void copy_to(int in, int where);");
+
+    assert!(soul.contains("extern \"C\" copy_to(in_: c_int, where_: c_int)"));
+}
@@ -3,17 +3,30 @@ use hir::{ComplexLiteral, ExpressionId, HirTree, LocalId, PlaceId, StructId, Typ
 use typed_hir::{Field, Struct};
 
 pub(crate) mod binary;
+mod range;
 pub(crate) mod unary;
 mod utils;
-use soul_utils::{Ident, ids::IdAlloc, vec_map::VecMap};
+use soul_utils::{
+    Ident, compile_options::CompilerOptions, crate_store::CrateContext, ids::IdAlloc,
+    sementic_level::SementicFault, vec_map::VecMap,
+};
 use typed_hir::{ThirType, TypedHir};
 pub(crate) use utils::*;
 
 use crate::{binary::interpret_binary, unary::interpret_unary};
 
-pub fn literal_resolve(hir: &HirTree, types: &TypedHir) -> VecMap<ExpressionId, ComplexLiteral> {
+pub fn literal_resolve(
+    hir: &HirTree,
+    types: &TypedHir,
+    options: &CompilerOptions,
+    context: &mut CrateContext,
+) -> VecMap<ExpressionId, ComplexLiteral> {
     let mut interpreter = LiteralInterpreter::new(hir, types);
 
+    for err in interpreter.check_literal_ranges(options.target_info()) {
+        context.faults.push(SementicFault::error(err));
+    }
+
     interpreter.collect_literals();
     interpreter.resolve_literals();
     interpreter.consume_to_literals()
@@ -66,17 +79,21 @@ impl<'a> LiteralInterpreter<'a> {
             hir::ExpressionKind::Null
             | hir::ExpressionKind::Error
             | hir::ExpressionKind::Block(_)
+            | hir::ExpressionKind::Panic(_)
+            | hir::ExpressionKind::Format(_)
             | hir::ExpressionKind::DeRef(_)
             | hir::ExpressionKind::Sizeof(_)
             | hir::ExpressionKind::Literal(_)
             | hir::ExpressionKind::If { .. }
             | hir::ExpressionKind::Ref { .. }
+            | hir::ExpressionKind::Slice { .. }
             | hir::ExpressionKind::Function(_)
             | hir::ExpressionKind::Call { .. }
             | hir::ExpressionKind::Cast { .. }
             | hir::ExpressionKind::While { .. }
             | hir::ExpressionKind::InnerRawStackArray { .. }
-            | hir::ExpressionKind::ExternalCall { .. }
+            | hir::ExpressionKind::New { .. }
+            | hir::ExpressionKind::NewArray { .. }
             | hir::ExpressionKind::EnumVariant { .. } => None,
 
             hir::ExpressionKind::StructConstructor { ty, values, .. } => {
@@ -85,6 +102,9 @@ impl<'a> LiteralInterpreter<'a> {
 
             hir::ExpressionKind::Load(place) => self.interpret_place(*place),
             hir::ExpressionKind::Local(id) => self.interpret_local(*id),
+            hir::ExpressionKind::Copy(inner) => self
+                .try_get_literal(*inner)
+                .map(LiteralRef::consume_to_complex),
             hir::ExpressionKind::Unary(unary) => {
                 let value = self.try_get_literal(unary.expression)?;
                 interpret_unary(&unary.operator, value.try_basic_ref()?).map(|l| l.to_complex())
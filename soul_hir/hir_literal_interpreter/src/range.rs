@@ -0,0 +1,174 @@
+use ast::Literal;
+use hir::{ExpressionId, TypeId};
+use soul_utils::{
+    compile_options::TargetInfo,
+    error::{SoulError, SoulErrorKind},
+    soul_names::PrimitiveTypes,
+    vec_map::VecMap,
+};
+use typed_hir::ThirTypeKind;
+
+use crate::LiteralInterpreter;
+
+impl<'a> LiteralInterpreter<'a> {
+    /// Checks every integer and char literal against the type it ends up as, so `300` passed
+    /// to a `u8` or `'\u{1F600}'` as an 8-bit `char` errors here instead of being truncated by
+    /// the backend.
+    pub(crate) fn check_literal_ranges(&self, target: &TargetInfo) -> Vec<SoulError> {
+        let targets = self.literal_targets();
+
+        let mut errors = vec![];
+        for (id, expression) in self.hir.nodes.expressions.entries() {
+            let hir::ExpressionKind::Literal(literal) = &expression.kind else {
+                continue;
+            };
+
+            let ty = match targets.get(id) {
+                Some(ty) => *ty,
+                None => self.expression_type(id),
+            };
+            if let Some(err) = self.check_literal_range(id, literal, ty, target) {
+                errors.push(err);
+            }
+        }
+        errors
+    }
+
+    /// The type a literal is cast to when it is used, untyped literals only get their final
+    /// type from the parameter, local, place or operand they flow into.
+    fn literal_targets(&self) -> VecMap<ExpressionId, TypeId> {
+        let types = &self.types.types_table;
+        let mut targets = VecMap::const_default();
+        let mut insert_literal = |value: ExpressionId, ty: Option<&TypeId>| {
+            if self.hir.nodes.expressions[value].is_literal()
+                && let Some(ty) = ty
+            {
+                targets.insert(value, *ty);
+            }
+        };
+
+        for (id, local) in self.hir.nodes.locals.entries() {
+            if let hir::LocalKind::Variable(Some(value)) = local.kind {
+                insert_literal(value, types.locals.get(id));
+            }
+        }
+
+        for block in self.hir.nodes.blocks.values() {
+            for statement in &block.statements {
+                if let hir::StatementKind::Assign(assign) = &statement.kind {
+                    insert_literal(assign.value, types.places.get(assign.place));
+                }
+            }
+        }
+
+        for expression in self.hir.nodes.expressions.values() {
+            match &expression.kind {
+                hir::ExpressionKind::Call {
+                    function,
+                    arguments,
+                    ..
+                } => {
+                    let Some(function) = self.hir.nodes.functions.get(*function) else {
+                        continue;
+                    };
+                    for (argument, parameter) in arguments.iter().zip(&function.parameters) {
+                        insert_literal(*argument, types.locals.get(parameter.local));
+                    }
+                }
+                hir::ExpressionKind::Binary(binary) => {
+                    let left = self.expression_type(binary.left);
+                    let right = self.expression_type(binary.right);
+                    insert_literal(binary.left, Some(&right));
+                    insert_literal(binary.right, Some(&left));
+                }
+                hir::ExpressionKind::StructConstructor { ty, values, .. } => {
+                    let Some(r#struct) = self.types.types_map.id_to_struct(*ty) else {
+                        continue;
+                    };
+                    for (name, value) in values {
+                        let field = self
+                            .find_field_index(r#struct, name.as_str())
+                            .map(|i| &r#struct.fields[i].ty);
+                        insert_literal(*value, field);
+                    }
+                }
+                _ => (),
+            }
+        }
+        targets
+    }
+
+    fn check_literal_range(
+        &self,
+        id: ExpressionId,
+        literal: &Literal,
+        ty: TypeId,
+        target: &TargetInfo,
+    ) -> Option<SoulError> {
+        let primitive = match self.get_type(ty).kind {
+            ThirTypeKind::Primitive(primitive)
+                if (primitive.is_any_interger() && !primitive.is_untyped_numeric())
+                    || primitive.is_character() =>
+            {
+                primitive
+            }
+            _ => return None,
+        };
+
+        let bits = primitive.to_size_bit_u8(
+            target.c_int_bit_size,
+            target.int_bit_size,
+            target.char_bit_size,
+        ) as u32;
+        if let Literal::Char(char) = *literal {
+            if !primitive.is_character() || bits >= 32 || (char as u32) < (1 << bits) {
+                return None;
+            }
+            return Some(SoulError::new(
+                format!(
+                    "char literal {char:?} does not fit in '{}', which is {bits} bits",
+                    primitive.as_str(),
+                ),
+                SoulErrorKind::InvalidNumber,
+                Some(self.hir.info.spans.expressions[id]),
+            ));
+        }
+        if !primitive.is_any_interger() {
+            return None;
+        }
+
+        let fits = match *literal {
+            Literal::Int(value) if primitive.is_unsigned_interger() => value >= 0,
+            Literal::Int(value) => bits >= 128 || value >= -(1i128 << (bits - 1)),
+            Literal::Uint(value) if primitive.is_unsigned_interger() => {
+                bits >= 128 || value < (1u128 << bits)
+            }
+            Literal::Uint(value) => value < (1u128 << (bits - 1)),
+            _ => true,
+        };
+        if fits {
+            return None;
+        }
+
+        Some(SoulError::new(
+            format!(
+                "literal '{}' is out of range for '{}', which holds {}",
+                literal.value_to_string(),
+                primitive.as_str(),
+                integer_range(primitive, bits),
+            ),
+            SoulErrorKind::InvalidNumber,
+            Some(self.hir.info.spans.expressions[id]),
+        ))
+    }
+}
+
+fn integer_range(primitive: PrimitiveTypes, bits: u32) -> String {
+    if primitive.is_unsigned_interger() {
+        let max = u128::MAX >> (128 - bits);
+        format!("0..={max}")
+    } else {
+        let max = i128::MAX >> (128 - bits);
+        format!("{}..={max}", -max - 1)
+    }
+}
@@ -64,11 +64,44 @@ pub enum ExpressionKind {
         mutable: bool,
     },
 
+    /// Borrows `place[start..end]` as a slice (`@array[start..end]` or `&array[start..end]`).
+    ///
+    /// A missing `start` begins at `0` and a missing `end` runs to the length of `place`.
+    Slice {
+        place: PlaceId,
+        start: Option<ExpressionId>,
+        end: Option<ExpressionId>,
+        mutable: bool,
+    },
+
     /// Dereferences a pointer or reference expression.
     DeRef(ExpressionId),
 
+    /// Explicitly copies a value instead of moving it (`copy x`).
+    Copy(ExpressionId),
+
     InnerRawStackArray(LazyTypeId),
 
+    /// Allocates `value` with the allocator runtime (`new value`).
+    ///
+    /// Gives `*T`, or `?*T` which is `null` on a failed allocation when `fallible`.
+    New {
+        value: ExpressionId,
+        fallible: bool,
+    },
+
+    /// Allocates `len` copies of `element` with the allocator runtime (`new:[for len => element]`).
+    ///
+    /// Gives `[*]T`, or `?[*]T` which is `null` on a failed allocation when `fallible`.
+    NewArray {
+        element: ExpressionId,
+        len: ExpressionId,
+        fallible: bool,
+    },
+
+    /// Builds the text of a format string `f"x: {x}"` as a heap string `[*]char`.
+    Format(Vec<FormatPiece>),
+
     // --- Operators ---
     /// A unary operation.
     Unary(Unary),
@@ -98,6 +131,11 @@ pub enum ExpressionKind {
 
     Block(BlockId),
 
+    /// Stops the program with an optional `str` message (`panic(msg)`).
+    ///
+    /// Has type `never` so it fits in any expression position.
+    Panic(Option<ExpressionId>),
+
     // --- Calls ---
     /// A function or method call.
     ///
@@ -109,14 +147,6 @@ pub enum ExpressionKind {
         arguments: Vec<ExpressionId>,
     },
 
-    /// An external crate function call.
-    ExternalCall {
-        crate_name: String,
-        function_name: String,
-        generics: Vec<TypeId>,
-        arguments: Vec<ExpressionId>,
-    },
-
     // --- Type operations ---
     /// An explicit type cast.
     Cast {
@@ -138,6 +168,16 @@ pub enum ExpressionKind {
     Sizeof(LazyTypeId),
 }
 
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum FormatPiece {
+    Text(String),
+    /// `{value}` padded with spaces to `width` chars for `{value:width}`.
+    Argument {
+        value: ExpressionId,
+        width: Option<usize>,
+    },
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Unary {
     pub operator: UnaryOperator,
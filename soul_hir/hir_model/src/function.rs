@@ -1,5 +1,9 @@
 use ast::{ExternLanguage, FunctionKind};
-use soul_utils::{Ident, ids::FunctionId};
+use soul_utils::{
+    DefId, Ident,
+    ids::FunctionId,
+    span::{Attribute, Visibility},
+};
 
 use crate::{BlockId, ExpressionId, GenericId, LazyTypeId, LocalId, TypeId};
 
@@ -26,19 +30,38 @@ pub struct Function {
     /// Function parameters.
     pub parameters: Vec<Parameter>,
 
+    /// Takes C variadic arguments after `parameters`.
+    pub variadic: bool,
+
     pub generics: Vec<GenericId>,
 
     /// Return type of the function.
     pub return_type: TypeId,
 
+    /// Indices into `parameters` that a returned reference may borrow from,
+    /// `None` when the lifetime is elided so it may borrow from any of them.
+    pub return_borrows: Option<Vec<usize>>,
+
     /// Body of the function.
     pub body: FunctionBody,
+
+    /// Calling convention of an `extern "C"` definition or declaration,
+    /// `None` for a regular Soul function.
+    pub abi: Option<ExternLanguage>,
+
+    /// Attributes written before the function, like `#[inline]`.
+    pub attributes: Vec<Attribute>,
+
+    /// `pub` functions can be called from other modules and crates.
+    pub visibility: Visibility,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum FunctionBody {
     Internal(BlockId),
     External(ExternLanguage),
+    /// Declared for a call into a dependency crate, defined as `DefId` in that crate.
+    Dependency(DefId<FunctionId>),
 }
 
 /// A function parameter.
@@ -49,6 +72,9 @@ pub struct Parameter {
     /// Local variable ID bound to this parameter.
     pub local: LocalId,
 
+    /// Source-level name of the parameter, `this` for the receiver of a methode.
+    pub name: Ident,
+
     /// Type of the parameter.
     pub ty: LazyTypeId,
 
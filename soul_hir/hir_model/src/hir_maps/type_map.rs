@@ -14,6 +14,7 @@ use crate::{
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct TypesMap {
     pub array_struct: StructId,
+    pub range_struct: StructId,
     types: BiMap<TypeId, HirType>,
     structs: VecMap<StructId, Struct>,
     enums: VecMap<EnumId, Enum>,
@@ -28,6 +29,7 @@ impl Default for TypesMap {
     fn default() -> Self {
         Self {
             array_struct: IdAlloc::error(),
+            range_struct: IdAlloc::error(),
 
             types: Default::default(),
             enums: Default::default(),
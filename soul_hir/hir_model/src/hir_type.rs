@@ -1,8 +1,9 @@
 use ast::{ArrayKind, Literal};
 use soul_utils::{
     Ident,
-    ids::IdAlloc,
+    ids::{FunctionId, IdAlloc},
     soul_names::{PrimitiveTypes, TypeModifier},
+    span::{Attribute, Visibility},
     symbool_kind::SymbolKind,
     vec_map::VecMapIndex,
 };
@@ -128,6 +129,10 @@ impl HirType {
         matches!(self.kind, HirTypeKind::Primitive(PrimitiveTypes::Boolean))
     }
 
+    pub const fn is_never_type(&self) -> bool {
+        matches!(self.kind, HirTypeKind::Primitive(PrimitiveTypes::Never))
+    }
+
     pub const fn is_pointer(&self) -> bool {
         matches!(self.kind, HirTypeKind::Pointer(_))
     }
@@ -217,12 +222,24 @@ impl TypeId {
 pub struct Enum {
     pub name: Ident,
     pub variants: Vec<Ident>,
+    /// Attributes written before the enum, like `#[repr(C)]`.
+    pub attributes: Vec<Attribute>,
+    pub visibility: Visibility,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Struct {
     pub name: Ident,
     pub fields: Vec<Field>,
+    /// `impl AutoCopy{}` was declared for this struct.
+    pub auto_copy: bool,
+    /// the `Drop` methode of `impl Drop{}` if declared for this struct.
+    pub drop: Option<FunctionId>,
+    /// the `Display` methode of `impl Display{}` if declared for this struct.
+    pub display: Option<FunctionId>,
+    /// Attributes written before the struct, like `#[packed]`.
+    pub attributes: Vec<Attribute>,
+    pub visibility: Visibility,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -231,6 +248,9 @@ pub struct Field {
     pub id: FieldId,
     pub name: Ident,
     pub ty: LazyTypeId,
+    pub attributes: Vec<Attribute>,
+    /// Private fields can only be used in the module of their struct.
+    pub visibility: Visibility,
 }
 
 pub trait DisplayType {
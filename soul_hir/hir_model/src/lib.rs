@@ -16,7 +16,7 @@ use soul_utils::{
     Ident,
     ids::{FunctionId, IdAlloc},
     soul_names::INIT_GLOBALS_FUNCTION_NAME,
-    span::{ModuleId, Span},
+    span::{ModuleId, Span, Visibility},
 };
 pub use statement::*;
 
@@ -42,10 +42,15 @@ impl HirTree {
             id: init_globals,
             generics: vec![],
             parameters: vec![],
+            variadic: false,
             owner_type: TypeId::error(),
             return_type: TypeId::error(),
+            return_borrows: None,
             kind: FunctionKind::Static,
             body: FunctionBody::Internal(BlockId::error()),
+            abi: None,
+            attributes: vec![],
+            visibility: Visibility::Private,
             name: Ident::new(
                 INIT_GLOBALS_FUNCTION_NAME.to_string(),
                 Span::default(ast_root.id),
@@ -54,7 +59,7 @@ impl HirTree {
 
         let mut nodes = NodeMaps::new(init_global_function);
         let root = Module {
-            is_public: true,
+            visibility: Visibility::Public,
             id: ast_root.id,
             globals: vec![],
             modules: ast_root.modules.entries().collect(),
@@ -74,12 +79,12 @@ impl HirTree {
     pub fn insert_module(
         &mut self,
         module_id: ModuleId,
-        is_public: bool,
+        visibility: Visibility,
         sub_modules: Vec<ModuleId>,
     ) {
         if !self.nodes.modules.contains(module_id) {
             let module = Module {
-                is_public,
+                visibility,
                 id: module_id,
                 globals: vec![],
                 modules: sub_modules,
@@ -92,7 +97,7 @@ impl HirTree {
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Module {
     pub id: ModuleId,
-    pub is_public: bool,
+    pub visibility: Visibility,
     pub globals: Vec<Global>,
     pub modules: Vec<ModuleId>,
 }
@@ -101,6 +106,8 @@ pub struct Module {
 pub struct Global {
     pub kind: GlobalKind,
     pub id: StatementId,
+    /// `pub` global variables can be used from other modules.
+    pub visibility: Visibility,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -115,7 +122,16 @@ pub enum GlobalKind {
 }
 impl Global {
     pub fn new(kind: GlobalKind, id: StatementId) -> Self {
-        Self { kind, id }
+        Self {
+            kind,
+            id,
+            visibility: Visibility::Private,
+        }
+    }
+
+    pub fn with_visibility(mut self, visibility: Visibility) -> Self {
+        self.visibility = visibility;
+        self
     }
 
     pub fn get_span(&self, spans: &SpanMap) -> Span {
@@ -155,6 +171,8 @@ pub struct Block {
     pub id: BlockId,
     pub statements: Vec<Statement>,
     pub terminator: Option<Terminator>,
+    /// `unsafe{}` block, raw pointers can be dereferenced and extern functions called in it.
+    pub is_unsafe: bool,
 }
 impl Block {
     pub fn new(id: BlockId) -> Self {
@@ -162,6 +180,7 @@ impl Block {
             id,
             terminator: None,
             statements: vec![],
+            is_unsafe: false,
         }
     }
 }
@@ -40,6 +40,9 @@ pub enum StatementKind {
 
     /// Continues execution of the current loop.
     Continue,
+
+    /// Runs the expression whenever the enclosing block exits.
+    Defer(ExpressionId),
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
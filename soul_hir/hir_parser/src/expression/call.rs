@@ -7,6 +7,7 @@ use soul_utils::{
     IdAlloc,
     error::{SoulError, SoulErrorKind},
     ids::FunctionId,
+    soul_names::PrimitiveTypes,
     span::Span,
 };
 
@@ -19,12 +20,7 @@ impl<'a> HirContext<'a> {
         function_call: &ast::FunctionCall,
     ) -> hir::Expression {
         if let Some(intrinsic) = function_call.intrinsic {
-            return self.lower_intrinsic(
-                id,
-                intrinsic,
-                function_call.name.span,
-                function_call.intrinsic_value.as_deref(),
-            );
+            return self.lower_intrinsic(id, intrinsic, function_call);
         }
 
         if let Some(external_ref) = &function_call.external_ref {
@@ -98,7 +94,14 @@ impl<'a> HirContext<'a> {
             }
         }
 
+        // extra positional arguments of a variadic function go after the parameters
+        let mut variadic_arguments = vec![];
         for (i, argument) in function_call.arguments.iter().enumerate() {
+            if signature.variadic && argument.name.is_none() && i >= signature.parameters.len() {
+                variadic_arguments.push(self.lower_expression(&argument.value));
+                continue;
+            }
+
             let ast_param_idx = match self.get_parameter_index(
                 i,
                 argument,
@@ -155,6 +158,7 @@ impl<'a> HirContext<'a> {
                 }
             };
         }
+        arguments.extend(variadic_arguments);
 
         let mut generics = vec![];
         for soul_type in &function_call.generics {
@@ -259,17 +263,18 @@ impl<'a> HirContext<'a> {
         &mut self,
         id: hir::ExpressionId,
         intrinsic: Intrinsic,
-        span: Span,
-        intrinsic_value: Option<&str>,
+        function_call: &ast::FunctionCall,
     ) -> hir::Expression {
+        let span = function_call.name.span;
+        let intrinsic_value = function_call.intrinsic_value.as_deref();
         match intrinsic {
-            Intrinsic::InFile => {
-                let path_str = intrinsic_value.unwrap_or("unknown").to_string();
-                let ty = self.type_from_literal(&Literal::Str(path_str.clone()));
+            Intrinsic::InFile | Intrinsic::TargetOs | Intrinsic::TargetArch => {
+                let value = intrinsic_value.unwrap_or("unknown").to_string();
+                let ty = self.type_from_literal(&Literal::Str(value.clone()));
                 hir::Expression {
                     id,
                     ty: LazyTypeId::Known(ty),
-                    kind: hir::ExpressionKind::Literal(Literal::Str(path_str)),
+                    kind: hir::ExpressionKind::Literal(Literal::Str(value)),
                 }
             }
             Intrinsic::InLine => {
@@ -283,6 +288,29 @@ impl<'a> HirContext<'a> {
                     kind: hir::ExpressionKind::Literal(Literal::Int(line)),
                 }
             }
+            Intrinsic::Panic => {
+                if function_call.arguments.len() > 1 {
+                    self.log_error(SoulError::new(
+                        format!(
+                            "'panic' takes one message argument but got {} arguments",
+                            function_call.arguments.len()
+                        ),
+                        SoulErrorKind::InvalidContext,
+                        Some(span),
+                    ));
+                }
+
+                let message = function_call
+                    .arguments
+                    .first()
+                    .map(|argument| self.lower_expression(&argument.value));
+                let ty = self.add_type(HirType::primitive_type(PrimitiveTypes::Never));
+                hir::Expression {
+                    id,
+                    ty: LazyTypeId::Known(ty),
+                    kind: hir::ExpressionKind::Panic(message),
+                }
+            }
         }
     }
 
@@ -293,19 +321,35 @@ impl<'a> HirContext<'a> {
         crate_name: &str,
         full_name: &str,
     ) -> hir::Expression {
-        let arguments = function_call
-            .arguments
-            .iter()
-            .map(|arg| self.lower_expression(&arg.value))
-            .collect();
+        let span = function_call.name.span;
+        let function = match self.declare_dependency_function(crate_name, full_name, span) {
+            Some(val) => val,
+            None => return hir::Expression::error(id),
+        };
+
+        let mut arguments = vec![];
+        for argument in &function_call.arguments {
+            if let Some(name) = &argument.name {
+                self.log_error(SoulError::new(
+                    format!(
+                        "arguments of '{}' from crate '{crate_name}' can not be named, pass them in order",
+                        function_call.name.as_str(),
+                    ),
+                    SoulErrorKind::InvalidContext,
+                    Some(name.span),
+                ));
+            }
+            arguments.push(self.lower_expression(&argument.value));
+        }
 
+        let return_type = self.tree.nodes.functions[function].return_type;
         hir::Expression {
             id,
-            ty: LazyTypeId::error(),
-            kind: hir::ExpressionKind::ExternalCall {
-                crate_name: crate_name.to_string(),
-                function_name: full_name.to_string(),
+            ty: return_type.to_lazy(),
+            kind: hir::ExpressionKind::Call {
+                function,
                 generics: vec![],
+                has_callee: false,
                 arguments,
             },
         }
@@ -0,0 +1,39 @@
+use ast::ArrayKind;
+use hir::{HirType, HirTypeKind};
+use soul_utils::soul_names::PrimitiveTypes;
+
+use crate::HirContext;
+
+impl<'a> HirContext<'a> {
+    pub(super) fn lower_format_string(
+        &mut self,
+        id: hir::ExpressionId,
+        format: &ast::FormatString,
+    ) -> hir::Expression {
+        let pieces = format
+            .pieces
+            .iter()
+            .map(|piece| match piece {
+                ast::FormatPiece::Text(text) => hir::FormatPiece::Text(text.clone()),
+                ast::FormatPiece::Argument { value, width } => hir::FormatPiece::Argument {
+                    value: self.lower_expression(value),
+                    width: *width,
+                },
+            })
+            .collect();
+
+        let char_type = self
+            .add_type(HirType::primitive_type(PrimitiveTypes::Char))
+            .to_lazy();
+        let ty = self.add_type(HirType::new(HirTypeKind::Array {
+            element: char_type,
+            kind: ArrayKind::HeapArray,
+        }));
+
+        hir::Expression {
+            id,
+            ty: ty.to_lazy(),
+            kind: hir::ExpressionKind::Format(pieces),
+        }
+    }
+}
@@ -8,6 +8,7 @@ use soul_utils::{
     Ident,
     error::{SoulError, SoulErrorKind},
     ids::IdAlloc,
+    soul_names::PrimitiveTypes,
     span::Span,
 };
 
@@ -15,7 +16,9 @@ use crate::HirContext;
 
 mod array;
 mod call;
+mod format;
 mod r#if;
+mod range;
 
 impl<'a> HirContext<'a> {
     pub(crate) fn lower_expression(&mut self, expression: &ast::Expression) -> hir::ExpressionId {
@@ -37,18 +40,25 @@ impl<'a> HirContext<'a> {
             ast::ExpressionKind::If(ast_if) => self.lower_if(id, ast_if, span),
             ast::ExpressionKind::Unary(unary) => self.lower_unary(id, unary, span),
             ast::ExpressionKind::Array(array) => self.lower_array(id, array, span),
-            ast::ExpressionKind::Block(block) => return self.lower_block_expression(block),
+            ast::ExpressionKind::Block(block) => return self.lower_block_expression(block, false),
+            ast::ExpressionKind::Unsafe(block) => return self.lower_block_expression(block, true),
             ast::ExpressionKind::Index(index) => self.lower_index(id, index, span),
             ast::ExpressionKind::Null(_node_id) => self.lower_null(id, span),
             ast::ExpressionKind::Binary(binary) => self.lower_binary(id, binary, span),
+            ast::ExpressionKind::Range(range) => self.lower_range(id, range, span),
+            ast::ExpressionKind::FormatString(format) => self.lower_format_string(id, format),
             ast::ExpressionKind::While(ast_while) => self.lower_while(id, ast_while),
             ast::ExpressionKind::As(as_type_cast) => self.lower_cast(id, as_type_cast),
             ast::ExpressionKind::Deref { id: _, inner } => self.lower_deref(id, inner),
+            ast::ExpressionKind::Copy { id: _, inner } => self.lower_copy(id, inner),
+            ast::ExpressionKind::New(new) => self.lower_new(id, new, span),
             ast::ExpressionKind::FieldAccess(field_access) => {
                 self.lower_field_access(id, field_access, span)
             }
             ast::ExpressionKind::FunctionCall(function_call) => self.lower_call(id, function_call),
-            ast::ExpressionKind::Literal((_node_id, literal)) => self.lower_literal(id, literal),
+            ast::ExpressionKind::Literal {
+                literal, suffix, ..
+            } => self.lower_literal(id, literal, *suffix),
             ast::ExpressionKind::Variable {
                 id: _,
                 ident,
@@ -63,23 +73,25 @@ impl<'a> HirContext<'a> {
                 self.lower_struct_contructor(id, struct_constructor, span)
             }
 
-            ast::ExpressionKind::ExternalExpression(_external_expression) => {
+            // the name resolver replaces every path it resolves, the rest already has an error
+            ast::ExpressionKind::ExternalExpression(_) => hir::Expression::error(id),
+            ast::ExpressionKind::Default(_node_id) => {
                 self.log_error(soul_error_internal!(
-                    "ExternalExpression expression is unstable",
+                    "Default expression is unstable",
                     Some(span)
                 ));
                 hir::Expression::error(id)
             }
-            ast::ExpressionKind::Default(_node_id) => {
+            ast::ExpressionKind::ReturnLike(_) => {
                 self.log_error(soul_error_internal!(
-                    "Default expression is unstable",
+                    "return like should be unreachable in HirContext::lower_expression",
                     Some(span)
                 ));
                 hir::Expression::error(id)
             }
-            ast::ExpressionKind::ReturnLike(_) => {
+            ast::ExpressionKind::Defer { .. } => {
                 self.log_error(soul_error_internal!(
-                    "return like should be unreachable in HirContext::lower_expression",
+                    "defer should be unreachable in HirContext::lower_expression",
                     Some(span)
                 ));
                 hir::Expression::error(id)
@@ -145,7 +157,7 @@ impl<'a> HirContext<'a> {
         span: Span,
     ) -> hir::Expression {
         let amount = match &ctor.amount.node {
-            ast::ExpressionKind::Literal((_, literal)) => match literal {
+            ast::ExpressionKind::Literal { literal, .. } => match literal {
                 Literal::Uint(num) => *num,
                 _ => {
                     self.log_error(SoulError::new(
@@ -245,16 +257,16 @@ impl<'a> HirContext<'a> {
         let inner = self.lower_expression(expression);
         let of_type = self.tree.nodes.expressions[inner].ty;
 
-        let local = match &expression.node {
+        let place_kind = match &expression.node {
             ast::ExpressionKind::Variable { ident, .. } => match self.find_local(ident) {
-                Some(val) => val,
+                Some(val) => PlaceKind::Local(val),
                 None => {
                     self.log_error(SoulError::new(
                         format!("'{}' not found in scope", ident.as_str()),
                         SoulErrorKind::NotFoundInScope,
                         Some(ident.span),
                     ));
-                    LocalId::error()
+                    PlaceKind::Local(LocalId::error())
                 }
             },
             _ => {
@@ -262,15 +274,11 @@ impl<'a> HirContext<'a> {
 
                 let variable = hir::Variable { local: temp_local };
                 self.insert_desugar_variable(variable, of_type, inner, span);
-                temp_local
+                PlaceKind::Temp(temp_local)
             }
         };
 
-        let place = Place::new(
-            self.id_generator.alloc_place(),
-            PlaceKind::Local(local),
-            span,
-        );
+        let place = Place::new(self.id_generator.alloc_place(), place_kind, span);
 
         let ty = self.add_type(HirType::new(HirTypeKind::Ref {
             of_type,
@@ -375,6 +383,34 @@ impl<'a> HirContext<'a> {
         }
     }
 
+    fn lower_copy(&mut self, id: ExpressionId, inner: &ast::Expression) -> hir::Expression {
+        hir::Expression {
+            id,
+            ty: self.new_infer_type(vec![], None, inner.span),
+            kind: hir::ExpressionKind::Copy(self.lower_expression(inner)),
+        }
+    }
+
+    fn lower_new(&mut self, id: ExpressionId, new: &ast::New, span: Span) -> hir::Expression {
+        let kind = match &new.kind {
+            ast::NewKind::Value(value) => hir::ExpressionKind::New {
+                value: self.lower_expression(value),
+                fallible: new.fallible,
+            },
+            ast::NewKind::Array(ctor) => hir::ExpressionKind::NewArray {
+                len: self.lower_expression(&ctor.amount),
+                element: self.lower_expression(&ctor.element),
+                fallible: new.fallible,
+            },
+        };
+
+        hir::Expression {
+            id,
+            ty: self.new_infer_type(vec![], None, span),
+            kind,
+        }
+    }
+
     fn lower_cast(&mut self, id: ExpressionId, cast: &AsTypeCast) -> hir::Expression {
         let value = self.lower_expression(&cast.left);
         let cast_to = self.lower_type(&cast.type_cast, cast.left.span);
@@ -399,10 +435,19 @@ impl<'a> HirContext<'a> {
         }
     }
 
-    fn lower_literal(&mut self, id: ExpressionId, literal: &ast::Literal) -> hir::Expression {
+    fn lower_literal(
+        &mut self,
+        id: ExpressionId,
+        literal: &ast::Literal,
+        suffix: Option<PrimitiveTypes>,
+    ) -> hir::Expression {
+        let ty = match suffix {
+            Some(suffix) => self.add_type(HirType::new(HirTypeKind::Primitive(suffix))),
+            None => self.type_from_literal(literal),
+        };
         hir::Expression {
             id,
-            ty: hir::LazyTypeId::Known(self.type_from_literal(literal)),
+            ty: hir::LazyTypeId::Known(ty),
             kind: hir::ExpressionKind::Literal(literal.clone()),
         }
     }
@@ -436,6 +481,25 @@ impl<'a> HirContext<'a> {
     }
 
     fn lower_index(&mut self, id: ExpressionId, index: &ast::Index, span: Span) -> hir::Expression {
+        if let ast::ExpressionKind::Range(range) = &index.index.node {
+            // `@array[a..b]` parses as indexing into `@array`
+            if let ast::ExpressionKind::Ref {
+                is_mutable,
+                expression,
+                ..
+            } = &index.collection.node
+            {
+                return self.lower_slice(id, expression, range, *is_mutable, span);
+            }
+
+            self.log_error(SoulError::new(
+                "slicing an array has to be borrowed e.g., '@array[start..end]' or '&array[start..end]'",
+                SoulErrorKind::InvalidContext,
+                Some(span),
+            ));
+            return hir::Expression::error(id);
+        }
+
         let place = Place::new(
             self.id_generator.alloc_place(),
             PlaceKind::Index {
@@ -452,8 +516,9 @@ impl<'a> HirContext<'a> {
         }
     }
 
-    fn lower_block_expression(&mut self, block: &ast::Block) -> hir::ExpressionId {
+    fn lower_block_expression(&mut self, block: &ast::Block, is_unsafe: bool) -> hir::ExpressionId {
         let body = self.lower_block(block);
+        self.tree.nodes.blocks[body].is_unsafe = is_unsafe;
 
         let ty = match &self.tree.nodes.blocks[body].terminator {
             Some(Terminator::Return(value)) | Some(Terminator::Expression(value)) => {
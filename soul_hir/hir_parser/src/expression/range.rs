@@ -0,0 +1,94 @@
+use hir::{ExpressionId, HirType, LazyTypeId};
+use soul_utils::{
+    Ident,
+    error::{SoulError, SoulErrorKind},
+    span::Span,
+};
+
+use crate::HirContext;
+
+impl<'a> HirContext<'a> {
+    pub(super) fn lower_range(
+        &mut self,
+        id: ExpressionId,
+        range: &ast::Range,
+        span: Span,
+    ) -> hir::Expression {
+        let start = match &range.start {
+            Some(start) => self.lower_expression(start),
+            None => self.lower_range_zero(span),
+        };
+
+        let end = match &range.end {
+            Some(end) => self.lower_expression(end),
+            None => {
+                self.log_error(SoulError::new(
+                    "range without an end can only be used to slice an array e.g., '@array[start..]'",
+                    SoulErrorKind::InvalidContext,
+                    Some(span),
+                ));
+                return hir::Expression::error(id);
+            }
+        };
+
+        let range_struct = self.tree.info.types.range_struct;
+        let ty = self.add_type(HirType::new(hir::HirTypeKind::CustomType(
+            hir::CustomTypeId::Struct(range_struct),
+        )));
+
+        hir::Expression {
+            id,
+            ty: ty.to_lazy(),
+            kind: hir::ExpressionKind::StructConstructor {
+                ty: range_struct,
+                defaults: false,
+                values: vec![
+                    (Ident::new("start".to_string(), span), start),
+                    (Ident::new("end".to_string(), span), end),
+                ],
+            },
+        }
+    }
+
+    /// Lowers `@collection[start..end]` or `&collection[start..end]`.
+    pub(super) fn lower_slice(
+        &mut self,
+        id: ExpressionId,
+        collection: &ast::Expression,
+        range: &ast::Range,
+        mutable: bool,
+        span: Span,
+    ) -> hir::Expression {
+        let place = self.lower_place(collection);
+        let start = range
+            .start
+            .as_ref()
+            .map(|start| self.lower_expression(start));
+        let end = range.end.as_ref().map(|end| self.lower_expression(end));
+
+        hir::Expression {
+            id,
+            ty: self.new_infer_type(vec![], None, span),
+            kind: hir::ExpressionKind::Slice {
+                place,
+                start,
+                end,
+                mutable,
+            },
+        }
+    }
+
+    fn lower_range_zero(&mut self, span: Span) -> ExpressionId {
+        let uint = LazyTypeId::Known(self.add_type(HirType::index_type()));
+
+        let id = self.alloc_expression(span);
+        self.insert_expression(
+            id,
+            hir::Expression {
+                id,
+                ty: uint,
+                kind: hir::ExpressionKind::Literal(ast::Literal::Uint(0)),
+            },
+        )
+    }
+}
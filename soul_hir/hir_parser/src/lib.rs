@@ -1,10 +1,10 @@
-use ast::{AbtractSyntaxTree, Visibility, scope::NodeId};
+use ast::{AbtractSyntaxTree, scope::NodeId};
 use hir::{
     BlockId, CustomTypeId, ExpressionId, GenericId, HirTree, LazyTypeId, LocalId, StatementId,
 };
 use soul_utils::{
     Ident,
-    crate_store::{CrateContext, CrateExports},
+    crate_store::{CrateContext, CrateStore, DefId},
     error::{SoulError, SoulErrorKind},
     ids::FunctionId,
     sementic_level::SementicFault,
@@ -25,10 +25,10 @@ mod r#type;
 pub fn lower_hir(
     faults: &mut CrateContext,
     ast_context: &AbtractSyntaxTree,
-    crate_exports: &CrateExports,
+    crates: &CrateStore,
     root: ModuleId,
 ) -> HirTree {
-    let mut context = HirContext::new(faults, ast_context, crate_exports, root);
+    let mut context = HirContext::new(faults, ast_context, crates, root);
 
     context.lower_internal_structs();
     context.lower_module(root);
@@ -49,7 +49,9 @@ struct HirContext<'a> {
     pub scopes: Vec<Scope>,
     pub id_generator: IdAllocalor,
     pub ast_context: &'a AbtractSyntaxTree,
-    pub _crate_exports: &'a CrateExports,
+    pub crates: &'a CrateStore,
+    /// Declarations of the dependency functions called so far.
+    pub dependency_functions: HashMap<DefId<FunctionId>, FunctionId>,
 
     pub context: &'a mut CrateContext,
     pub node_id_to_local: VecMap<NodeId, LocalId>,
@@ -59,7 +61,7 @@ impl<'a> HirContext<'a> {
     fn new(
         context: &'a mut CrateContext,
         ast_context: &'a AbtractSyntaxTree,
-        crate_exports: &'a CrateExports,
+        crates: &'a CrateStore,
         root_id: ModuleId,
     ) -> Self {
         let mut id_generator = IdAllocalor::new(ast_context.function_generators.clone());
@@ -81,7 +83,8 @@ impl<'a> HirContext<'a> {
         Self {
             context,
             ast_context,
-            _crate_exports: crate_exports,
+            crates,
+            dependency_functions: HashMap::new(),
             id_generator,
             scopes: vec![Scope::default()],
             node_id_to_local: VecMap::new(),
@@ -100,8 +103,7 @@ impl<'a> HirContext<'a> {
         for sub_module_id in sub_modules.iter().cloned() {
             let sub_ast_module = &ast_context.modules[sub_module_id];
             let sub_sub_modules: Vec<ModuleId> = sub_ast_module.modules.entries().collect();
-            let is_public = matches!(ast_module.visibility, Visibility::Public);
-            tree.insert_module(sub_module_id, is_public, sub_sub_modules);
+            tree.insert_module(sub_module_id, sub_ast_module.visibility, sub_sub_modules);
             Self::init_submodules(tree, ast_context, sub_module_id);
         }
     }
@@ -114,8 +116,8 @@ impl<'a> HirContext<'a> {
 
         for statement in &ast_module.global.statements {
             match &statement.node {
-                ast::StatementKind::Struct(object) => self.add_struct(object),
-                ast::StatementKind::Enum(object) => self.add_enum(object),
+                ast::StatementKind::Struct(object) => self.add_struct(object, &statement.meta_data),
+                ast::StatementKind::Enum(object) => self.add_enum(object, &statement.meta_data),
                 _ => (),
             }
         }
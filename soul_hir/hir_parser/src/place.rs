@@ -22,7 +22,12 @@ impl<'a> HirContext<'a> {
                 place.span,
             ),
             ast::ExpressionKind::Deref { id: _, inner } => {
-                Place::new(id, PlaceKind::Deref(self.lower_place(inner)), place.span)
+                let pointer = if is_place_expression(inner) {
+                    self.lower_place(inner)
+                } else {
+                    self.lower_temp_place(inner)
+                };
+                Place::new(id, PlaceKind::Deref(pointer), place.span)
             }
             ast::ExpressionKind::Variable {
                 id: _,
@@ -58,6 +63,23 @@ impl<'a> HirContext<'a> {
         self.insert_place(place)
     }
 
+    /// Stores `value` in a temp so a computed pointer, e.g. `*(ptr + 1) = 2`, can be dereferenced.
+    fn lower_temp_place(&mut self, value: &ast::Expression) -> hir::PlaceId {
+        let span = value.span;
+        let value = self.lower_expression(value);
+        let ty = self.tree.nodes.expressions[value].ty;
+
+        let local = self.id_generator.alloc_local();
+        self.insert_desugar_variable(hir::Variable { local }, ty, value, span);
+
+        let place = Place::new(
+            self.id_generator.alloc_place(),
+            PlaceKind::Temp(local),
+            span,
+        );
+        self.insert_place(place)
+    }
+
     pub(crate) fn find_local(&mut self, name: &Ident) -> Option<LocalId> {
         for store in self.scopes.iter().rev() {
             if let Some(id) = store.locals.get(name.as_str()).copied() {
@@ -78,3 +100,13 @@ impl<'a> HirContext<'a> {
         id
     }
 }
+
+fn is_place_expression(expression: &ast::Expression) -> bool {
+    matches!(
+        expression.node,
+        ast::ExpressionKind::Index(_)
+            | ast::ExpressionKind::Deref { .. }
+            | ast::ExpressionKind::Variable { .. }
+            | ast::ExpressionKind::FieldAccess(_)
+    )
+}
@@ -36,7 +36,7 @@ impl<'a> HirContext<'a> {
 
         for statement in &body.statements {
             match &statement.node {
-                ast::StatementKind::Struct(object) => self.add_struct(object),
+                ast::StatementKind::Struct(object) => self.add_struct(object, &statement.meta_data),
                 _ => (),
             }
         }
@@ -49,6 +49,7 @@ impl<'a> HirContext<'a> {
             id,
             statements: vec![],
             terminator: None,
+            is_unsafe: false,
         };
         self.insert_block(id, block, body.span);
 
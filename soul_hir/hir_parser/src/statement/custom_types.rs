@@ -1,5 +1,5 @@
 use hir::{CustomTypeId, Field, HirType, Struct};
-use soul_utils::{Ident, Span, soul_error_internal};
+use soul_utils::{Ident, Span, soul_error_internal, span::Visibility};
 
 use crate::HirContext;
 
@@ -34,6 +34,8 @@ impl<'a> HirContext<'a> {
                 ty,
                 struct_id,
                 name: field.name.clone(),
+                attributes: field.meta_data.attributes.clone(),
+                visibility: field.visibility,
             };
 
             fields.push(hir_field.clone());
@@ -47,6 +49,11 @@ impl<'a> HirContext<'a> {
     }
 
     pub(crate) fn lower_internal_structs(&mut self) {
+        self.lower_internal_array_struct();
+        self.lower_internal_range_struct();
+    }
+
+    fn lower_internal_array_struct(&mut self) {
         let struct_id = self.tree.info.types.alloc_struct();
         let name = Ident::new("___Array".to_string(), Span::default(self.root_id));
 
@@ -59,12 +66,16 @@ impl<'a> HirContext<'a> {
                 id: self.id_generator.alloc_field(),
                 name: Ident::new("ptr".to_string(), Span::error()),
                 ty: ptr_type,
+                attributes: vec![],
+                visibility: Visibility::Public,
             },
             Field {
                 struct_id,
                 id: self.id_generator.alloc_field(),
                 name: Ident::new("len".to_string(), Span::error()),
                 ty: len_type.to_lazy(),
+                attributes: vec![],
+                visibility: Visibility::Public,
             },
         ];
 
@@ -76,6 +87,64 @@ impl<'a> HirContext<'a> {
             )))
             .apply_generics(vec![len_type]),
         );
-        self.insert_struct(struct_id, Struct { name, fields });
+        self.insert_struct(
+            struct_id,
+            Struct {
+                name,
+                fields,
+                auto_copy: false,
+                drop: None,
+                display: None,
+                attributes: vec![],
+                visibility: Visibility::Public,
+            },
+        );
+    }
+
+    fn lower_internal_range_struct(&mut self) {
+        let struct_id = self.tree.info.types.alloc_struct();
+        let name = Ident::new("___Range".to_string(), Span::default(self.root_id));
+
+        let index_type = self.add_type(HirType::index_type()).to_lazy();
+        let fields = vec![
+            Field {
+                struct_id,
+                id: self.id_generator.alloc_field(),
+                name: Ident::new("start".to_string(), Span::error()),
+                ty: index_type,
+                attributes: vec![],
+                visibility: Visibility::Public,
+            },
+            Field {
+                struct_id,
+                id: self.id_generator.alloc_field(),
+                name: Ident::new("end".to_string(), Span::error()),
+                ty: index_type,
+                attributes: vec![],
+                visibility: Visibility::Public,
+            },
+        ];
+
+        for field in &fields {
+            self.tree.nodes.fields.insert(field.id, field.clone());
+        }
+
+        self.tree.info.types.range_struct = struct_id;
+        // to insure struct is in compiler
+        self.add_type(HirType::new(hir::HirTypeKind::CustomType(
+            CustomTypeId::Struct(struct_id),
+        )));
+        self.insert_struct(
+            struct_id,
+            Struct {
+                name,
+                fields,
+                auto_copy: true,
+                drop: None,
+                display: None,
+                attributes: vec![],
+                visibility: Visibility::Public,
+            },
+        );
     }
 }
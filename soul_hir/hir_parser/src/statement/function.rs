@@ -1,16 +1,42 @@
-use ast::{FunctionKind, NamedTupleElement, ReferenceType, SoulType, TypeKind};
-use hir::TypeId;
+use ast::{ExternLanguage, FunctionKind, NamedTupleElement, ReferenceType, SoulType, TypeKind};
+use hir::{HirType, TypeId};
 use soul_utils::{
     Ident,
+    crate_store::ExportType,
     error::{SoulError, SoulErrorKind},
     ids::{FunctionId, IdAlloc},
     soul_error_internal,
+    soul_names::AttributeKind,
+    span::{Attribute, ItemMetaData, Span, Visibility},
 };
 
-use crate::HirContext;
+use crate::{HirContext, create_local_name};
 
 impl<'a> HirContext<'a> {
-    pub(super) fn lower_function(&mut self, function: &ast::Function) -> FunctionId {
+    pub(super) fn lower_function(
+        &mut self,
+        function: &ast::Function,
+        meta_data: &ItemMetaData,
+    ) -> FunctionId {
+        self.inner_function(function, meta_data, false)
+    }
+
+    /// Lowers an `extern "C" name(..)` declaration, its body is supplied by the linker.
+    pub(super) fn lower_external_function(
+        &mut self,
+        function: &ast::Function,
+        meta_data: &ItemMetaData,
+    ) -> FunctionId {
+        const IS_DECLARATION: bool = true;
+        self.inner_function(function, meta_data, IS_DECLARATION)
+    }
+
+    fn inner_function(
+        &mut self,
+        function: &ast::Function,
+        meta_data: &ItemMetaData,
+        is_declaration: bool,
+    ) -> FunctionId {
         let id = match function.signature.node.id {
             Some(val) => val,
             None => {
@@ -68,6 +94,7 @@ impl<'a> HirContext<'a> {
             self.insert_parameter(&this_name, local, recv_ty);
             parameters.push(hir::Parameter {
                 local,
+                name: this_name,
                 ty: recv_ty,
                 default: None,
             });
@@ -90,12 +117,17 @@ impl<'a> HirContext<'a> {
             self.insert_parameter(name, local, ty);
 
             let default = default.as_ref().map(|value| self.lower_expression(value));
-            parameters.push(hir::Parameter { local, ty, default });
+            parameters.push(hir::Parameter {
+                local,
+                name: name.clone(),
+                ty,
+                default,
+            });
         }
 
-        let body = match function.signature.node.external {
-            Some(language) => hir::FunctionBody::External(language),
-            None => hir::FunctionBody::Internal(self.lower_block(&function.block)),
+        let body = match signature.external {
+            Some(language) if is_declaration => hir::FunctionBody::External(language),
+            _ => hir::FunctionBody::Internal(self.lower_block(&function.block)),
         };
 
         let return_type = match self.lower_type(&signature.return_type, signature.return_type.span)
@@ -110,6 +142,7 @@ impl<'a> HirContext<'a> {
                 TypeId::error()
             }
         };
+        let return_borrows = self.lower_return_borrows(signature);
         let owner_type = match self.lower_type(&signature.methode_type, signature.methode_type.span)
         {
             hir::LazyTypeId::Known(type_id) => type_id,
@@ -133,12 +166,153 @@ impl<'a> HirContext<'a> {
             parameters,
             owner_type,
             return_type,
+            return_borrows,
             kind: signature.function_kind,
+            abi: signature.external,
+            variadic: signature.variadic,
+            attributes: meta_data.attributes.clone(),
+            visibility: signature.visibility,
         };
         self.tree.nodes.functions.insert(id, hir_function);
         id
     }
 
+    /// Declares `full_name` of the dependency `crate_name` the first time it is called, its
+    /// body is in the object code of that crate.
+    pub(crate) fn declare_dependency_function(
+        &mut self,
+        crate_name: &str,
+        full_name: &str,
+        span: Span,
+    ) -> Option<FunctionId> {
+        let Some((def_id, export)) = self.crates.get_export_function(crate_name, full_name) else {
+            self.log_error(SoulError::new(
+                format!("function '{full_name}' not found in crate '{crate_name}'"),
+                SoulErrorKind::NotFoundInScope,
+                Some(span),
+            ));
+            return None;
+        };
+
+        if let Some(id) = self.dependency_functions.get(&def_id) {
+            return Some(*id);
+        }
+
+        let opaque = export
+            .parameters
+            .iter()
+            .chain([&export.return_type])
+            .find_map(ExportType::find_opaque);
+        if let Some(type_name) = opaque {
+            self.log_error(SoulError::new(
+                format!(
+                    "'{full_name}' of crate '{crate_name}' can not be called from another crate, type '{type_name}' in its signature is not shared between crates"
+                ),
+                SoulErrorKind::InvalidContext,
+                Some(span),
+            ));
+            return None;
+        }
+
+        let mut parameters = vec![];
+        for ty in &export.parameters {
+            let ty = self.import_type(ty).to_lazy();
+            let local = self.id_generator.alloc_local();
+            let name = Ident::new(create_local_name(local), span);
+
+            self.tree.info.spans.locals.insert(local, span);
+            self.tree.nodes.locals.insert(
+                local,
+                hir::LocalInfo {
+                    ty,
+                    kind: hir::LocalKind::Parameter,
+                    span: Some(span),
+                },
+            );
+            parameters.push(hir::Parameter {
+                local,
+                name,
+                ty,
+                default: None,
+            });
+        }
+
+        let mut attributes = vec![];
+        if export.no_mangle {
+            let name = Ident::new(AttributeKind::NoMangle.as_str().to_string(), span);
+            attributes.push(Attribute {
+                name,
+                values: vec![],
+            });
+        }
+
+        let id = self.id_generator.alloc_function();
+        let hir_function = hir::Function {
+            id,
+            name: Ident::new(export.name.clone(), span),
+            kind: FunctionKind::Static,
+            owner_type: self.add_type(HirType::none_type()),
+            parameters,
+            variadic: false,
+            generics: vec![],
+            return_type: self.import_type(&export.return_type),
+            return_borrows: None,
+            body: hir::FunctionBody::Dependency(def_id),
+            abi: export.c_abi.then_some(ExternLanguage::C),
+            attributes,
+            visibility: Visibility::Private,
+        };
+        self.tree.info.spans.functions.insert(id, span);
+        self.tree.nodes.functions.insert(id, hir_function);
+
+        let statement = self.alloc_statement(&ItemMetaData::default(), span);
+        let global = hir::Global::new(hir::GlobalKind::Function(id), statement);
+        self.insert_global(self.root_id, global);
+
+        self.dependency_functions.insert(def_id, id);
+        Some(id)
+    }
+
+    /// Parameters sharing the lifetime of the returned reference (`'a` in `Get(a: @'a T): @'a T`),
+    /// `None` when the return type has no lifetime.
+    fn lower_return_borrows(&mut self, signature: &ast::FunctionSignature) -> Option<Vec<usize>> {
+        let lifetime = match &signature.return_type.kind {
+            TypeKind::Reference(reference) => reference.lifetime.as_ref()?,
+            _ => return None,
+        };
+
+        // `this` can not name a lifetime
+        let offset = match signature.function_kind {
+            FunctionKind::Static => 0,
+            _ => 1,
+        };
+
+        let mut borrows = vec![];
+        for (i, parameter) in signature.parameters.iter().enumerate() {
+            if let TypeKind::Reference(reference) = &parameter.ty.kind
+                && reference
+                    .lifetime
+                    .as_ref()
+                    .is_some_and(|name| name.as_str() == lifetime.as_str())
+            {
+                borrows.push(i + offset);
+            }
+        }
+
+        if borrows.is_empty() {
+            self.log_error(SoulError::new(
+                format!(
+                    "lifetime '{}' is not declared by any parameter",
+                    lifetime.as_str()
+                ),
+                SoulErrorKind::InvalidLifetime,
+                Some(lifetime.span),
+            ));
+            return None;
+        }
+        Some(borrows)
+    }
+
     fn insert_function(&mut self, name: &Ident, function: FunctionId) {
         let scope = match self.scopes.last_mut() {
             Some(val) => val,
@@ -1,38 +1,21 @@
-use ast::UseBlock;
 use hir::{Assign, StatementId};
 use soul_utils::{
     error::{SoulError, SoulErrorKind},
     soul_names::KeyWord,
-    span::ModuleId,
+    span::{ItemMetaData, ModuleId},
 };
 
 use crate::HirContext;
 mod block;
 mod custom_types;
 mod function;
+mod use_block;
 
 impl<'a> HirContext<'a> {
     pub fn lower_global(&mut self, module_id: ModuleId, global: &ast::Statement) {
-        let kind = match &global.node {
-            ast::StatementKind::UseBlock(UseBlock {
-                use_type: _,
-                generics,
-                impls,
-                methodes,
-            }) => {
-                if !generics.is_empty() {
-                    todo!()
-                }
-
-                for methode in methodes {
-                    let kind = hir::GlobalKind::Function(self.lower_function(methode));
-                    let id = self.alloc_statement(&global.meta_data, global.span);
-                    self.insert_global(module_id, hir::Global::new(kind, id));
-                }
-
-                if !impls.is_empty() {
-                    todo!()
-                }
+        let (kind, visibility) = match &global.node {
+            ast::StatementKind::UseBlock(use_block) => {
+                self.lower_use_block(module_id, use_block, &global.meta_data, global.span);
                 return;
             }
             ast::StatementKind::Import(_) => {
@@ -46,13 +29,20 @@ impl<'a> HirContext<'a> {
                 // already added in lower_module
                 return;
             }
-            ast::StatementKind::Variable(variable) => {
-                hir::GlobalKind::Variable(self.lower_variable(variable))
-            }
-            ast::StatementKind::Function(function)
-            | ast::StatementKind::ExternalFunction(function) => {
-                hir::GlobalKind::Function(self.lower_function(function))
-            }
+            ast::StatementKind::Variable(variable) => (
+                hir::GlobalKind::Variable(self.lower_variable(variable)),
+                variable.visibility,
+            ),
+            ast::StatementKind::Function(function) => (
+                hir::GlobalKind::Function(self.lower_function(function, &global.meta_data)),
+                function.signature.node.visibility,
+            ),
+            ast::StatementKind::ExternalFunction(function) => (
+                hir::GlobalKind::Function(
+                    self.lower_external_function(function, &global.meta_data),
+                ),
+                function.signature.node.visibility,
+            ),
 
             ast::StatementKind::Assignment(_) | ast::StatementKind::Expression { .. } => {
                 self.log_error(SoulError::new(
@@ -68,7 +58,10 @@ impl<'a> HirContext<'a> {
         };
 
         let id = self.alloc_statement(&global.meta_data, global.span);
-        self.insert_global(module_id, hir::Global::new(kind, id));
+        self.insert_global(
+            module_id,
+            hir::Global::new(kind, id).with_visibility(visibility),
+        );
     }
 
     pub fn lower_statement(
@@ -77,32 +70,15 @@ impl<'a> HirContext<'a> {
         global: &ast::Statement,
     ) -> Option<hir::Statement> {
         let kind = match &global.node {
-            ast::StatementKind::UseBlock(UseBlock {
-                use_type: _,
-                generics,
-                impls,
-                methodes,
-            }) => {
-                if !generics.is_empty() {
-                    todo!()
-                }
-
-                for methode in methodes {
-                    let kind = hir::GlobalKind::Function(self.lower_function(methode));
-                    let id = self.alloc_statement(&global.meta_data, global.span);
-                    self.insert_global(module_id, hir::Global::new(kind, id));
-                }
-
-                if !impls.is_empty() {
-                    todo!()
-                }
+            ast::StatementKind::UseBlock(use_block) => {
+                self.lower_use_block(module_id, use_block, &global.meta_data, global.span);
                 return None;
             }
             ast::StatementKind::Import(_) => {
                 return None;
             }
             ast::StatementKind::Struct(object) => {
-                self.add_struct(object);
+                self.add_struct(object, &global.meta_data);
                 return None;
             }
             ast::StatementKind::Enum(_) => {
@@ -115,7 +91,12 @@ impl<'a> HirContext<'a> {
             | ast::StatementKind::ExternalFunction(function) => {
                 let id = self.alloc_statement(&global.meta_data, global.span);
 
-                let hir_function = self.lower_function(function);
+                let hir_function = match &global.node {
+                    ast::StatementKind::ExternalFunction(_) => {
+                        self.lower_external_function(function, &global.meta_data)
+                    }
+                    _ => self.lower_function(function, &global.meta_data),
+                };
                 let kind = hir::GlobalKind::Function(hir_function);
                 self.insert_global(module_id, hir::Global::new(kind, id));
                 return None;
@@ -129,10 +110,12 @@ impl<'a> HirContext<'a> {
                 expression,
                 ends_semicolon,
             } => {
-                use ast::ExpressionKind::ReturnLike;
+                use ast::ExpressionKind::{Defer, ReturnLike};
 
                 if let ReturnLike(return_like) = &expression.node {
                     self.lower_return_like(return_like)
+                } else if let Defer { id: _, inner } = &expression.node {
+                    hir::StatementKind::Defer(self.lower_expression(inner))
                 } else {
                     hir::StatementKind::Expression {
                         value: self.lower_expression(expression),
@@ -171,7 +154,7 @@ impl<'a> HirContext<'a> {
         hir::Variable { local }
     }
 
-    pub(crate) fn add_struct(&mut self, object: &ast::Struct) {
+    pub(crate) fn add_struct(&mut self, object: &ast::Struct, meta_data: &ItemMetaData) {
         let name = object.name.clone();
 
         let mut generics = vec![];
@@ -186,11 +169,16 @@ impl<'a> HirContext<'a> {
             hir::Struct {
                 name,
                 fields: vec![],
+                auto_copy: false,
+                drop: None,
+                display: None,
+                attributes: meta_data.attributes.clone(),
+                visibility: object.visibility,
             },
         );
     }
 
-    pub(crate) fn add_enum(&mut self, object: &ast::Enum) {
+    pub(crate) fn add_enum(&mut self, object: &ast::Enum, meta_data: &ItemMetaData) {
         let name = object.name.clone();
 
         let enum_id = self.tree.info.types.alloc_enum();
@@ -199,6 +187,8 @@ impl<'a> HirContext<'a> {
             hir::Enum {
                 name,
                 variants: object.variants.clone(),
+                attributes: meta_data.attributes.clone(),
+                visibility: object.visibility,
             },
         );
     }
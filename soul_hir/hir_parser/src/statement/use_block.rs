@@ -0,0 +1,182 @@
+use ast::{FunctionKind, ImplBlock, UseBlock};
+use hir::{CustomTypeId, HirTypeKind, LazyTypeId, StructId};
+use soul_utils::{
+    error::{SoulError, SoulErrorKind},
+    ids::FunctionId,
+    soul_names::BuiltinTrait,
+    span::{ItemMetaData, ModuleId, Span},
+};
+
+use crate::HirContext;
+
+impl<'a> HirContext<'a> {
+    pub(super) fn lower_use_block(
+        &mut self,
+        module_id: ModuleId,
+        use_block: &UseBlock,
+        meta_data: &ItemMetaData,
+        span: Span,
+    ) {
+        if !use_block.generics.is_empty() {
+            todo!()
+        }
+
+        for methode in &use_block.methodes {
+            let kind = hir::GlobalKind::Function(self.lower_function(methode, meta_data));
+            let id = self.alloc_statement(meta_data, span);
+            let global =
+                hir::Global::new(kind, id).with_visibility(methode.signature.node.visibility);
+            self.insert_global(module_id, global);
+        }
+
+        for impl_block in &use_block.impls {
+            self.lower_impl_block(&use_block.use_type, impl_block);
+
+            for methode in &impl_block.methodes {
+                let kind = hir::GlobalKind::Function(self.lower_function(methode, meta_data));
+                let id = self.alloc_statement(meta_data, span);
+                let global =
+                    hir::Global::new(kind, id).with_visibility(methode.signature.node.visibility);
+                self.insert_global(module_id, global);
+            }
+        }
+    }
+
+    fn lower_impl_block(&mut self, use_type: &ast::SoulType, impl_block: &ImplBlock) {
+        let trait_name = match &impl_block.impl_trait.kind {
+            ast::TypeKind::Stub(stub) => stub.name.as_str(),
+            _ => return,
+        };
+
+        let span = impl_block.impl_trait.span;
+        match BuiltinTrait::try_from_str(trait_name) {
+            Some(BuiltinTrait::AutoCopy) => {
+                if !impl_block.methodes.is_empty() {
+                    self.log_error(SoulError::new(
+                        format!("'{}' can not have methodes", trait_name),
+                        SoulErrorKind::InvalidContext,
+                        Some(span),
+                    ));
+                }
+
+                if let Some(struct_id) = self.use_type_struct(use_type, span) {
+                    if let Some(object) = self.tree.info.types.id_to_struct_mut(struct_id) {
+                        object.auto_copy = true;
+                    }
+                    self.check_copy_and_drop(struct_id, span);
+                }
+            }
+            Some(BuiltinTrait::Drop) => {
+                let drop_id = self.builtin_trait_methode(
+                    impl_block,
+                    BuiltinTrait::Drop,
+                    FunctionKind::MutRef,
+                    "Drop(&this)",
+                );
+
+                if let Some(struct_id) = self.use_type_struct(use_type, span) {
+                    if let Some(object) = self.tree.info.types.id_to_struct_mut(struct_id) {
+                        object.drop = drop_id;
+                    }
+                    self.check_copy_and_drop(struct_id, span);
+                }
+            }
+            Some(BuiltinTrait::Display) => {
+                let display_id = self.builtin_trait_methode(
+                    impl_block,
+                    BuiltinTrait::Display,
+                    FunctionKind::ConstRef,
+                    "Display(@this): [*]char",
+                );
+
+                if let Some(struct_id) = self.use_type_struct(use_type, span)
+                    && let Some(object) = self.tree.info.types.id_to_struct_mut(struct_id)
+                {
+                    object.display = display_id;
+                }
+            }
+            None => (),
+        }
+    }
+
+    /// Finds the methode named after `builtin` in `impl_block`, which has to take `this` as
+    /// `receiver`, `signature` is shown when it is missing.
+    fn builtin_trait_methode(
+        &mut self,
+        impl_block: &ImplBlock,
+        builtin: BuiltinTrait,
+        receiver: FunctionKind,
+        signature: &str,
+    ) -> Option<FunctionId> {
+        let trait_name = builtin.as_str();
+        let methode = impl_block
+            .methodes
+            .iter()
+            .find(|methode| methode.signature.node.name.as_str() == trait_name);
+
+        match methode {
+            Some(methode) if methode.signature.node.function_kind != receiver => {
+                self.log_error(SoulError::new(
+                    format!(
+                        "'{}' methode should take '{}'",
+                        trait_name,
+                        receiver.display().unwrap_or_default()
+                    ),
+                    SoulErrorKind::InvalidContext,
+                    Some(methode.signature.span),
+                ));
+                None
+            }
+            Some(methode) => methode.signature.node.id,
+            None => {
+                self.log_error(SoulError::new(
+                    format!("'{}' is missing methode '{}'", trait_name, signature),
+                    SoulErrorKind::InvalidContext,
+                    Some(impl_block.impl_trait.span),
+                ));
+                None
+            }
+        }
+    }
+
+    fn check_copy_and_drop(&mut self, struct_id: StructId, span: Span) {
+        let is_both = self
+            .tree
+            .info
+            .types
+            .id_to_struct(struct_id)
+            .is_some_and(|object| object.auto_copy && object.drop.is_some());
+
+        if is_both {
+            self.log_error(SoulError::new(
+                format!(
+                    "struct can not implement both '{}' and '{}'",
+                    BuiltinTrait::AutoCopy.as_str(),
+                    BuiltinTrait::Drop.as_str(),
+                ),
+                SoulErrorKind::InvalidType,
+                Some(span),
+            ));
+        }
+    }
+
+    fn use_type_struct(&mut self, use_type: &ast::SoulType, span: Span) -> Option<StructId> {
+        let ty = match self.lower_type(use_type, span) {
+            LazyTypeId::Known(val) => val,
+            LazyTypeId::Infer(_) => return None,
+        };
+
+        match self.tree.info.types.id_to_type(ty)?.kind {
+            HirTypeKind::CustomType(CustomTypeId::Struct(struct_id)) => Some(struct_id),
+            HirTypeKind::Error => None,
+            _ => {
+                self.log_error(SoulError::new(
+                    "builtin traits can only be implemented for structs",
+                    SoulErrorKind::InvalidType,
+                    Some(span),
+                ));
+                None
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
-use ast::Stub;
+use ast::{ArrayKind, Stub};
 use hir::{EnumId, GenericId, HirType, HirTypeKind, LazyTypeId, StructId, TypeId, TypesMap};
 use soul_utils::{
+    crate_store::{ExportArrayKind, ExportType},
     error::{SoulError, SoulErrorKind, SoulResult},
     soul_names::{PrimitiveTypes, TypeModifier},
     span::Span,
@@ -24,6 +25,32 @@ impl<'a> HirContext<'a> {
         self.tree.info.types.insert_type(ty)
     }
 
+    /// A type from the signature of a dependency function, opaque types are rejected before.
+    pub(crate) fn import_type(&mut self, ty: &ExportType) -> TypeId {
+        let kind = match ty {
+            ExportType::None => HirTypeKind::None,
+            ExportType::Primitive(prim) => HirTypeKind::Primitive(*prim),
+            ExportType::Array { element, kind } => HirTypeKind::Array {
+                element: self.import_type(element).to_lazy(),
+                kind: match *kind {
+                    ExportArrayKind::StackArray(size) => ArrayKind::StackArray(size),
+                    ExportArrayKind::HeapArray => ArrayKind::HeapArray,
+                    ExportArrayKind::MutSlice => ArrayKind::MutSlice,
+                    ExportArrayKind::ConstSlice => ArrayKind::ConstSlice,
+                },
+            },
+            ExportType::Ref { of_type, mutable } => HirTypeKind::Ref {
+                of_type: self.import_type(of_type).to_lazy(),
+                mutable: *mutable,
+            },
+            ExportType::Pointer(inner) => HirTypeKind::Pointer(self.import_type(inner).to_lazy()),
+            ExportType::Optional(inner) => HirTypeKind::Optional(self.import_type(inner).to_lazy()),
+            ExportType::Opaque(_) => HirTypeKind::Error,
+        };
+
+        self.add_type(HirType::new(kind))
+    }
+
     /// this function is needed is the borrow checker does not validate self.lower_type
     pub(crate) fn convert_type(
         ty: &ast::SoulType,
@@ -4,7 +4,7 @@ use hir_literal_interpreter::literal_resolve;
 use hir_parser::lower_hir;
 use soul_utils::{
     compile_options::CompilerOptions,
-    crate_store::{CrateContext, CrateExports},
+    crate_store::{CrateContext, CrateStore},
     error::SoulError,
     sementic_level::SementicFault,
     span::{ModuleId, Span},
@@ -23,13 +23,13 @@ pub fn to_hir(
     ast_context: &AbtractSyntaxTree,
     options: &CompilerOptions,
     context: &mut CrateContext,
-    crate_exports: &CrateExports,
+    crates: &CrateStore,
     root: ModuleId,
 ) -> HirResponse {
-    let hir = lower_hir(context, ast_context, crate_exports, root);
+    let hir = lower_hir(context, ast_context, crates, root);
     let typed = lower_typed_hir(&hir, options, context);
 
-    let literal_resolves = literal_resolve(&hir, &typed);
+    let literal_resolves = literal_resolve(&hir, &typed, options, context);
     if options.debug_view_literal_resolve() {
         for (id, literal) in literal_resolves.entries() {
             let span = hir.info.spans.expressions[id];
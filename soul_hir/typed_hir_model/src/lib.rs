@@ -8,7 +8,7 @@ use hir::{
 use soul_utils::{
     bimap::BiMap,
     ids::{FunctionId, IdAlloc},
-    soul_names::{PrimitiveTypes, TypeModifier},
+    soul_names::{PrimitiveSize, PrimitiveTypes, TypeModifier},
     span::Span,
     vec_map::VecMap,
     vec_set::VecSet,
@@ -60,6 +60,114 @@ impl ThirTypesMap {
     pub fn id_to_generic(&self, id: GenericId) -> Option<&str> {
         self.generics.get(id).map(|s| s.as_str())
     }
+
+    /// Returns `true` if values of this type are copied instead of moved when used by value.
+    pub fn is_auto_copy(&self, id: TypeId) -> bool {
+        let Some(ty) = self.id_to_type(id) else {
+            return true;
+        };
+
+        match ty.kind {
+            ThirTypeKind::None
+            | ThirTypeKind::Type
+            | ThirTypeKind::Error
+            | ThirTypeKind::Primitive(_)
+            | ThirTypeKind::Pointer(_)
+            | ThirTypeKind::CustomTypes(CustomTypeId::Enum(_)) => true,
+            ThirTypeKind::Ref { mutable, .. } => !mutable,
+            ThirTypeKind::Optional(inner) => self.is_auto_copy(inner),
+            ThirTypeKind::Array { element, kind } => match kind {
+                ArrayKind::StackArray(_) => self.is_auto_copy(element),
+                ArrayKind::ConstSlice => true,
+                ArrayKind::MutSlice | ArrayKind::HeapArray => false,
+            },
+            ThirTypeKind::Generic(_) => false,
+            ThirTypeKind::CustomTypes(CustomTypeId::Struct(struct_id)) => self
+                .id_to_struct(struct_id)
+                .map(|object| object.auto_copy)
+                .unwrap_or(true),
+        }
+    }
+
+    /// Returns `true` if a value of this type has to run drop glue when it goes out of scope,
+    /// that is it (or one of its fields) implements `Drop` or owns a heap buffer.
+    pub fn needs_drop(&self, id: TypeId) -> bool {
+        self.inner_needs_drop(id, &mut vec![])
+    }
+
+    fn inner_needs_drop(&self, id: TypeId, visiting: &mut Vec<StructId>) -> bool {
+        let Some(ty) = self.id_to_type(id) else {
+            return false;
+        };
+
+        match ty.kind {
+            ThirTypeKind::Array {
+                kind: ArrayKind::HeapArray,
+                ..
+            } => true,
+            ThirTypeKind::CustomTypes(CustomTypeId::Struct(struct_id)) => {
+                // recursive structs are reported by the type checker
+                if visiting.contains(&struct_id) {
+                    return false;
+                }
+
+                let Some(object) = self.id_to_struct(struct_id) else {
+                    return false;
+                };
+
+                visiting.push(struct_id);
+                let needs_drop = object.drop.is_some()
+                    || object
+                        .fields
+                        .iter()
+                        .any(|field| self.inner_needs_drop(field.ty, visiting));
+                visiting.pop();
+                needs_drop
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if a value of this type can hold a reference (`&`, `@`, `[&]` or `[@]`),
+    /// so it has to keep the borrows it was made from alive.
+    pub fn contains_reference(&self, id: TypeId) -> bool {
+        self.inner_contains_reference(id, &mut vec![])
+    }
+
+    fn inner_contains_reference(&self, id: TypeId, visiting: &mut Vec<StructId>) -> bool {
+        let Some(ty) = self.id_to_type(id) else {
+            return false;
+        };
+
+        match ty.kind {
+            ThirTypeKind::Ref { .. } | ThirTypeKind::Generic(_) => true,
+            ThirTypeKind::Optional(inner) => self.inner_contains_reference(inner, visiting),
+            ThirTypeKind::Array { element, kind } => match kind {
+                ArrayKind::ConstSlice | ArrayKind::MutSlice => true,
+                ArrayKind::StackArray(_) | ArrayKind::HeapArray => {
+                    self.inner_contains_reference(element, visiting)
+                }
+            },
+            ThirTypeKind::CustomTypes(CustomTypeId::Struct(struct_id)) => {
+                if visiting.contains(&struct_id) {
+                    return false;
+                }
+
+                let Some(object) = self.id_to_struct(struct_id) else {
+                    return false;
+                };
+
+                visiting.push(struct_id);
+                let contains = object
+                    .fields
+                    .iter()
+                    .any(|field| self.inner_contains_reference(field.ty, visiting));
+                visiting.pop();
+                contains
+            }
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -106,6 +214,14 @@ pub struct Struct {
     pub name: String,
     pub fields: Vec<Field>,
     pub packed: bool,
+    /// `#[repr(C)]` was written before this struct, it is laid out and passed like a C struct.
+    pub repr_c: bool,
+    /// `impl AutoCopy{}` was declared for this struct.
+    pub auto_copy: bool,
+    /// the `Drop` methode of `impl Drop{}` if declared for this struct.
+    pub drop: Option<FunctionId>,
+    /// the `Display` methode of `impl Display{}` if declared for this struct.
+    pub display: Option<FunctionId>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -113,6 +229,24 @@ pub struct Enum {
     pub id: EnumId,
     pub name: String,
     pub variants: Vec<String>,
+    /// `#[repr(C)]` was written before this enum, its tag is a C `int`.
+    pub repr_c: bool,
+}
+impl Enum {
+    /// The size of the tag, the smallest integer that fits the variants or `c_int` for `#[repr(C)]`.
+    pub fn tag_size(&self) -> PrimitiveSize {
+        if self.repr_c {
+            return PrimitiveSize::CIntSize;
+        }
+
+        let bit_width = (self.variants.len() as u64).next_power_of_two().max(8);
+        match bit_width {
+            1..=8 => PrimitiveSize::Bit8,
+            9..=16 => PrimitiveSize::Bit16,
+            17..=32 => PrimitiveSize::Bit32,
+            _ => PrimitiveSize::Bit64,
+        }
+    }
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -126,6 +260,11 @@ pub struct TypeTable {
     pub none_type: TypeId,
     pub bool_type: TypeId,
     pub u32_type: TypeId,
+    /// `c_int`, `i64`, `u64` and `f64`, the types C variadic arguments are promoted to.
+    pub c_int_type: TypeId,
+    pub i64_type: TypeId,
+    pub u64_type: TypeId,
+    pub f64_type: TypeId,
 
     pub expressions: VecMap<ExpressionId, TypeId>,
     pub statements: VecMap<StatementId, TypeId>,
@@ -139,6 +278,8 @@ pub struct TypeTable {
     pub place_fields: VecMap<PlaceId, FieldId>,
 
     pub auto_copy: VecSet<ExpressionId>,
+    /// `[N]T` and `[*]T` expressions given where a slice is expected, with that slice type.
+    pub slice_coercions: VecMap<ExpressionId, TypeId>,
     pub generic_instantiations: VecMap<GenericId, VecSet<TypeId>>,
 }
 
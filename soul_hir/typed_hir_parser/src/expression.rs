@@ -14,7 +14,7 @@ use soul_utils::{
     error::{SoulError, SoulErrorKind, SoulResult},
     ids::{FunctionId, IdAlloc},
     soul_error_internal,
-    soul_names::{PrimitiveTypes, TypeModifier},
+    soul_names::{KeyWord, PrimitiveTypes, TypeModifier},
     span::Span,
     vec_map::VecMap,
 };
@@ -51,8 +51,23 @@ impl<'a> TypedHirContext<'a> {
                 variant_name,
             } => self.infer_enum_variant(*enum_id, variant_name, span),
             hir::ExpressionKind::DeRef(inner) => self.infer_deref(*inner, span),
+            hir::ExpressionKind::Copy(inner) => self.infer_copy(*inner, span),
+            hir::ExpressionKind::New { value, fallible } => self.infer_new(*value, *fallible),
+            hir::ExpressionKind::NewArray {
+                element,
+                len,
+                fallible,
+            } => self.infer_new_array(*element, *len, *fallible),
+            hir::ExpressionKind::Panic(message) => self.infer_panic(expression_id, *message),
+            hir::ExpressionKind::Format(pieces) => self.infer_format(pieces, value.ty),
             hir::ExpressionKind::Function(function) => self.functions[*function].to_lazy(),
             hir::ExpressionKind::Ref { place, mutable } => self.infer_ref(*place, *mutable, span),
+            hir::ExpressionKind::Slice {
+                place,
+                start,
+                end,
+                mutable,
+            } => self.infer_slice(*place, *start, *end, *mutable, span),
             hir::ExpressionKind::Cast { value, cast_to } => self.infer_cast(*value, *cast_to),
             hir::ExpressionKind::While { condition, body } => {
                 self.infer_while(*condition, *body, span)
@@ -73,13 +88,6 @@ impl<'a> TypedHirContext<'a> {
                 arguments,
                 ..
             } => self.infer_call(*function, *has_callee, generics, arguments, span),
-            hir::ExpressionKind::ExternalCall {
-                crate_name,
-                function_name,
-                generics,
-                arguments,
-                ..
-            } => self.infer_external_call(crate_name, function_name, generics, arguments, span),
             hir::ExpressionKind::If {
                 condition,
                 then_block,
@@ -205,12 +213,17 @@ impl<'a> TypedHirContext<'a> {
             ));
         }
 
-        if function.parameters.len() != arguments.len() {
+        let parameter_count = function.parameters.len();
+        let count_matches = match function.variadic {
+            true => arguments.len() >= parameter_count,
+            false => arguments.len() == parameter_count,
+        };
+        if !count_matches {
+            let at_least = if function.variadic { "at least " } else { "" };
             self.log_error(SoulError::new(
                 format!(
-                    "functionCall has {} arguments but expects {} arguments",
+                    "functionCall has {} arguments but expects {at_least}{parameter_count} arguments",
                     arguments.len(),
-                    function.parameters.len()
                 ),
                 SoulErrorKind::InvalidContext,
                 Some(span),
@@ -226,20 +239,12 @@ impl<'a> TypedHirContext<'a> {
             self.unify(*argument, should_be, ty, span);
         }
 
-        return_type
-    }
+        // variadic arguments keep their own type, MIR promotes them like C does
+        for argument in &arguments[parameter_count..] {
+            self.infer_expression(*argument);
+        }
 
-    fn infer_external_call(
-        &mut self,
-        _crate_name: &str,
-        _function_name: &str,
-        _generics: &Vec<TypeId>,
-        _arguments: &Vec<ExpressionId>,
-        _span: Span,
-    ) -> LazyTypeId {
-        // External calls - for now, assume returns unit
-        // In a real implementation, look up signature from typed HIR
-        self.none_type.to_lazy()
+        return_type
     }
 
     fn infer_binary(
@@ -251,7 +256,16 @@ impl<'a> TypedHirContext<'a> {
     ) -> LazyTypeId {
         let left_id = self.infer_expression(left);
         let right_id = self.infer_expression(right);
-        let binary_typecheck = to_binary_typecheck(&operator.node);
+        let binary_typecheck = match to_binary_typecheck(&operator.node) {
+            Some(val) => val,
+            None => {
+                self.log_error(soul_error_internal!(
+                    format!("'{}' should not be a binary", operator.node.as_str()),
+                    Some(span)
+                ));
+                return LazyTypeId::error();
+            }
+        };
         match binary_typecheck {
             BinaryTypeCheck::Logical => {
                 let bool = self.bool_type.to_lazy();
@@ -276,10 +290,14 @@ impl<'a> TypedHirContext<'a> {
                     None => return LazyTypeId::error(),
                 };
 
-                if self.id_to_type(left_strict).is_pointer()
-                    && self.id_to_type(right_strict).is_non_float_numeric_type()
-                {
-                    return left_strict.to_lazy();
+                if self.id_to_type(left_strict).is_pointer() {
+                    return self.infer_pointer_arithmetic(
+                        left_strict,
+                        operator,
+                        right,
+                        right_strict,
+                        span,
+                    );
                 }
 
                 self.infer_bitwise_numaric(left, left_id, operator, right, right_id, span)
@@ -287,6 +305,44 @@ impl<'a> TypedHirContext<'a> {
         }
     }
 
+    /// `*T + int` and `*T - int` move the pointer by whole elements, `*T - *T` gives the
+    /// distance in elements as `int`.
+    fn infer_pointer_arithmetic(
+        &mut self,
+        left: TypeId,
+        operator: &BinaryOperator,
+        right: ExpressionId,
+        right_id: TypeId,
+        span: Span,
+    ) -> LazyTypeId {
+        let is_offset = matches!(
+            operator.node,
+            BinaryOperatorKind::Add | BinaryOperatorKind::Sub
+        );
+        let right_type = self.id_to_type(right_id);
+        if is_offset && right_type.is_non_float_numeric_type() {
+            return left.to_lazy();
+        }
+
+        if operator.node == BinaryOperatorKind::Sub && right_type.is_pointer() {
+            self.unify(right, left.to_lazy(), right_id.to_lazy(), span);
+            return self
+                .add_type(HirType::primitive_type(PrimitiveTypes::Int))
+                .to_lazy();
+        }
+
+        self.log_error(SoulError::new(
+            format!(
+                "'{}' is not allowed on pointer '{}', pointers can only be offset by an integer or subtracted from each other",
+                operator.node.as_str(),
+                self.id_to_type(left).display(&self.types, &self.infers),
+            ),
+            SoulErrorKind::InvalidType,
+            Some(span),
+        ));
+        LazyTypeId::error()
+    }
+
     fn infer_bitwise_numaric(
         &mut self,
         left: ExpressionId,
@@ -485,6 +541,65 @@ impl<'a> TypedHirContext<'a> {
         }
     }
 
+    fn infer_slice(
+        &mut self,
+        place: PlaceId,
+        start: Option<ExpressionId>,
+        end: Option<ExpressionId>,
+        mutable: bool,
+        span: Span,
+    ) -> LazyTypeId {
+        let index_type = self.add_type(HirType::index_type()).to_lazy();
+        for bound in [start, end].into_iter().flatten() {
+            let bound_span = self.expression_span(bound);
+            let bound_type = self.infer_expression(bound);
+            _ = self.unify(bound, index_type, bound_type, bound_span);
+        }
+
+        let place_type = self.infer_place(place);
+        let ty = match self.resolve_type_strict(place_type, span) {
+            Some(val) => val,
+            None => return LazyTypeId::error(),
+        };
+
+        let collection = self.id_to_type(ty);
+        let (element, kind) = match &collection.kind {
+            HirTypeKind::Array { element, kind } => (*element, *kind),
+            other => {
+                self.log_error(SoulError::new(
+                    format!("typekind '{}' can not be sliced", other.display_variant()),
+                    SoulErrorKind::InvalidType,
+                    Some(span),
+                ));
+                return LazyTypeId::error();
+            }
+        };
+
+        let is_mutable = match kind {
+            ArrayKind::MutSlice => true,
+            ArrayKind::ConstSlice => false,
+            ArrayKind::HeapArray | ArrayKind::StackArray(_) => {
+                collection.modifier == Some(TypeModifier::Mut)
+            }
+        };
+        if mutable && !is_mutable {
+            let type_str = collection.display(&self.types, &self.hir.info.infers);
+            self.log_error(SoulError::new(
+                format!("can only take a mutable slice of mutable arrays type '{type_str}' is not mutable"),
+                SoulErrorKind::InvalidMutability,
+                Some(span),
+            ));
+            return LazyTypeId::error();
+        }
+
+        let kind = match mutable {
+            MUT => ArrayKind::MutSlice,
+            CONST => ArrayKind::ConstSlice,
+        };
+        self.add_type(HirType::new(HirTypeKind::Array { element, kind }))
+            .to_lazy()
+    }
+
     fn create_ref(
         &mut self,
         type_id: LazyTypeId,
@@ -539,24 +654,129 @@ impl<'a> TypedHirContext<'a> {
         }
     }
 
+    fn infer_copy(&mut self, copied: ExpressionId, span: Span) -> LazyTypeId {
+        let inner = self.infer_expression(copied);
+        let ty = match self.resolve_type_strict(inner, span) {
+            Some(val) => val,
+            None => return LazyTypeId::error(),
+        };
+
+        if let HirTypeKind::Ref { mutable: true, .. } = self.id_to_type(ty).kind {
+            self.log_error(SoulError::new(
+                format!("can not '{}' a mutable refrence", KeyWord::Copy.as_str()),
+                SoulErrorKind::InvalidType,
+                Some(span),
+            ));
+            return LazyTypeId::error();
+        }
+
+        self.copies.push((copied, span));
+        inner
+    }
+
+    /// The message has to be a `str`, the `panic` itself keeps the `never` type hir gave it.
+    fn infer_panic(&mut self, panic: ExpressionId, message: Option<ExpressionId>) -> LazyTypeId {
+        if let Some(message) = message {
+            let message_span = self.expression_span(message);
+            let message_type = self.infer_expression(message);
+            let char_type = self
+                .add_type(HirType::primitive_type(PrimitiveTypes::Char))
+                .to_lazy();
+            let str_type = self
+                .add_type(HirType::new(HirTypeKind::Array {
+                    element: char_type,
+                    kind: ArrayKind::ConstSlice,
+                }))
+                .to_lazy();
+            _ = self.unify(message, str_type, message_type, message_span);
+        }
+
+        self.hir.nodes.expressions[panic].ty
+    }
+
+    fn infer_new(&mut self, value: ExpressionId, fallible: bool) -> LazyTypeId {
+        let value = self.infer_expression(value);
+        let pointer = self.add_type(HirType::new(HirTypeKind::Pointer(value)));
+        self.new_result_type(pointer, fallible)
+    }
+
+    fn infer_new_array(
+        &mut self,
+        element: ExpressionId,
+        len: ExpressionId,
+        fallible: bool,
+    ) -> LazyTypeId {
+        let len_span = self.expression_span(len);
+        let len_type = self.infer_expression(len);
+        let index_type = self.add_type(HirType::index_type()).to_lazy();
+        _ = self.unify(len, index_type, len_type, len_span);
+
+        let element = self.infer_expression(element);
+        let array = self.add_type(HirType::new(HirTypeKind::Array {
+            element,
+            kind: ArrayKind::HeapArray,
+        }));
+        self.new_result_type(array, fallible)
+    }
+
+    /// Arguments can have any type here, that they can be shown is checked once the types
+    /// are known in mir.
+    fn infer_format(&mut self, pieces: &[hir::FormatPiece], ty: LazyTypeId) -> LazyTypeId {
+        for piece in pieces {
+            if let hir::FormatPiece::Argument { value, .. } = piece {
+                self.infer_expression(*value);
+            }
+        }
+        ty
+    }
+
+    /// `new?` gives `null` on a failed allocation so its result is wrapped in an optional.
+    fn new_result_type(&mut self, ty: TypeId, fallible: bool) -> LazyTypeId {
+        if !fallible {
+            return ty.to_lazy();
+        }
+
+        self.add_type(HirType::new(HirTypeKind::Optional(ty.to_lazy())))
+            .to_lazy()
+    }
+
     fn infer_struct_constructor(
         &mut self,
         ty: LazyTypeId,
         values: &Vec<(Ident, ExpressionId)>,
         span: Span,
     ) -> LazyTypeId {
-        let mut fields = match self.expect_struct(ty, span) {
-            Ok(struct_info) => struct_info
-                .fields
-                .iter()
-                .map(|field| (field.name.to_string(), field.ty))
-                .collect::<HashMap<String, LazyTypeId>>(),
+        let (mut fields, private_field) = match self.expect_struct(ty, span) {
+            Ok(struct_info) => (
+                struct_info
+                    .fields
+                    .iter()
+                    .map(|field| (field.name.to_string(), field.ty))
+                    .collect::<HashMap<String, LazyTypeId>>(),
+                struct_info
+                    .fields
+                    .iter()
+                    .find(|field| {
+                        !field.visibility.is_public() && field.name.span.module != span.module
+                    })
+                    .map(|field| (struct_info.name.to_string(), field.name.to_string())),
+            ),
             Err(err) => {
                 self.log_error(err);
                 return LazyTypeId::error();
             }
         };
 
+        if let Some((struct_name, field_name)) = private_field {
+            self.log_error(SoulError::new(
+                format!(
+                    "'{struct_name}' can not be constructed outside of its module, field '{field_name}' is private"
+                ),
+                SoulErrorKind::InvalidModuleAccess,
+                Some(span),
+            ));
+        }
+
         for (name, value) in values {
             let field_type = match fields.remove(name.as_str()) {
                 Some(val) => val,
@@ -688,10 +908,11 @@ enum BinaryTypeCheck {
     Bitwise,
 }
 
-fn to_binary_typecheck(operator: &BinaryOperatorKind) -> BinaryTypeCheck {
+/// Ranges are parsed into their own expression so they never reach here.
+fn to_binary_typecheck(operator: &BinaryOperatorKind) -> Option<BinaryTypeCheck> {
     use ast::BinaryOperatorKind as Binary;
 
-    match operator {
+    Some(match operator {
         Binary::Add
         | Binary::Sub
         | Binary::Mul
@@ -709,7 +930,8 @@ fn to_binary_typecheck(operator: &BinaryOperatorKind) -> BinaryTypeCheck {
 
         Binary::BitOr | Binary::BitAnd | Binary::BitXor => BinaryTypeCheck::Bitwise,
 
-        Binary::Range | Binary::TypeOf => todo!("{} not yet impl", operator.as_str()),
+        Binary::Range => return None,
+        Binary::TypeOf => todo!("{} not yet impl", operator.as_str()),
         Binary::Invalid => todo!("should not be invalid"),
-    }
+    })
 }
@@ -1,19 +1,23 @@
+use std::mem::take;
+
 use hir::{DisplayType, FieldId, HirTypeKind, LazyTypeId, TypeId};
 use soul_utils::{
     error::{SoulError, SoulErrorKind, SoulResult},
     ids::IdAlloc,
     soul_error_internal,
-    span::Span,
+    soul_names::{AttributeKind, BuiltinTrait, KeyWord},
+    span::{Attribute, Span},
     vec_map::{VecMap, VecMapIndex},
 };
-use typed_hir::{FieldInfo, ThirType, ThirTypeKind, ThirTypesMap, TypedHir};
+use typed_hir::{
+    FieldInfo, ThirType, ThirTypeKind, ThirTypesMap, TypedHir, display_thir::DisplayThirType,
+};
 
 use crate::{TypedHirContext, infer_table::InferBinding};
 
 impl<'a> TypedHirContext<'a> {
     pub(crate) fn finalize(mut self) -> TypedHir {
         self.finalize_infers();
-        use std::mem::take;
 
         let expressions = take(&mut self.expressions);
         let statements = take(&mut self.statements);
@@ -21,11 +25,16 @@ impl<'a> TypedHirContext<'a> {
         let places = take(&mut self.places);
         let locals = take(&mut self.locals);
         let blocks = take(&mut self.blocks);
+        let slice_coercions = take(&mut self.slice_coercions);
 
         let table = typed_hir::TypeTable {
             none_type: self.none_type,
             bool_type: self.bool_type,
             u32_type: self.u32_type,
+            c_int_type: self.c_int_type,
+            i64_type: self.i64_type,
+            u64_type: self.u64_type,
+            f64_type: self.f64_type,
 
             expressions: self.resolve_map(expressions),
             sizeofs: self.resolve_map(sizeofs),
@@ -39,11 +48,13 @@ impl<'a> TypedHirContext<'a> {
             place_fields: take(&mut self.place_fields),
 
             auto_copy: take(&mut self.auto_copys),
+            slice_coercions: self.resolve_map(slice_coercions),
             generic_instantiations: take(&mut self.generic_defines),
         };
 
         // 3. convert TypesMap → ThirTypesMap
         let types_map = self.lower_types_map();
+        self.check_copies(&table, &types_map);
 
         TypedHir {
             types_map,
@@ -100,13 +111,20 @@ impl<'a> TypedHirContext<'a> {
                 fields.push(typed_hir::Field { id, ty })
             }
 
+            // `#[repr(C)]` opts out of the default packing, `#[packed]` still applies
+            let repr_c = Attribute::is_repr_c(&struct_.attributes);
             out.structs.insert(
                 id,
                 typed_hir::Struct {
                     id,
                     fields,
                     name: struct_.name.to_string(),
-                    packed: self.options.default_packed(),
+                    packed: (self.options.default_packed() && !repr_c)
+                        || Attribute::contains(&struct_.attributes, AttributeKind::Packed),
+                    repr_c,
+                    auto_copy: struct_.auto_copy,
+                    drop: struct_.drop,
+                    display: struct_.display,
                 },
             );
         }
@@ -122,6 +140,7 @@ impl<'a> TypedHirContext<'a> {
                         .iter()
                         .map(|v| v.as_str().to_string())
                         .collect(),
+                    repr_c: Attribute::is_repr_c(&enum_.attributes),
                 },
             );
         }
@@ -133,11 +152,61 @@ impl<'a> TypedHirContext<'a> {
             {
                 self.log_error(err);
             }
+
+            if struct_.auto_copy {
+                self.check_auto_copy_fields(&out, struct_);
+            }
         }
 
         out
     }
 
+    /// A bitwise copy of a value with drop glue would run that glue twice.
+    fn check_copies(&mut self, table: &typed_hir::TypeTable, thir_map: &ThirTypesMap) {
+        for (copied, span) in take(&mut self.copies) {
+            let Some(ty) = table.expressions.get(copied).copied() else {
+                continue;
+            };
+            if !thir_map.needs_drop(ty) || thir_map.is_auto_copy(ty) {
+                continue;
+            }
+
+            let type_name = thir_map
+                .id_to_type(ty)
+                .map(|ty| ty.display(thir_map))
+                .unwrap_or_default();
+            self.log_error(SoulError::new(
+                format!(
+                    "can not '{}' a value of type '{type_name}', it needs to be dropped and is not '{}'",
+                    KeyWord::Copy.as_str(),
+                    BuiltinTrait::AutoCopy.as_str(),
+                ),
+                SoulErrorKind::InvalidType,
+                Some(span),
+            ));
+        }
+    }
+
+    fn check_auto_copy_fields(&mut self, thir_map: &ThirTypesMap, struct_: &typed_hir::Struct) {
+        for field in &struct_.fields {
+            if thir_map.is_auto_copy(field.ty) {
+                continue;
+            }
+
+            let span = self.fields.get(field.id).map(|f| f.span);
+            self.log_error(SoulError::new(
+                format!(
+                    "struct '{}' can not implement '{}' because this field is not '{}'",
+                    struct_.name,
+                    BuiltinTrait::AutoCopy.as_str(),
+                    BuiltinTrait::AutoCopy.as_str(),
+                ),
+                SoulErrorKind::InvalidType,
+                span,
+            ));
+        }
+    }
+
     fn check_for_recursive_inclusion(
         &mut self,
         thir_map: &ThirTypesMap,
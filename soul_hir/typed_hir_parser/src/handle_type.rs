@@ -27,6 +27,10 @@ impl<'a> TypedHirContext<'a> {
                 self.auto_copys.insert(value);
                 true
             }
+            Ok(UnifyResult::NeedsSliceCoercion) => {
+                self.slice_coercions.insert(value, expect);
+                true
+            }
             Err(err) => {
                 self.log_error(err);
                 self.posion_expression(value);
@@ -238,7 +242,21 @@ impl<'a> TypedHirContext<'a> {
             }
         };
 
-        Some(field.id)
+        let field_id = field.id;
+        if !field.visibility.is_public() && field.name.span.module != span.module {
+            let message = format!(
+                "field '{}' of '{}' is private",
+                field_ident,
+                object_struct.name.as_str()
+            );
+            self.log_error(SoulError::new(
+                message,
+                SoulErrorKind::InvalidModuleAccess,
+                Some(span),
+            ));
+        }
+
+        Some(field_id)
     }
 
     pub(crate) fn is_mutable_or_modifier_none(&self, ty: LazyTypeId) -> bool {
@@ -1,3 +1,4 @@
+use ast::ArrayKind;
 use hir::{
     DisplayType, HirType, HirTypeKind, InferType, InferTypeId, InferTypesMap, LazyTypeId, TypeId,
     TypesMap,
@@ -17,6 +18,8 @@ pub enum UnifyResult {
     Ok,
     /// error if auto copy not impl
     NeedsAutoCopy,
+    /// `[N]T` or `[*]T` given where a `[@]T` or `[&]T` is expected, sliced whole
+    NeedsSliceCoercion,
 }
 
 #[derive(Debug, Clone)]
@@ -114,14 +117,39 @@ impl InferTable {
                     kind: b_kind,
                 },
             ) => {
-                if let Err(msg) = HirTypeKind::arraykind_compatible(a_kind, b_kind) {
+                let array_result = match HirTypeKind::arraykind_compatible(b_kind, a_kind) {
+                    Ok(val) => val,
+                    Err(msg) => {
+                        return Err(SoulError::new(
+                            msg,
+                            SoulErrorKind::UnifyTypeError,
+                            Some(span),
+                        ));
+                    }
+                };
+
+                let coerces = matches!(array_result, UnifyResult::NeedsSliceCoercion);
+                if coerces
+                    && *a_kind == ArrayKind::MutSlice
+                    && b_ty.modifier != Some(TypeModifier::Mut)
+                {
                     return Err(SoulError::new(
-                        msg,
-                        SoulErrorKind::UnifyTypeError,
+                        format!(
+                            "can only take a mutable slice of mutable arrays type '{}' is not mutable",
+                            b_ty.display(types, infers)
+                        ),
+                        SoulErrorKind::InvalidMutability,
                         Some(span),
                     ));
                 }
-                self.unify_type_type(types, infers, *a_el, *b_el, span)
+
+                let element_result =
+                    self.unify_inner_type_type(types, infers, *a_el, *b_el, span)?;
+                Ok(if coerces {
+                    array_result
+                } else {
+                    element_result
+                })
             }
 
             (
@@ -145,12 +173,12 @@ impl InferTable {
                         Some(span),
                     ));
                 }
-                self.unify_type_type(types, infers, *a_id, *b_id, span)
+                self.unify_inner_type_type(types, infers, *a_id, *b_id, span)
             }
 
             (HirTypeKind::Pointer(a_id), HirTypeKind::Pointer(b_id))
             | (HirTypeKind::Optional(a_id), HirTypeKind::Optional(b_id)) => {
-                self.unify_type_type(types, infers, *a_id, *b_id, span)
+                self.unify_inner_type_type(types, infers, *a_id, *b_id, span)
             }
 
             (HirTypeKind::Error, _) | (_, HirTypeKind::Error) => Ok(UnifyResult::Ok),
@@ -169,6 +197,26 @@ impl InferTable {
         }
     }
 
+    /// Unifies the type behind an array, reference, pointer or optional, an array there can
+    /// not be coerced to a slice because the value is not rebuild.
+    fn unify_inner_type_type(
+        &mut self,
+        types: &mut TypesMap,
+        infers: &InferTypesMap,
+        expected: LazyTypeId,
+        got_type: LazyTypeId,
+        span: Span,
+    ) -> SoulResult<UnifyResult> {
+        match self.unify_type_type(types, infers, expected, got_type, span)? {
+            UnifyResult::NeedsSliceCoercion => Err(SoulError::new(
+                "only a whole array can be coerced to a slice, not one nested in another type",
+                SoulErrorKind::UnifyTypeError,
+                Some(span),
+            )),
+            result => Ok(result),
+        }
+    }
+
     pub(crate) fn resolve_type_lazy(
         &mut self,
         types: &mut TypesMap,
@@ -332,6 +380,13 @@ impl InferTable {
             ));
         }
 
+        if let LazyTypeId::Known(known) = ty
+            && self.get_type(types, known)?.is_never_type()
+        {
+            // `never` fits any type, binding it would reject the real type later on
+            return Ok(UnifyResult::Ok);
+        }
+
         match self.get_binding(root)? {
             InferBinding::Unbound(_) => {
                 let known = self.resolve_type_strict(types, ty, Some(span))?;
@@ -65,10 +65,17 @@ struct TypedHirContext<'a> {
     infer_table: InferTable,
     options: &'a CompilerOptions,
     auto_copys: VecSet<ExpressionId>,
+    slice_coercions: VecMap<ExpressionId, LazyTypeId>,
+    /// `copy` expressions, checked once the types are known.
+    copies: Vec<(ExpressionId, Span)>,
     current_function: Option<FunctionId>,
     field_names: VecMap<FieldId, String>,
 
     u32_type: TypeId,
+    c_int_type: TypeId,
+    i64_type: TypeId,
+    u64_type: TypeId,
+    f64_type: TypeId,
     none_type: TypeId,
     bool_type: TypeId,
     places: VecMap<PlaceId, LazyTypeId>,
@@ -103,9 +110,15 @@ impl<'a> TypedHirContext<'a> {
 
             fields: VecMap::new(),
             auto_copys: VecSet::new(),
+            slice_coercions: VecMap::new(),
+            copies: vec![],
             none_type: TypeId::error(),
             bool_type: TypeId::error(),
             u32_type: TypeId::error(),
+            c_int_type: TypeId::error(),
+            i64_type: TypeId::error(),
+            u64_type: TypeId::error(),
+            f64_type: TypeId::error(),
             place_fields: VecMap::new(),
             generic_defines: VecMap::new(),
             sizeofs: VecMap::new(),
@@ -120,6 +133,10 @@ impl<'a> TypedHirContext<'a> {
         this.none_type = this.add_type(HirType::none_type());
         this.bool_type = this.add_type(HirType::bool_type());
         this.u32_type = this.add_type(HirType::primitive_type(PrimitiveTypes::Uint32));
+        this.c_int_type = this.add_type(HirType::primitive_type(PrimitiveTypes::CInt));
+        this.i64_type = this.add_type(HirType::primitive_type(PrimitiveTypes::Int64));
+        this.u64_type = this.add_type(HirType::primitive_type(PrimitiveTypes::Uint64));
+        this.f64_type = this.add_type(HirType::primitive_type(PrimitiveTypes::Float64));
         this
     }
 
@@ -206,6 +223,10 @@ impl<'a> TypedHirContext<'a> {
 
             (LazyTypeId::Known(known), LazyTypeId::Infer(infer))
             | (LazyTypeId::Infer(infer), LazyTypeId::Known(known)) => {
+                if self.id_to_type(known).is_never_type() {
+                    return LazyTypeId::Infer(infer);
+                }
+
                 let ty = known.to_lazy();
                 self.infer_table.add_infer_binding(infer, ty);
                 return ty;
@@ -258,6 +279,7 @@ impl<'a> TypedHirContext<'a> {
         let info = &self.hir.nodes.locals[variable.local];
         match &info.kind {
             hir::LocalKind::Variable(expression_id) => *expression_id,
+            hir::LocalKind::Temp(expression_id) => Some(*expression_id),
             other => {
                 self.log_error(soul_error_internal!(
                     format!("LocalKind::{} should be unreachable in TypedHirContext::get_variable_value", other.display_variant()), 
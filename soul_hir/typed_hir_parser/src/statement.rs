@@ -46,6 +46,10 @@ impl<'a> TypedHirContext<'a> {
                 self.infer_variable(variable, self.statement_span(statement.id))
             }
             StatementKind::Expression { value, .. } => self.infer_expression(*value),
+            StatementKind::Defer(value) => {
+                self.infer_expression(*value);
+                self.none_type.to_lazy()
+            }
 
             StatementKind::Fall(value) => {
                 if let Some(value) = value {
@@ -101,7 +105,9 @@ impl<'a> TypedHirContext<'a> {
 
         let body = match function.body {
             hir::FunctionBody::Internal(block_id) => block_id,
-            hir::FunctionBody::External(_) => return function.return_type,
+            hir::FunctionBody::External(_) | hir::FunctionBody::Dependency { .. } => {
+                return function.return_type;
+            }
         };
 
         let span = self.hir.info.spans.blocks[body];
@@ -14,7 +14,10 @@ use crate::infer_table::UnifyResult;
 pub(crate) type MishmatchReason = String;
 
 pub(crate) trait ArrayKindCompatible {
-    fn arraykind_compatible(is: &ArrayKind, should_be: &ArrayKind) -> Result<(), MishmatchReason>;
+    fn arraykind_compatible(
+        is: &ArrayKind,
+        should_be: &ArrayKind,
+    ) -> Result<UnifyResult, MishmatchReason>;
 }
 
 pub(crate) trait TypeCompatible {
@@ -69,7 +72,9 @@ impl GetPriority for HirType {
             }
         }
 
-        if self.is_untyped_interger_type() && other.is_untyped_interger_type() {
+        if self.is_never_type() {
+            Priority::Right
+        } else if self.is_untyped_interger_type() && other.is_untyped_interger_type() {
             if number_precendence(self) < number_precendence(other) {
                 Priority::Left
             } else {
@@ -119,7 +124,8 @@ impl TypeCompatible for HirType {
             return Ok(UnifyResult::Ok);
         }
 
-        self.kind.compatible_type_kind(&should_be.kind)?;
+        // `self` is the expected type here, the kind is checked from the given type
+        should_be.kind.compatible_type_kind(&self.kind)?;
         Ok(result.unwrap_or(UnifyResult::Ok))
     }
 }
@@ -127,6 +133,9 @@ impl TypeCompatible for HirType {
 impl TypeKindCompatible for HirTypeKind {
     fn compatible_type_kind(&self, should_be: &Self) -> Result<(), MishmatchReason> {
         match (self, should_be) {
+            // `never` expressions do not produce a value so they fit any type
+            (HirTypeKind::Primitive(PrimitiveTypes::Never), _) => Ok(()),
+
             (HirTypeKind::Primitive(a), HirTypeKind::Primitive(b)) => {
                 if !primitive_compatible(a, b) {
                     return Err(format!(
@@ -161,7 +170,10 @@ impl TypeKindCompatible for HirTypeKind {
 }
 
 impl ArrayKindCompatible for HirTypeKind {
-    fn arraykind_compatible(is: &ArrayKind, should_be: &ArrayKind) -> Result<(), MishmatchReason> {
+    fn arraykind_compatible(
+        is: &ArrayKind,
+        should_be: &ArrayKind,
+    ) -> Result<UnifyResult, MishmatchReason> {
         let default_format = |a: &ArrayKind, b: &ArrayKind| {
             format!(
                 "arraykind '{}' is not compatible with arraykind '{}'",
@@ -173,13 +185,20 @@ impl ArrayKindCompatible for HirTypeKind {
         match (is, should_be) {
             (ArrayKind::MutSlice, ArrayKind::MutSlice)
             | (ArrayKind::HeapArray, ArrayKind::HeapArray)
-            | (ArrayKind::ConstSlice, ArrayKind::ConstSlice) => Ok(()),
+            | (ArrayKind::ConstSlice, ArrayKind::ConstSlice)
+            | (ArrayKind::MutSlice, ArrayKind::ConstSlice) => Ok(UnifyResult::Ok),
+
+            // `[N]T` and `[*]T` are sliced whole when a `[@]T` or `[&]T` is expected
+            (
+                ArrayKind::StackArray(_) | ArrayKind::HeapArray,
+                ArrayKind::ConstSlice | ArrayKind::MutSlice,
+            ) => Ok(UnifyResult::NeedsSliceCoercion),
 
             (ArrayKind::StackArray(a_num), ArrayKind::StackArray(b_num)) => {
                 if a_num != b_num {
                     Err(default_format(is, should_be))
                 } else {
-                    Ok(())
+                    Ok(UnifyResult::Ok)
                 }
             }
             (ArrayKind::StackArray(_), ArrayKind::HeapArray) => Err(format!(
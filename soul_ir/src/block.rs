@@ -1,4 +1,4 @@
-use crate::{GenericSubstitute, LlvmBackend};
+use crate::{GenericSubstitute, LlvmBackend, c_abi::CPassing};
 use hir::TypeId;
 use inkwell::values::FunctionValue;
 use mir_parser::mir::{BlockId, FunctionBody, Operand, PlaceId, PlaceKind, Terminator};
@@ -13,7 +13,7 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
         let function = &self.mir.tree.functions[function_id];
 
         let blocks = match &function.body {
-            FunctionBody::External(_) => return,
+            FunctionBody::External(_) | FunctionBody::Dependency { .. } => return,
             FunctionBody::Internal { blocks, .. } => blocks,
         };
 
@@ -49,6 +49,21 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
 
                 self.builder.build_unreachable()?;
             }
+            Terminator::Panic(message) => {
+                if let Some(span) = self.mir.tree.terminator_spans.get(block_id) {
+                    self.current.set_span(*span);
+                }
+
+                match message {
+                    Some(message) => {
+                        let message = self.lower_operand(message, generics)?;
+                        let message_ptr = message.get_or_convert_pointer(&self.builder)?;
+                        let (data, len) = self.load_array_fields(message_ptr, generics)?;
+                        self.build_panic_call(data, len)?;
+                    }
+                    None => self.build_panic("explicit panic")?,
+                }
+            }
             Terminator::Return(value) => {
                 if let Some(operand) = value {
                     let return_value = self.lower_operand(operand, generics)?;
@@ -59,6 +74,10 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
                     } else {
                         return_value.value
                     };
+
+                    if self.is_c_abi_function(self.current.function_key()) {
+                        return self.build_c_return(return_value);
+                    }
                     self.builder.build_return(Some(&return_value))?
                 } else {
                     self.builder.build_return(None)?
@@ -108,10 +127,30 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
     ) -> SoulResult<()> {
         let called_function = &self.mir.tree.functions[id];
         let called_generics = GenericSubstitute::new(&called_function.generics, type_args);
-        let mut ir_arguments = Vec::with_capacity(arguments.len());
+        let is_c_abi = called_function.abi.is_some();
+        let mut ir_arguments = Vec::with_capacity(arguments.len() + 1);
+
+        // a struct the C ABI returns in memory is written to a caller owned `sret` slot
+        let c_return_type = match is_c_abi {
+            true => self.lower_type(called_function.return_type, &called_generics)?,
+            false => None,
+        };
+        let sret = match c_return_type {
+            Some(ty) if matches!(self.c_passing(ty), CPassing::Indirect) => {
+                let slot = self.builder.build_alloca(ty, "c_abi_sret")?;
+                ir_arguments.push(slot.into());
+                Some(slot)
+            }
+            _ => None,
+        };
         for (index, arg) in arguments.iter().enumerate() {
             let operand = self.lower_operand(arg, generics)?;
-            let value = if operand.info.is_unloaded {
+            let value = if operand.info.is_unloaded && index >= called_function.parameters.len() {
+                // a variadic argument is already promoted to the type it is passed as
+                let ptr = operand.value.into_pointer_value();
+                self.builder
+                    .build_load(operand.info.ir_type, ptr, "vararg_load")?
+            } else if operand.info.is_unloaded {
                 let ptr = operand.value.into_pointer_value();
                 let parameter =
                     called_function
@@ -140,6 +179,10 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
             } else {
                 operand.value
             };
+            let value = match is_c_abi {
+                true => self.c_argument(value)?,
+                false => value,
+            };
             let meta_data_value = value.into();
             ir_arguments.push(meta_data_value);
         }
@@ -155,7 +198,11 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
             None => return Ok(()),
         };
 
-        let return_value = call.try_as_basic_value().basic().ok_or_else(|| {
+        let return_value = match sret {
+            Some(slot) => Some(slot.into()),
+            None => call.try_as_basic_value().basic(),
+        };
+        let mut return_value = return_value.ok_or_else(|| {
             soul_utils::soul_error_internal!(
                 format!(
                     "call to {:?} returned no value but return_place was provided",
@@ -164,6 +211,9 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
                 None
             )
         })?;
+        if let Some(ty) = c_return_type {
+            return_value = self.c_value(return_value, ty)?;
+        }
         match &place.kind {
             PlaceKind::Temp(temp_id) => {
                 let value = self.new_loaded_operand(return_value, place.ty, generics)?;
@@ -175,6 +225,9 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
             PlaceKind::Deref(_) => {
                 panic!("call return value should be Place::Temp not Place::Deref")
             }
+            PlaceKind::Index { .. } => {
+                panic!("call return value should be Place::Temp not Place::Index")
+            }
             PlaceKind::Local(_) => {
                 panic!("call return value should be Place::Temp not Place::Local")
             }
@@ -0,0 +1,310 @@
+use inkwell::{
+    attributes::{Attribute, AttributeLoc},
+    types::{AnyType, BasicTypeEnum},
+    values::{BasicValueEnum, FunctionValue},
+};
+use soul_utils::{
+    compile_options::{Arch, Os},
+    error::SoulResult,
+    soul_error_internal,
+};
+
+use crate::LlvmBackend;
+
+/// How a parameter or return value of an `extern "C"` function is passed, following
+/// the C calling convention of the target.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum CPassing<'a> {
+    /// Passed as its own llvm type.
+    Direct,
+    /// Reinterpreted as a same sized register type, like `i64` or `<2 x float>`.
+    Coerce(BasicTypeEnum<'a>),
+    /// Passed as a pointer to a copy, returned through an `sret` pointer.
+    Indirect,
+}
+
+/// The largest struct passed in registers by the System V and AArch64 ABIs.
+const MAX_REGISTER_STRUCT: u32 = 16;
+const EIGHTBYTE: u32 = 8;
+
+impl<'f, 'a> LlvmBackend<'f, 'a> {
+    /// Classifies `ty` for the C ABI, only structs are passed differently than in Soul.
+    pub(crate) fn c_passing(&self, ty: BasicTypeEnum<'a>) -> CPassing<'a> {
+        if !ty.is_struct_type() {
+            return CPassing::Direct;
+        }
+
+        let (size, _) = self.c_layout(ty);
+        if size == 0 {
+            return CPassing::Direct;
+        }
+
+        let target = self.options.target_info();
+        match (target.arch, target.os) {
+            (Arch::X86_64, Os::Windows) => match size {
+                1 | 2 | 4 | 8 => {
+                    CPassing::Coerce(self.context.custom_width_int_type(size * 8).into())
+                }
+                _ => CPassing::Indirect,
+            },
+            (Arch::X86_64, Os::Linux | Os::Macos) => self.sysv_passing(ty, size),
+            (Arch::AArch64, _) => self.aarch64_passing(ty, size),
+            _ => CPassing::Direct,
+        }
+    }
+
+    /// Adds the `byval`/`sret` attributes the classification of `function` needs.
+    pub(crate) fn add_c_abi_attributes(
+        &self,
+        llvm_function: FunctionValue<'a>,
+        parameters: &[BasicTypeEnum<'a>],
+        return_type: Option<BasicTypeEnum<'a>>,
+    ) {
+        let mut offset = 0;
+        if let Some(ty) = return_type
+            && let CPassing::Indirect = self.c_passing(ty)
+        {
+            llvm_function.add_attribute(AttributeLoc::Param(0), self.type_attribute("sret", ty));
+            offset = 1;
+        }
+
+        // only System V copies the struct onto the stack, the others pass a pointer to a copy
+        let target = self.options.target_info();
+        if !matches!(
+            (target.arch, target.os),
+            (Arch::X86_64, Os::Linux | Os::Macos)
+        ) {
+            return;
+        }
+
+        for (i, ty) in parameters.iter().enumerate() {
+            if let CPassing::Indirect = self.c_passing(*ty) {
+                let attribute = self.type_attribute("byval", *ty);
+                llvm_function.add_attribute(AttributeLoc::Param(i as u32 + offset), attribute);
+            }
+        }
+    }
+
+    /// Reinterprets the bytes of `value` as `to`, going through a stack slot big enough for both.
+    pub(crate) fn c_coerce(
+        &self,
+        value: BasicValueEnum<'a>,
+        to: BasicTypeEnum<'a>,
+    ) -> SoulResult<BasicValueEnum<'a>> {
+        let from = value.get_type();
+        let slot_type = match self.c_layout(from).0 > self.c_layout(to).0 {
+            true => from,
+            false => to,
+        };
+
+        let slot = self.builder.build_alloca(slot_type, "c_abi_coerce")?;
+        self.builder.store_parameter(slot, value)?;
+        self.builder.build_load(to, slot, "c_abi_value")
+    }
+
+    /// Converts a loaded argument of an `extern "C"` call to the way the C ABI passes it.
+    pub(crate) fn c_argument(&self, value: BasicValueEnum<'a>) -> SoulResult<BasicValueEnum<'a>> {
+        match self.c_passing(value.get_type()) {
+            CPassing::Direct => Ok(value),
+            CPassing::Coerce(ty) => self.c_coerce(value, ty),
+            CPassing::Indirect => {
+                let copy = self.builder.build_alloca(value.get_type(), "c_abi_arg")?;
+                self.builder.store_parameter(copy, value)?;
+                Ok(copy.into())
+            }
+        }
+    }
+
+    /// Reads a parameter or return value passed the C way back as a value of `ty`,
+    /// `passed` is the pointer to the value when it is passed indirectly.
+    pub(crate) fn c_value(
+        &self,
+        passed: BasicValueEnum<'a>,
+        ty: BasicTypeEnum<'a>,
+    ) -> SoulResult<BasicValueEnum<'a>> {
+        match self.c_passing(ty) {
+            CPassing::Direct => Ok(passed),
+            CPassing::Coerce(_) => self.c_coerce(passed, ty),
+            CPassing::Indirect => {
+                self.builder
+                    .build_load(ty, passed.into_pointer_value(), "c_abi_value")
+            }
+        }
+    }
+
+    /// Returns `value` from the current `extern "C"` function.
+    pub(crate) fn build_c_return(&self, value: BasicValueEnum<'a>) -> SoulResult<()> {
+        match self.c_passing(value.get_type()) {
+            CPassing::Direct => {
+                self.builder.build_return(Some(&value))?;
+            }
+            CPassing::Coerce(ty) => {
+                let value = self.c_coerce(value, ty)?;
+                self.builder.build_return(Some(&value))?;
+            }
+            CPassing::Indirect => {
+                let sret = self
+                    .functions
+                    .get(self.current.function_key())
+                    .and_then(|function| function.get_nth_param(0))
+                    .ok_or(soul_error_internal!("sret parameter not found", None))?;
+                self.builder
+                    .store_parameter(sret.into_pointer_value(), value)?;
+                self.builder.build_return(None)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn type_attribute(&self, name: &str, ty: BasicTypeEnum<'a>) -> Attribute {
+        let kind = Attribute::get_named_enum_kind_id(name);
+        self.context
+            .create_type_attribute(kind, ty.as_any_type_enum())
+    }
+
+    /// System V x86_64, structs up to two eightbytes go in integer or sse registers.
+    fn sysv_passing(&self, ty: BasicTypeEnum<'a>, size: u32) -> CPassing<'a> {
+        if size > MAX_REGISTER_STRUCT {
+            return CPassing::Indirect;
+        }
+
+        let mut scalars = vec![];
+        if !self.flatten_scalars(ty, 0, &mut scalars) {
+            // unaligned fields are passed in memory
+            return CPassing::Indirect;
+        }
+
+        let f32_type = BasicTypeEnum::from(self.context.f32_type());
+        let f64_type = BasicTypeEnum::from(self.context.f64_type());
+        let mut eightbytes = vec![];
+        for start in (0..size).step_by(EIGHTBYTE as usize) {
+            let bytes = (size - start).min(EIGHTBYTE);
+            let mut in_eightbyte = scalars
+                .iter()
+                .filter(|(offset, _)| (start..start + EIGHTBYTE).contains(offset))
+                .map(|(_, scalar)| *scalar);
+
+            let is_sse = in_eightbyte.clone().next().is_some()
+                && in_eightbyte.all(|scalar| scalar == f32_type || scalar == f64_type);
+
+            let eightbyte: BasicTypeEnum<'a> = match is_sse {
+                true if bytes <= 4 => f32_type,
+                true if scalars.contains(&(start, f64_type)) => f64_type,
+                true => self.context.f32_type().vec_type(2).into(),
+                false => self.context.custom_width_int_type(bytes * 8).into(),
+            };
+            eightbytes.push(eightbyte);
+        }
+
+        match eightbytes.as_slice() {
+            [single] => CPassing::Coerce(*single),
+            pair => CPassing::Coerce(self.context.struct_type(pair, false).into()),
+        }
+    }
+
+    /// AAPCS64, homogeneous float aggregates go in float registers and
+    /// other structs up to 16 bytes in integer registers.
+    fn aarch64_passing(&self, ty: BasicTypeEnum<'a>, size: u32) -> CPassing<'a> {
+        const MAX_HFA_MEMBERS: usize = 4;
+
+        let mut scalars = vec![];
+        let aligned = self.flatten_scalars(ty, 0, &mut scalars);
+
+        if let Some((_, first)) = scalars.first()
+            && first.is_float_type()
+            && scalars.len() <= MAX_HFA_MEMBERS
+            && scalars.iter().all(|(_, scalar)| scalar == first)
+        {
+            let float = first.into_float_type();
+            return CPassing::Coerce(float.array_type(scalars.len() as u32).into());
+        }
+
+        if size > MAX_REGISTER_STRUCT || !aligned {
+            return CPassing::Indirect;
+        }
+
+        let count = size.div_ceil(EIGHTBYTE);
+        CPassing::Coerce(self.context.i64_type().array_type(count).into())
+    }
+
+    /// Collects the scalar fields of `ty` with their byte offset,
+    /// returns `false` when one of them is not naturally aligned.
+    fn flatten_scalars(
+        &self,
+        ty: BasicTypeEnum<'a>,
+        offset: u32,
+        out: &mut Vec<(u32, BasicTypeEnum<'a>)>,
+    ) -> bool {
+        match ty {
+            BasicTypeEnum::StructType(struct_type) => {
+                let mut field_offset = 0u32;
+                let mut aligned = true;
+                for field in struct_type.get_field_types() {
+                    let (size, align) = self.c_layout(field);
+                    if !struct_type.is_packed() {
+                        field_offset = field_offset.next_multiple_of(align);
+                    }
+
+                    aligned &= self.flatten_scalars(field, offset + field_offset, out);
+                    field_offset += size;
+                }
+                aligned
+            }
+            BasicTypeEnum::ArrayType(array_type) => {
+                let element = array_type.get_element_type();
+                let (size, _) = self.c_layout(element);
+                (0..array_type.len()).all(|i| self.flatten_scalars(element, offset + i * size, out))
+            }
+            scalar => {
+                out.push((offset, scalar));
+                offset % self.c_layout(scalar).1 == 0
+            }
+        }
+    }
+
+    /// Size and alignment in bytes of `ty` as a C compiler lays it out.
+    fn c_layout(&self, ty: BasicTypeEnum<'a>) -> (u32, u32) {
+        match ty {
+            BasicTypeEnum::IntType(int) => {
+                let size = int.get_bit_width().div_ceil(8).next_power_of_two();
+                (size, size.min(EIGHTBYTE))
+            }
+            BasicTypeEnum::FloatType(float) => {
+                let size = match float {
+                    _ if float == self.context.f16_type() => 2,
+                    _ if float == self.context.f32_type() => 4,
+                    _ if float == self.context.f64_type() => 8,
+                    _ => 16,
+                };
+                (size, size)
+            }
+            BasicTypeEnum::PointerType(_) => {
+                let size = self.default_ptr_size as u32 / 8;
+                (size, size)
+            }
+            BasicTypeEnum::ArrayType(array_type) => {
+                let (size, align) = self.c_layout(array_type.get_element_type());
+                (size * array_type.len(), align)
+            }
+            BasicTypeEnum::VectorType(vector) => {
+                let (size, _) = self.c_layout(vector.get_element_type());
+                let size = (size * vector.get_size()).next_power_of_two();
+                (size, size)
+            }
+            BasicTypeEnum::StructType(struct_type) => {
+                let mut size = 0u32;
+                let mut struct_align = 1u32;
+                for field in struct_type.get_field_types() {
+                    let (field_size, align) = self.c_layout(field);
+                    if !struct_type.is_packed() {
+                        size = size.next_multiple_of(align);
+                        struct_align = struct_align.max(align);
+                    }
+                    size += field_size;
+                }
+                (size.next_multiple_of(struct_align), struct_align)
+            }
+            BasicTypeEnum::ScalableVectorType(_) => (0, 1),
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use inkwell::{
+    IntPredicate,
+    intrinsics::Intrinsic,
+    module::Linkage,
+    values::{IntValue, PointerValue},
+};
+use soul_utils::{error::SoulResult, soul_error_internal};
+
+use crate::LlvmBackend;
+
+/// Integer operations that are checked for overflow when `debug_checks` is on.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum CheckedIntOp {
+    Add,
+    Sub,
+    Mul,
+}
+impl CheckedIntOp {
+    fn intrinsic_name(self, signed: bool) -> &'static str {
+        match (self, signed) {
+            (CheckedIntOp::Add, true) => "llvm.sadd.with.overflow",
+            (CheckedIntOp::Add, false) => "llvm.uadd.with.overflow",
+            (CheckedIntOp::Sub, true) => "llvm.ssub.with.overflow",
+            (CheckedIntOp::Sub, false) => "llvm.usub.with.overflow",
+            (CheckedIntOp::Mul, true) => "llvm.smul.with.overflow",
+            (CheckedIntOp::Mul, false) => "llvm.umul.with.overflow",
+        }
+    }
+
+    fn message(self) -> &'static str {
+        match self {
+            CheckedIntOp::Add => "attempt to add with overflow",
+            CheckedIntOp::Sub => "attempt to subtract with overflow",
+            CheckedIntOp::Mul => "attempt to multiply with overflow",
+        }
+    }
+}
+
+impl<'f, 'a> LlvmBackend<'f, 'a> {
+    /// Panics with `message` at the current statement when `ok` is false, the builder continues
+    /// in the block where the check passed.
+    pub(crate) fn build_check(&self, ok: IntValue<'a>, message: &str) -> SoulResult<()> {
+        let function = self.current_llvm_function()?;
+        let passed = self.context.append_basic_block(function, "check_ok");
+        let failed = self.context.append_basic_block(function, "check_failed");
+        self.builder.build_conditional_branch(ok, passed, failed)?;
+
+        self.builder.position_at_end(failed);
+        self.build_panic(message)?;
+
+        self.builder.position_at_end(passed);
+        Ok(())
+    }
+
+    /// Calls the panic runtime with the location of the statement being lowered.
+    pub(crate) fn build_panic(&self, message: &str) -> SoulResult<()> {
+        let message_len = self.default_int_type.const_int(message.len() as u64, false);
+        let message = self.const_c_string(message);
+        self.build_panic_call(message, message_len)
+    }
+
+    /// Like [`Self::build_panic`] with a message that is only known at runtime, e.g. from `panic(msg)`.
+    pub(crate) fn build_panic_call(
+        &self,
+        message: PointerValue<'a>,
+        message_len: IntValue<'a>,
+    ) -> SoulResult<()> {
+        let panic = self.get_or_define_panic()?;
+        let span = self.current.span();
+        let file = match self.module_store.get_path(span.module) {
+            Some(path) => path.display().to_string(),
+            None => "unknown".to_string(),
+        };
+
+        let i32_type = self.context.i32_type();
+        let file = self.const_c_string(&file);
+        let line = i32_type.const_int(span.start_line as u64, false);
+        let column = i32_type.const_int(span.start_offset as u64, false);
+
+        self.builder.build_call(
+            panic,
+            &[
+                file.into(),
+                line.into(),
+                column.into(),
+                message.into(),
+                message_len.into(),
+            ],
+        )?;
+        self.builder.build_unreachable()?;
+        Ok(())
+    }
+
+    /// `left op right` through `llvm.{s,u}{op}.with.overflow`, panics when the result wraps.
+    pub(crate) fn build_checked_int_op(
+        &self,
+        op: CheckedIntOp,
+        left: IntValue<'a>,
+        right: IntValue<'a>,
+        signed: bool,
+    ) -> SoulResult<IntValue<'a>> {
+        let name = op.intrinsic_name(signed);
+        let intrinsic = Intrinsic::find(name)
+            .and_then(|intrinsic| {
+                intrinsic.get_declaration(&self.module, &[left.get_type().into()])
+            })
+            .ok_or(soul_error_internal!(
+                format!("intrinsic '{name}' not found"),
+                None
+            ))?;
+
+        let result = self
+            .builder
+            .build_call(intrinsic, &[left.into(), right.into()])?
+            .try_as_basic_value()
+            .basic()
+            .ok_or(soul_error_internal!(
+                format!("intrinsic '{name}' should return a value"),
+                None
+            ))?
+            .into_struct_value();
+
+        let value = self
+            .builder
+            .build_extract_value(result, 0, "checked_value")?
+            .into_int_value();
+        let overflow = self
+            .builder
+            .build_extract_value(result, 1, "overflow")?
+            .into_int_value();
+
+        let no_overflow = self.builder.build_not(overflow)?;
+        self.build_check(no_overflow, op.message())?;
+        Ok(value)
+    }
+
+    /// Panics when `divisor` is zero, used before integer `/` and `%`.
+    pub(crate) fn build_zero_division_check(&self, divisor: IntValue<'a>) -> SoulResult<()> {
+        let zero = divisor.get_type().const_zero();
+        let not_zero = self
+            .builder
+            .build_int_compare(IntPredicate::NE, divisor, zero)?;
+        self.build_check(not_zero, "attempt to divide by zero")
+    }
+
+    /// Panics on `INT_MIN / -1` and `INT_MIN % -1`, the signed division whose result does
+    /// not fit in the type.
+    pub(crate) fn build_signed_division_overflow_check(
+        &self,
+        dividend: IntValue<'a>,
+        divisor: IntValue<'a>,
+        remainder: bool,
+    ) -> SoulResult<()> {
+        let ty = dividend.get_type();
+        let sign_bit = ty.const_int(ty.get_bit_width() as u64 - 1, false);
+        let min = ty.const_int(1, false).const_shl(sign_bit);
+        let minus_one = ty.const_all_ones();
+
+        let is_min = self
+            .builder
+            .build_int_compare(IntPredicate::EQ, dividend, min)?;
+        let is_minus_one = self
+            .builder
+            .build_int_compare(IntPredicate::EQ, divisor, minus_one)?;
+        let overflow = self.builder.build_and(is_min, is_minus_one)?;
+        let no_overflow = self.builder.build_not(overflow)?;
+
+        let message = match remainder {
+            true => "attempt to calculate the remainder with overflow",
+            false => "attempt to divide with overflow",
+        };
+        self.build_check(no_overflow, message)
+    }
+
+    /// Panics when `ptr` is null, used before dereferencing a `*T`.
+    pub(crate) fn build_null_check(&self, ptr: PointerValue<'a>) -> SoulResult<()> {
+        let is_null = self.builder.build_is_null(ptr)?;
+        let not_null = self.builder.build_not(is_null)?;
+        self.build_check(not_null, "attempt to dereference a null pointer")
+    }
+
+    /// Panics when `index` is not below `len`.
+    pub(crate) fn build_bounds_check(
+        &self,
+        index: IntValue<'a>,
+        len: IntValue<'a>,
+    ) -> SoulResult<()> {
+        let in_bounds = self
+            .builder
+            .build_int_compare(IntPredicate::ULT, index, len)?;
+        self.build_check(in_bounds, "index out of bounds")
+    }
+
+    /// Panics unless `start <= end <= len`, the bounds of `array[start..end]`.
+    pub(crate) fn build_slice_bounds_check(
+        &self,
+        start: IntValue<'a>,
+        end: IntValue<'a>,
+        len: IntValue<'a>,
+    ) -> SoulResult<()> {
+        let ordered = self
+            .builder
+            .build_int_compare(IntPredicate::ULE, start, end)?;
+        let in_bounds = self
+            .builder
+            .build_int_compare(IntPredicate::ULE, end, len)?;
+        let valid = self.builder.build_and(ordered, in_bounds)?;
+        self.build_check(valid, "slice index out of bounds")
+    }
+
+    /// Null terminated string in a private global, for the C strings the panic runtime takes.
+    pub(crate) fn const_c_string(&self, text: &str) -> PointerValue<'a> {
+        let bytes = self.context.const_string(text.as_bytes(), true);
+
+        let global = self.module.add_global(bytes.get_type(), None, "cstr");
+        global.set_constant(true);
+        global.set_linkage(Linkage::Private);
+        global.set_initializer(&bytes);
+        global.as_pointer_value()
+    }
+}
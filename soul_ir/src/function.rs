@@ -2,19 +2,18 @@ use std::fmt::Write;
 
 use hir::TypeId;
 use inkwell::{
+    AddressSpace,
+    attributes::{self, AttributeLoc},
     types::{BasicMetadataTypeEnum, BasicType, BasicTypeEnum, FunctionType, VoidType},
     values::FunctionValue,
 };
-use mir_parser::mir::{self, FunctionBody};
+use mir_parser::mir::FunctionBody;
 use soul_utils::{
-    Ident,
-    error::{SoulError, SoulErrorKind},
-    ids::FunctionId,
-    vec_map::VecMapIndex,
+    DefId, Ident, ids::FunctionId, soul_names::AttributeKind, span::Attribute, vec_map::VecMapIndex,
 };
 use typed_hir::{ThirType, ThirTypeKind, display_thir::DisplayThirType};
 
-use crate::{FunctionKeyId, GenericSubstitute, LlvmBackend};
+use crate::{FunctionKeyId, GenericSubstitute, LlvmBackend, c_abi::CPassing};
 
 impl<'f, 'a> LlvmBackend<'f, 'a> {
     pub(crate) fn declare_function_instance(
@@ -24,22 +23,21 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
         generics: &GenericSubstitute,
     ) -> FunctionValue<'a> {
         let function = &self.mir.tree.functions[function_id];
+        let is_c_abi = function.abi.is_some();
 
-        let return_type: FunctionReturnType<'a> =
-            match self.lower_type(function.return_type, generics) {
-                Ok(Some(val)) => val.into(),
-                Ok(None) => self.context.void_type().into(),
-                Err(err) => {
-                    self.log_error(err);
-                    self.context.void_type().into()
-                }
-            };
+        let ir_return_type = match self.lower_type(function.return_type, generics) {
+            Ok(val) => val,
+            Err(err) => {
+                self.log_error(err);
+                None
+            }
+        };
 
-        let mut args = vec![];
+        let mut parameter_types = vec![];
         for param in &function.parameters {
             let ty = self.mir.tree.locals[*param].ty();
             let arg_type = match self.lower_type(ty, generics) {
-                Ok(Some(val)) => val.into(),
+                Ok(Some(val)) => val,
                 Ok(None) => self.context.i8_type().into(),
                 Err(err) => {
                     self.log_error(err);
@@ -47,18 +45,42 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
                 }
             };
 
-            args.push(arg_type);
+            parameter_types.push(arg_type);
         }
 
-        let function_type = return_type.fn_type(&args, false);
+        let function_type = match is_c_abi {
+            true => self.c_function_type(&parameter_types, ir_return_type, function.variadic),
+            false => {
+                let return_type: FunctionReturnType<'a> = match ir_return_type {
+                    Some(val) => val.into(),
+                    None => self.context.void_type().into(),
+                };
+                let args: Vec<BasicMetadataTypeEnum<'a>> =
+                    parameter_types.iter().map(|ty| (*ty).into()).collect();
+                return_type.fn_type(&args, false)
+            }
+        };
 
-        let name = if function.body.is_internal() {
-            &self.mangle(&function.name, function.owner_type, function_id, type_args)
+        let no_mangle = Attribute::contains(&function.attributes, AttributeKind::NoMangle);
+        let name = if !is_c_abi && !no_mangle {
+            // a dependency function is linked under the symbol its own crate gave it
+            let symbol = match function.body {
+                FunctionBody::Dependency(def_id) => def_id,
+                _ => DefId::new(self.crate_id, function_id),
+            };
+            &self.mangle(&function.name, function.owner_type, symbol, type_args)
         } else {
-            self.check_non_mangle(function);
             function.name.as_str()
         };
         let llvm_function = self.module.add_function(name, function_type, None);
+        if is_c_abi {
+            self.add_c_abi_attributes(llvm_function, &parameter_types, ir_return_type);
+        }
+        if Attribute::contains(&function.attributes, AttributeKind::Inline) {
+            let kind = attributes::Attribute::get_named_enum_kind_id("inlinehint");
+            let inline_hint = self.context.create_enum_attribute(kind, 0);
+            llvm_function.add_attribute(AttributeLoc::Function, inline_hint);
+        }
 
         self.create_block(function_id, llvm_function);
         llvm_function
@@ -74,7 +96,7 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
         self.current.set_function_key(function_key);
         let function = &self.mir.tree.functions[function_id];
         let blocks = match &function.body {
-            FunctionBody::External(_) => return,
+            FunctionBody::External(_) | FunctionBody::Dependency { .. } => return,
             FunctionBody::Internal { blocks, .. } => blocks,
         };
 
@@ -92,11 +114,12 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
         }
     }
 
+    /// `C{crate}_F{id}___{name}`, the crate keeps the symbols of linked crates apart.
     pub(crate) fn mangle(
         &mut self,
         name: &Ident,
         owner: TypeId,
-        id: FunctionId,
+        id: DefId<FunctionId>,
         type_args: &Vec<TypeId>,
     ) -> String {
         const SEPARATOR: &str = "_";
@@ -105,8 +128,12 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
         }
 
         let mut sb = String::new();
-        sb.write_fmt(format_args!("F{}___", id.index()))
-            .expect("no fmt error");
+        sb.write_fmt(format_args!(
+            "C{}_F{}___",
+            id.krate.index(),
+            id.local.index()
+        ))
+        .expect("no fmt error");
 
         sb.push_str(name.as_str());
         let owner_type = match self.get_type(owner) {
@@ -146,26 +173,37 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
         sb
     }
 
-    fn check_non_mangle(&mut self, function: &mir::Function) {
-        let id = match self.non_mangels.get(&function.name.node) {
-            Some(val) => val,
-            None => {
-                self.non_mangels
-                    .insert(function.name.to_string(), function.id);
-                return;
-            }
+    /// The llvm signature of an `extern "C"` function, see [`Self::c_passing`].
+    fn c_function_type(
+        &self,
+        parameters: &[BasicTypeEnum<'a>],
+        return_type: Option<BasicTypeEnum<'a>>,
+        variadic: bool,
+    ) -> FunctionType<'a> {
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let mut args: Vec<BasicMetadataTypeEnum<'a>> = vec![];
+
+        let return_type: FunctionReturnType<'a> = match return_type {
+            None => self.context.void_type().into(),
+            Some(ty) => match self.c_passing(ty) {
+                CPassing::Direct => ty.into(),
+                CPassing::Coerce(coerced) => coerced.into(),
+                CPassing::Indirect => {
+                    args.push(ptr_type.into());
+                    self.context.void_type().into()
+                }
+            },
         };
 
-        if *id != function.id {
-            self.log_error(SoulError::new(
-                format!(
-                    "function: '{}' is not mangeled and name already exists somewhere in crate",
-                    function.name.as_str(),
-                ),
-                SoulErrorKind::AlreadyFoundInScope,
-                Some(function.name.span),
-            ));
+        for ty in parameters {
+            args.push(match self.c_passing(*ty) {
+                CPassing::Direct => (*ty).into(),
+                CPassing::Coerce(coerced) => coerced.into(),
+                CPassing::Indirect => ptr_type.into(),
+            });
         }
+
+        return_type.fn_type(&args, variadic)
     }
 }
 
@@ -150,24 +150,15 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
     }
 
     pub(crate) fn get_enum_size(&self, id: hir::EnumId) -> PrimitiveSize {
-        if let Some(object) = self.types.types_map.id_to_enum(id) {
-            let variant_count = object.variants.len() as u64;
-            let bit_width = variant_count.next_power_of_two().max(8);
-            let ty = match bit_width {
-                1..=8 => PrimitiveSize::Bit8,
-                9..=16 => PrimitiveSize::Bit16,
-                17..=32 => PrimitiveSize::Bit32,
-                _ => PrimitiveSize::Bit64,
-            };
-            ty
-        } else {
-            PrimitiveSize::Bit32
+        match self.types.types_map.id_to_enum(id) {
+            Some(object) => object.tag_size(),
+            None => PrimitiveSize::Bit32,
         }
     }
 
     fn lower_primitive_type(&self, primitive: PrimitiveTypes) -> Option<BasicTypeEnum<'a>> {
         Some(match primitive {
-            PrimitiveTypes::None => return None,
+            PrimitiveTypes::None | PrimitiveTypes::Never => return None,
 
             PrimitiveTypes::Char => self.default_char_type.into(),
             PrimitiveTypes::CStr => self.context.ptr_type(AddressSpace::default()).into(),
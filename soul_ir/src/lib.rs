@@ -4,23 +4,27 @@ use hir::{FieldId, TypeId};
 use inkwell::{
     basic_block::BasicBlock,
     context::Context,
-    module::Module,
-    types::{BasicTypeEnum, IntType},
-    values::{BasicValueEnum, FunctionValue, PointerValue},
+    module::{Linkage, Module},
+    types::{BasicTypeEnum, FunctionType, IntType},
+    values::{BasicMetadataValueEnum, BasicValueEnum, FunctionValue, PointerValue},
 };
 use mir_parser::mir::{BlockId, LocalId, TempId};
 use run_mir::MirResponse;
 use soul_utils::{
+    CrateId,
     compile_options::CompilerOptions,
     error::{SoulError, SoulResult},
     ids::{FunctionId, IdGenerator},
     impl_soul_ids,
-    sementic_level::SementicFault,
+    sementic_level::{ModuleStore, SementicFault},
     soul_error_internal,
+    soul_names::{ALLOC_FUNCTION_NAME, FREE_FUNCTION_NAME, PANIC_FUNCTION_NAME},
     vec_map::VecMap,
 };
 
 mod block;
+mod c_abi;
+mod checks;
 mod function;
 mod ir_type;
 mod llvm_builder;
@@ -37,6 +41,8 @@ pub struct IrRequest<'ctx> {
     pub context: &'ctx Context,
     pub mir: &'ctx MirResponse,
     pub types: &'ctx TypedHir,
+    pub module_store: &'ctx ModuleStore,
+    pub crate_id: CrateId,
     pub crate_name: String,
 }
 impl<'ctx> IrRequest<'ctx> {
@@ -44,12 +50,16 @@ impl<'ctx> IrRequest<'ctx> {
         mir: &'ctx MirResponse,
         types: &'ctx TypedHir,
         context: &'ctx Context,
+        module_store: &'ctx ModuleStore,
+        crate_id: CrateId,
         crate_name: String,
     ) -> Self {
         Self {
             mir,
             types,
             context,
+            module_store,
+            crate_id,
             crate_name,
         }
     }
@@ -61,7 +71,7 @@ pub struct IrResponse<'a> {
 }
 
 pub fn to_llvm_ir<'f, 'a>(
-    request: &'a IrRequest<'a>,
+    request: &IrRequest<'a>,
     options: &'a CompilerOptions,
     faults: &'f mut Vec<SementicFault>,
 ) -> IrResponse<'a> {
@@ -135,15 +145,16 @@ pub struct LlvmBackend<'f, 'a> {
     default_c_int_type: IntType<'a>,
 
     types: TypedHir,
+    crate_id: CrateId,
     current: Current,
     module: Module<'a>,
     context: &'a Context,
     mir: &'a MirResponse,
     builder: IrBuilder<'a>,
     options: &'a CompilerOptions,
+    module_store: &'a ModuleStore,
     exit_function: Option<FunctionValue<'a>>,
 
-    non_mangels: HashMap<String, FunctionId>,
     temps: HashMap<(FunctionKeyId, TempId), IrOperand<'a>>,
     locals: HashMap<(FunctionKeyId, LocalId), Local<'a>>,
     blocks: HashMap<(FunctionKeyId, BlockId), BasicBlock<'a>>,
@@ -165,7 +176,7 @@ pub enum Local<'a> {
 
 impl<'f, 'a> LlvmBackend<'f, 'a> {
     pub fn new(
-        request: &'a IrRequest<'a>,
+        request: &IrRequest<'a>,
         options: &'a CompilerOptions,
         faults: &'f mut Vec<SementicFault>,
     ) -> Self {
@@ -195,15 +206,16 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
             builder,
             options,
             mir: request.mir,
+            module_store: request.module_store,
             exit_function: None,
             temps: HashMap::new(),
             blocks: HashMap::new(),
             locals: HashMap::new(),
             functions: VecMap::new(),
             context: request.context,
-            non_mangels: HashMap::new(),
             structs: StructStore::new(),
             types: request.types.clone(),
+            crate_id: request.crate_id,
             field_indexs: VecMap::const_default().into(),
             lowered_types: VecMap::const_default().into(),
             current: Current::start(function_keys.global_key()),
@@ -227,13 +239,148 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
 
         exit_fn.set_linkage(inkwell::module::Linkage::External);
 
-        // Use raw enum ID 39 for noreturn (LLVM 16)
-        let noreturn_attr = self.context.create_enum_attribute(39, 0);
+        let noreturn = inkwell::attributes::Attribute::get_named_enum_kind_id("noreturn");
+        let noreturn_attr = self.context.create_enum_attribute(noreturn, 0);
         exit_fn.add_attribute(inkwell::attributes::AttributeLoc::Function, noreturn_attr);
 
         self.exit_function = Some(exit_fn);
     }
 
+    /// `__soul_alloc(size) -> ptr`, the allocator runtime `new` allocates with.
+    ///
+    /// Defined weak on top of libc `malloc` so a program can link its own allocator.
+    fn get_or_define_alloc(&self) -> SoulResult<FunctionValue<'a>> {
+        if let Some(alloc) = self.module.get_function(ALLOC_FUNCTION_NAME) {
+            return Ok(alloc);
+        }
+
+        let ptr_type = self.context.ptr_type(inkwell::AddressSpace::default());
+        let alloc_type = ptr_type.fn_type(&[self.context.i64_type().into()], false);
+        let malloc = self.get_or_declare_libc("malloc", alloc_type);
+        let alloc =
+            self.module
+                .add_function(ALLOC_FUNCTION_NAME, alloc_type, Some(Linkage::WeakAny));
+
+        self.define_runtime_forward(alloc, malloc)?;
+        Ok(alloc)
+    }
+
+    /// `__soul_free(ptr)`, the allocator runtime heap arrays are released with.
+    ///
+    /// Defined weak on top of libc `free`, see [`Self::get_or_define_alloc`].
+    fn get_or_define_free(&self) -> SoulResult<FunctionValue<'a>> {
+        if let Some(free) = self.module.get_function(FREE_FUNCTION_NAME) {
+            return Ok(free);
+        }
+
+        let ptr_type = self.context.ptr_type(inkwell::AddressSpace::default());
+        let free_type = self.context.void_type().fn_type(&[ptr_type.into()], false);
+        let libc_free = self.get_or_declare_libc("free", free_type);
+        let free = self
+            .module
+            .add_function(FREE_FUNCTION_NAME, free_type, Some(Linkage::WeakAny));
+
+        self.define_runtime_forward(free, libc_free)?;
+        Ok(free)
+    }
+
+    /// `__soul_panic(file, line, column, message)`, called when a runtime check fails.
+    ///
+    /// Defined weak on top of libc `printf` and `exit`, see [`Self::get_or_define_alloc`].
+    fn get_or_define_panic(&self) -> SoulResult<FunctionValue<'a>> {
+        if let Some(panic) = self.module.get_function(PANIC_FUNCTION_NAME) {
+            return Ok(panic);
+        }
+
+        let ptr_type = self.context.ptr_type(inkwell::AddressSpace::default());
+        let i32_type = self.context.i32_type();
+        let panic_type = self.context.void_type().fn_type(
+            &[
+                ptr_type.into(),
+                i32_type.into(),
+                i32_type.into(),
+                ptr_type.into(),
+                self.default_int_type.into(),
+            ],
+            false,
+        );
+        let printf_type = i32_type.fn_type(&[ptr_type.into()], true);
+        let printf = self.get_or_declare_libc("printf", printf_type);
+        let exit = self
+            .exit_function
+            .expect("should have initialize exit function");
+
+        let panic =
+            self.module
+                .add_function(PANIC_FUNCTION_NAME, panic_type, Some(Linkage::WeakAny));
+        let noreturn = inkwell::attributes::Attribute::get_named_enum_kind_id("noreturn");
+        let noreturn_attr = self.context.create_enum_attribute(noreturn, 0);
+        panic.add_attribute(inkwell::attributes::AttributeLoc::Function, noreturn_attr);
+
+        let previous = self.builder.get_insert_block();
+        let entry = self.context.append_basic_block(panic, "entry");
+        self.builder.position_at_end(entry);
+
+        let format = self
+            .builder
+            .build_global_string_ptr("panic at %s:%u:%u: %.*s\n", "panic_format")?;
+        let params: Vec<BasicValueEnum> = panic.get_param_iter().collect();
+        let message_len = self
+            .builder
+            .build_int_cast(params[4].into_int_value(), i32_type)?;
+        let arguments: Vec<BasicMetadataValueEnum> = vec![
+            format.as_pointer_value().into(),
+            params[0].into(),
+            params[1].into(),
+            params[2].into(),
+            message_len.into(),
+            params[3].into(),
+        ];
+        self.builder.build_call(printf, &arguments)?;
+        let exit_code = i32_type.const_int(1, false);
+        self.builder.build_call(exit, &[exit_code.into()])?;
+        self.builder.build_unreachable()?;
+
+        if let Some(block) = previous {
+            self.builder.position_at_end(block);
+        }
+        Ok(panic)
+    }
+
+    fn get_or_declare_libc(&self, name: &str, ty: FunctionType<'a>) -> FunctionValue<'a> {
+        if let Some(function) = self.module.get_function(name) {
+            return function;
+        }
+
+        self.module.add_function(name, ty, Some(Linkage::External))
+    }
+
+    /// Gives `runtime` a body that passes its single parameter on to `target`.
+    fn define_runtime_forward(
+        &self,
+        runtime: FunctionValue<'a>,
+        target: FunctionValue<'a>,
+    ) -> SoulResult<()> {
+        let previous = self.builder.get_insert_block();
+        let entry = self.context.append_basic_block(runtime, "entry");
+        self.builder.position_at_end(entry);
+
+        let parameter = runtime.get_nth_param(0).ok_or(soul_error_internal!(
+            "runtime function without parameter",
+            None
+        ))?;
+        let call = self.builder.build_call(target, &[parameter.into()])?;
+        match call.try_as_basic_value().basic() {
+            Some(value) => self.builder.build_return(Some(&value))?,
+            None => self.builder.build_return(None)?,
+        };
+
+        if let Some(block) = previous {
+            self.builder.position_at_end(block);
+        }
+        Ok(())
+    }
+
     fn get_or_create_function(
         &mut self,
         function_id: FunctionId,
@@ -286,6 +433,14 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
         function.body.is_internal()
     }
 
+    /// Whether the function of `id` is an `extern "C"` definition or declaration.
+    fn is_c_abi_function(&self, id: FunctionKeyId) -> bool {
+        match self.function_keys.id_to_key(id) {
+            Some(key) => self.mir.tree.functions[key.function_id()].abi.is_some(),
+            None => false,
+        }
+    }
+
     fn get_block(&self, id: BlockId) -> BasicBlock<'a> {
         self.blocks[&(self.current.function_key(), id)]
     }
@@ -5,8 +5,9 @@ use inkwell::{
     context::Context,
     types::{BasicType, FloatMathType, IntMathType, PointerMathType},
     values::{
-        BasicMetadataValueEnum, BasicValue, BasicValueEnum, CallSiteValue, FloatMathValue,
-        FunctionValue, InstructionValue, IntMathValue, IntValue, PointerMathValue, PointerValue,
+        AggregateValue, BasicMetadataValueEnum, BasicValue, BasicValueEnum, CallSiteValue,
+        FloatMathValue, FunctionValue, GlobalValue, InstructionValue, IntMathValue, IntValue,
+        PointerMathValue, PointerValue,
     },
 };
 use soul_utils::{
@@ -54,6 +55,16 @@ impl<'ctx> IrBuilder<'ctx> {
             .map_err(build_error)
     }
 
+    pub fn build_global_string_ptr(
+        &self,
+        value: &str,
+        name: &str,
+    ) -> SoulResult<GlobalValue<'ctx>> {
+        self.inkwell
+            .build_global_string_ptr(value, name)
+            .map_err(build_error)
+    }
+
     pub fn build_unreachable(&self) -> SoulResult<InstructionValue<'ctx>> {
         self.inkwell.build_unreachable().map_err(build_error)
     }
@@ -80,6 +91,43 @@ impl<'ctx> IrBuilder<'ctx> {
         self.inkwell.position_at_end(block);
     }
 
+    pub fn get_insert_block(&self) -> Option<BasicBlock<'ctx>> {
+        self.inkwell.get_insert_block()
+    }
+
+    pub fn build_is_null(&self, ptr: PointerValue<'ctx>) -> SoulResult<IntValue<'ctx>> {
+        self.inkwell
+            .build_is_null(ptr, "is_null")
+            .map_err(build_error)
+    }
+
+    /// Pointer to element `index` of the buffer at `ptr` holding `element_type` values.
+    pub fn build_element_ptr<T>(
+        &self,
+        element_type: T,
+        ptr: PointerValue<'ctx>,
+        index: IntValue<'ctx>,
+    ) -> SoulResult<PointerValue<'ctx>>
+    where
+        T: BasicType<'ctx>,
+    {
+        // SAFETY: callers only index inside the buffer behind `ptr`
+        unsafe {
+            self.inkwell
+                .build_in_bounds_gep(element_type, ptr, &[index], "element_ptr")
+                .map_err(build_error)
+        }
+    }
+
+    pub fn build_int_cast<T>(&self, int_value: T, int_type: T::BaseType) -> SoulResult<T>
+    where
+        T: IntMathValue<'ctx>,
+    {
+        self.inkwell
+            .build_int_cast(int_value, int_type, "cast_int")
+            .map_err(build_error)
+    }
+
     pub fn store_parameter<V>(
         &self,
         ptr: PointerValue<'ctx>,
@@ -320,6 +368,20 @@ impl<'ctx> IrBuilder<'ctx> {
         self.inkwell.build_not(left, "not_int").map_err(build_error)
     }
 
+    pub fn build_extract_value<T>(
+        &self,
+        aggregate: T,
+        index: u32,
+        name: &str,
+    ) -> SoulResult<BasicValueEnum<'ctx>>
+    where
+        T: AggregateValue<'ctx>,
+    {
+        self.inkwell
+            .build_extract_value(aggregate, index, name)
+            .map_err(build_error)
+    }
+
     pub fn build_int_to_ptr<T>(
         &self,
         int: T,
@@ -350,6 +412,63 @@ impl<'ctx> IrBuilder<'ctx> {
             .map_err(build_error)
     }
 
+    /// `(left - right) / sizeof(pointee_ty)`, the distance between two pointers in elements.
+    pub fn build_ptr_diff<T>(
+        &self,
+        pointee_ty: T,
+        left: PointerValue<'ctx>,
+        right: PointerValue<'ctx>,
+    ) -> SoulResult<IntValue<'ctx>>
+    where
+        T: BasicType<'ctx>,
+    {
+        self.inkwell
+            .build_ptr_diff(pointee_ty, left, right, "ptr_diff")
+            .map_err(build_error)
+    }
+
+    pub fn build_select<V>(
+        &self,
+        condition: IntValue<'ctx>,
+        then: V,
+        otherwise: V,
+    ) -> SoulResult<BasicValueEnum<'ctx>>
+    where
+        V: BasicValue<'ctx>,
+    {
+        self.inkwell
+            .build_select(condition, then, otherwise, "select")
+            .map_err(build_error)
+    }
+
+    /// Copies `size` bytes from `src` to `dest`, the buffers may not overlap.
+    pub fn build_memcpy(
+        &self,
+        dest: PointerValue<'ctx>,
+        src: PointerValue<'ctx>,
+        size: IntValue<'ctx>,
+    ) -> SoulResult<()> {
+        self.inkwell
+            .build_memcpy(dest, 1, src, 1, size)
+            .map_err(build_error)?;
+
+        Ok(())
+    }
+
+    /// Sets `size` bytes at `dest` to the byte `value`.
+    pub fn build_memset(
+        &self,
+        dest: PointerValue<'ctx>,
+        value: IntValue<'ctx>,
+        size: IntValue<'ctx>,
+    ) -> SoulResult<()> {
+        self.inkwell
+            .build_memset(dest, 1, value, size)
+            .map_err(build_error)?;
+
+        Ok(())
+    }
+
     pub fn build_int_s_extend<T>(&self, int_value: T, int_type: T::BaseType) -> SoulResult<T>
     where
         T: IntMathValue<'ctx>,
@@ -1,4 +1,4 @@
-use crate::{GenericSubstitute, LlvmBackend};
+use crate::{GenericSubstitute, LlvmBackend, c_abi::CPassing};
 use hir::{ComplexLiteral, TypeId};
 use inkwell::{types::BasicTypeEnum, values::PointerValue};
 use mir_parser::mir::{self, Function, FunctionBody, LocalId};
@@ -15,7 +15,7 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
         generics: &GenericSubstitute,
     ) {
         let (entry_block, locals) = match &function.body {
-            FunctionBody::External(_) => {
+            FunctionBody::External(_) | FunctionBody::Dependency { .. } => {
                 panic!("can not call allocate_function_locals in external function")
             }
             FunctionBody::Internal {
@@ -58,7 +58,8 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
                 }
             };
 
-            let ir_global = self.module.add_global(ty, None, &self.local_name(local));
+            let name = format!("C{}{}", self.crate_id.index(), self.local_name(local));
+            let ir_global = self.module.add_global(ty, None, &name);
             self.push_global(local, ir_global.as_pointer_value());
             if let Some(comptime) = &global.literal {
                 let ir_operand = match self.lower_literal(comptime, global.ty, generics) {
@@ -84,6 +85,15 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
         type_args: &Vec<TypeId>,
         generics: &GenericSubstitute,
     ) {
+        // the `sret` pointer of an `extern "C"` function comes before its parameters
+        let sret_offset = match function.abi {
+            Some(_) => match self.lower_type(function.return_type, generics) {
+                Ok(Some(ty)) if matches!(self.c_passing(ty), CPassing::Indirect) => 1,
+                _ => 0,
+            },
+            None => 0,
+        };
+
         for (i, local_id) in function.parameters.iter().enumerate() {
             let local = &self.mir.tree.locals[*local_id];
             let name = self.local_name(*local_id);
@@ -114,10 +124,20 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
                     continue;
                 }
             };
-            let function = self.get_or_create_function(function.id, type_args);
-            let param = function
-                .get_nth_param(i as u32)
+            let llvm_function = self.get_or_create_function(function.id, type_args);
+            let param = llvm_function
+                .get_nth_param(i as u32 + sret_offset)
                 .expect("should have parameter");
+            let param = match function.abi {
+                Some(_) => match self.c_value(param, ty) {
+                    Ok(val) => val,
+                    Err(err) => {
+                        self.log_error(err);
+                        continue;
+                    }
+                },
+                None => param,
+            };
 
             if let Err(err) = self.builder.store_parameter(ptr, param) {
                 self.log_error(err);
@@ -1,4 +1,4 @@
-use inkwell::values::BasicValueEnum;
+use inkwell::{AddressSpace, values::BasicValueEnum};
 use mir_parser::mir::{
     BlockId, OperandKind, PlaceId, PlaceKind, Rvalue, RvalueKind, StatementKind,
 };
@@ -14,6 +14,9 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
         let block = &self.mir.tree.blocks[block_id];
         for statement_id in &block.statements {
             let statement = &self.mir.tree.statements[*statement_id];
+            if let Some(span) = self.mir.tree.statement_spans.get(*statement_id) {
+                self.current.set_span(*span);
+            }
             match &statement.kind {
                 StatementKind::Assign { place, value } => {
                     if let Err(err) = self.lower_assign(*place, value, generics) {
@@ -37,12 +40,38 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
                         self.log_error(err);
                     }
                 }
+                StatementKind::Free(place) => {
+                    if let Err(err) = self.lower_free(*place, generics) {
+                        self.log_error(err);
+                    }
+                }
                 StatementKind::StorageDead(_) => (),
                 StatementKind::StorageStart(_) => (),
             }
         }
     }
 
+    /// Frees the buffer of a heap array place, `free(NULL)` is a no-op so empty arrays need no check.
+    fn lower_free(&mut self, place_id: PlaceId, generics: &GenericSubstitute) -> SoulResult<()> {
+        let array_struct = self.types.types_map.array_struct;
+        let struct_ir = self.get_or_create_struct(array_struct, generics)?;
+
+        let base_operand = self.lower_place_to_operand(place_id, generics)?;
+        let base_ptr = base_operand.get_or_convert_pointer(&self.builder)?;
+        let data_ptr_ptr =
+            self.builder
+                .build_struct_gep_index(struct_ir, base_ptr, 0, "array_data_ptr")?;
+
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let data_ptr = self
+            .builder
+            .build_load(ptr_type, data_ptr_ptr, "array_data")?;
+
+        let free = self.get_or_define_free()?;
+        self.builder.build_call(free, &[data_ptr.into()])?;
+        Ok(())
+    }
+
     fn lower_assign(
         &mut self,
         place_id: PlaceId,
@@ -106,8 +135,14 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
                     }
                 };
 
+                self.check_deref(operand, ptr)?;
                 self.builder.store_operand(ptr, ir_value)?;
             }
+            PlaceKind::Index { base, index } => {
+                let element = self.lower_index_access(*base, index, ty, generics)?;
+                self.builder
+                    .store_operand(element.value.into_pointer_value(), ir_value)?;
+            }
         }
 
         Ok(())
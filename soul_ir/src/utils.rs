@@ -5,6 +5,7 @@ use inkwell::types::StructType;
 use mir_parser::mir;
 use soul_utils::{
     ids::{FunctionId, IdAlloc},
+    span::Span,
     vec_map::VecMap,
 };
 
@@ -33,12 +34,14 @@ impl<'a> StructStore<'a> {
 pub struct Current {
     function_key: FunctionKeyId,
     block: mir::BlockId,
+    span: Span,
 }
 impl Current {
     pub fn start(global: FunctionKeyId) -> Self {
         Self {
             function_key: global,
             block: mir::BlockId::error(),
+            span: Span::default_const(),
         }
     }
 
@@ -57,6 +60,15 @@ impl Current {
     pub fn set_block(&mut self, id: mir::BlockId) {
         self.block = id
     }
+
+    /// Span of the statement being lowered, runtime checks report it.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = span
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
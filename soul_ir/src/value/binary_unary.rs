@@ -1,9 +1,17 @@
 use ast::{BinaryOperator, UnaryOperator};
-use inkwell::{FloatPredicate, IntPredicate, values::BasicValueEnum};
+use hir::TypeId;
+use inkwell::{
+    FloatPredicate, IntPredicate,
+    values::{BasicValueEnum, IntValue},
+};
 use mir_parser::mir;
-use soul_utils::error::{SoulError, SoulErrorKind, SoulResult};
+use soul_utils::{
+    error::{SoulError, SoulErrorKind, SoulResult},
+    soul_error_internal,
+};
+use typed_hir::ThirTypeKind;
 
-use crate::{GenericSubstitute, IrOperand, LlvmBackend};
+use crate::{GenericSubstitute, IrOperand, LlvmBackend, checks::CheckedIntOp};
 
 impl<'f, 'a> LlvmBackend<'f, 'a> {
     pub(super) fn lower_binary(
@@ -11,6 +19,7 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
         left: &mir::Operand,
         operator: &BinaryOperator,
         right: &mir::Operand,
+        ty: TypeId,
         generics: &GenericSubstitute,
     ) -> SoulResult<IrOperand<'a>> {
         let mut ir_left = self.lower_operand(left, generics)?;
@@ -30,6 +39,10 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
                 .build_load(ir_right.info.ir_type, ptr, "load_left")?;
         }
 
+        if let ThirTypeKind::Pointer(element) = self.get_type(left.ty)?.kind {
+            return self.pointer_arithmetic(element, operator, ir_left, ir_right, ty, generics);
+        }
+
         match operator.node {
             ast::BinaryOperatorKind::Invalid => {
                 return Err(SoulError::new(
@@ -58,7 +71,12 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
             ast::BinaryOperatorKind::Log => todo!("impl log llvm"),
             ast::BinaryOperatorKind::Pow => todo!("impl pow llvm"),
             ast::BinaryOperatorKind::Root => todo!("impl root llvm"),
-            ast::BinaryOperatorKind::Range => todo!("impl range llvm"),
+            ast::BinaryOperatorKind::Range => {
+                return Err(soul_error_internal!(
+                    "range should be lowered as a struct, not a binary",
+                    None
+                ));
+            }
             ast::BinaryOperatorKind::TypeOf => todo!("impl typeof llvm"),
         }
     }
@@ -86,10 +104,103 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
         }
     }
 
+    /// `ptr + int` and `ptr - int` move `ptr` by whole elements, `ptr - ptr` gives the
+    /// distance in elements.
+    fn pointer_arithmetic(
+        &self,
+        element: TypeId,
+        operator: &BinaryOperator,
+        left: IrOperand<'a>,
+        right: IrOperand<'a>,
+        ty: TypeId,
+        generics: &GenericSubstitute,
+    ) -> SoulResult<IrOperand<'a>> {
+        let element_type = self
+            .lower_type(element, generics)?
+            .unwrap_or(self.context.i8_type().into());
+        let ptr = left.value.into_pointer_value();
+
+        let offset = match (operator.node, right.value) {
+            (ast::BinaryOperatorKind::Sub, BasicValueEnum::PointerValue(other)) => {
+                let distance = self.builder.build_ptr_diff(element_type, ptr, other)?;
+                let distance = self
+                    .builder
+                    .build_int_cast(distance, self.default_int_type)?;
+                return self.new_loaded_operand(distance.into(), ty, generics);
+            }
+            (ast::BinaryOperatorKind::Add, BasicValueEnum::IntValue(offset)) => {
+                self.build_offset_cast(offset, &right)?
+            }
+            (ast::BinaryOperatorKind::Sub, BasicValueEnum::IntValue(offset)) => {
+                let offset = self.build_offset_cast(offset, &right)?;
+                self.builder.build_int_neg(offset)?
+            }
+            _ => {
+                return Err(soul_error_internal!(
+                    format!(
+                        "'{}' on a pointer should be rejected by the type checker",
+                        operator.node.as_str()
+                    ),
+                    None
+                ));
+            }
+        };
+
+        let value = self.builder.build_element_ptr(element_type, ptr, offset)?;
+        Ok(IrOperand {
+            value: value.into(),
+            info: left.info.clone(),
+        })
+    }
+
+    /// Widens or truncates the offset of pointer arithmetic to `default_int_type`, keeping
+    /// unsigned offsets positive.
+    fn build_offset_cast(
+        &self,
+        offset: IntValue<'a>,
+        operand: &IrOperand<'a>,
+    ) -> SoulResult<IntValue<'a>> {
+        let int_type = self.default_int_type;
+        let from_width = offset.get_type().get_bit_width();
+        let to_width = int_type.get_bit_width();
+        if from_width > to_width {
+            self.builder.build_int_truncate(offset, int_type)
+        } else if from_width == to_width {
+            Ok(offset)
+        } else if self.is_signed_interger(&operand.info) {
+            self.builder.build_int_s_extend(offset, int_type)
+        } else {
+            self.builder.build_int_z_extend(offset, int_type)
+        }
+    }
+
+    /// Integer `+ - *`, overflow panics when `debug_checks` is on and wraps otherwise.
+    fn int_arithmetic(
+        &self,
+        op: CheckedIntOp,
+        left: &IrOperand<'a>,
+        l: IntValue<'a>,
+        r: IntValue<'a>,
+    ) -> SoulResult<BasicValueEnum<'a>> {
+        if self.options.debug_checks() {
+            let signed = self.is_signed_interger(&left.info);
+            return self
+                .build_checked_int_op(op, l, r, signed)
+                .map(BasicValueEnum::from);
+        }
+
+        match op {
+            CheckedIntOp::Add => self.builder.build_int_add(l, r),
+            CheckedIntOp::Sub => self.builder.build_int_sub(l, r),
+            CheckedIntOp::Mul => self.builder.build_int_mul(l, r),
+        }
+        .map(BasicValueEnum::from)
+    }
+
     fn add(&self, left: IrOperand<'a>, right: IrOperand<'a>) -> SoulResult<IrOperand<'a>> {
         let value = match (left.value, right.value) {
             (BasicValueEnum::IntValue(l), BasicValueEnum::IntValue(r)) => {
-                self.builder.build_int_add(l, r).map(BasicValueEnum::from)
+                self.int_arithmetic(CheckedIntOp::Add, &left, l, r)
             }
             (BasicValueEnum::FloatValue(l), BasicValueEnum::FloatValue(r)) => {
                 self.builder.build_float_add(l, r).map(BasicValueEnum::from)
@@ -113,7 +224,7 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
     fn sub(&self, left: IrOperand<'a>, right: IrOperand<'a>) -> SoulResult<IrOperand<'a>> {
         let value = match (left.value, right.value) {
             (BasicValueEnum::IntValue(l), BasicValueEnum::IntValue(r)) => {
-                self.builder.build_int_sub(l, r).map(BasicValueEnum::from)
+                self.int_arithmetic(CheckedIntOp::Sub, &left, l, r)
             }
             (BasicValueEnum::FloatValue(l), BasicValueEnum::FloatValue(r)) => {
                 self.builder.build_float_sub(l, r).map(BasicValueEnum::from)
@@ -137,7 +248,7 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
     fn mul(&self, left: IrOperand<'a>, right: IrOperand<'a>) -> SoulResult<IrOperand<'a>> {
         let value = match (left.value, right.value) {
             (BasicValueEnum::IntValue(l), BasicValueEnum::IntValue(r)) => {
-                self.builder.build_int_mul(l, r).map(BasicValueEnum::from)
+                self.int_arithmetic(CheckedIntOp::Mul, &left, l, r)
             }
             (BasicValueEnum::FloatValue(l), BasicValueEnum::FloatValue(r)) => {
                 self.builder.build_float_mul(l, r).map(BasicValueEnum::from)
@@ -161,7 +272,15 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
     fn div(&self, left: IrOperand<'a>, right: IrOperand<'a>) -> SoulResult<IrOperand<'a>> {
         let value = match (left.value, right.value) {
             (BasicValueEnum::IntValue(l), BasicValueEnum::IntValue(r)) => {
-                if self.is_signed_interger(&left.info) {
+                let signed = self.is_signed_interger(&left.info);
+                if self.options.debug_checks() {
+                    self.build_zero_division_check(r)?;
+                    if signed {
+                        self.build_signed_division_overflow_check(l, r, false)?;
+                    }
+                }
+
+                if signed {
                     self.builder
                         .build_int_signed_div(l, r)
                         .map(BasicValueEnum::from)
@@ -193,7 +312,15 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
     fn modulo(&self, left: IrOperand<'a>, right: IrOperand<'a>) -> SoulResult<IrOperand<'a>> {
         let value = match (left.value, right.value) {
             (BasicValueEnum::IntValue(l), BasicValueEnum::IntValue(r)) => {
-                if self.is_signed_interger(&left.info) {
+                let signed = self.is_signed_interger(&left.info);
+                if self.options.debug_checks() {
+                    self.build_zero_division_check(r)?;
+                    if signed {
+                        self.build_signed_division_overflow_check(l, r, true)?;
+                    }
+                }
+
+                if signed {
                     self.builder
                         .build_int_signed_rem(l, r)
                         .map(BasicValueEnum::from)
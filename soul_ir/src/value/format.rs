@@ -0,0 +1,253 @@
+use ast::ArrayKind;
+use hir::TypeId;
+use inkwell::{
+    AddressSpace, IntPredicate,
+    values::{BasicMetadataValueEnum, BasicValueEnum, FunctionValue, IntValue, PointerValue},
+};
+use mir_parser::mir::{FormatPiece, Operand, OperandKind};
+use soul_utils::{error::SoulResult, soul_error_internal, soul_names::PrimitiveTypes};
+use typed_hir::ThirTypeKind;
+
+use crate::{GenericSubstitute, IrOperand, LlvmBackend};
+
+/// Room `snprintf` gets for a number next to its width, fits any `i64` or `%g` float.
+const NUMBER_BUFFER_SIZE: u64 = 64;
+
+/// A piece of a format string once its text is known, copied into the result after all
+/// lengths are summed.
+struct FormatText<'a> {
+    data: PointerValue<'a>,
+    len: IntValue<'a>,
+    /// Spaces written after the text to reach the width of `{value:width}`.
+    padding: IntValue<'a>,
+}
+
+impl<'f, 'a> LlvmBackend<'f, 'a> {
+    /// Builds the `___Array` of a format string, the text is written into one new allocation.
+    pub(super) fn lower_format(
+        &self,
+        pieces: &[FormatPiece],
+        ty: TypeId,
+        generics: &GenericSubstitute,
+    ) -> SoulResult<IrOperand<'a>> {
+        let zero = self.default_int_type.const_zero();
+        let mut texts = Vec::with_capacity(pieces.len());
+        for piece in pieces {
+            let text = match piece {
+                FormatPiece::Text(text) => FormatText {
+                    data: self.const_c_string(text),
+                    len: self.default_int_type.const_int(text.len() as u64, false),
+                    padding: zero,
+                },
+                FormatPiece::Argument { value, width } => {
+                    self.lower_format_argument(value, *width, generics)?
+                }
+            };
+            texts.push(text);
+        }
+
+        let mut total = zero;
+        for text in &texts {
+            total = self.builder.build_int_add(total, text.len)?;
+            total = self.builder.build_int_add(total, text.padding)?;
+        }
+
+        // one byte more so an empty format string still gets an allocation
+        let one = self.default_int_type.const_int(1, false);
+        let size = self.builder.build_int_add(total, one)?;
+        let size = self.builder.build_int_cast(size, self.context.i64_type())?;
+        let (data, _, done) = self.build_checked_alloc(size, false)?;
+
+        let char_type = self.context.i8_type();
+        let space = char_type.const_int(b' ' as u64, false);
+        let mut offset = zero;
+        for text in texts {
+            let dest = self.builder.build_element_ptr(char_type, data, offset)?;
+            self.builder.build_memcpy(dest, text.data, text.len)?;
+            offset = self.builder.build_int_add(offset, text.len)?;
+
+            let dest = self.builder.build_element_ptr(char_type, data, offset)?;
+            self.builder.build_memset(dest, space, text.padding)?;
+            offset = self.builder.build_int_add(offset, text.padding)?;
+        }
+        self.builder.build_unconditional_branch(done)?;
+        self.builder.position_at_end(done);
+
+        let array_struct = self.types.types_map.array_struct;
+        let array_type = self.get_or_create_struct(array_struct, generics)?;
+        let array_ptr = self.builder.build_alloca(array_type, "format")?;
+        self.builder
+            .store_field(array_type, array_ptr, BasicValueEnum::from(data), 0)?;
+        self.builder
+            .store_field(array_type, array_ptr, BasicValueEnum::from(total), 1)?;
+        let array = self
+            .builder
+            .build_load(array_type, array_ptr, "format_value")?;
+
+        self.new_loaded_operand(array, ty, generics)
+    }
+
+    fn lower_format_argument(
+        &self,
+        value: &Operand,
+        width: Option<usize>,
+        generics: &GenericSubstitute,
+    ) -> SoulResult<FormatText<'a>> {
+        match self.get_type(value.ty)?.kind {
+            ThirTypeKind::Primitive(primitive) => {
+                self.lower_format_primitive(value, primitive, width, generics)
+            }
+            ThirTypeKind::Array { kind, .. } => {
+                let OperandKind::Ref { place, .. } = value.kind else {
+                    return Err(soul_error_internal!(
+                        "formatted array should be a Ref",
+                        None
+                    ));
+                };
+
+                let base = self.lower_place_to_operand(place, generics)?;
+                let base_ptr = base.get_or_convert_pointer(&self.builder)?;
+                let (data, len) = match kind {
+                    ArrayKind::StackArray(len) => {
+                        (base_ptr, self.default_int_type.const_int(len, false))
+                    }
+                    ArrayKind::HeapArray | ArrayKind::MutSlice | ArrayKind::ConstSlice => {
+                        self.load_array_fields(base_ptr, generics)?
+                    }
+                };
+
+                let padding = match width {
+                    Some(width) => self.build_format_padding(len, width)?,
+                    None => self.default_int_type.const_zero(),
+                };
+                Ok(FormatText { data, len, padding })
+            }
+            _ => Err(soul_error_internal!(
+                "format argument should be a primitive or char array",
+                None
+            )),
+        }
+    }
+
+    /// Writes a number, `char` or `bool` with `snprintf`, numbers are right aligned in their
+    /// width and `char`/`bool` left aligned.
+    fn lower_format_primitive(
+        &self,
+        value: &Operand,
+        primitive: PrimitiveTypes,
+        width: Option<usize>,
+        generics: &GenericSubstitute,
+    ) -> SoulResult<FormatText<'a>> {
+        let operand = self.lower_operand(value, generics)?;
+        let loaded = self.load_if_unloaded(operand)?;
+
+        let (format, argument): (&str, BasicMetadataValueEnum<'a>) = if primitive.is_float() {
+            let float = loaded.into_float_value();
+            let f64_type = self.context.f64_type();
+            let float = if float.get_type() == f64_type {
+                float
+            } else {
+                self.builder.build_float_ext(float, f64_type)?
+            };
+            ("%*g", float.into())
+        } else if primitive == PrimitiveTypes::Boolean {
+            let text = self.builder.build_select(
+                loaded.into_int_value(),
+                self.const_c_string("true"),
+                self.const_c_string("false"),
+            )?;
+            ("%-*s", text.into())
+        } else if primitive.is_character() {
+            let character = loaded.into_int_value();
+            let character = self
+                .builder
+                .build_int_cast(character, self.context.i32_type())?;
+            ("%-*c", character.into())
+        } else {
+            let signed = primitive.is_signed_interger();
+            let int = self.build_int_to_i64(loaded.into_int_value(), signed)?;
+            (if signed { "%*lld" } else { "%*llu" }, int.into())
+        };
+
+        let width = width.unwrap_or(0) as u64;
+        let buffer_size = self
+            .default_int_type
+            .const_int(width + NUMBER_BUFFER_SIZE, false);
+        let buffer_type = self
+            .context
+            .i8_type()
+            .array_type((width + NUMBER_BUFFER_SIZE) as u32);
+        let buffer = self.builder.build_alloca(buffer_type, "format_buffer")?;
+
+        let format = self.const_c_string(format);
+        let width = self.context.i32_type().const_int(width, false);
+        let snprintf = self.get_or_declare_snprintf();
+        let written = self
+            .builder
+            .build_call(
+                snprintf,
+                &[
+                    buffer.into(),
+                    buffer_size.into(),
+                    format.into(),
+                    width.into(),
+                    argument,
+                ],
+            )?
+            .try_as_basic_value()
+            .basic()
+            .ok_or(soul_error_internal!("snprintf should return an int", None))?
+            .into_int_value();
+        let len = self
+            .builder
+            .build_int_cast(written, self.default_int_type)?;
+
+        Ok(FormatText {
+            data: buffer,
+            len,
+            padding: self.default_int_type.const_zero(),
+        })
+    }
+
+    /// `width - len` when the text is shorter than `width`, otherwise `0`.
+    fn build_format_padding(&self, len: IntValue<'a>, width: usize) -> SoulResult<IntValue<'a>> {
+        let width = self.default_int_type.const_int(width as u64, false);
+        let shorter = self
+            .builder
+            .build_int_compare(IntPredicate::ULT, len, width)?;
+        let padding = self.builder.build_int_sub(width, len)?;
+        let zero = self.default_int_type.const_zero();
+
+        Ok(self
+            .builder
+            .build_select(shorter, padding, zero)?
+            .into_int_value())
+    }
+
+    fn build_int_to_i64(&self, int: IntValue<'a>, signed: bool) -> SoulResult<IntValue<'a>> {
+        let i64_type = self.context.i64_type();
+        let bit_width = int.get_type().get_bit_width();
+        if bit_width > 64 {
+            self.builder.build_int_truncate(int, i64_type)
+        } else if bit_width == 64 {
+            Ok(int)
+        } else if signed {
+            self.builder.build_int_s_extend(int, i64_type)
+        } else {
+            self.builder.build_int_z_extend(int, i64_type)
+        }
+    }
+
+    fn get_or_declare_snprintf(&self) -> FunctionValue<'a> {
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let snprintf_type = self.context.i32_type().fn_type(
+            &[
+                ptr_type.into(),
+                self.default_int_type.into(),
+                ptr_type.into(),
+            ],
+            true,
+        );
+        self.get_or_declare_libc("snprintf", snprintf_type)
+    }
+}
@@ -0,0 +1,220 @@
+use hir::TypeId;
+use inkwell::{
+    IntPredicate,
+    basic_block::BasicBlock,
+    types::{BasicType, BasicTypeEnum},
+    values::{BasicValueEnum, FunctionValue, IntValue, PointerValue},
+};
+use mir_parser::mir::Operand;
+use soul_utils::{error::SoulResult, soul_error_internal};
+
+use crate::{GenericSubstitute, IrOperand, LlvmBackend};
+
+impl<'f, 'a> LlvmBackend<'f, 'a> {
+    pub(super) fn lower_new(
+        &self,
+        value: &Operand,
+        fallible: bool,
+        ty: TypeId,
+        generics: &GenericSubstitute,
+    ) -> SoulResult<IrOperand<'a>> {
+        let value = self.lower_operand(value, generics)?;
+        let size = value
+            .info
+            .ir_type
+            .size_of()
+            .ok_or(soul_error_internal!("'new' value should be sized", None))?;
+
+        let (ptr, is_null, done) = self.build_checked_alloc(size, fallible)?;
+        self.builder.store_operand(ptr, value)?;
+        self.builder.build_unconditional_branch(done)?;
+        self.builder.position_at_end(done);
+
+        self.wrap_allocation(ptr.into(), is_null, fallible, ty, generics)
+    }
+
+    pub(super) fn lower_new_array(
+        &self,
+        element: &Operand,
+        len: &Operand,
+        fallible: bool,
+        ty: TypeId,
+        generics: &GenericSubstitute,
+    ) -> SoulResult<IrOperand<'a>> {
+        let element = self.lower_operand(element, generics)?;
+        let element_value = self.load_if_unloaded(element)?;
+        let element_type = element.info.ir_type;
+
+        let len = self.lower_operand(len, generics)?;
+        let len = self.load_if_unloaded(len)?.into_int_value();
+        let len = self.builder.build_int_cast(len, self.default_int_type)?;
+
+        let element_size = element_type.size_of().ok_or(soul_error_internal!(
+            "heap array element should be sized",
+            None
+        ))?;
+        let byte_len = self.builder.build_int_cast(len, self.context.i64_type())?;
+        let size = self.builder.build_int_mul(byte_len, element_size)?;
+
+        let (data, is_null, done) = self.build_checked_alloc(size, fallible)?;
+        self.build_fill(data, element_type, element_value, len)?;
+        self.builder.build_unconditional_branch(done)?;
+        self.builder.position_at_end(done);
+
+        let array_struct = self.types.types_map.array_struct;
+        let array_type = self.get_or_create_struct(array_struct, generics)?;
+        let array_ptr = self.builder.build_alloca(array_type, "heap_array")?;
+        self.builder
+            .store_field(array_type, array_ptr, BasicValueEnum::from(data), 0)?;
+        self.builder
+            .store_field(array_type, array_ptr, BasicValueEnum::from(len), 1)?;
+        let array = self
+            .builder
+            .build_load(array_type, array_ptr, "heap_array_value")?;
+
+        self.wrap_allocation(array, is_null, fallible, ty, generics)
+    }
+
+    /// Calls the allocator runtime and leaves the builder in the block where the allocation
+    /// succeeded, which has to branch to the returned end block.
+    ///
+    /// A failed allocation panics, or skips to the end block when `fallible`.
+    pub(super) fn build_checked_alloc(
+        &self,
+        size: IntValue<'a>,
+        fallible: bool,
+    ) -> SoulResult<(PointerValue<'a>, IntValue<'a>, BasicBlock<'a>)> {
+        let alloc = self.get_or_define_alloc()?;
+        let ptr = self
+            .builder
+            .build_call(alloc, &[size.into()])?
+            .try_as_basic_value()
+            .basic()
+            .ok_or(soul_error_internal!(
+                "allocator should return a pointer",
+                None
+            ))?
+            .into_pointer_value();
+        let is_null = self.builder.build_is_null(ptr)?;
+
+        let function = self.current_llvm_function()?;
+        let success = self.context.append_basic_block(function, "alloc_ok");
+        let done = self.context.append_basic_block(function, "alloc_done");
+        if fallible {
+            self.builder
+                .build_conditional_branch(is_null, done, success)?;
+        } else {
+            let failed = self.context.append_basic_block(function, "alloc_failed");
+            self.builder
+                .build_conditional_branch(is_null, failed, success)?;
+
+            self.builder.position_at_end(failed);
+            self.build_out_of_memory()?;
+        }
+
+        self.builder.position_at_end(success);
+        Ok((ptr, is_null, done))
+    }
+
+    /// Stores `element` in the first `len` slots of `data`.
+    fn build_fill(
+        &self,
+        data: PointerValue<'a>,
+        element_type: BasicTypeEnum<'a>,
+        element: BasicValueEnum<'a>,
+        len: IntValue<'a>,
+    ) -> SoulResult<()> {
+        let function = self.current_llvm_function()?;
+        let condition = self.context.append_basic_block(function, "fill_condition");
+        let body = self.context.append_basic_block(function, "fill_body");
+        let end = self.context.append_basic_block(function, "fill_end");
+
+        let index_ptr = self
+            .builder
+            .build_alloca(self.default_int_type, "fill_index")?;
+        self.builder
+            .store_parameter(index_ptr, self.default_int_type.const_zero())?;
+        self.builder.build_unconditional_branch(condition)?;
+
+        self.builder.position_at_end(condition);
+        let index = self
+            .builder
+            .build_load(self.default_int_type, index_ptr, "index")?
+            .into_int_value();
+        let in_bounds = self
+            .builder
+            .build_int_compare(IntPredicate::ULT, index, len)?;
+        self.builder
+            .build_conditional_branch(in_bounds, body, end)?;
+
+        self.builder.position_at_end(body);
+        let slot = self.builder.build_element_ptr(element_type, data, index)?;
+        self.builder.store_parameter(slot, element)?;
+        let one = self.default_int_type.const_int(1, false);
+        let next = self.builder.build_int_add(index, one)?;
+        self.builder.store_parameter(index_ptr, next)?;
+        self.builder.build_unconditional_branch(condition)?;
+
+        self.builder.position_at_end(end);
+        Ok(())
+    }
+
+    /// `new?` results are optionals (`{i1 is_null, T}`) that are null when allocation failed.
+    fn wrap_allocation(
+        &self,
+        value: BasicValueEnum<'a>,
+        is_null: IntValue<'a>,
+        fallible: bool,
+        ty: TypeId,
+        generics: &GenericSubstitute,
+    ) -> SoulResult<IrOperand<'a>> {
+        if !fallible {
+            return self.new_loaded_operand(value, ty, generics);
+        }
+
+        let optional_type = self
+            .lower_type(ty, generics)?
+            .ok_or(soul_error_internal!("'new?' type should be Some", None))?;
+        let optional_ptr = self.builder.build_alloca(optional_type, "optional")?;
+        self.builder.store_field(
+            optional_type,
+            optional_ptr,
+            BasicValueEnum::from(is_null),
+            0,
+        )?;
+        self.builder
+            .store_field(optional_type, optional_ptr, value, 1)?;
+        let optional = self
+            .builder
+            .build_load(optional_type, optional_ptr, "optional_value")?;
+
+        self.new_loaded_operand(optional, ty, generics)
+    }
+
+    pub(crate) fn current_llvm_function(&self) -> SoulResult<FunctionValue<'a>> {
+        self.builder
+            .get_insert_block()
+            .and_then(|block| block.get_parent())
+            .ok_or(soul_error_internal!(
+                "builder should be inside a function",
+                None
+            ))
+    }
+
+    fn build_out_of_memory(&self) -> SoulResult<()> {
+        self.build_panic("out of memory")
+    }
+
+    pub(super) fn load_if_unloaded(
+        &self,
+        operand: IrOperand<'a>,
+    ) -> SoulResult<BasicValueEnum<'a>> {
+        if !operand.info.is_unloaded {
+            return Ok(operand.value);
+        }
+
+        let ptr = operand.value.into_pointer_value();
+        self.builder
+            .build_load(operand.info.ir_type, ptr, "loaded_value")
+    }
+}
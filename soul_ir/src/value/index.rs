@@ -0,0 +1,60 @@
+use ast::ArrayKind;
+use hir::TypeId;
+use inkwell::values::PointerValue;
+use mir_parser::mir::{Operand, PlaceId};
+use soul_utils::{error::SoulResult, soul_error_internal};
+use typed_hir::ThirTypeKind;
+
+use crate::{GenericSubstitute, IrOperand, LlvmBackend};
+
+impl<'f, 'a> LlvmBackend<'f, 'a> {
+    /// Unloaded operand of `base[index]`, bounds checked against `len` when `debug_checks` is on.
+    pub(crate) fn lower_index_access(
+        &self,
+        base: PlaceId,
+        index: &Operand,
+        ty: TypeId,
+        generics: &GenericSubstitute,
+    ) -> SoulResult<IrOperand<'a>> {
+        let element_ptr = self.lower_element_ptr(base, index, generics)?;
+        self.new_unloaded_operand(element_ptr.into(), ty, generics)
+    }
+
+    fn lower_element_ptr(
+        &self,
+        base: PlaceId,
+        index: &Operand,
+        generics: &GenericSubstitute,
+    ) -> SoulResult<PointerValue<'a>> {
+        let (element, kind) = match self.get_type(self.mir.tree.places[base].ty)?.kind {
+            ThirTypeKind::Array { element, kind } => (element, kind),
+            _ => {
+                return Err(soul_error_internal!(
+                    "indexed place should be an array",
+                    None
+                ));
+            }
+        };
+        let element_type = self
+            .lower_type(element, generics)?
+            .unwrap_or(self.context.i8_type().into());
+
+        let base_operand = self.lower_place_to_operand(base, generics)?;
+        let base_ptr = base_operand.get_or_convert_pointer(&self.builder)?;
+        let (data, len) = match kind {
+            ArrayKind::StackArray(len) => (base_ptr, self.default_int_type.const_int(len, false)),
+            ArrayKind::HeapArray | ArrayKind::MutSlice | ArrayKind::ConstSlice => {
+                self.load_array_fields(base_ptr, generics)?
+            }
+        };
+
+        let index = self.lower_operand(index, generics)?;
+        let index = self.load_if_unloaded(index)?.into_int_value();
+        let index = self.builder.build_int_cast(index, self.default_int_type)?;
+        if self.options.debug_checks() {
+            self.build_bounds_check(index, len)?;
+        }
+
+        self.builder.build_element_ptr(element_type, data, index)
+    }
+}
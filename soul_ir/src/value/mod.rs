@@ -1,14 +1,21 @@
 use crate::{GenericSubstitute, IrOperand, LlvmBackend, Local, OperandInfo};
 use ast::ArrayKind;
 use hir::{ComplexLiteral, StructId, TypeId};
-use inkwell::{types::StructType, values::BasicValueEnum};
+use inkwell::{
+    types::StructType,
+    values::{BasicValueEnum, PointerValue},
+};
 use mir_parser::mir::{self, AggregateBody, Place, PlaceId, Rvalue, RvalueKind};
 use soul_utils::{error::SoulResult, soul_error_internal};
 use typed_hir::{FieldInfo, ThirTypeKind, display_thir::DisplayThirType};
 
 pub(crate) mod binary_unary;
 pub(crate) mod cast;
+pub(crate) mod format;
+pub(crate) mod heap;
+pub(crate) mod index;
 pub(crate) mod operand;
+pub(crate) mod slice;
 
 impl<'f, 'a> LlvmBackend<'f, 'a> {
     pub(crate) fn lower_rvalue(
@@ -20,14 +27,26 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
         match &value.kind {
             RvalueKind::Place(place) => self.lower_rvalue_place(place, generics),
             RvalueKind::CastUse { value, cast_to } => self.lower_cast(value, *cast_to, generics),
-            RvalueKind::Operand(operand) => self.lower_operand(operand, generics),
+            RvalueKind::Operand(operand) | RvalueKind::Copy(operand) => {
+                self.lower_operand(operand, generics)
+            }
             RvalueKind::Binary {
                 left,
                 operator,
                 right,
-            } => self.lower_binary(left, operator, right, generics),
+            } => self.lower_binary(left, operator, right, ty, generics),
             RvalueKind::Unary { operator, value } => self.lower_unary(value, operator, generics),
             RvalueKind::StackAlloc(ty) => self.lower_stack_alloc(*ty, generics),
+            RvalueKind::New { value, fallible } => self.lower_new(value, *fallible, ty, generics),
+            RvalueKind::NewArray {
+                element,
+                len,
+                fallible,
+            } => self.lower_new_array(element, len, *fallible, ty, generics),
+            RvalueKind::Slice { array, start, end } => {
+                self.lower_slice(array, start.as_ref(), end.as_ref(), ty, generics)
+            }
+            RvalueKind::Format(pieces) => self.lower_format(pieces, ty, generics),
             RvalueKind::Aggregate { struct_type, body } => {
                 self.lower_struct_contructor(ty, *struct_type, body, generics)
             }
@@ -88,7 +107,11 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
                 };
                 self.lower_field_access(*base, field_info, generics)
             }
-            mir::PlaceKind::Temp(_) | mir::PlaceKind::Deref(_) | mir::PlaceKind::Local(_) => {
+            mir::PlaceKind::Index { base, index } => {
+                self.lower_index_access(*base, index, place.ty, generics)
+            }
+            mir::PlaceKind::Deref(operand) => self.load_deref(operand, place.ty, generics),
+            mir::PlaceKind::Temp(_) | mir::PlaceKind::Local(_) => {
                 unreachable!()
             }
         }
@@ -239,16 +262,7 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
                 let temp_op = self.get_temp(*temp_id)?;
                 Ok(temp_op.clone())
             }
-            mir::PlaceKind::Deref(operand) => {
-                let ty = self
-                    .lower_type(operand.ty, generics)?
-                    .unwrap_or(self.context.i8_type().into());
-
-                let ptr_op = self.lower_operand(operand, generics)?;
-                let ptr = ptr_op.value.into_pointer_value();
-                let value = self.builder.build_load(ty, ptr, "load")?.into();
-                self.new_loaded_operand(value, operand.ty, generics)
-            }
+            mir::PlaceKind::Deref(operand) => self.load_deref(operand, ty, generics),
             mir::PlaceKind::Field {
                 struct_type: _,
                 base,
@@ -257,6 +271,43 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
                 let field_info = &self.types.types_table.fields[*field_id];
                 self.lower_field_access(*base, field_info, generics)
             }
+            mir::PlaceKind::Index { base, index } => {
+                self.lower_index_access(*base, index, ty, generics)
+            }
+        }
+    }
+
+    /// Loads the `ty` behind the pointer `operand`.
+    fn load_deref(
+        &self,
+        operand: &mir::Operand,
+        ty: TypeId,
+        generics: &GenericSubstitute,
+    ) -> SoulResult<IrOperand<'a>> {
+        let ir_type = self
+            .lower_type(ty, generics)?
+            .unwrap_or(self.context.i8_type().into());
+
+        let ptr_op = self.lower_operand(operand, generics)?;
+        let ptr = ptr_op.value.into_pointer_value();
+        self.check_deref(operand, ptr)?;
+        let value = self.builder.build_load(ir_type, ptr, "load")?;
+        self.new_loaded_operand(value, ty, generics)
+    }
+
+    /// Null checks the dereference of a `*T`, references can never be null.
+    pub(crate) fn check_deref(
+        &self,
+        operand: &mir::Operand,
+        ptr: PointerValue<'a>,
+    ) -> SoulResult<()> {
+        if !self.options.debug_checks() {
+            return Ok(());
+        }
+
+        match self.get_type(operand.ty)?.kind {
+            ThirTypeKind::Pointer(_) => self.build_null_check(ptr),
+            _ => Ok(()),
         }
     }
 
@@ -188,9 +188,14 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
                     PrimitiveSize::Bit128 => self.context.i128_type(),
                 };
 
-                let value = int_type.const_int(*value as u64, negative).into();
+                let value = match size {
+                    PrimitiveSize::Bit128 => {
+                        int_type.const_int_arbitrary_precision(&to_words(*value as u128))
+                    }
+                    _ => int_type.const_int(*value as u64, negative),
+                };
 
-                self.new_loaded_operand(value, should_be, generics)?
+                self.new_loaded_operand(value.into(), should_be, generics)?
             }
             ast::Literal::Uint(value) => {
                 let hir_type = self
@@ -223,9 +228,14 @@ impl<'f, 'a> LlvmBackend<'f, 'a> {
                     PrimitiveSize::Bit128 => self.context.i128_type(),
                 };
 
-                let value = int_type.const_int(*value as u64, false).into();
+                let value = match size {
+                    PrimitiveSize::Bit128 => {
+                        int_type.const_int_arbitrary_precision(&to_words(*value))
+                    }
+                    _ => int_type.const_int(*value as u64, false),
+                };
 
-                self.new_loaded_operand(value, should_be, generics)?
+                self.new_loaded_operand(value.into(), should_be, generics)?
             }
             ast::Literal::Float(value) => {
                 let size = match self
@@ -511,3 +521,8 @@ impl Alignment {
         self as u32
     }
 }
+
+/// The little-endian 64-bit words of a 128-bit constant.
+fn to_words(value: u128) -> [u64; 2] {
+    [value as u64, (value >> 64) as u64]
+}
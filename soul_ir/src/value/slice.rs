@@ -0,0 +1,116 @@
+use ast::ArrayKind;
+use hir::TypeId;
+use inkwell::{
+    AddressSpace,
+    values::{BasicValueEnum, IntValue, PointerValue},
+};
+use mir_parser::mir::{Operand, OperandKind};
+use soul_utils::{error::SoulResult, soul_error_internal};
+use typed_hir::ThirTypeKind;
+
+use crate::{GenericSubstitute, IrOperand, LlvmBackend};
+
+impl<'f, 'a> LlvmBackend<'f, 'a> {
+    /// Builds the `___Array` of `array[start..end]`, which is `{ptr + start, end - start}`,
+    /// checked for `start <= end <= len` when `debug_checks` is on.
+    pub(super) fn lower_slice(
+        &self,
+        array: &Operand,
+        start: Option<&Operand>,
+        end: Option<&Operand>,
+        ty: TypeId,
+        generics: &GenericSubstitute,
+    ) -> SoulResult<IrOperand<'a>> {
+        let OperandKind::Ref { place, .. } = array.kind else {
+            return Err(soul_error_internal!("sliced array should be a Ref", None));
+        };
+
+        let (element, kind) = match self.get_type(array.ty)?.kind {
+            ThirTypeKind::Array { element, kind } => (element, kind),
+            _ => {
+                return Err(soul_error_internal!(
+                    "sliced place should be an array",
+                    None
+                ));
+            }
+        };
+        let element_type = self
+            .lower_type(element, generics)?
+            .unwrap_or(self.context.i8_type().into());
+
+        let base = self.lower_place_to_operand(place, generics)?;
+        let base_ptr = base.get_or_convert_pointer(&self.builder)?;
+        let (data, len) = match kind {
+            ArrayKind::StackArray(len) => (base_ptr, self.default_int_type.const_int(len, false)),
+            ArrayKind::HeapArray | ArrayKind::MutSlice | ArrayKind::ConstSlice => {
+                self.load_array_fields(base_ptr, generics)?
+            }
+        };
+
+        let start = match start {
+            Some(start) => self.lower_slice_bound(start, generics)?,
+            None => self.default_int_type.const_zero(),
+        };
+        let end = match end {
+            Some(end) => self.lower_slice_bound(end, generics)?,
+            None => len,
+        };
+        if self.options.debug_checks() {
+            self.build_slice_bounds_check(start, end, len)?;
+        }
+
+        let data = self.builder.build_element_ptr(element_type, data, start)?;
+        let len = self.builder.build_int_sub(end, start)?;
+
+        let array_struct = self.types.types_map.array_struct;
+        let array_type = self.get_or_create_struct(array_struct, generics)?;
+        let slice_ptr = self.builder.build_alloca(array_type, "slice")?;
+        self.builder
+            .store_field(array_type, slice_ptr, BasicValueEnum::from(data), 0)?;
+        self.builder
+            .store_field(array_type, slice_ptr, BasicValueEnum::from(len), 1)?;
+        let slice = self
+            .builder
+            .build_load(array_type, slice_ptr, "slice_value")?;
+
+        self.new_loaded_operand(slice, ty, generics)
+    }
+
+    pub(crate) fn load_array_fields(
+        &self,
+        array_ptr: PointerValue<'a>,
+        generics: &GenericSubstitute,
+    ) -> SoulResult<(PointerValue<'a>, IntValue<'a>)> {
+        let array_struct = self.types.types_map.array_struct;
+        let array_type = self.get_or_create_struct(array_struct, generics)?;
+
+        let data_ptr =
+            self.builder
+                .build_struct_gep_index(array_type, array_ptr, 0, "array_data_ptr")?;
+        let ptr_type = self.context.ptr_type(AddressSpace::default());
+        let data = self
+            .builder
+            .build_load(ptr_type, data_ptr, "array_data")?
+            .into_pointer_value();
+
+        let len_ptr =
+            self.builder
+                .build_struct_gep_index(array_type, array_ptr, 1, "array_len_ptr")?;
+        let len = self
+            .builder
+            .build_load(self.default_int_type, len_ptr, "array_len")?
+            .into_int_value();
+
+        Ok((data, len))
+    }
+
+    fn lower_slice_bound(
+        &self,
+        bound: &Operand,
+        generics: &GenericSubstitute,
+    ) -> SoulResult<IntValue<'a>> {
+        let bound = self.lower_operand(bound, generics)?;
+        let bound = self.load_if_unloaded(bound)?.into_int_value();
+        self.builder.build_int_cast(bound, self.default_int_type)
+    }
+}
@@ -0,0 +1,993 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use hir::FieldId;
+use soul_utils::{
+    error::{SoulError, SoulErrorKind},
+    ids::FunctionId,
+    span::Span,
+    vec_map::VecMap,
+    vec_set::VecSet,
+};
+use typed_hir::ThirTypesMap;
+
+use crate::{
+    MirContext,
+    mir::{self, BlockId, LocalId, MirTree, PlaceId, TempId},
+};
+
+/// A local or temp that can store a reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Holder {
+    Local(LocalId),
+    Temp(TempId),
+}
+
+/// What a reference stored in a [`Holder`] may point into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Origin {
+    /// Borrow of a local of this function, keyed by the borrowed place.
+    Loan(PlaceId),
+    /// Reference passed in through the n-th parameter.
+    Parameter(usize),
+}
+
+/// Origins of the references each holder may contain.
+type OriginState = BTreeMap<Holder, BTreeSet<Origin>>;
+
+/// Holders that are used later on, mapped to the span of that use.
+type LiveState = BTreeMap<Holder, Span>;
+
+/// `local.field.field`, the part of a local a borrow or access touches.
+#[derive(Debug, Clone)]
+struct Path {
+    local: LocalId,
+    fields: Vec<FieldId>,
+}
+impl Path {
+    /// Paths overlap when one is a prefix of the other (`a` and `a.b`).
+    fn overlaps(&self, other: &Path) -> bool {
+        self.local == other.local
+            && self
+                .fields
+                .iter()
+                .zip(&other.fields)
+                .all(|(this, other)| this == other)
+    }
+}
+
+struct Loan {
+    path: Path,
+    mutable: bool,
+    span: Span,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum AccessKind {
+    Read,
+    Move,
+    Write,
+    Borrow {
+        loan: PlaceId,
+        mutable: bool,
+    },
+    /// `StorageDead`, the value stops existing.
+    Dead,
+}
+impl AccessKind {
+    fn conflicts_with(&self, loan: &Loan) -> bool {
+        match self {
+            AccessKind::Read | AccessKind::Borrow { mutable: false, .. } => loan.mutable,
+            AccessKind::Move
+            | AccessKind::Write
+            | AccessKind::Dead
+            | AccessKind::Borrow { mutable: true, .. } => true,
+        }
+    }
+}
+
+struct Access {
+    path: Path,
+    kind: AccessKind,
+}
+
+/// An error together with the notes pointing at the borrow, its later use and the drop.
+struct BorrowFault {
+    error: SoulError,
+    notes: Vec<SoulError>,
+}
+
+impl<'a> MirContext<'a> {
+    /// Reports accesses that break aliasing XOR mutability of `&`/`@` borrows
+    /// and borrows that outlive the value they point into.
+    pub(crate) fn check_borrows(&mut self) {
+        let types = &self.hir_response.typed.types_map;
+        let functions = &self.hir_response.hir.nodes.functions;
+
+        let mut faults = vec![];
+        for function in self.tree.functions.values() {
+            if let Some(mut checker) = BorrowChecker::new(&self.tree, types, functions, function) {
+                checker.check();
+                faults.append(&mut checker.faults);
+            }
+        }
+
+        for BorrowFault { error, notes } in faults {
+            self.log_error(error);
+            for note in notes {
+                self.log_note(note);
+            }
+        }
+    }
+}
+
+struct BorrowChecker<'t> {
+    tree: &'t MirTree,
+    types: &'t ThirTypesMap,
+    functions: &'t VecMap<FunctionId, hir::Function>,
+    function: &'t mir::Function,
+    entry_block: BlockId,
+    blocks: &'t [BlockId],
+    /// locals owned by this function, globals are never borrow checked
+    tracked: VecSet<LocalId>,
+    loans: VecMap<PlaceId, Loan>,
+    live_in: VecMap<BlockId, LiveState>,
+    faults: Vec<BorrowFault>,
+}
+
+impl<'t> BorrowChecker<'t> {
+    fn new(
+        tree: &'t MirTree,
+        types: &'t ThirTypesMap,
+        functions: &'t VecMap<FunctionId, hir::Function>,
+        function: &'t mir::Function,
+    ) -> Option<Self> {
+        let (entry_block, locals, blocks) = match &function.body {
+            mir::FunctionBody::Internal {
+                entry_block,
+                locals,
+                blocks,
+            } => (*entry_block, locals, blocks),
+            mir::FunctionBody::External(_) | mir::FunctionBody::Dependency { .. } => return None,
+        };
+
+        let mut tracked = VecSet::from_slice(&function.parameters);
+        for local in locals {
+            tracked.insert(*local);
+        }
+
+        Some(Self {
+            tree,
+            types,
+            functions,
+            function,
+            entry_block,
+            blocks,
+            tracked,
+            loans: VecMap::const_default(),
+            live_in: VecMap::const_default(),
+            faults: vec![],
+        })
+    }
+
+    fn check(&mut self) {
+        self.collect_loans();
+        self.compute_liveness();
+
+        let mut entry_state = OriginState::new();
+        for (i, parameter) in self.function.parameters.iter().enumerate() {
+            let holds_reference = self
+                .tree
+                .locals
+                .get(*parameter)
+                .is_some_and(|local| self.types.contains_reference(local.ty()));
+
+            if holds_reference {
+                entry_state.insert(
+                    Holder::Local(*parameter),
+                    BTreeSet::from([Origin::Parameter(i)]),
+                );
+            }
+        }
+
+        let mut entry_states: VecMap<BlockId, OriginState> = VecMap::const_default();
+        entry_states.insert(self.entry_block, entry_state);
+
+        let mut worklist = vec![self.entry_block];
+        while let Some(block_id) = worklist.pop() {
+            let mut state = entry_states[block_id].clone();
+            self.visit_block(block_id, &mut state, None);
+
+            for successor in self.tree.blocks[block_id].terminator.successors() {
+                let changed = match entry_states.get_mut(successor) {
+                    Some(entry) => join(entry, &state),
+                    None => {
+                        entry_states.insert(successor, state.clone());
+                        true
+                    }
+                };
+
+                if changed {
+                    worklist.push(successor);
+                }
+            }
+        }
+
+        let mut faults = vec![];
+        for (block_id, entry) in entry_states.entries() {
+            let mut state = entry.clone();
+            self.visit_block(block_id, &mut state, Some(&mut faults));
+        }
+        self.faults = faults;
+    }
+
+    fn collect_loans(&mut self) {
+        for block_id in self.blocks {
+            let block = &self.tree.blocks[*block_id];
+            for statement_id in &block.statements {
+                let span = self.statement_span(*statement_id);
+                let statement = &self.tree.statements[*statement_id];
+                for operand in statement_operands(&statement.kind) {
+                    self.collect_loan(operand, span);
+                }
+            }
+
+            if let Some(operand) = terminator_operand(&block.terminator) {
+                self.collect_loan(operand, self.terminator_span(*block_id));
+            }
+        }
+    }
+
+    fn collect_loan(&mut self, operand: &mir::Operand, span: Span) {
+        let mir::OperandKind::Ref { place, mutable } = operand.kind else {
+            return;
+        };
+
+        if let Some(path) = self.place_path(&self.tree.places[place]) {
+            self.loans.insert(
+                place,
+                Loan {
+                    path,
+                    mutable,
+                    span,
+                },
+            );
+        }
+    }
+
+    /// Backward dataflow of the holders that are still used later on.
+    fn compute_liveness(&mut self) {
+        loop {
+            let mut changed = false;
+            for block_id in self.blocks.iter().rev() {
+                let mut live = self.live_out(*block_id);
+                self.block_liveness(*block_id, &mut live);
+
+                changed |= match self.live_in.get_mut(*block_id) {
+                    Some(entry) => join_live(entry, &live),
+                    None => {
+                        self.live_in.insert(*block_id, live);
+                        true
+                    }
+                };
+            }
+
+            if !changed {
+                break;
+            }
+        }
+    }
+
+    fn live_out(&self, block_id: BlockId) -> LiveState {
+        let mut live = LiveState::new();
+        for successor in self.tree.blocks[block_id].terminator.successors() {
+            if let Some(entry) = self.live_in.get(successor) {
+                join_live(&mut live, entry);
+            }
+        }
+        live
+    }
+
+    /// Turns `live` from the state after the block into the state before it,
+    /// returns the state after every statement.
+    fn block_liveness(&self, block_id: BlockId, live: &mut LiveState) -> Vec<LiveState> {
+        let block = &self.tree.blocks[block_id];
+        if let Some(operand) = terminator_operand(&block.terminator) {
+            let span = self.terminator_span(block_id);
+            for holder in self.operand_uses(operand) {
+                live.insert(holder, span);
+            }
+        }
+
+        let mut live_after = vec![LiveState::new(); block.statements.len()];
+        for (i, statement_id) in block.statements.iter().enumerate().rev() {
+            live_after[i] = live.clone();
+
+            let statement = &self.tree.statements[*statement_id];
+            if let Some(holder) = self.statement_def(&statement.kind) {
+                live.remove(&holder);
+            }
+            if let mir::StatementKind::StorageDead(local) = statement.kind {
+                live.remove(&Holder::Local(local));
+            }
+
+            let span = self.statement_span(*statement_id);
+            for holder in self.statement_uses(&statement.kind) {
+                live.insert(holder, span);
+            }
+        }
+        live_after
+    }
+
+    fn visit_block(
+        &self,
+        block_id: BlockId,
+        state: &mut OriginState,
+        mut faults: Option<&mut Vec<BorrowFault>>,
+    ) {
+        let block = &self.tree.blocks[block_id];
+
+        let live_out = self.live_out(block_id);
+        let live_after = match faults {
+            Some(_) => self.block_liveness(block_id, &mut live_out.clone()),
+            None => vec![],
+        };
+
+        for (i, statement_id) in block.statements.iter().enumerate() {
+            let statement = &self.tree.statements[*statement_id];
+            if let Some(faults) = faults.as_deref_mut() {
+                let span = self.statement_span(*statement_id);
+                let def = self.statement_def(&statement.kind);
+                let accesses = self.statement_accesses(&statement.kind);
+                self.check_accesses(&accesses, state, &live_after[i], def, span, faults);
+            }
+
+            self.transfer(&statement.kind, state);
+        }
+
+        let Some(faults) = faults else {
+            return;
+        };
+
+        let span = self.terminator_span(block_id);
+        if let Some(operand) = terminator_operand(&block.terminator) {
+            let accesses = self.operand_accesses(operand);
+            self.check_accesses(&accesses, state, &live_out, None, span, faults);
+        }
+
+        if let mir::Terminator::Return(Some(operand)) = &block.terminator {
+            self.check_return(block, operand, state, span, faults);
+        }
+    }
+
+    fn check_accesses(
+        &self,
+        accesses: &[Access],
+        state: &OriginState,
+        live: &LiveState,
+        def: Option<Holder>,
+        span: Span,
+        faults: &mut Vec<BorrowFault>,
+    ) {
+        // loans kept alive by a holder that is used after this statement
+        let mut active: Vec<(PlaceId, Span)> = vec![];
+        for (holder, use_span) in live {
+            if def == Some(*holder) {
+                continue;
+            }
+
+            for origin in state.get(holder).into_iter().flatten() {
+                if let Origin::Loan(loan) = origin
+                    && !active.iter().any(|(active, _)| active == loan)
+                {
+                    active.push((*loan, *use_span));
+                }
+            }
+        }
+
+        for (i, access) in accesses.iter().enumerate() {
+            // borrows of earlier operands stay alive while the statement runs
+            let earlier = accesses[..i]
+                .iter()
+                .filter_map(|earlier| match earlier.kind {
+                    AccessKind::Borrow { loan, .. } => Some((loan, span)),
+                    _ => None,
+                });
+
+            let conflict = active.iter().copied().chain(earlier).find(|(loan_id, _)| {
+                let loan = &self.loans[*loan_id];
+                loan.path.overlaps(&access.path) && access.kind.conflicts_with(loan)
+            });
+
+            if let Some((loan_id, use_span)) = conflict {
+                let loan = &self.loans[loan_id];
+                faults.push(conflict_fault(loan, access.kind, span, use_span));
+            }
+        }
+    }
+
+    /// A returned reference may not borrow a local and may only borrow from
+    /// the parameters sharing the lifetime of the return type.
+    fn check_return(
+        &self,
+        block: &mir::Block,
+        operand: &mir::Operand,
+        state: &OriginState,
+        span: Span,
+        faults: &mut Vec<BorrowFault>,
+    ) {
+        // a borrow held by a temp is already reported at the `StorageDead` of its local
+        if let mir::OperandKind::Ref { place, .. } = operand.kind
+            && let Some(loan) = self.loans.get(place)
+        {
+            let drop_span = block.statements.iter().find_map(|statement_id| {
+                match self.tree.statements[*statement_id].kind {
+                    mir::StatementKind::StorageDead(local) if local == loan.path.local => {
+                        Some(self.statement_span(*statement_id))
+                    }
+                    _ => None,
+                }
+            });
+
+            faults.push(BorrowFault {
+                error: SoulError::new(
+                    "cannot return a reference to a local value",
+                    SoulErrorKind::BorrowOutlivesValue,
+                    Some(span),
+                ),
+                notes: drop_span
+                    .map(|drop_span| {
+                        SoulError::new(
+                            "value dropped here before the return",
+                            SoulErrorKind::BorrowOutlivesValue,
+                            Some(drop_span),
+                        )
+                    })
+                    .into_iter()
+                    .collect(),
+            });
+            return;
+        }
+
+        let Some(return_borrows) = self
+            .functions
+            .get(self.function.id)
+            .and_then(|function| function.return_borrows.as_ref())
+        else {
+            return;
+        };
+
+        let escapes = self
+            .operand_origins(operand, state)
+            .into_iter()
+            .any(|origin| matches!(origin, Origin::Parameter(i) if !return_borrows.contains(&i)));
+
+        if escapes {
+            faults.push(BorrowFault {
+                error: SoulError::new(
+                    "returned reference borrows from a parameter that does not share the lifetime of the return type",
+                    SoulErrorKind::BorrowOutlivesValue,
+                    Some(span),
+                ),
+                notes: vec![],
+            });
+        }
+    }
+
+    fn transfer(&self, kind: &mir::StatementKind, state: &mut OriginState) {
+        match kind {
+            mir::StatementKind::Assign { place, value } => {
+                let origins = self.rvalue_origins(value, state);
+                self.assign_origins(*place, origins, state);
+            }
+            mir::StatementKind::Call {
+                id,
+                arguments,
+                return_place: Some(place),
+                ..
+            } => {
+                let origins = self.call_origins(*id, arguments, state);
+                self.assign_origins(*place, origins, state);
+            }
+            mir::StatementKind::StorageStart(locals) => {
+                for local in locals {
+                    state.remove(&Holder::Local(*local));
+                }
+            }
+            mir::StatementKind::StorageDead(local) => {
+                state.remove(&Holder::Local(*local));
+            }
+            mir::StatementKind::Eval(_)
+            | mir::StatementKind::Call { .. }
+            | mir::StatementKind::Free(_) => (),
+        }
+    }
+
+    fn assign_origins(
+        &self,
+        place_id: PlaceId,
+        origins: BTreeSet<Origin>,
+        state: &mut OriginState,
+    ) {
+        let place = &self.tree.places[place_id];
+        let origins = match self.types.contains_reference(place.ty) {
+            true => origins,
+            false => BTreeSet::new(),
+        };
+
+        match &place.kind {
+            mir::PlaceKind::Local(local) => set_origins(state, Holder::Local(*local), origins),
+            mir::PlaceKind::Temp(temp) => set_origins(state, Holder::Temp(*temp), origins),
+            // writing a field or element keeps the origins of the others
+            mir::PlaceKind::Field { .. } | mir::PlaceKind::Index { .. } => {
+                if let Some(holder) = self.root_holder(place)
+                    && !origins.is_empty()
+                {
+                    state.entry(holder).or_default().extend(origins);
+                }
+            }
+            mir::PlaceKind::Deref(_) => (),
+        }
+    }
+
+    fn call_origins(
+        &self,
+        function: FunctionId,
+        arguments: &[mir::Operand],
+        state: &OriginState,
+    ) -> BTreeSet<Origin> {
+        let return_borrows = self
+            .functions
+            .get(function)
+            .and_then(|function| function.return_borrows.as_ref());
+
+        let mut origins = BTreeSet::new();
+        for (i, argument) in arguments.iter().enumerate() {
+            if return_borrows.is_none_or(|borrows| borrows.contains(&i)) {
+                origins.extend(self.operand_origins(argument, state));
+            }
+        }
+        origins
+    }
+
+    fn rvalue_origins(&self, value: &mir::Rvalue, state: &OriginState) -> BTreeSet<Origin> {
+        match &value.kind {
+            mir::RvalueKind::Place(place) => self.place_origins(place, state),
+            mir::RvalueKind::Operand(operand)
+            | mir::RvalueKind::Copy(operand)
+            | mir::RvalueKind::CastUse { value: operand, .. }
+            | mir::RvalueKind::New { value: operand, .. }
+            | mir::RvalueKind::NewArray {
+                element: operand, ..
+            }
+            | mir::RvalueKind::Slice { array: operand, .. } => self.operand_origins(operand, state),
+            mir::RvalueKind::Aggregate {
+                body: mir::AggregateBody::Runtime(fields),
+                ..
+            } => fields
+                .iter()
+                .flat_map(|field| self.operand_origins(field, state))
+                .collect(),
+            mir::RvalueKind::Aggregate { .. }
+            | mir::RvalueKind::Binary { .. }
+            | mir::RvalueKind::Unary { .. }
+            | mir::RvalueKind::Format(_)
+            | mir::RvalueKind::StackAlloc(_) => BTreeSet::new(),
+        }
+    }
+
+    fn operand_origins(&self, operand: &mir::Operand, state: &OriginState) -> BTreeSet<Origin> {
+        match &operand.kind {
+            mir::OperandKind::Local(local) => holder_origins(state, Holder::Local(*local)),
+            mir::OperandKind::Temp(temp) => holder_origins(state, Holder::Temp(*temp)),
+            mir::OperandKind::Ref { place, .. } => {
+                let mut origins = self.place_origins(&self.tree.places[*place], state);
+                if self.loans.contains(*place) {
+                    origins.insert(Origin::Loan(*place));
+                }
+                origins
+            }
+            mir::OperandKind::Comptime(_)
+            | mir::OperandKind::Sizeof(_)
+            | mir::OperandKind::Nullptr
+            | mir::OperandKind::None => BTreeSet::new(),
+        }
+    }
+
+    fn place_origins(&self, place: &mir::Place, state: &OriginState) -> BTreeSet<Origin> {
+        match &place.kind {
+            mir::PlaceKind::Local(local) => holder_origins(state, Holder::Local(*local)),
+            mir::PlaceKind::Temp(temp) => holder_origins(state, Holder::Temp(*temp)),
+            mir::PlaceKind::Field { base, .. } | mir::PlaceKind::Index { base, .. } => {
+                self.place_origins(&self.tree.places[*base], state)
+            }
+            mir::PlaceKind::Deref(operand) => self.operand_origins(operand, state),
+        }
+    }
+
+    fn statement_accesses(&self, kind: &mir::StatementKind) -> Vec<Access> {
+        match kind {
+            mir::StatementKind::Eval(operand) => self.operand_accesses(operand),
+            mir::StatementKind::Assign { place, value } => {
+                let mut accesses = match &value.kind {
+                    mir::RvalueKind::Place(place) => self.place_read_accesses(place),
+                    _ => rvalue_operands(value)
+                        .into_iter()
+                        .flat_map(|operand| self.operand_accesses(operand))
+                        .collect(),
+                };
+                accesses.extend(self.place_write_accesses(*place));
+                accesses
+            }
+            mir::StatementKind::Call {
+                arguments,
+                return_place,
+                ..
+            } => {
+                let mut accesses = arguments
+                    .iter()
+                    .flat_map(|argument| self.operand_accesses(argument))
+                    .collect::<Vec<_>>();
+
+                if let Some(place) = return_place {
+                    accesses.extend(self.place_write_accesses(*place));
+                }
+                accesses
+            }
+            mir::StatementKind::StorageDead(local) if self.tracked.contains(*local) => {
+                vec![Access {
+                    path: Path {
+                        local: *local,
+                        fields: vec![],
+                    },
+                    kind: AccessKind::Dead,
+                }]
+            }
+            mir::StatementKind::StorageDead(_)
+            | mir::StatementKind::StorageStart(_)
+            | mir::StatementKind::Free(_) => vec![],
+        }
+    }
+
+    fn operand_accesses(&self, operand: &mir::Operand) -> Vec<Access> {
+        match &operand.kind {
+            mir::OperandKind::Local(local) if self.tracked.contains(*local) => {
+                let kind = match self.types.is_auto_copy(operand.ty) {
+                    true => AccessKind::Read,
+                    false => AccessKind::Move,
+                };
+
+                vec![Access {
+                    path: Path {
+                        local: *local,
+                        fields: vec![],
+                    },
+                    kind,
+                }]
+            }
+            mir::OperandKind::Ref { place, mutable } => match self.loans.get(*place) {
+                Some(loan) => vec![Access {
+                    path: loan.path.clone(),
+                    kind: AccessKind::Borrow {
+                        loan: *place,
+                        mutable: *mutable,
+                    },
+                }],
+                None => self.deref_accesses(&self.tree.places[*place]),
+            },
+            _ => vec![],
+        }
+    }
+
+    fn place_read_accesses(&self, place: &mir::Place) -> Vec<Access> {
+        match self.place_path(place) {
+            Some(path) => vec![Access {
+                path,
+                kind: AccessKind::Read,
+            }],
+            None => self.deref_accesses(place),
+        }
+    }
+
+    fn place_write_accesses(&self, place_id: PlaceId) -> Vec<Access> {
+        let place = &self.tree.places[place_id];
+        match self.place_path(place) {
+            Some(path) => vec![Access {
+                path,
+                kind: AccessKind::Write,
+            }],
+            None => self.deref_accesses(place),
+        }
+    }
+
+    /// Going through `*reference` only reads the reference itself.
+    fn deref_accesses(&self, place: &mir::Place) -> Vec<Access> {
+        match &place.kind {
+            mir::PlaceKind::Deref(operand) => match &operand.kind {
+                mir::OperandKind::Local(local) if self.tracked.contains(*local) => {
+                    vec![Access {
+                        path: Path {
+                            local: *local,
+                            fields: vec![],
+                        },
+                        kind: AccessKind::Read,
+                    }]
+                }
+                _ => self.operand_accesses(operand),
+            },
+            mir::PlaceKind::Field { base, .. } => self.deref_accesses(&self.tree.places[*base]),
+            mir::PlaceKind::Index { base, index } => {
+                let mut accesses = self.deref_accesses(&self.tree.places[*base]);
+                accesses.extend(self.operand_accesses(index));
+                accesses
+            }
+            mir::PlaceKind::Local(_) | mir::PlaceKind::Temp(_) => vec![],
+        }
+    }
+
+    /// The path of a place made of fields of a tracked local, `None` if it goes through a deref.
+    ///
+    /// Elements are not told apart, indexing gives the path of the whole array.
+    fn place_path(&self, place: &mir::Place) -> Option<Path> {
+        match &place.kind {
+            mir::PlaceKind::Local(local) => self.tracked.contains(*local).then(|| Path {
+                local: *local,
+                fields: vec![],
+            }),
+            mir::PlaceKind::Field { base, field_id, .. } => {
+                let mut path = self.place_path(&self.tree.places[*base])?;
+                path.fields.push(*field_id);
+                Some(path)
+            }
+            mir::PlaceKind::Index { base, .. } => self.place_path(&self.tree.places[*base]),
+            mir::PlaceKind::Temp(_) | mir::PlaceKind::Deref(_) => None,
+        }
+    }
+
+    fn root_holder(&self, place: &mir::Place) -> Option<Holder> {
+        match &place.kind {
+            mir::PlaceKind::Local(local) => Some(Holder::Local(*local)),
+            mir::PlaceKind::Temp(temp) => Some(Holder::Temp(*temp)),
+            mir::PlaceKind::Field { base, .. } | mir::PlaceKind::Index { base, .. } => {
+                self.root_holder(&self.tree.places[*base])
+            }
+            mir::PlaceKind::Deref(_) => None,
+        }
+    }
+
+    /// The holder a statement overwrites as a whole.
+    fn statement_def(&self, kind: &mir::StatementKind) -> Option<Holder> {
+        let place = match kind {
+            mir::StatementKind::Assign { place, .. } => *place,
+            mir::StatementKind::Call {
+                return_place: Some(place),
+                ..
+            } => *place,
+            _ => return None,
+        };
+
+        match &self.tree.places[place].kind {
+            mir::PlaceKind::Local(local) => Some(Holder::Local(*local)),
+            mir::PlaceKind::Temp(temp) => Some(Holder::Temp(*temp)),
+            mir::PlaceKind::Field { .. }
+            | mir::PlaceKind::Index { .. }
+            | mir::PlaceKind::Deref(_) => None,
+        }
+    }
+
+    fn statement_uses(&self, kind: &mir::StatementKind) -> Vec<Holder> {
+        let mut uses = statement_operands(kind)
+            .into_iter()
+            .flat_map(|operand| self.operand_uses(operand))
+            .collect::<Vec<_>>();
+
+        match kind {
+            mir::StatementKind::Assign { place, value } => {
+                if let mir::RvalueKind::Place(read) = &value.kind {
+                    uses.extend(self.place_uses(read));
+                }
+                uses.extend(self.target_uses(&self.tree.places[*place]));
+            }
+            mir::StatementKind::Call {
+                return_place: Some(place),
+                ..
+            } => uses.extend(self.target_uses(&self.tree.places[*place])),
+            mir::StatementKind::Free(place) => {
+                uses.extend(self.place_uses(&self.tree.places[*place]))
+            }
+            _ => (),
+        }
+        uses
+    }
+
+    fn operand_uses(&self, operand: &mir::Operand) -> Vec<Holder> {
+        match &operand.kind {
+            mir::OperandKind::Local(local) => vec![Holder::Local(*local)],
+            mir::OperandKind::Temp(temp) => vec![Holder::Temp(*temp)],
+            mir::OperandKind::Ref { place, .. } => self.place_uses(&self.tree.places[*place]),
+            _ => vec![],
+        }
+    }
+
+    fn place_uses(&self, place: &mir::Place) -> Vec<Holder> {
+        match &place.kind {
+            mir::PlaceKind::Local(local) => vec![Holder::Local(*local)],
+            mir::PlaceKind::Temp(temp) => vec![Holder::Temp(*temp)],
+            mir::PlaceKind::Field { base, .. } => self.place_uses(&self.tree.places[*base]),
+            mir::PlaceKind::Index { base, index } => {
+                let mut uses = self.place_uses(&self.tree.places[*base]);
+                uses.extend(self.operand_uses(index));
+                uses
+            }
+            mir::PlaceKind::Deref(operand) => self.operand_uses(operand),
+        }
+    }
+
+    /// Writing into a place only uses the references it goes through.
+    fn target_uses(&self, place: &mir::Place) -> Vec<Holder> {
+        match &place.kind {
+            mir::PlaceKind::Local(_) | mir::PlaceKind::Temp(_) => vec![],
+            mir::PlaceKind::Field { base, .. } => self.target_uses(&self.tree.places[*base]),
+            mir::PlaceKind::Index { base, index } => {
+                let mut uses = self.target_uses(&self.tree.places[*base]);
+                uses.extend(self.operand_uses(index));
+                uses
+            }
+            mir::PlaceKind::Deref(operand) => self.operand_uses(operand),
+        }
+    }
+
+    fn statement_span(&self, statement_id: mir::StatementId) -> Span {
+        self.tree
+            .statement_spans
+            .get(statement_id)
+            .copied()
+            .unwrap_or(Span::error())
+    }
+
+    fn terminator_span(&self, block_id: BlockId) -> Span {
+        self.tree
+            .terminator_spans
+            .get(block_id)
+            .copied()
+            .unwrap_or(Span::error())
+    }
+}
+
+fn conflict_fault(loan: &Loan, access: AccessKind, span: Span, use_span: Span) -> BorrowFault {
+    let later_use = |kind| SoulError::new("borrow later used here", kind, Some(use_span));
+
+    if let AccessKind::Dead = access {
+        return BorrowFault {
+            error: SoulError::new(
+                "borrowed value does not live long enough",
+                SoulErrorKind::BorrowOutlivesValue,
+                Some(loan.span),
+            ),
+            notes: vec![
+                SoulError::new(
+                    "value dropped here while still borrowed",
+                    SoulErrorKind::BorrowOutlivesValue,
+                    Some(span),
+                ),
+                later_use(SoulErrorKind::BorrowOutlivesValue),
+            ],
+        };
+    }
+
+    let message = match access {
+        AccessKind::Read => "cannot use value because it is mutably borrowed",
+        AccessKind::Move => "cannot move out of value because it is borrowed",
+        AccessKind::Write => "cannot assign to value because it is borrowed",
+        AccessKind::Borrow { mutable: true, .. } => {
+            "cannot borrow value as mutable because it is also borrowed"
+        }
+        AccessKind::Borrow { mutable: false, .. } => {
+            "cannot borrow value as immutable because it is also borrowed as mutable"
+        }
+        AccessKind::Dead => unreachable!(),
+    };
+
+    let mut notes = vec![SoulError::new(
+        "value is borrowed here",
+        SoulErrorKind::BorrowConflict,
+        Some(loan.span),
+    )];
+    if use_span != span {
+        notes.push(later_use(SoulErrorKind::BorrowConflict));
+    }
+
+    BorrowFault {
+        error: SoulError::new(message, SoulErrorKind::BorrowConflict, Some(span)),
+        notes,
+    }
+}
+
+fn statement_operands(kind: &mir::StatementKind) -> Vec<&mir::Operand> {
+    match kind {
+        mir::StatementKind::Eval(operand) => vec![operand],
+        mir::StatementKind::Assign { value, .. } => rvalue_operands(value),
+        mir::StatementKind::Call { arguments, .. } => arguments.iter().collect(),
+        mir::StatementKind::StorageStart(_)
+        | mir::StatementKind::StorageDead(_)
+        | mir::StatementKind::Free(_) => vec![],
+    }
+}
+
+fn rvalue_operands(value: &mir::Rvalue) -> Vec<&mir::Operand> {
+    match &value.kind {
+        mir::RvalueKind::Operand(operand)
+        | mir::RvalueKind::Copy(operand)
+        | mir::RvalueKind::CastUse { value: operand, .. }
+        | mir::RvalueKind::Unary { value: operand, .. } => vec![operand],
+        mir::RvalueKind::Binary { left, right, .. } => vec![left, right],
+        mir::RvalueKind::New { value, .. } => vec![value],
+        mir::RvalueKind::NewArray { element, len, .. } => vec![len, element],
+        mir::RvalueKind::Slice { array, start, end } => std::iter::once(array)
+            .chain(start.iter())
+            .chain(end.iter())
+            .collect(),
+        mir::RvalueKind::Aggregate {
+            body: mir::AggregateBody::Runtime(fields),
+            ..
+        } => fields.iter().collect(),
+        mir::RvalueKind::Format(pieces) => pieces
+            .iter()
+            .filter_map(|piece| match piece {
+                mir::FormatPiece::Argument { value, .. } => Some(value),
+                mir::FormatPiece::Text(_) => None,
+            })
+            .collect(),
+        mir::RvalueKind::Aggregate { .. }
+        | mir::RvalueKind::Place(_)
+        | mir::RvalueKind::StackAlloc(_) => vec![],
+    }
+}
+
+fn terminator_operand(terminator: &mir::Terminator) -> Option<&mir::Operand> {
+    match terminator {
+        mir::Terminator::Return(Some(operand)) | mir::Terminator::Panic(Some(operand)) => {
+            Some(operand)
+        }
+        mir::Terminator::If { condition, .. } => Some(condition),
+        mir::Terminator::Return(None)
+        | mir::Terminator::Panic(None)
+        | mir::Terminator::Goto(_)
+        | mir::Terminator::Exit
+        | mir::Terminator::Unreachable => None,
+    }
+}
+
+fn holder_origins(state: &OriginState, holder: Holder) -> BTreeSet<Origin> {
+    state.get(&holder).cloned().unwrap_or_default()
+}
+
+fn set_origins(state: &mut OriginState, holder: Holder, origins: BTreeSet<Origin>) {
+    match origins.is_empty() {
+        true => state.remove(&holder),
+        false => state.insert(holder, origins),
+    };
+}
+
+/// Merges `other` into `entry`, returns `true` if `entry` changed.
+fn join(entry: &mut OriginState, other: &OriginState) -> bool {
+    let mut changed = false;
+    for (holder, origins) in other {
+        let entry = entry.entry(*holder).or_default();
+        for origin in origins {
+            changed |= entry.insert(*origin);
+        }
+    }
+    changed
+}
+
+/// Merges `other` into `entry`, returns `true` if a holder became live.
+fn join_live(entry: &mut LiveState, other: &LiveState) -> bool {
+    let mut changed = false;
+    for (holder, span) in other {
+        if !entry.contains_key(holder) {
+            entry.insert(*holder, *span);
+            changed = true;
+        }
+    }
+    changed
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use hir::{CustomTypeId, TypeId};
+use soul_utils::{
+    error::{SoulError, SoulErrorKind},
+    ids::FunctionId,
+    soul_names::AttributeKind,
+    span::Attribute,
+};
+use typed_hir::ThirTypeKind;
+
+use crate::{MirContext, mir};
+
+impl<'a> MirContext<'a> {
+    /// Reports `extern "C"` signatures that pass a non `#[repr(C)]` struct by value and
+    /// unmangled function names that are used more than once in the crate.
+    pub(crate) fn check_c_abi(&mut self) {
+        let mut errors = vec![];
+        let mut unmangled: HashMap<&str, FunctionId> = HashMap::new();
+        for function in self.tree.functions.values() {
+            let no_mangle = Attribute::contains(&function.attributes, AttributeKind::NoMangle);
+            if function.abi.is_none() && !no_mangle {
+                continue;
+            }
+
+            let first = *unmangled
+                .entry(function.name.as_str())
+                .or_insert(function.id);
+            if first != function.id {
+                errors.push(SoulError::new(
+                    format!(
+                        "function: '{}' is not mangeled and name already exists somewhere in crate",
+                        function.name.as_str(),
+                    ),
+                    SoulErrorKind::AlreadyFoundInScope,
+                    Some(function.name.span),
+                ));
+            }
+
+            // a dependency checked its own signatures
+            if function.abi.is_none() || matches!(function.body, mir::FunctionBody::Dependency(_)) {
+                continue;
+            }
+
+            let parameters = function
+                .parameters
+                .iter()
+                .map(|param| self.tree.locals[*param].ty());
+            for ty in parameters.chain([function.return_type]) {
+                if let Some(name) = self.non_repr_c_struct(ty) {
+                    errors.push(SoulError::new(
+                        format!(
+                            "struct '{name}' is not #[repr(C)], use it behind a pointer in extern \"C\" function '{}'",
+                            function.name.as_str(),
+                        ),
+                        SoulErrorKind::InvalidType,
+                        Some(function.name.span),
+                    ));
+                }
+            }
+        }
+
+        for error in errors {
+            self.log_error(error);
+        }
+    }
+
+    /// The name of `ty` when it is a struct without `#[repr(C)]`.
+    fn non_repr_c_struct(&self, ty: TypeId) -> Option<&str> {
+        let types = &self.hir_response.typed.types_map;
+        let ThirTypeKind::CustomTypes(CustomTypeId::Struct(id)) = types.id_to_type(ty)?.kind else {
+            return None;
+        };
+
+        let object = types.id_to_struct(id)?;
+        (!object.repr_c).then_some(object.name.as_str())
+    }
+}
@@ -0,0 +1,9 @@
+//! Dataflow and `extern "C"` checks that run on the finished [`crate::mir::MirTree`],
+//! and the `unsafe{}` check that runs while lowering.
+
+mod borrow_check;
+mod c_abi_check;
+mod move_check;
+mod unsafe_check;
+
+pub(crate) use unsafe_check::UnsafeOperation;
@@ -0,0 +1,424 @@
+use soul_utils::{
+    error::{SoulError, SoulErrorKind},
+    soul_names::KeyWord,
+    span::Span,
+    vec_map::VecMap,
+    vec_set::VecSet,
+};
+use typed_hir::{ThirTypeKind, ThirTypesMap};
+
+use crate::{
+    MirContext,
+    mir::{self, BlockId, LocalId, MirTree, TempId},
+};
+
+/// Locals that may have been moved out of, mapped to the span of the move.
+type MoveState = VecMap<LocalId, Span>;
+
+/// An error together with the note pointing at the earlier span that caused it.
+struct MoveFault {
+    error: SoulError,
+    note: SoulError,
+}
+
+impl<'a> MirContext<'a> {
+    /// Reports use-after-move and move-out-of-borrow in every internal function.
+    pub(crate) fn check_moves(&mut self) {
+        let types = &self.hir_response.typed.types_map;
+
+        let mut faults = vec![];
+        for function in self.tree.functions.values() {
+            if let Some(mut checker) = MoveChecker::new(&self.tree, types, function) {
+                checker.check();
+                faults.append(&mut checker.faults);
+            }
+        }
+
+        for MoveFault { error, note } in faults {
+            self.log_error(error);
+            self.log_note(note);
+        }
+    }
+}
+
+struct MoveChecker<'t> {
+    tree: &'t MirTree,
+    types: &'t ThirTypesMap,
+    entry_block: BlockId,
+    blocks: &'t [BlockId],
+    /// locals owned by this function, globals are never tracked
+    tracked: VecSet<LocalId>,
+    /// temps holding a value read through a `@`/`&` reference, with the span of the deref
+    borrowed_temps: VecMap<TempId, Span>,
+    faults: Vec<MoveFault>,
+}
+
+impl<'t> MoveChecker<'t> {
+    fn new(
+        tree: &'t MirTree,
+        types: &'t ThirTypesMap,
+        function: &'t mir::Function,
+    ) -> Option<Self> {
+        let (entry_block, locals, blocks) = match &function.body {
+            mir::FunctionBody::Internal {
+                entry_block,
+                locals,
+                blocks,
+            } => (*entry_block, locals, blocks),
+            mir::FunctionBody::External(_) | mir::FunctionBody::Dependency { .. } => return None,
+        };
+
+        let mut tracked = VecSet::from_slice(&function.parameters);
+        for local in locals {
+            tracked.insert(*local);
+        }
+
+        Some(Self {
+            tree,
+            types,
+            entry_block,
+            blocks,
+            tracked,
+            borrowed_temps: VecMap::const_default(),
+            faults: vec![],
+        })
+    }
+
+    fn check(&mut self) {
+        self.collect_borrowed_temps();
+
+        let mut entry_states: VecMap<BlockId, MoveState> = VecMap::const_default();
+        entry_states.insert(self.entry_block, MoveState::const_default());
+
+        let mut worklist = vec![self.entry_block];
+        while let Some(block_id) = worklist.pop() {
+            let mut state = entry_states[block_id].clone();
+            self.visit_block(block_id, &mut state, None);
+
+            for successor in self.tree.blocks[block_id].terminator.successors() {
+                let changed = match entry_states.get_mut(successor) {
+                    Some(entry) => join(entry, &state),
+                    None => {
+                        entry_states.insert(successor, state.clone());
+                        true
+                    }
+                };
+
+                if changed {
+                    worklist.push(successor);
+                }
+            }
+        }
+
+        let mut faults = vec![];
+        for (block_id, entry) in entry_states.entries() {
+            let mut state = entry.clone();
+            self.visit_block(block_id, &mut state, Some(&mut faults));
+        }
+        self.faults = faults;
+    }
+
+    fn collect_borrowed_temps(&mut self) {
+        for block_id in self.blocks {
+            let block = &self.tree.blocks[*block_id];
+            for statement_id in &block.statements {
+                let mir::StatementKind::Assign { place, value } =
+                    &self.tree.statements[*statement_id].kind
+                else {
+                    continue;
+                };
+
+                let place = &self.tree.places[*place];
+                let (mir::PlaceKind::Temp(temp), mir::RvalueKind::Operand(operand)) =
+                    (&place.kind, &value.kind)
+                else {
+                    continue;
+                };
+
+                if place.ty != operand.ty
+                    && self.is_ref(operand.ty)
+                    && !self.types.is_auto_copy(place.ty)
+                {
+                    let span = self.statement_span(*statement_id);
+                    self.borrowed_temps.insert(*temp, span);
+                }
+            }
+        }
+    }
+
+    fn visit_block(
+        &self,
+        block_id: BlockId,
+        state: &mut MoveState,
+        mut faults: Option<&mut Vec<MoveFault>>,
+    ) {
+        let block = &self.tree.blocks[block_id];
+        for statement_id in &block.statements {
+            let span = self.statement_span(*statement_id);
+            let mut visitor = Visitor {
+                checker: self,
+                state,
+                faults: faults.as_deref_mut(),
+                span,
+            };
+            visitor.visit_statement(&self.tree.statements[*statement_id]);
+        }
+
+        let span = self
+            .tree
+            .terminator_spans
+            .get(block_id)
+            .copied()
+            .unwrap_or(Span::error());
+
+        let mut visitor = Visitor {
+            checker: self,
+            state,
+            faults,
+            span,
+        };
+        match &block.terminator {
+            mir::Terminator::Return(Some(operand)) | mir::Terminator::Panic(Some(operand)) => {
+                visitor.move_operand(operand)
+            }
+            mir::Terminator::If { condition, .. } => visitor.move_operand(condition),
+            mir::Terminator::Return(None)
+            | mir::Terminator::Panic(None)
+            | mir::Terminator::Goto(_)
+            | mir::Terminator::Exit
+            | mir::Terminator::Unreachable => (),
+        }
+    }
+
+    fn is_ref(&self, ty: hir::TypeId) -> bool {
+        self.types
+            .id_to_type(ty)
+            .is_some_and(|ty| matches!(ty.kind, ThirTypeKind::Ref { .. }))
+    }
+
+    fn statement_span(&self, statement_id: mir::StatementId) -> Span {
+        self.tree
+            .statement_spans
+            .get(statement_id)
+            .copied()
+            .unwrap_or(Span::error())
+    }
+}
+
+struct Visitor<'c, 't> {
+    checker: &'c MoveChecker<'t>,
+    state: &'c mut MoveState,
+    faults: Option<&'c mut Vec<MoveFault>>,
+    span: Span,
+}
+
+impl<'c, 't> Visitor<'c, 't> {
+    fn visit_statement(&mut self, statement: &mir::Statement) {
+        match &statement.kind {
+            mir::StatementKind::Eval(operand) => self.move_operand(operand),
+            mir::StatementKind::Assign { place, value } => {
+                self.visit_rvalue(value);
+                self.assign_place(*place);
+            }
+            mir::StatementKind::Call {
+                arguments,
+                return_place,
+                ..
+            } => {
+                for argument in arguments {
+                    self.move_operand(argument);
+                }
+
+                if let Some(place) = return_place {
+                    self.assign_place(*place);
+                }
+            }
+            mir::StatementKind::StorageStart(locals) => {
+                for local in locals {
+                    self.state.remove(*local);
+                }
+            }
+            mir::StatementKind::StorageDead(local) => {
+                self.state.remove(*local);
+            }
+            mir::StatementKind::Free(place) => {
+                let place = &self.checker.tree.places[*place];
+                self.read_place(place);
+            }
+        }
+    }
+
+    fn visit_rvalue(&mut self, value: &mir::Rvalue) {
+        match &value.kind {
+            mir::RvalueKind::Place(place) => self.read_place(place),
+            mir::RvalueKind::Copy(operand) => self.read_operand(operand),
+            mir::RvalueKind::Operand(operand)
+            | mir::RvalueKind::CastUse { value: operand, .. }
+            | mir::RvalueKind::Unary { value: operand, .. } => self.move_operand(operand),
+            mir::RvalueKind::Binary { left, right, .. } => {
+                self.move_operand(left);
+                self.move_operand(right);
+            }
+            mir::RvalueKind::Aggregate { body, .. } => {
+                if let mir::AggregateBody::Runtime(fields) = body {
+                    for field in fields {
+                        self.move_operand(field);
+                    }
+                }
+            }
+            mir::RvalueKind::New { value, .. } => self.move_operand(value),
+            mir::RvalueKind::NewArray { element, len, .. } => {
+                self.read_operand(len);
+                self.read_operand(element);
+            }
+            mir::RvalueKind::Slice { array, start, end } => {
+                self.read_operand(array);
+                for bound in [start, end].into_iter().flatten() {
+                    self.read_operand(bound);
+                }
+            }
+            mir::RvalueKind::Format(pieces) => {
+                for piece in pieces {
+                    if let mir::FormatPiece::Argument { value, .. } = piece {
+                        self.read_operand(value);
+                    }
+                }
+            }
+            mir::RvalueKind::StackAlloc(_) => (),
+        }
+    }
+
+    /// Writing a whole local gives it a new value, writing into it requires it to be alive.
+    fn assign_place(&mut self, place_id: mir::PlaceId) {
+        let place = &self.checker.tree.places[place_id];
+        match &place.kind {
+            mir::PlaceKind::Local(local) => {
+                self.state.remove(*local);
+            }
+            _ => self.read_place(place),
+        }
+    }
+
+    fn read_place(&mut self, place: &mir::Place) {
+        match &place.kind {
+            mir::PlaceKind::Temp(_) => (),
+            mir::PlaceKind::Local(local) => self.check_alive(*local, "use of moved value"),
+            mir::PlaceKind::Deref(operand) => self.read_operand(operand),
+            mir::PlaceKind::Field { base, .. } => {
+                let base = &self.checker.tree.places[*base];
+                self.read_place(base);
+            }
+            mir::PlaceKind::Index { base, index } => {
+                self.read_operand(index);
+                let base = &self.checker.tree.places[*base];
+                self.read_place(base);
+            }
+        }
+    }
+
+    fn read_operand(&mut self, operand: &mir::Operand) {
+        match &operand.kind {
+            mir::OperandKind::Local(local) => self.check_alive(*local, "use of moved value"),
+            mir::OperandKind::Ref { place, .. } => {
+                let place = &self.checker.tree.places[*place];
+                self.check_place_borrow(place);
+            }
+            mir::OperandKind::Temp(_)
+            | mir::OperandKind::Comptime(_)
+            | mir::OperandKind::Sizeof(_)
+            | mir::OperandKind::Nullptr
+            | mir::OperandKind::None => (),
+        }
+    }
+
+    /// A by-value use, moves the operand unless its type is `AutoCopy`.
+    fn move_operand(&mut self, operand: &mir::Operand) {
+        match &operand.kind {
+            mir::OperandKind::Local(local) => {
+                self.check_alive(*local, "use of moved value");
+
+                let Some(info) = self.checker.tree.locals.get(*local) else {
+                    return;
+                };
+
+                if self.checker.tracked.contains(*local)
+                    && !self.checker.types.is_auto_copy(info.ty())
+                {
+                    self.state.insert(*local, self.span);
+                }
+            }
+            mir::OperandKind::Temp(temp) => {
+                if let Some(borrow_span) = self.checker.borrowed_temps.get(*temp) {
+                    self.report(
+                        SoulError::new(
+                            "cannot move out of a borrowed value",
+                            SoulErrorKind::MoveOutOfBorrow,
+                            Some(self.span),
+                        ),
+                        SoulError::new(
+                            format!(
+                                "value is borrowed here, use '{}' to copy it instead",
+                                KeyWord::Copy.as_str()
+                            ),
+                            SoulErrorKind::MoveOutOfBorrow,
+                            Some(*borrow_span),
+                        ),
+                    );
+                }
+            }
+            _ => self.read_operand(operand),
+        }
+    }
+
+    fn check_place_borrow(&mut self, place: &mir::Place) {
+        match &place.kind {
+            mir::PlaceKind::Local(local) => self.check_alive(*local, "borrow of moved value"),
+            mir::PlaceKind::Field { base, .. } => {
+                let base = &self.checker.tree.places[*base];
+                self.check_place_borrow(base);
+            }
+            mir::PlaceKind::Index { base, index } => {
+                self.read_operand(index);
+                let base = &self.checker.tree.places[*base];
+                self.check_place_borrow(base);
+            }
+            mir::PlaceKind::Deref(operand) => self.read_operand(operand),
+            mir::PlaceKind::Temp(_) => (),
+        }
+    }
+
+    fn check_alive(&mut self, local: LocalId, message: &str) {
+        let Some(moved_at) = self.state.get(local).copied() else {
+            return;
+        };
+
+        let note = if moved_at == self.span {
+            "value moved here, in previous iteration of loop"
+        } else {
+            "value moved here"
+        };
+
+        self.report(
+            SoulError::new(message, SoulErrorKind::UseAfterMove, Some(self.span)),
+            SoulError::new(note, SoulErrorKind::UseAfterMove, Some(moved_at)),
+        );
+    }
+
+    fn report(&mut self, error: SoulError, note: SoulError) {
+        if let Some(faults) = self.faults.as_deref_mut() {
+            faults.push(MoveFault { error, note });
+        }
+    }
+}
+
+/// Merges `other` into `entry`, returns `true` if `entry` changed.
+fn join(entry: &mut MoveState, other: &MoveState) -> bool {
+    let mut changed = false;
+    for (local, span) in other.entries() {
+        if !entry.contains(local) {
+            entry.insert(local, *span);
+            changed = true;
+        }
+    }
+    changed
+}
@@ -0,0 +1,52 @@
+use soul_utils::{
+    error::{SoulError, SoulErrorKind},
+    soul_names::KeyWord,
+    span::Span,
+};
+
+use typed_hir::ThirTypeKind;
+
+use crate::MirContext;
+
+/// Operations that are only allowed inside an `unsafe{}` block.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum UnsafeOperation {
+    /// `*ptr` of a raw pointer `*T`.
+    PointerDeref,
+    /// `ptr + int`, `ptr - int` and `ptr - ptr`.
+    PointerArithmetic,
+    /// A call of an `extern "C"` function.
+    ExternCall,
+}
+impl UnsafeOperation {
+    fn as_str(self) -> &'static str {
+        match self {
+            UnsafeOperation::PointerDeref => "dereferencing a raw pointer",
+            UnsafeOperation::PointerArithmetic => "pointer arithmetic",
+            UnsafeOperation::ExternCall => "calling an extern function",
+        }
+    }
+}
+
+impl<'a> MirContext<'a> {
+    /// Logs an error at `span` of `operation` when it is not inside `unsafe{}`.
+    pub(crate) fn require_unsafe(&mut self, operation: UnsafeOperation, span: Span) {
+        if self.current.in_unsafe {
+            return;
+        }
+
+        self.log_error(SoulError::new(
+            format!(
+                "{} is only allowed inside an '{}' block",
+                operation.as_str(),
+                KeyWord::Unsafe.as_str(),
+            ),
+            SoulErrorKind::UnsafeOutsideUnsafeBlock,
+            Some(span),
+        ));
+    }
+
+    pub(crate) fn is_pointer_type(&mut self, ty: hir::TypeId) -> bool {
+        matches!(self.id_to_type(ty).kind, ThirTypeKind::Pointer(_))
+    }
+}
@@ -0,0 +1,656 @@
+//! Drop elaboration, inserts the drop glue of owned locals at their `StorageDead`
+//! and before they are overwritten. Locals that are only moved out of on some paths
+//! get a runtime drop flag.
+
+use std::mem::replace;
+
+use ast::{BinaryOperatorKind, Literal};
+use hir::{ComplexLiteral, CustomTypeId, TypeId};
+use soul_utils::{
+    ids::FunctionId,
+    span::{Span, Spanned},
+    vec_map::VecMap,
+    vec_set::VecSet,
+};
+use typed_hir::{ThirTypeKind, ThirTypesMap};
+
+use crate::{
+    MirContext, ScopeItem,
+    mir::{self, BlockId, LocalId},
+};
+
+/// Init state of the owned locals, on merging paths a local can be in both sets.
+#[derive(Debug, Clone, Default)]
+struct InitState {
+    maybe_init: VecSet<LocalId>,
+    maybe_uninit: VecSet<LocalId>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Init {
+    Yes,
+    No,
+    Maybe,
+}
+
+impl InitState {
+    fn init(&mut self, local: LocalId) {
+        self.maybe_init.insert(local);
+        self.maybe_uninit.remove(local);
+    }
+
+    fn uninit(&mut self, local: LocalId) {
+        self.maybe_init.remove(local);
+        self.maybe_uninit.insert(local);
+    }
+
+    fn get(&self, local: LocalId) -> Init {
+        match (
+            self.maybe_init.contains(local),
+            self.maybe_uninit.contains(local),
+        ) {
+            (true, false) => Init::Yes,
+            (true, true) => Init::Maybe,
+            (false, _) => Init::No,
+        }
+    }
+
+    /// Merges `other` into `self`, returns `true` if `self` changed.
+    fn join(&mut self, other: &InitState) -> bool {
+        let mut changed = false;
+        for local in other.maybe_init.entries() {
+            changed |= self.maybe_init.insert(local).is_none();
+        }
+        for local in other.maybe_uninit.entries() {
+            changed |= self.maybe_uninit.insert(local).is_none();
+        }
+        changed
+    }
+}
+
+/// How a statement changes the init state of one owned local.
+enum Effect {
+    Moved(LocalId),
+    Dropped(LocalId),
+    Initialized(LocalId),
+    StorageStart(LocalId),
+}
+
+impl<'a> MirContext<'a> {
+    /// Calls `Drop(&this)`, drops fields and frees heap buffers of owned locals
+    /// when they go out of scope or are overwritten.
+    pub(crate) fn elaborate_drops(&mut self) {
+        let function_ids = self.tree.functions.keys().collect::<Vec<_>>();
+        for function_id in function_ids {
+            self.elaborate_function_drops(function_id);
+        }
+    }
+
+    fn elaborate_function_drops(&mut self, function_id: FunctionId) {
+        let types = &self.hir_response.typed.types_map;
+        let function = &self.tree.functions[function_id];
+        let (entry_block, locals, blocks) = match &function.body {
+            mir::FunctionBody::Internal {
+                entry_block,
+                locals,
+                blocks,
+            } => (*entry_block, locals, blocks.clone()),
+            mir::FunctionBody::External(_) | mir::FunctionBody::Dependency { .. } => return,
+        };
+
+        let owned = function
+            .parameters
+            .iter()
+            .chain(locals)
+            .copied()
+            .filter(|local| {
+                let local = &self.tree.locals[*local];
+                local.is_runtime() && types.needs_drop(local.ty())
+            })
+            .collect::<VecSet<_>>();
+
+        if owned.is_empty() {
+            return;
+        }
+
+        let mut entry = InitState::default();
+        for local in owned.entries() {
+            match function.parameters.contains(&local) {
+                true => entry.init(local),
+                false => entry.uninit(local),
+            }
+        }
+
+        let entry_states = self.init_entry_states(entry_block, entry.clone(), &owned);
+
+        // a local dropped while it may already be moved out of needs a runtime flag
+        let mut flagged = VecSet::new();
+        for (block_id, state) in entry_states.entries() {
+            let mut state = state.clone();
+            self.visit_drop_block(block_id, &mut state, &owned, |local, init| {
+                if init == Init::Maybe {
+                    flagged.insert(local);
+                }
+            });
+        }
+
+        let parent_function = replace(&mut self.current.function, function_id);
+        let parent_span = self.current.span;
+
+        let mut flags = VecMap::new();
+        for local in flagged.entries() {
+            let flag = self.new_drop_flag(function_id);
+            flags.insert(local, flag);
+
+            self.current.span = self.function_span(function_id);
+            let statement = self.set_flag(flag, entry.get(local) == Init::Yes);
+            let id = self.alloc_statement(statement);
+            self.tree.blocks[entry_block].statements.insert(0, id);
+        }
+
+        for block_id in blocks {
+            if let Some(state) = entry_states.get(block_id) {
+                self.elaborate_block(block_id, state.clone(), &owned, &flags);
+            }
+        }
+
+        self.current.function = parent_function;
+        self.current.span = parent_span;
+    }
+
+    fn init_entry_states(
+        &self,
+        entry_block: BlockId,
+        entry: InitState,
+        owned: &VecSet<LocalId>,
+    ) -> VecMap<BlockId, InitState> {
+        let mut entry_states: VecMap<BlockId, InitState> = VecMap::const_default();
+        entry_states.insert(entry_block, entry);
+
+        let mut worklist = vec![entry_block];
+        while let Some(block_id) = worklist.pop() {
+            let mut state = entry_states[block_id].clone();
+            self.visit_drop_block(block_id, &mut state, owned, |_, _| ());
+
+            for successor in self.tree.blocks[block_id].terminator.successors() {
+                let changed = match entry_states.get_mut(successor) {
+                    Some(entry) => entry.join(&state),
+                    None => {
+                        entry_states.insert(successor, state.clone());
+                        true
+                    }
+                };
+
+                if changed {
+                    worklist.push(successor);
+                }
+            }
+        }
+
+        entry_states
+    }
+
+    /// Runs `state` through a block, calling `on_drop` for every drop point.
+    fn visit_drop_block(
+        &self,
+        block_id: BlockId,
+        state: &mut InitState,
+        owned: &VecSet<LocalId>,
+        mut on_drop: impl FnMut(LocalId, Init),
+    ) {
+        for statement_id in &self.tree.blocks[block_id].statements {
+            let statement = &self.tree.statements[*statement_id];
+            for effect in self.statement_effects(statement, owned) {
+                match effect {
+                    Effect::Moved(local) | Effect::StorageStart(local) => state.uninit(local),
+                    Effect::Dropped(local) => {
+                        on_drop(local, state.get(local));
+                        state.uninit(local);
+                    }
+                    Effect::Initialized(local) => state.init(local),
+                }
+            }
+        }
+    }
+
+    fn elaborate_block(
+        &mut self,
+        block_id: BlockId,
+        mut state: InitState,
+        owned: &VecSet<LocalId>,
+        flags: &VecMap<LocalId, LocalId>,
+    ) {
+        let statements = std::mem::take(&mut self.tree.blocks[block_id].statements);
+
+        let mut current = block_id;
+        for statement_id in statements {
+            self.current.span = self.statement_span_of(statement_id);
+
+            let statement = &self.tree.statements[statement_id];
+            let effects = self.statement_effects(statement, owned);
+
+            let mut touched = vec![];
+            for effect in &effects {
+                let local = match *effect {
+                    Effect::Moved(local)
+                    | Effect::Dropped(local)
+                    | Effect::Initialized(local)
+                    | Effect::StorageStart(local) => local,
+                };
+                if !touched.iter().any(|(id, _)| *id == local) {
+                    touched.push((local, state.get(local)));
+                }
+
+                match *effect {
+                    Effect::Moved(local) | Effect::StorageStart(local) => state.uninit(local),
+                    Effect::Dropped(local) => {
+                        match state.get(local) {
+                            Init::Yes => current = self.push_drop_glue(local, current),
+                            Init::Maybe => match flags.get(local) {
+                                Some(flag) => {
+                                    current = self.push_conditional_drop(local, *flag, current)
+                                }
+                                None => current = self.push_drop_glue(local, current),
+                            },
+                            Init::No => (),
+                        }
+                        state.uninit(local);
+                    }
+                    Effect::Initialized(local) => state.init(local),
+                }
+            }
+
+            self.tree.blocks[current].statements.push(statement_id);
+
+            // a flag only has to be written when the init state of its local changed
+            for (local, before) in touched {
+                let after = state.get(local);
+                if let Some(flag) = flags.get(local).filter(|_| before != after) {
+                    let statement = self.set_flag(*flag, after == Init::Yes);
+                    self.push_statement_from(statement, current);
+                }
+            }
+        }
+    }
+
+    /// Effects of `statement` on owned locals in execution order,
+    /// operands are moved out of before the assigned place is written.
+    fn statement_effects(
+        &self,
+        statement: &mir::Statement,
+        owned: &VecSet<LocalId>,
+    ) -> Vec<Effect> {
+        let mut effects = vec![];
+        let mut moved = |operand: &mir::Operand| {
+            if let mir::OperandKind::Local(local) = operand.kind
+                && owned.contains(local)
+            {
+                effects.push(Effect::Moved(local));
+            }
+        };
+
+        let written = match &statement.kind {
+            mir::StatementKind::Eval(operand) => {
+                moved(operand);
+                None
+            }
+            mir::StatementKind::Assign { place, value } => {
+                match &value.kind {
+                    mir::RvalueKind::Operand(operand)
+                    | mir::RvalueKind::CastUse { value: operand, .. }
+                    | mir::RvalueKind::Unary { value: operand, .. }
+                    | mir::RvalueKind::New { value: operand, .. } => moved(operand),
+                    mir::RvalueKind::Binary { left, right, .. } => {
+                        moved(left);
+                        moved(right);
+                    }
+                    mir::RvalueKind::Aggregate {
+                        body: mir::AggregateBody::Runtime(fields),
+                        ..
+                    } => fields.iter().for_each(&mut moved),
+                    mir::RvalueKind::Aggregate { .. }
+                    | mir::RvalueKind::Place(_)
+                    | mir::RvalueKind::Copy(_)
+                    | mir::RvalueKind::NewArray { .. }
+                    | mir::RvalueKind::Slice { .. }
+                    | mir::RvalueKind::Format(_)
+                    | mir::RvalueKind::StackAlloc(_) => (),
+                }
+                Some(*place)
+            }
+            mir::StatementKind::Call {
+                arguments,
+                return_place,
+                ..
+            } => {
+                arguments.iter().for_each(&mut moved);
+                *return_place
+            }
+            mir::StatementKind::StorageStart(locals) => {
+                for local in locals {
+                    if owned.contains(*local) {
+                        effects.push(Effect::StorageStart(*local));
+                    }
+                }
+                None
+            }
+            mir::StatementKind::StorageDead(local) => {
+                if owned.contains(*local) {
+                    effects.push(Effect::Dropped(*local));
+                }
+                None
+            }
+            mir::StatementKind::Free(_) => None,
+        };
+
+        let written = written.and_then(|place| match self.tree.places[place].kind {
+            mir::PlaceKind::Local(local) if owned.contains(local) => Some(local),
+            _ => None,
+        });
+
+        if let Some(local) = written {
+            // the old value is dropped before it is overwritten
+            effects.push(Effect::Dropped(local));
+            effects.push(Effect::Initialized(local));
+        }
+        effects
+    }
+
+    /// Splits `block` so the drop glue of `local` only runs if its flag is set,
+    /// returns the block the remaining statements go in.
+    fn push_conditional_drop(&mut self, local: LocalId, flag: LocalId, block: BlockId) -> BlockId {
+        let drop_bb = self.new_block();
+        let rest_bb = self.new_block();
+
+        let dropped_bb = self.push_drop_glue(local, drop_bb);
+        self.insert_terminator(dropped_bb, mir::Terminator::Goto(rest_bb));
+
+        let bool_type = self.hir_response.typed.types_table.bool_type;
+        let condition = mir::Operand::new(bool_type, mir::OperandKind::Local(flag));
+        let terminator = replace(
+            &mut self.tree.blocks[block].terminator,
+            mir::Terminator::If {
+                condition,
+                then: drop_bb,
+                arm: rest_bb,
+            },
+        );
+
+        let terminator_span = self.tree.terminator_spans.insert(block, self.current.span);
+        self.insert_terminator_at(
+            rest_bb,
+            terminator,
+            terminator_span.unwrap_or(self.current.span),
+        );
+        rest_bb
+    }
+
+    /// Keeps `place` to be dropped at the end of the statement if its type needs drop, for
+    /// temporaries that are only borrowed like the text of an `f"..."` passed as a slice.
+    pub(crate) fn drop_after_statement(&mut self, place: mir::PlaceId, ty: TypeId) {
+        if self.hir_response.typed.types_map.needs_drop(ty) {
+            self.current.statement_temps.push((place, ty));
+        }
+    }
+
+    /// Drops the temporaries kept by `drop_after_statement`, last kept first.
+    pub(crate) fn drop_statement_temps(&mut self) {
+        let temps = std::mem::take(&mut self.current.statement_temps);
+        for (place, ty) in temps.into_iter().rev() {
+            self.drop_temp(place, ty);
+        }
+    }
+
+    /// Moves the temporaries kept by `drop_after_statement` into the current scope, so
+    /// they are dropped when it is exited instead of at the end of the statement.
+    pub(crate) fn keep_statement_temps(&mut self) {
+        let temps = std::mem::take(&mut self.current.statement_temps);
+        for (place, ty) in temps {
+            self.current.scope.push(ScopeItem::Temp { place, ty });
+        }
+    }
+
+    pub(crate) fn drop_temp(&mut self, place: mir::PlaceId, ty: TypeId) {
+        let block = self.expect_current_block();
+        let block = self.push_place_drop_glue(place, ty, block);
+        self.current.block = Some(block);
+    }
+
+    /// Pushes the drop glue of `local` in `block`, returns the block the statements
+    /// after it go in.
+    fn push_drop_glue(&mut self, local: LocalId, block: BlockId) -> BlockId {
+        let ty = self.tree.locals[local].ty();
+        let place = self.new_place(mir::Place::new(mir::PlaceKind::Local(local), ty));
+        self.push_place_drop_glue(place, ty, block)
+    }
+
+    fn push_place_drop_glue(
+        &mut self,
+        place: mir::PlaceId,
+        ty: TypeId,
+        mut block: BlockId,
+    ) -> BlockId {
+        let types: &'a ThirTypesMap = &self.hir_response.typed.types_map;
+        let Some(thir_type) = types.id_to_type(ty) else {
+            return block;
+        };
+
+        match thir_type.kind {
+            ThirTypeKind::Array {
+                kind: ast::ArrayKind::HeapArray,
+                element,
+            } => {
+                if types.needs_drop(element) {
+                    block = self.push_elements_drop_glue(place, element, block);
+                }
+                self.push_statement_from(
+                    mir::Statement::new(mir::StatementKind::Free(place)),
+                    block,
+                );
+            }
+            ThirTypeKind::CustomTypes(CustomTypeId::Struct(struct_id)) => {
+                let Some(object) = types.id_to_struct(struct_id) else {
+                    return block;
+                };
+
+                if let Some(drop) = object.drop {
+                    let this = mir::Operand::new(
+                        self.drop_this_type(drop, ty),
+                        mir::OperandKind::Ref {
+                            place,
+                            mutable: true,
+                        },
+                    );
+                    let statement = mir::Statement::new(mir::StatementKind::Call {
+                        id: drop,
+                        arguments: vec![this],
+                        type_args: vec![],
+                        return_place: None,
+                    });
+                    self.push_statement_from(statement, block);
+                }
+
+                for field in &object.fields {
+                    if !types.needs_drop(field.ty) {
+                        continue;
+                    }
+
+                    let field_place = self.new_place(mir::Place::new(
+                        mir::PlaceKind::Field {
+                            base: place,
+                            field_id: field.id,
+                            struct_type: struct_id,
+                        },
+                        field.ty,
+                    ));
+                    block = self.push_place_drop_glue(field_place, field.ty, block);
+                }
+            }
+            _ => (),
+        }
+        block
+    }
+    /// Loops over the elements of the heap array `place` to drop each of them,
+    /// `block` jumps into the loop and its terminator moves to the returned exit block.
+    fn push_elements_drop_glue(
+        &mut self,
+        place: mir::PlaceId,
+        element: TypeId,
+        block: BlockId,
+    ) -> BlockId {
+        let types: &'a ThirTypesMap = &self.hir_response.typed.types_map;
+        // `___Array` is `{ptr, len}`
+        let Some(len_field) = types
+            .id_to_struct(types.array_struct)
+            .and_then(|array| array.fields.get(1))
+        else {
+            return block;
+        };
+        let index_type = len_field.ty;
+        let bool_type = self.hir_response.typed.types_table.bool_type;
+        let span = self.current.span;
+
+        let cond_bb = self.new_block();
+        let body_bb = self.new_block();
+        let exit_bb = self.new_block();
+
+        let terminator = replace(
+            &mut self.tree.blocks[block].terminator,
+            mir::Terminator::Goto(cond_bb),
+        );
+        let terminator_span = self.tree.terminator_spans.insert(block, span);
+        self.insert_terminator_at(exit_bb, terminator, terminator_span.unwrap_or(span));
+
+        let index = self.new_hidden_local(self.current.function, index_type);
+        let index_place = self.new_place(mir::Place::new(mir::PlaceKind::Local(index), index_type));
+        let index_operand = mir::Operand::new(index_type, mir::OperandKind::Local(index));
+        let uint = |value| {
+            mir::Operand::new(
+                index_type,
+                mir::OperandKind::Comptime(ComplexLiteral::Basic(Literal::Uint(value))),
+            )
+        };
+        self.push_assign(index_place, mir::RvalueKind::Operand(uint(0)), block);
+
+        // cond: `index < place.len`
+        let len_place = mir::Place::new(
+            mir::PlaceKind::Field {
+                base: place,
+                field_id: len_field.id,
+                struct_type: types.array_struct,
+            },
+            index_type,
+        );
+        let len = self.push_temp_assign(index_type, mir::RvalueKind::Place(len_place), cond_bb);
+        let in_bounds = self.push_temp_assign(
+            bool_type,
+            mir::RvalueKind::Binary {
+                left: index_operand.clone(),
+                operator: Spanned::new(BinaryOperatorKind::Lt, span),
+                right: len,
+            },
+            cond_bb,
+        );
+        self.insert_terminator(
+            cond_bb,
+            mir::Terminator::If {
+                condition: in_bounds,
+                then: body_bb,
+                arm: exit_bb,
+            },
+        );
+
+        // body: drop `place[index]` and `index = index + 1`
+        let element_place = self.new_place(mir::Place::new(
+            mir::PlaceKind::Index {
+                base: place,
+                index: index_operand.clone(),
+            },
+            element,
+        ));
+        let body_end = self.push_place_drop_glue(element_place, element, body_bb);
+        let next = self.push_temp_assign(
+            index_type,
+            mir::RvalueKind::Binary {
+                left: index_operand,
+                operator: Spanned::new(BinaryOperatorKind::Add, span),
+                right: uint(1),
+            },
+            body_end,
+        );
+        self.push_assign(index_place, mir::RvalueKind::Operand(next), body_end);
+        self.insert_terminator(body_end, mir::Terminator::Goto(cond_bb));
+
+        exit_bb
+    }
+
+    fn push_assign(&mut self, place: mir::PlaceId, value: mir::RvalueKind, block: BlockId) {
+        let statement = mir::Statement::new(mir::StatementKind::Assign {
+            place,
+            value: mir::Rvalue::new(value),
+        });
+        self.push_statement_from(statement, block);
+    }
+
+    /// Assigns `value` to a new temp in `block` and returns that temp.
+    fn push_temp_assign(
+        &mut self,
+        ty: TypeId,
+        value: mir::RvalueKind,
+        block: BlockId,
+    ) -> mir::Operand {
+        let temp = self.new_temp(ty);
+        let place = self.new_place(mir::Place::new(mir::PlaceKind::Temp(temp), ty));
+        self.push_assign(place, value, block);
+        mir::Operand::new(ty, mir::OperandKind::Temp(temp))
+    }
+    /// Type of the `&this` parameter of `drop`, the `&Struct` the drop glue passes.
+    fn drop_this_type(&self, drop: FunctionId, struct_type: TypeId) -> TypeId {
+        self.tree
+            .functions
+            .get(drop)
+            .and_then(|function| function.parameters.first())
+            .map(|this| self.tree.locals[*this].ty())
+            .unwrap_or(struct_type)
+    }
+
+    fn new_drop_flag(&mut self, function_id: FunctionId) -> LocalId {
+        let ty = self.hir_response.typed.types_table.bool_type;
+        self.new_hidden_local(function_id, ty)
+    }
+
+    /// A runtime local of `function_id` that has no `hir` local, like drop flags.
+    fn new_hidden_local(&mut self, function_id: FunctionId, ty: TypeId) -> LocalId {
+        let id = self.id_generators.alloc_local();
+        self.tree.locals.insert(id, mir::Local::Runtime { id, ty });
+
+        if let mir::FunctionBody::Internal { locals, .. } =
+            &mut self.tree.functions[function_id].body
+        {
+            locals.push(id);
+        }
+        id
+    }
+
+    fn set_flag(&mut self, flag: LocalId, value: bool) -> mir::Statement {
+        let ty = self.hir_response.typed.types_table.bool_type;
+        let place = self.new_place(mir::Place::new(mir::PlaceKind::Local(flag), ty));
+        let value = mir::Operand::new(
+            ty,
+            mir::OperandKind::Comptime(ComplexLiteral::Basic(Literal::Bool(value))),
+        );
+
+        mir::Statement::new(mir::StatementKind::Assign {
+            place,
+            value: mir::Rvalue::new(mir::RvalueKind::Operand(value)),
+        })
+    }
+
+    fn statement_span_of(&self, statement_id: mir::StatementId) -> Span {
+        self.tree
+            .statement_spans
+            .get(statement_id)
+            .copied()
+            .unwrap_or(Span::error())
+    }
+}
@@ -22,7 +22,7 @@ impl<'a> MirContext<'a> {
                 Some(ModuleNodeId::FunctionId(*function))
             }
             hir::GlobalKind::Variable(variable) | hir::GlobalKind::InternalVariable(variable) => {
-                let local = match self.lower_global_variable(variable) {
+                let local = match self.lower_global_variable(global, variable) {
                     Some(val) => val,
                     None => return None,
                 };
@@ -61,7 +61,11 @@ impl<'a> MirContext<'a> {
         }
     }
 
-    fn lower_global_variable(&mut self, variable: &hir::Variable) -> Option<mir::Place> {
+    fn lower_global_variable(
+        &mut self,
+        global: &hir::Global,
+        variable: &hir::Variable,
+    ) -> Option<mir::Place> {
         let local_info = &self.hir_response.hir.nodes.locals[variable.local];
         let ty = self.local_type(variable.local);
         if local_info.is_temp() {
@@ -87,11 +91,23 @@ impl<'a> MirContext<'a> {
 
         let literal = self.get_expression_literal(value_id);
         let is_literal = literal.is_some();
+        let attributes = match self
+            .hir_response
+            .hir
+            .info
+            .meta_data
+            .statements
+            .get(global.id)
+        {
+            Some(meta_data) => meta_data.attributes.clone(),
+            None => vec![],
+        };
         let global = mir::Global {
             id,
             ty,
             local,
             literal,
+            attributes,
         };
         self.tree.globals.insert(id, global);
         if is_literal {
@@ -3,7 +3,8 @@ use hir::{ComplexLiteral, TypeId};
 use hir_literal_interpreter::ToComplex;
 use run_hir::HirResponse;
 use soul_utils::{
-    crate_store::{CrateContext, CrateExports},
+    compile_options::CompilerOptions,
+    crate_store::{CrateContext, CrateStore},
     error::SoulError,
     ids::{FunctionId, IdAlloc},
     sementic_level::SementicFault,
@@ -14,6 +15,8 @@ use soul_utils::{
 
 use typed_hir::ThirType;
 pub(crate) use utils::*;
+mod check;
+mod drops;
 mod global;
 mod id_generators;
 pub mod mir;
@@ -24,17 +27,23 @@ use crate::{id_generators::IdGenerators, mir::MirTree};
 pub fn mir_lower(
     hir_reponse: &HirResponse,
     ast_modules: &AstModuleStore,
+    options: &CompilerOptions,
     context: &mut CrateContext,
-    crate_exports: &CrateExports,
+    crates: &CrateStore,
     root: ModuleId,
 ) -> MirTree {
-    let mut context = MirContext::new(hir_reponse, ast_modules, context, crate_exports, root);
+    let mut context = MirContext::new(hir_reponse, ast_modules, options, context, crates, root);
 
     for module_id in hir_reponse.hir.nodes.modules.keys() {
         context.lower_module(module_id);
     }
 
     context.lower_main_call();
+    context.collect_reexports();
+    context.check_c_abi();
+    context.check_moves();
+    context.check_borrows();
+    context.elaborate_drops();
     context.to_mir_tree()
 }
 
@@ -50,20 +59,52 @@ struct MirContext<'a> {
 
     hir_response: &'a HirResponse,
     context: &'a mut CrateContext,
+    options: &'a CompilerOptions,
     ast_modules: &'a AstModuleStore,
-    _crate_exports: &'a CrateExports,
+    _crates: &'a CrateStore,
     root: ModuleId,
 }
 
+/// entry of a lexical scope, exited in reverse order
+#[derive(Debug, Clone, Copy)]
+enum ScopeItem {
+    Local(mir::LocalId),
+    /// `defer` expression lowered at every exit of the scope, `in_unsafe` is taken from
+    /// where the `defer` is written
+    Defer {
+        value: hir::ExpressionId,
+        in_unsafe: bool,
+    },
+    /// temporary borrowed by a variable, dropped when the scope is exited
+    Temp {
+        place: mir::PlaceId,
+        ty: TypeId,
+    },
+}
+
 struct CurrentContext {
     module: ModuleId,
-    scope: Vec<mir::LocalId>,
+    scope: Vec<ScopeItem>,
+    /// enclosing scopes of `scope`, outermost first
+    parent_scopes: Vec<Vec<ScopeItem>>,
     function: FunctionId,
     block: Option<mir::BlockId>,
     target_place: Option<mir::PlaceId>,
 
     loop_finish: Option<mir::BlockId>,
     loop_continue: Option<mir::BlockId>,
+    /// number of `parent_scopes` outside the current loop body
+    loop_depth: usize,
+    /// `true` while lowering a `defer` expression
+    in_defer: bool,
+    /// `true` while lowering an `unsafe{}` block
+    in_unsafe: bool,
+    /// temporaries that own memory but were only borrowed, dropped at the end of the statement
+    /// or at the end of the scope when the statement stores the borrow
+    statement_temps: Vec<(mir::PlaceId, TypeId)>,
+
+    /// span of the hir node currently being lowered
+    span: Span,
 }
 impl CurrentContext {
     pub fn new(function: FunctionId, module: ModuleId) -> Self {
@@ -72,9 +113,15 @@ impl CurrentContext {
             function,
             block: None,
             scope: vec![],
+            parent_scopes: vec![],
+            loop_depth: 0,
+            in_defer: false,
+            in_unsafe: false,
+            statement_temps: vec![],
             loop_finish: None,
             target_place: None,
             loop_continue: None,
+            span: Span::error(),
         }
     }
 }
@@ -83,8 +130,9 @@ impl<'a> MirContext<'a> {
     fn new(
         hir_reponse: &'a HirResponse,
         ast_modules: &'a AstModuleStore,
+        options: &'a CompilerOptions,
         context: &'a mut CrateContext,
-        crate_exports: &'a CrateExports,
+        crates: &'a CrateStore,
         root: ModuleId,
     ) -> Self {
         let init_global_function = hir_reponse.hir.init_globals;
@@ -115,12 +163,15 @@ impl<'a> MirContext<'a> {
             globals: VecMap::const_default(),
             functions: VecMap::const_default(),
             statements: VecMap::const_default(),
+            statement_spans: VecMap::const_default(),
+            terminator_spans: VecMap::const_default(),
         };
 
         let mut this = Self {
             main,
             tree,
             context,
+            options,
             hir_response: hir_reponse,
             error_type: ThirType {
                 kind: typed_hir::ThirTypeKind::Error,
@@ -128,7 +179,7 @@ impl<'a> MirContext<'a> {
                 modifier: None,
             },
             ast_modules,
-            _crate_exports: crate_exports,
+            _crates: crates,
             id_generators: IdGenerators::new(),
             temp_remap: VecMap::const_default(),
             place_typed: VecMap::const_default(),
@@ -172,12 +223,49 @@ impl<'a> MirContext<'a> {
                 parent: ast_module.parent,
                 name: ast_module.name.clone(),
                 modules: module.modules.clone(),
+                reexports: vec![],
             },
         );
 
         self.current.module = parent_module;
     }
 
+    /// The ast header of a module also holds the items it re-exports, only needed for the
+    /// exports of a lib.
+    fn collect_reexports(&mut self) {
+        if !self.context.is_lib {
+            return;
+        }
+
+        let module_ids: Vec<ModuleId> = self.tree.modules.keys().collect();
+        for module_id in module_ids {
+            let Some(ast_module) = self.ast_modules.get(module_id) else {
+                continue;
+            };
+
+            let mut reexports = vec![];
+            for (name, entry) in &ast_module.header {
+                let Some(function) = entry.function.filter(|kind| kind.visibility.is_public())
+                else {
+                    continue;
+                };
+
+                let is_reexport = self
+                    .tree
+                    .functions
+                    .get(function.value)
+                    .is_some_and(|function| function.from_module != module_id);
+
+                if is_reexport {
+                    reexports.push((name.clone(), function.value));
+                }
+            }
+
+            reexports.sort();
+            self.tree.modules[module_id].reexports = reexports;
+        }
+    }
+
     fn lower_main_call(&mut self) {
         if self.main.is_none() || self.main == Some(FunctionId::error()) {
             return;
@@ -205,6 +293,10 @@ impl<'a> MirContext<'a> {
         self.context.faults.push(SementicFault::error(err));
     }
 
+    fn log_note(&mut self, note: SoulError) {
+        self.context.faults.push(SementicFault::note(note));
+    }
+
     fn new_function_block(&mut self) -> mir::BlockId {
         let id = self.id_generators.alloc_block();
         let block = mir::Block {
@@ -234,7 +326,9 @@ impl<'a> MirContext<'a> {
             .expect("should have id");
 
         match &mut function.body {
-            mir::FunctionBody::External(_) => panic!("should be internal function"),
+            mir::FunctionBody::External(_) | mir::FunctionBody::Dependency { .. } => {
+                panic!("should be internal function")
+            }
             mir::FunctionBody::Internal { blocks, .. } => {
                 blocks.push(id);
             }
@@ -250,7 +344,7 @@ impl<'a> MirContext<'a> {
         self.local_remap.insert(local, id);
         self.tree.locals.insert(id, mir::Local::Runtime { id, ty });
 
-        self.current.scope.push(id);
+        self.current.scope.push(ScopeItem::Local(id));
         id
     }
 
@@ -265,17 +359,19 @@ impl<'a> MirContext<'a> {
         self.local_remap.insert(local, id);
 
         let immutable = !self.id_to_type(ty).is_mutable();
+        // values that need drop must live in memory so `Drop(&this)` can reference them
+        let needs_drop = self.hir_response.typed.types_map.needs_drop(ty);
         let local_kind = match comptime {
-            Some(value) if immutable => mir::Local::Comptime { id, ty, value },
+            Some(value) if immutable && !needs_drop => mir::Local::Comptime { id, ty, value },
             _ => mir::Local::Runtime { id, ty },
         };
 
         self.tree.locals.insert(id, local_kind);
 
-        self.current.scope.push(id);
+        self.current.scope.push(ScopeItem::Local(id));
 
         match &mut self.tree.functions[self.current.function].body {
-            mir::FunctionBody::External(_) => return id,
+            mir::FunctionBody::External(_) | mir::FunctionBody::Dependency { .. } => return id,
             mir::FunctionBody::Internal { locals, .. } => locals.push(id),
         };
 
@@ -288,7 +384,7 @@ impl<'a> MirContext<'a> {
         self.local_remap.insert(local, id);
         self.tree.locals.insert(id, mir::Local::Runtime { id, ty });
 
-        self.current.scope.push(id);
+        self.current.scope.push(ScopeItem::Local(id));
         id
     }
 
@@ -314,14 +410,31 @@ impl<'a> MirContext<'a> {
         statement: mir::Statement,
         block_id: mir::BlockId,
     ) -> mir::StatementId {
+        let id = self.alloc_statement(statement);
+        self.tree.blocks[block_id].statements.push(id);
+        id
+    }
+
+    /// Stores `statement` without adding it to a block.
+    fn alloc_statement(&mut self, statement: mir::Statement) -> mir::StatementId {
         let id = self.id_generators.alloc_statement();
         self.tree.statements.insert(id, statement);
-        self.tree.blocks[block_id].statements.push(id);
+        self.tree.statement_spans.insert(id, self.current.span);
         id
     }
 
     fn insert_terminator(&mut self, block: mir::BlockId, terminator: mir::Terminator) {
+        self.insert_terminator_at(block, terminator, self.current.span);
+    }
+
+    fn insert_terminator_at(
+        &mut self,
+        block: mir::BlockId,
+        terminator: mir::Terminator,
+        span: Span,
+    ) {
         self.tree.blocks[block].terminator = terminator;
+        self.tree.terminator_spans.insert(block, span);
     }
 
     fn id_to_type(&mut self, ty: hir::TypeId) -> &ThirType {
@@ -353,7 +466,9 @@ impl<'a> MirContext<'a> {
     fn expect_init_global_block(&mut self) -> mir::BlockId {
         let start = self.tree.init_global_function;
         let block = self.tree.functions.get(start).map(|func| match &func.body {
-            mir::FunctionBody::External(_) => panic!("should be internal function"),
+            mir::FunctionBody::External(_) | mir::FunctionBody::Dependency { .. } => {
+                panic!("should be internal function")
+            }
             mir::FunctionBody::Internal { blocks, .. } => blocks.get(0),
         });
 
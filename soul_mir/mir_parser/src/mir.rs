@@ -1,6 +1,12 @@
 use ast::{BinaryOperator, ExternLanguage, UnaryOperator};
 use hir::{ComplexLiteral, FieldId, GenericId, StructId, TypeId};
-use soul_utils::{Ident, ids::FunctionId, impl_soul_ids, span::ModuleId, vec_map::VecMap};
+use soul_utils::{
+    DefId, Ident,
+    ids::FunctionId,
+    impl_soul_ids,
+    span::{Attribute, ModuleId, Span},
+    vec_map::VecMap,
+};
 
 impl_soul_ids!(GlobalId, BlockId, LocalId, StatementId, PlaceId, TempId);
 
@@ -40,6 +46,11 @@ pub struct MirTree {
 
     /// Function metadata
     pub functions: VecMap<FunctionId, Function>,
+
+    /// Source span of the expression that produced each statement
+    pub statement_spans: VecMap<StatementId, Span>,
+    /// Source span of the expression that produced each block terminator
+    pub terminator_spans: VecMap<BlockId, Span>,
 }
 
 #[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
@@ -57,6 +68,9 @@ pub struct Module {
     pub modules: Vec<ModuleId>,
     pub parent: Option<ModuleId>,
     pub nodes: Vec<ModuleNodeId>,
+    /// Functions of other modules re-exported with `pub import`, by the name they are
+    /// exported under.
+    pub reexports: Vec<(String, FunctionId)>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -105,6 +119,8 @@ pub struct Function {
 
     /// Parameters are locals
     pub parameters: Vec<LocalId>,
+    /// Takes C variadic arguments after `parameters`
+    pub variadic: bool,
     pub generics: Vec<GenericId>,
     pub owner_type: TypeId,
 
@@ -112,11 +128,17 @@ pub struct Function {
     pub return_type: TypeId,
 
     pub from_module: ModuleId,
+    /// Calling convention of an `extern "C"` function, `None` for a Soul function.
+    pub abi: Option<ExternLanguage>,
+    /// Attributes the backend reads, like `#[inline]` and `#[no_mangle]`.
+    pub attributes: Vec<Attribute>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum FunctionBody {
     External(ExternLanguage),
+    /// Defined as `DefId` in a dependency crate.
+    Dependency(DefId<FunctionId>),
     Internal {
         entry_block: BlockId,
         /// All locals declared in the function body
@@ -137,6 +159,7 @@ pub struct Global {
     pub local: LocalId,
     pub ty: TypeId,
     pub literal: Option<ComplexLiteral>,
+    pub attributes: Vec<Attribute>,
 }
 impl Global {
     pub fn is_comptime(&self) -> bool {
@@ -193,6 +216,10 @@ pub enum StatementKind {
 
     StorageStart(Vec<LocalId>),
     StorageDead(LocalId),
+
+    /// Release the heap buffer of a heap array place (`[*]T`).
+    /// Inserted by drop elaboration.
+    Free(PlaceId),
 }
 
 /// A right-hand-side computation.
@@ -207,6 +234,8 @@ pub enum RvalueKind {
     Place(Place),
     /// Move or copy an operand.
     Operand(Operand),
+    /// Explicit copy of an operand (`copy x`), never moves its source.
+    Copy(Operand),
     CastUse {
         value: Operand,
         cast_to: TypeId,
@@ -231,6 +260,46 @@ pub enum RvalueKind {
     },
 
     StackAlloc(TypeId),
+
+    /// Moves `value` into a new allocation of the allocator runtime (`new value`).
+    ///
+    /// Gives `*T`, or `?*T` that is `null` on a failed allocation when `fallible`.
+    New {
+        value: Operand,
+        fallible: bool,
+    },
+
+    /// Allocates `len` copies of `element` with the allocator runtime (`new:[for len => element]`).
+    ///
+    /// Gives `[*]T`, or `?[*]T` that is `null` on a failed allocation when `fallible`.
+    NewArray {
+        element: Operand,
+        len: Operand,
+        fallible: bool,
+    },
+
+    /// Builds the text of a format string `f"x: {x}"` into a new heap string `[*]char`.
+    Format(Vec<FormatPiece>),
+
+    /// Borrows `array[start..end]` as `[&]T` or `[@]T`, `array` is always an `OperandKind::Ref`.
+    ///
+    /// A missing `start` begins at `0` and a missing `end` runs to the length of `array`.
+    Slice {
+        array: Operand,
+        start: Option<Operand>,
+        end: Option<Operand>,
+    },
+}
+
+/// A piece of [`RvalueKind::Format`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum FormatPiece {
+    Text(String),
+    /// A number, `char` or `bool`, or an `OperandKind::Ref` to a char array.
+    Argument {
+        value: Operand,
+        width: Option<usize>,
+    },
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -257,6 +326,9 @@ pub enum Terminator {
 
     Exit,
 
+    /// Stops the program through the panic runtime with an optional `str` message.
+    Panic(Option<Operand>),
+
     /// Indicates unreachable code (after errors or diverging control flow).
     Unreachable,
 }
@@ -318,6 +390,26 @@ pub enum PlaceKind {
         field_id: FieldId,
         struct_type: StructId,
     },
+
+    /// Element of an array or slice place: `base[index]`
+    Index {
+        base: PlaceId,
+        index: Operand,
+    },
+}
+
+impl Terminator {
+    /// Blocks control can flow to from this terminator.
+    pub fn successors(&self) -> Vec<BlockId> {
+        match self {
+            Terminator::Goto(target) => vec![*target],
+            Terminator::If { then, arm, .. } => vec![*then, *arm],
+            Terminator::Return(_)
+            | Terminator::Exit
+            | Terminator::Panic(_)
+            | Terminator::Unreachable => vec![],
+        }
+    }
 }
 
 impl Statement {
@@ -1,13 +1,16 @@
-use soul_utils::soul_error_internal;
+use soul_utils::{soul_error_internal, span::Span};
 
 use crate::{
-    EndBlock, MirContext,
+    EndBlock, MirContext, ScopeItem,
     mir::{self, OperandKind},
+    parse::statement::is_valid_statement_expression,
 };
 
 struct LiveIndex {
     block: mir::BlockId,
     index: usize,
+    /// span of the hir block, the end of scope statements point at it
+    span: Span,
 }
 
 impl<'a> MirContext<'a> {
@@ -19,10 +22,19 @@ impl<'a> MirContext<'a> {
         let mut this_block = mir_block;
 
         self.current.block = Some(this_block);
-        let (live_i, parent_scope) = self.start_scope(this_block);
+        let block_span = self
+            .hir_response
+            .hir
+            .info
+            .spans
+            .blocks
+            .get(hir_block)
+            .copied();
+        let live_i = self.start_scope(this_block, block_span.unwrap_or(self.current.span));
         let block = &self.hir_response.hir.nodes.blocks[hir_block];
 
         let mut terminator = None;
+        let mut terminator_span = self.current.span;
         let mut block_operand = None;
         let mut block_operand_id = None;
         let is_end = &mut false;
@@ -30,7 +42,10 @@ impl<'a> MirContext<'a> {
         for statement in &block.statements {
             let response = self.lower_statement(statement).pass(is_end);
             match response.terminator {
-                Some(val) => terminator = Some(val),
+                Some(val) => {
+                    terminator = Some(val);
+                    terminator_span = self.statement_span(statement.id);
+                }
                 None => (),
             }
             match response.expression_operand {
@@ -53,9 +68,10 @@ impl<'a> MirContext<'a> {
                 Some(mir::Terminator::Return(operand)) => {
                     let return_value =
                         operand.filter(|value| !matches!(value.kind, OperandKind::None));
-                    self.insert_terminator(
+                    self.insert_terminator_at(
                         this_block,
                         mir::Terminator::Return(return_value.clone()),
+                        terminator_span,
                     );
                     return_value
                 }
@@ -63,7 +79,12 @@ impl<'a> MirContext<'a> {
                     self.insert_terminator(this_block, mir::Terminator::Goto(target));
                     block_operand.filter(|value| !matches!(value.kind, OperandKind::None))
                 }
-                None => block_operand.filter(|value| !matches!(value.kind, OperandKind::None)),
+                None => block_operand
+                    .filter(|value| !matches!(value.kind, OperandKind::None))
+                    .map(|value| {
+                        let depth = self.current.parent_scopes.len();
+                        self.move_out_of_scope(value, depth)
+                    }),
                 _ => {
                     self.log_error(soul_error_internal!(
                         "should not have this terminator kind in block",
@@ -73,14 +94,15 @@ impl<'a> MirContext<'a> {
                 }
             };
 
-            self.end_scope(live_i, parent_scope);
+            self.end_scope(live_i, *is_end);
             return EndBlock::new(value, is_end);
         }
 
-        match (terminator, block.terminator) {
-            (Some(terminator), _) => self.insert_terminator(this_block, terminator),
+        let (terminator, terminator_span) = match (terminator, block.terminator) {
+            (Some(terminator), _) => (terminator, terminator_span),
             (_, Some(expression)) => {
                 let expression_id = expression.get_expression_id();
+                let expression_span = self.expression_span(expression_id);
                 let value = if block_operand_id == Some(expression_id) {
                     match block_operand {
                         Some(val) => val,
@@ -93,40 +115,54 @@ impl<'a> MirContext<'a> {
                 let return_value = if matches!(value.kind, OperandKind::None) {
                     None
                 } else {
-                    Some(value)
+                    // the parameters are killed after the function body
+                    Some(self.move_out_of_scope(value, 0))
                 };
 
-                self.insert_terminator(this_block, mir::Terminator::Return(return_value))
+                (mir::Terminator::Return(return_value), expression_span)
             }
-            _ => self.insert_terminator(this_block, mir::Terminator::Return(None)),
-        }
+            _ => (mir::Terminator::Return(None), self.current.span),
+        };
 
-        self.end_scope(live_i, parent_scope);
+        // deferred expressions can start new blocks so the terminator goes in the last one
+        self.end_scope(live_i, *is_end);
+        let end_block = self.expect_current_block();
+        self.insert_terminator_at(end_block, terminator, terminator_span);
         EndBlock::new(None, is_end)
     }
-
-    fn start_scope(&mut self, entry_block: mir::BlockId) -> (LiveIndex, Vec<mir::LocalId>) {
-        let parent_scope = self.push_scope();
+    fn start_scope(&mut self, entry_block: mir::BlockId, span: Span) -> LiveIndex {
+        self.push_scope();
 
         let i = self.tree.blocks[entry_block].statements.len();
         self.push_statement(mir::Statement::new(mir::StatementKind::StorageStart(
             vec![],
         )));
-        (
-            LiveIndex {
-                block: entry_block,
-                index: i,
-            },
-            parent_scope,
-        )
+        LiveIndex {
+            block: entry_block,
+            index: i,
+            span,
+        }
     }
 
-    fn end_scope(&mut self, i_live: LiveIndex, parent_scope: Vec<mir::LocalId>) {
-        let this_scope = self.pop_scope(parent_scope);
-        for local in &this_scope {
-            self.push_statement(mir::Statement::new(mir::StatementKind::StorageDead(*local)));
+    /// `exited` is `true` when the block ended in `return`, `break` or `continue`
+    /// which already killed the locals of this scope
+    fn end_scope(&mut self, i_live: LiveIndex, exited: bool) {
+        if !exited {
+            let parent_span = self.current.span;
+            self.current.span = i_live.span;
+            self.exit_scopes(self.current.parent_scopes.len());
+            self.current.span = parent_span;
         }
 
+        let this_scope = self
+            .pop_scope()
+            .into_iter()
+            .filter_map(|item| match item {
+                ScopeItem::Local(local) => Some(local),
+                ScopeItem::Defer { .. } | ScopeItem::Temp { .. } => None,
+            })
+            .collect();
+
         let statement_id = self.tree.blocks[i_live.block].statements[i_live.index];
         let statement = &mut self.tree.statements[statement_id];
         if let mir::StatementKind::StorageStart(scope) = &mut statement.kind {
@@ -134,20 +170,101 @@ impl<'a> MirContext<'a> {
         }
     }
 
-    /// makes new scope in `current.scope` and return parent scope
-    fn push_scope(&mut self) -> Vec<mir::LocalId> {
-        use std::mem::swap;
+    /// makes new scope in `current.scope` and stores the parent scope in `current.parent_scopes`
+    fn push_scope(&mut self) {
+        use std::mem::take;
 
-        let mut parent_scope = vec![];
-        swap(&mut parent_scope, &mut self.current.scope);
-        parent_scope
+        let parent_scope = take(&mut self.current.scope);
+        self.current.parent_scopes.push(parent_scope);
     }
 
-    /// inserts parant_scope current scope and returns child scope
-    fn pop_scope(&mut self, mut parent_scope: Vec<mir::LocalId>) -> Vec<mir::LocalId> {
-        use std::mem::swap;
+    /// restores the parent scope as current scope and returns child scope
+    fn pop_scope(&mut self) -> Vec<ScopeItem> {
+        use std::mem::replace;
+
+        let parent_scope = self.current.parent_scopes.pop().unwrap_or_default();
+        replace(&mut self.current.scope, parent_scope)
+    }
+
+    /// Moves a by-value use of a local that dies in the scopes exited by
+    /// `exit_scopes(depth)` into a temp, so the value outlives the `StorageDead`.
+    pub(crate) fn move_out_of_scope(
+        &mut self,
+        operand: mir::Operand,
+        depth: usize,
+    ) -> mir::Operand {
+        let OperandKind::Local(local) = operand.kind else {
+            return operand;
+        };
+
+        let parent_scopes = self.current.parent_scopes.get(depth..).unwrap_or_default();
+        let is_exited = parent_scopes
+            .iter()
+            .flatten()
+            .chain(&self.current.scope)
+            .any(|item| matches!(item, ScopeItem::Local(id) if *id == local));
+
+        if !is_exited {
+            return operand;
+        }
+
+        let ty = operand.ty;
+        let temp = self.new_temp(ty);
+        let place = self.new_place(mir::Place::new(mir::PlaceKind::Temp(temp), ty));
+        self.push_statement(mir::Statement::new(mir::StatementKind::Assign {
+            place,
+            value: mir::Rvalue::new(mir::RvalueKind::Operand(operand)),
+        }));
+        mir::Operand::new(ty, OperandKind::Temp(temp))
+    }
+
+    /// Pushes `StorageDead` for every local and lowers every `defer` in the current scope
+    /// and in the parent scopes from `depth` onwards, innermost item first.
+    /// Used by `return`, `break` and `continue` which leave several scopes at once.
+    pub(crate) fn exit_scopes(&mut self, depth: usize) {
+        let parent_scopes = self.current.parent_scopes.get(depth..).unwrap_or_default();
+        let items = parent_scopes
+            .iter()
+            .flatten()
+            .chain(&self.current.scope)
+            .rev()
+            .copied()
+            .collect::<Vec<_>>();
+
+        for item in items {
+            match item {
+                ScopeItem::Local(local) => {
+                    self.push_statement(mir::Statement::new(mir::StatementKind::StorageDead(
+                        local,
+                    )));
+                }
+                ScopeItem::Defer { value, in_unsafe } => self.lower_defer(value, in_unsafe),
+                ScopeItem::Temp { place, ty } => self.drop_temp(place, ty),
+            }
+        }
+    }
+
+    /// lowers a deferred expression at one of the exits of its scope
+    fn lower_defer(&mut self, expression: hir::ExpressionId, in_unsafe: bool) {
+        use std::mem::{replace, take};
+
+        let in_defer = replace(&mut self.current.in_defer, true);
+        let parent_in_unsafe = replace(&mut self.current.in_unsafe, in_unsafe);
+        let loop_finish = take(&mut self.current.loop_finish);
+        let loop_continue = take(&mut self.current.loop_continue);
+        let parent_span = self.current.span;
+
+        let is_end = &mut false;
+        let operand = self.lower_operand(expression).pass(is_end);
+        let kind = &self.hir_response.hir.nodes.expressions[expression].kind;
+        if is_valid_statement_expression(kind) && !matches!(operand.kind, OperandKind::None) {
+            self.push_statement(mir::Statement::new(mir::StatementKind::Eval(operand)));
+        }
 
-        swap(&mut parent_scope, &mut self.current.scope);
-        parent_scope
+        self.current.span = parent_span;
+        self.current.loop_continue = loop_continue;
+        self.current.loop_finish = loop_finish;
+        self.current.in_defer = in_defer;
+        self.current.in_unsafe = parent_in_unsafe;
     }
 }
@@ -9,6 +9,7 @@ impl<'a> MirContext<'a> {
     ) -> mir::Operand {
         let prev_finish = self.current.loop_finish;
         let prev_continue = self.current.loop_continue;
+        let prev_depth = self.current.loop_depth;
 
         let parent_bb = self.expect_current_block();
 
@@ -41,11 +42,22 @@ impl<'a> MirContext<'a> {
         }
 
         self.insert_terminator(loop_bb, mir::Terminator::Goto(condition_bb));
+        self.current.loop_depth = self.current.parent_scopes.len() + 1;
         self.lower_block(body_id, loop_bb);
 
+        // the body may have ended in another block than `loop_bb`
+        let body_end = self.expect_current_block();
+        if matches!(
+            self.tree.blocks[body_end].terminator,
+            mir::Terminator::Unreachable
+        ) {
+            self.insert_terminator(body_end, mir::Terminator::Goto(condition_bb));
+        }
+
         self.current.block = Some(join_bb);
         self.current.loop_finish = prev_finish;
         self.current.loop_continue = prev_continue;
+        self.current.loop_depth = prev_depth;
         mir::Operand::new(
             self.hir_response.typed.types_table.none_type,
             mir::OperandKind::None,
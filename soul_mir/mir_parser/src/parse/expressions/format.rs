@@ -0,0 +1,173 @@
+use hir::{CustomTypeId, TypeId};
+use soul_utils::{
+    error::{SoulError, SoulErrorKind},
+    ids::FunctionId,
+    soul_names::{BuiltinTrait, PrimitiveTypes},
+};
+use typed_hir::{ThirTypeKind, ThirTypesMap, display_thir::DisplayThirType};
+
+use crate::{EndBlock, MirContext, mir};
+
+/// How an argument of a format string is turned into text.
+enum FormatArgument {
+    /// Numbers, `char` and `bool` are written by the backend.
+    Primitive,
+    /// Char arrays are copied as is.
+    Text,
+    /// Structs give their text through the methode of `impl Display{}`.
+    Display(FunctionId),
+}
+
+impl<'a> MirContext<'a> {
+    /// Lowers `f"..."`. Struct arguments are first turned into text by their `Display` methode,
+    /// those strings are freed once the format string is build.
+    pub(super) fn lower_format(
+        &mut self,
+        pieces: &[hir::FormatPiece],
+        ty: TypeId,
+    ) -> EndBlock<mir::Operand> {
+        let is_end = &mut false;
+        let mut format = Vec::with_capacity(pieces.len());
+        let mut displayed = vec![];
+
+        for piece in pieces {
+            let (value_id, width) = match piece {
+                hir::FormatPiece::Text(text) => {
+                    format.push(mir::FormatPiece::Text(text.clone()));
+                    continue;
+                }
+                hir::FormatPiece::Argument { value, width } => (*value, *width),
+            };
+
+            let value_type = self.expression_type(value_id);
+            let value = match self.format_argument(value_type) {
+                Some(FormatArgument::Primitive) => self.lower_operand(value_id).pass(is_end),
+                Some(FormatArgument::Text) => {
+                    let place = self.lower_format_place(value_id).pass(is_end);
+                    mir::Operand::new(
+                        value_type,
+                        mir::OperandKind::Ref {
+                            place,
+                            mutable: false,
+                        },
+                    )
+                }
+                Some(FormatArgument::Display(display)) => {
+                    let place = self.lower_format_place(value_id).pass(is_end);
+                    let this = mir::Operand::new(
+                        value_type,
+                        mir::OperandKind::Ref {
+                            place,
+                            mutable: false,
+                        },
+                    );
+
+                    let text = self.new_temp(ty);
+                    let text_place =
+                        self.new_place(mir::Place::new(mir::PlaceKind::Temp(text), ty));
+                    self.push_statement(mir::Statement::new(mir::StatementKind::Call {
+                        id: display,
+                        arguments: vec![this],
+                        type_args: vec![],
+                        return_place: Some(text_place),
+                    }));
+
+                    displayed.push(text_place);
+                    mir::Operand::new(
+                        ty,
+                        mir::OperandKind::Ref {
+                            place: text_place,
+                            mutable: false,
+                        },
+                    )
+                }
+                None => {
+                    let span = self.expression_span(value_id);
+                    let types: &'a ThirTypesMap = &self.hir_response.typed.types_map;
+                    let type_name = self.id_to_type(value_type).display(types);
+                    self.log_error(SoulError::new(
+                        format!(
+                            "'{type_name}' can not be formatted, implement '{}' for it",
+                            BuiltinTrait::Display.as_str(),
+                        ),
+                        SoulErrorKind::InvalidType,
+                        Some(span),
+                    ));
+                    continue;
+                }
+            };
+            format.push(mir::FormatPiece::Argument { value, width });
+        }
+
+        let temp = self.new_temp(ty);
+        let statement = mir::Statement::new(mir::StatementKind::Assign {
+            place: self.new_place(mir::Place::new(mir::PlaceKind::Temp(temp), ty)),
+            value: mir::Rvalue::new(mir::RvalueKind::Format(format)),
+        });
+        self.push_statement(statement);
+
+        for place in displayed {
+            self.push_statement(mir::Statement::new(mir::StatementKind::Free(place)));
+        }
+
+        EndBlock::new(mir::Operand::new(ty, mir::OperandKind::Temp(temp)), is_end)
+    }
+
+    fn format_argument(&mut self, ty: TypeId) -> Option<FormatArgument> {
+        let types: &'a ThirTypesMap = &self.hir_response.typed.types_map;
+        match &self.id_to_type(ty).kind {
+            ThirTypeKind::Primitive(primitive) => {
+                let shown = primitive.is_numeric()
+                    || primitive.is_character()
+                    || *primitive == PrimitiveTypes::Boolean;
+                shown.then_some(FormatArgument::Primitive)
+            }
+            ThirTypeKind::Array { element, .. } => match types.id_to_type(*element)?.kind {
+                ThirTypeKind::Primitive(PrimitiveTypes::Char) => Some(FormatArgument::Text),
+                _ => None,
+            },
+            ThirTypeKind::CustomTypes(CustomTypeId::Struct(struct_id)) => types
+                .id_to_struct(*struct_id)?
+                .display
+                .map(FormatArgument::Display),
+            _ => None,
+        }
+    }
+
+    /// The place of a text or struct argument, which is only borrowed so it is not moved into
+    /// the format string.
+    fn lower_format_place(&mut self, value_id: hir::ExpressionId) -> EndBlock<mir::PlaceId> {
+        let is_end = &mut false;
+        if let hir::ExpressionKind::Load(place) =
+            self.hir_response.hir.nodes.expressions[value_id].kind
+        {
+            return self.lower_place(place);
+        }
+
+        let value = self.lower_operand(value_id).pass(is_end);
+        let ty = value.ty;
+        let place = match value.kind {
+            mir::OperandKind::Local(local) => {
+                return EndBlock::new(
+                    self.new_place(mir::Place::new(mir::PlaceKind::Local(local), ty)),
+                    is_end,
+                );
+            }
+            mir::OperandKind::Temp(temp) => mir::PlaceKind::Temp(temp),
+            _ => {
+                let temp = self.new_temp(ty);
+                let statement = mir::Statement::new(mir::StatementKind::Assign {
+                    place: self.new_place(mir::Place::new(mir::PlaceKind::Temp(temp), ty)),
+                    value: mir::Rvalue::new(mir::RvalueKind::Operand(value)),
+                });
+                self.push_statement(statement);
+                mir::PlaceKind::Temp(temp)
+            }
+        };
+
+        // a temporary argument is only borrowed, so nothing else frees it
+        let place = self.new_place(mir::Place::new(place, ty));
+        self.drop_after_statement(place, ty);
+        EndBlock::new(place, is_end)
+    }
+}
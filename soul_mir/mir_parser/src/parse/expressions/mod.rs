@@ -1,34 +1,105 @@
-use ast::Literal;
+use ast::{BinaryOperatorKind, Literal};
 use hir::{Binary, ComplexLiteral, CustomTypeId, ExpressionId, StructId, TypeId, Unary};
 use hir_literal_interpreter::ToComplex;
 use soul_utils::{
     Ident,
+    error::{SoulError, SoulErrorKind},
     ids::{FunctionId, IdAlloc},
     soul_error_internal,
+    soul_names::{BuiltinTrait, PrimitiveTypes},
+    span::Span,
 };
-use typed_hir::{Field, Struct, ThirTypeKind};
+use typed_hir::{Field, Struct, ThirTypeKind, ThirTypesMap, display_thir::DisplayThirType};
 use typed_hir_parser::UnifyPrimitiveCast;
 
 use crate::{
     EndBlock, MirContext,
+    check::UnsafeOperation,
     mir::{self, Operand},
 };
 
 mod conditionals;
+mod format;
 
 impl<'a> MirContext<'a> {
     pub(crate) fn lower_operand(&mut self, value_id: hir::ExpressionId) -> EndBlock<mir::Operand> {
+        let slice_coercions = &self.hir_response.typed.types_table.slice_coercions;
+        match slice_coercions.get(value_id) {
+            Some(slice_type) => self.lower_slice_coercion(value_id, *slice_type),
+            None => self.lower_uncoerced_operand(value_id),
+        }
+    }
+
+    /// `[N]T` or `[*]T` given where a `[@]T` or `[&]T` is expected, sliced whole like `array[..]`.
+    fn lower_slice_coercion(
+        &mut self,
+        value_id: hir::ExpressionId,
+        slice_type: TypeId,
+    ) -> EndBlock<mir::Operand> {
+        let is_end = &mut false;
+        let array_type = self.expression_type(value_id);
+
+        // a loaded array is sliced in place so it is borrowed instead of moved
+        let place = match &self.hir_response.hir.nodes.expressions[value_id].kind {
+            hir::ExpressionKind::Load(place) => self.lower_place(*place).pass(is_end),
+            _ => {
+                let value = self.lower_uncoerced_operand(value_id).pass(is_end);
+                let temp = self.new_temp(array_type);
+                let statement = mir::Statement::new(mir::StatementKind::Assign {
+                    place: self.new_place(mir::Place::new(mir::PlaceKind::Temp(temp), array_type)),
+                    value: mir::Rvalue::new(mir::RvalueKind::Operand(value)),
+                });
+                self.push_statement(statement);
+
+                let place = self.new_place(mir::Place::new(mir::PlaceKind::Temp(temp), array_type));
+                self.drop_after_statement(place, array_type);
+                place
+            }
+        };
+
+        let mutable = matches!(
+            self.id_to_type(slice_type).kind,
+            ThirTypeKind::Array {
+                kind: ast::ArrayKind::MutSlice,
+                ..
+            }
+        );
+        let array = mir::Operand::new(array_type, mir::OperandKind::Ref { place, mutable });
+
+        let temp = self.new_temp(slice_type);
+        let statement = mir::Statement::new(mir::StatementKind::Assign {
+            place: self.new_place(mir::Place::new(mir::PlaceKind::Temp(temp), slice_type)),
+            value: mir::Rvalue::new(mir::RvalueKind::Slice {
+                array,
+                start: None,
+                end: None,
+            }),
+        });
+        self.push_statement(statement);
+        EndBlock::new(
+            mir::Operand::new(slice_type, mir::OperandKind::Temp(temp)),
+            is_end,
+        )
+    }
+
+    fn lower_uncoerced_operand(&mut self, value_id: hir::ExpressionId) -> EndBlock<mir::Operand> {
         let value = &self.hir_response.hir.nodes.expressions[value_id];
         let span = self.expression_span(value_id);
         let value_type = self.expression_type(value_id);
         let is_end = &mut false;
 
-        if let Some(literal) = self.get_expression_literal(value_id) {
+        // non-copy locals stay locals (even when comptime known) so their moves are checked
+        if let Some(literal) = self.get_expression_literal(value_id)
+            && !self.is_moving_local_use(&value.kind, value_type)
+        {
             let operand =
                 mir::Operand::new(value_type, mir::OperandKind::Comptime(literal.clone()));
             return EndBlock::new(operand, is_end);
         }
 
+        let parent_span = self.current.span;
+        self.current.span = span;
+
         let operand = match &value.kind {
             hir::ExpressionKind::Sizeof(_) => {
                 let ty = self.sizeof_type(value_id);
@@ -96,8 +167,20 @@ impl<'a> MirContext<'a> {
                 let left = self.lower_operand(*left).pass(is_end);
                 let right = self.lower_operand(*right).pass(is_end);
 
-                let left = self.maybe_cast_literal_to(left, right.ty);
-                let right = self.maybe_cast_literal_to(right, left.ty);
+                // the offset of pointer arithmetic keeps its own integer type
+                let (left, right) = if self.is_pointer_type(left.ty) {
+                    if matches!(
+                        operator.node,
+                        BinaryOperatorKind::Add | BinaryOperatorKind::Sub
+                    ) {
+                        self.require_unsafe(UnsafeOperation::PointerArithmetic, span);
+                    }
+                    (left, right)
+                } else {
+                    let left = self.maybe_cast_literal_to(left, right.ty);
+                    let right = self.maybe_cast_literal_to(right, left.ty);
+                    (left, right)
+                };
 
                 let temp = self.new_temp(value_type);
 
@@ -118,18 +201,23 @@ impl<'a> MirContext<'a> {
                 has_callee: _,
                 arguments: hir_arguments,
             } => self
-                .lower_call(*function, generics, hir_arguments, value_type)
+                .lower_call(*function, generics, hir_arguments, value_type, span)
                 .pass(is_end),
             hir::ExpressionKind::Block(block_id) => {
+                let is_unsafe = self.hir_response.hir.nodes.blocks[*block_id].is_unsafe;
+                let in_unsafe = self.current.in_unsafe;
+                self.current.in_unsafe = in_unsafe || is_unsafe;
+
                 let main_body = self.expect_current_block();
-                self.lower_block(*block_id, main_body).pass(is_end);
+                let block_value = self.lower_block(*block_id, main_body).pass(is_end);
 
                 let operand = match self.hir_response.hir.nodes.blocks[*block_id].terminator {
                     Some(terminator) => {
-                        let inner = self
-                            .lower_operand(terminator.get_expression_id())
-                            .pass(is_end);
+                        // the terminator is also the last statement of the block so it is
+                        // already lowered, a block without value gave `none` or never ended
                         let terminator_type = self.expression_type(terminator.get_expression_id());
+                        let inner = block_value
+                            .unwrap_or(mir::Operand::new(terminator_type, mir::OperandKind::None));
                         let temp = self.new_temp(terminator_type);
 
                         let place =
@@ -144,6 +232,7 @@ impl<'a> MirContext<'a> {
                     None => mir::Operand::new(value_type, mir::OperandKind::None),
                 };
 
+                self.current.in_unsafe = in_unsafe;
                 operand
             }
 
@@ -159,6 +248,18 @@ impl<'a> MirContext<'a> {
                     mir::Operand::new(value_type, mir::OperandKind::None)
                 }
             }
+            hir::ExpressionKind::Panic(message) => {
+                let message = message.map(|message| self.lower_operand(message).pass(is_end));
+                let panic_block = self.expect_current_block();
+                self.insert_terminator(panic_block, mir::Terminator::Panic(message));
+
+                // anything lowered after the panic lands in a block nothing jumps to
+                self.current.block = Some(self.new_block());
+                mir::Operand::new(value_type, mir::OperandKind::None)
+            }
+            hir::ExpressionKind::Format(pieces) => {
+                self.lower_format(pieces, value_type).pass(is_end)
+            }
             hir::ExpressionKind::Function(_) => {
                 self.log_error(soul_error_internal!(
                     "ExpressionKind::Function not yet impl in mir",
@@ -173,9 +274,78 @@ impl<'a> MirContext<'a> {
                 let ptr = self.lower_operand(*inner).pass(is_end);
                 let temp = self.new_temp(value_type);
 
+                let value = if self.is_pointer_type(ptr.ty) {
+                    self.require_unsafe(UnsafeOperation::PointerDeref, span);
+                    let pointee = mir::Place::new(mir::PlaceKind::Deref(ptr), value_type);
+                    mir::Rvalue::new(mir::RvalueKind::Place(pointee))
+                } else {
+                    mir::Rvalue::new(mir::RvalueKind::Operand(ptr))
+                };
                 let statement = mir::Statement::new(mir::StatementKind::Assign {
                     place: self.new_place(mir::Place::new(mir::PlaceKind::Temp(temp), value_type)),
-                    value: mir::Rvalue::new(mir::RvalueKind::Operand(ptr)),
+                    value,
+                });
+
+                self.push_statement(statement);
+                mir::Operand::new(value_type, mir::OperandKind::Temp(temp))
+            }
+
+            hir::ExpressionKind::Copy(inner) => {
+                let value = self.lower_operand(*inner).pass(is_end);
+                let temp = self.new_temp(value_type);
+
+                let statement = mir::Statement::new(mir::StatementKind::Assign {
+                    place: self.new_place(mir::Place::new(mir::PlaceKind::Temp(temp), value_type)),
+                    value: mir::Rvalue::new(mir::RvalueKind::Copy(value)),
+                });
+
+                self.push_statement(statement);
+                mir::Operand::new(value_type, mir::OperandKind::Temp(temp))
+            }
+
+            hir::ExpressionKind::New { value, fallible } => {
+                let value = self.lower_operand(*value).pass(is_end);
+                let temp = self.new_temp(value_type);
+
+                let statement = mir::Statement::new(mir::StatementKind::Assign {
+                    place: self.new_place(mir::Place::new(mir::PlaceKind::Temp(temp), value_type)),
+                    value: mir::Rvalue::new(mir::RvalueKind::New {
+                        value,
+                        fallible: *fallible,
+                    }),
+                });
+
+                self.push_statement(statement);
+                mir::Operand::new(value_type, mir::OperandKind::Temp(temp))
+            }
+
+            hir::ExpressionKind::NewArray {
+                element: element_id,
+                len,
+                fallible,
+            } => {
+                let len = self.lower_operand(*len).pass(is_end);
+                let element = self.lower_operand(*element_id).pass(is_end);
+                if !self.hir_response.typed.types_map.is_auto_copy(element.ty) {
+                    let element_span = self.expression_span(*element_id);
+                    self.log_error(SoulError::new(
+                        format!(
+                            "heap array element has to be '{}' because it is copied into every slot",
+                            BuiltinTrait::AutoCopy.as_str(),
+                        ),
+                        SoulErrorKind::InvalidType,
+                        Some(element_span),
+                    ));
+                }
+
+                let temp = self.new_temp(value_type);
+                let statement = mir::Statement::new(mir::StatementKind::Assign {
+                    place: self.new_place(mir::Place::new(mir::PlaceKind::Temp(temp), value_type)),
+                    value: mir::Rvalue::new(mir::RvalueKind::NewArray {
+                        element,
+                        len,
+                        fallible: *fallible,
+                    }),
                 });
 
                 self.push_statement(statement);
@@ -196,6 +366,35 @@ impl<'a> MirContext<'a> {
                 )
             }
 
+            hir::ExpressionKind::Slice {
+                place,
+                start,
+                end,
+                mutable,
+            } => {
+                let start = start.map(|start| self.lower_operand(start).pass(is_end));
+                let end = end.map(|end| self.lower_operand(end).pass(is_end));
+
+                let ty = self.hir_response.typed.types_table.places[*place];
+                let place_id = self.lower_place(*place).pass(is_end);
+                let array = mir::Operand::new(
+                    ty,
+                    mir::OperandKind::Ref {
+                        place: place_id,
+                        mutable: *mutable,
+                    },
+                );
+
+                let temp = self.new_temp(value_type);
+                let statement = mir::Statement::new(mir::StatementKind::Assign {
+                    place: self.new_place(mir::Place::new(mir::PlaceKind::Temp(temp), value_type)),
+                    value: mir::Rvalue::new(mir::RvalueKind::Slice { array, start, end }),
+                });
+
+                self.push_statement(statement);
+                mir::Operand::new(value_type, mir::OperandKind::Temp(temp))
+            }
+
             hir::ExpressionKind::Cast { value, cast_to: _ } => {
                 let cast_to = self.hir_response.typed.types_table.expressions[value_id];
                 let inner_type = self.expression_type(*value);
@@ -219,19 +418,6 @@ impl<'a> MirContext<'a> {
 
             hir::ExpressionKind::InnerRawStackArray { .. } => self.new_none_operand(),
 
-            hir::ExpressionKind::ExternalCall {
-                crate_name: _,
-                function_name: _,
-                generics: _,
-                arguments: _,
-            } => {
-                self.log_error(soul_error_internal!(
-                    "ExternalCall is unstable in mir stage",
-                    Some(span)
-                ));
-                self.new_none_operand()
-            }
-
             hir::ExpressionKind::If {
                 condition,
                 then_block,
@@ -253,9 +439,23 @@ impl<'a> MirContext<'a> {
             ),
         };
 
+        self.current.span = parent_span;
         EndBlock::new(operand, is_end)
     }
 
+    fn is_moving_local_use(&self, kind: &hir::ExpressionKind, ty: TypeId) -> bool {
+        let is_local = match kind {
+            hir::ExpressionKind::Local(_) => true,
+            hir::ExpressionKind::Load(place) => matches!(
+                self.hir_response.hir.nodes.places[*place].kind,
+                hir::PlaceKind::Local(_)
+            ),
+            _ => false,
+        };
+
+        is_local && !self.hir_response.typed.types_map.is_auto_copy(ty)
+    }
+
     fn lower_enum_variant_index(&self, value_type: TypeId, variant_name: &Ident) -> Option<i128> {
         let hir_type = self.hir_response.typed.types_map.id_to_type(value_type)?;
 
@@ -287,10 +487,15 @@ impl<'a> MirContext<'a> {
         hir_generics: &Vec<TypeId>,
         hir_arguments: &Vec<hir::ExpressionId>,
         ty: hir::TypeId,
+        span: Span,
     ) -> EndBlock<mir::Operand> {
         let is_end = &mut false;
 
         let function = &self.hir_response.hir.nodes.functions[function_id];
+        if matches!(function.body, hir::FunctionBody::External(_)) {
+            self.require_unsafe(UnsafeOperation::ExternCall, span);
+        }
+
         let parameters = &function.parameters;
         let mut arguments = vec![];
         for (i, parameter) in parameters.iter().enumerate() {
@@ -328,6 +533,13 @@ impl<'a> MirContext<'a> {
             arguments.push(value);
         }
 
+        if function.variadic {
+            for arg in hir_arguments.iter().skip(parameters.len()) {
+                let value = self.lower_operand(*arg).pass(is_end);
+                arguments.push(self.promote_variadic_argument(*arg, value));
+            }
+        }
+
         let temp = if self.id_to_type(ty).kind == ThirTypeKind::None {
             None
         } else {
@@ -352,6 +564,110 @@ impl<'a> MirContext<'a> {
         EndBlock::new(operand, is_end)
     }
 
+    /// Applies the C default argument promotions to a variadic argument, floats become `f64`
+    /// and integers smaller than `c_int` become `c_int`. Untyped integers become the smallest
+    /// of `c_int`, `i64` and `u64` that holds them.
+    fn promote_variadic_argument(
+        &mut self,
+        arg: ExpressionId,
+        value: mir::Operand,
+    ) -> mir::Operand {
+        let c_int_type = self.hir_response.typed.types_table.c_int_type;
+        let f64_type = self.hir_response.typed.types_table.f64_type;
+        let promoted = match self.id_to_type(value.ty).kind {
+            ThirTypeKind::Primitive(
+                PrimitiveTypes::UntypedFloat | PrimitiveTypes::Float16 | PrimitiveTypes::Float32,
+            ) => Some(f64_type),
+            ThirTypeKind::Primitive(PrimitiveTypes::UntypedInt | PrimitiveTypes::UntypedUint) => {
+                match self.promote_untyped_integer(&value) {
+                    Some(ty) => Some(ty),
+                    None => {
+                        let span = self.expression_span(arg);
+                        self.log_error(SoulError::new(
+                            "integer literal does not fit in 'c_int', 'i64' or 'u64' so it can not be passed as a variadic argument",
+                            SoulErrorKind::InvalidNumber,
+                            Some(span),
+                        ));
+                        return value;
+                    }
+                }
+            }
+            ThirTypeKind::Primitive(
+                PrimitiveTypes::Int8
+                | PrimitiveTypes::Int16
+                | PrimitiveTypes::Uint8
+                | PrimitiveTypes::Uint16
+                | PrimitiveTypes::Boolean
+                | PrimitiveTypes::Char
+                | PrimitiveTypes::Char8
+                | PrimitiveTypes::Char16,
+            ) => Some(c_int_type),
+            ThirTypeKind::CustomTypes(CustomTypeId::Enum(_)) => Some(c_int_type),
+            ThirTypeKind::Primitive(primitive)
+                if !matches!(primitive, PrimitiveTypes::None | PrimitiveTypes::Never) =>
+            {
+                None
+            }
+            ThirTypeKind::Pointer(_) | ThirTypeKind::Ref { .. } => None,
+            _ => {
+                let span = self.expression_span(arg);
+                let types: &'a ThirTypesMap = &self.hir_response.typed.types_map;
+                let type_name = self.id_to_type(value.ty).display(types);
+                self.log_error(SoulError::new(
+                    format!(
+                        "'{type_name}' can not be passed as a variadic argument, pass a pointer to it instead"
+                    ),
+                    SoulErrorKind::UnifyTypeError,
+                    Some(span),
+                ));
+                return value;
+            }
+        };
+
+        let cast_to = match promoted {
+            Some(ty) if ty != value.ty => ty,
+            _ => return value,
+        };
+
+        let temp = self.new_temp(cast_to);
+        let place = self.new_place(mir::Place::new(mir::PlaceKind::Temp(temp), cast_to));
+        let rvalue = mir::Rvalue::new(mir::RvalueKind::CastUse { value, cast_to });
+        self.push_statement(mir::Statement::new(mir::StatementKind::Assign {
+            place,
+            value: rvalue,
+        }));
+
+        mir::Operand::new(cast_to, mir::OperandKind::Temp(temp))
+    }
+
+    /// The smallest of `c_int`, `i64` and `u64` that holds an untyped integer, `None` if none
+    /// of them do. Values not known at compile time stay `c_int`.
+    fn promote_untyped_integer(&self, value: &mir::Operand) -> Option<TypeId> {
+        let table = &self.hir_response.typed.types_table;
+        let c_int_bits = self.options.target_info().c_int_bit_size as u32;
+        let c_int_max = (1i128 << (c_int_bits - 1)) - 1;
+        let value = match &value.kind {
+            mir::OperandKind::Comptime(ComplexLiteral::Basic(Literal::Int(value))) => *value,
+            mir::OperandKind::Comptime(ComplexLiteral::Basic(Literal::Uint(value))) => {
+                match i128::try_from(*value) {
+                    Ok(value) => value,
+                    Err(_) => return None,
+                }
+            }
+            _ => return Some(table.c_int_type),
+        };
+
+        if (-c_int_max - 1..=c_int_max).contains(&value) {
+            Some(table.c_int_type)
+        } else if i64::try_from(value).is_ok() {
+            Some(table.i64_type)
+        } else if u64::try_from(value).is_ok() {
+            Some(table.u64_type)
+        } else {
+            None
+        }
+    }
+
     fn lower_struct_constructor(
         &mut self,
         values: &Vec<(Ident, ExpressionId)>,
@@ -1,3 +1,5 @@
+use std::mem::take;
+
 use soul_utils::{Ident, ids::FunctionId, soul_error_internal, span::Span};
 
 use crate::{MirContext, mir};
@@ -15,9 +17,12 @@ impl<'a> MirContext<'a> {
             },
             generics: vec![],
             parameters: vec![],
+            variadic: false,
             owner_type: self.hir_response.typed.types_table.none_type,
             return_type: self.hir_response.typed.types_table.none_type,
             from_module: self.hir_response.hir.root,
+            abi: None,
+            attributes: vec![],
         };
 
         self.tree.blocks.insert(
@@ -71,20 +76,27 @@ impl<'a> MirContext<'a> {
 
                 mir::FunctionBody::External(extern_language)
             }
+            hir::FunctionBody::Dependency(def_id) => mir::FunctionBody::Dependency(def_id),
         };
 
         let mir_function = mir::Function {
             body,
             id: function_id,
             parameters: vec![],
+            variadic: function.variadic,
             from_module: self.current.module,
             generics: function.generics.clone(),
             owner_type: function.owner_type,
             name: function.name.clone(),
             return_type: self.function_type(function_id),
+            abi: function.abi,
+            attributes: function.attributes.clone(),
         };
         self.tree.functions.insert(function_id, mir_function);
 
+        let parent_scope = take(&mut self.current.scope);
+        let parent_scopes = take(&mut self.current.parent_scopes);
+
         for parameter in &function.parameters {
             let ty = self.local_type(parameter.local);
             let local_id = self.new_parameter(parameter.local, ty);
@@ -93,13 +105,17 @@ impl<'a> MirContext<'a> {
             parameters.push(local_id);
         }
 
-        if self.context.is_lib && is_function_public(function.name.as_str()) {
+        if self.context.is_lib && function.visibility.is_public() {
             self.tree.public_functions.push(function_id);
         }
 
         let body = match function.body {
             hir::FunctionBody::Internal(block_id) => block_id,
-            hir::FunctionBody::External(_) => return,
+            hir::FunctionBody::External(_) | hir::FunctionBody::Dependency { .. } => {
+                self.current.scope = parent_scope;
+                self.current.parent_scopes = parent_scopes;
+                return;
+            }
         };
 
         if is_main {
@@ -111,14 +127,14 @@ impl<'a> MirContext<'a> {
             });
             self.push_statement_from(statement, entry_block);
         }
-        let _endblock = self.lower_block(body, entry_block);
-
-        if self.context.is_lib && is_function_public(function.name.as_str()) {
-            self.tree.public_functions.push(function_id);
+        let is_end = &mut false;
+        let _value = self.lower_block(body, entry_block).pass(is_end);
+        if !*is_end {
+            // an explicit `return` already killed the parameters
+            self.exit_scopes(0);
         }
-    }
-}
 
-fn is_function_public(name: &str) -> bool {
-    name.chars().next().is_some_and(|ch| ch.is_uppercase())
+        self.current.scope = parent_scope;
+        self.current.parent_scopes = parent_scopes;
+    }
 }
@@ -3,6 +3,7 @@ use soul_utils::{ids::IdAlloc, soul_error_internal, span::Span};
 
 use crate::{
     EndBlock, MirContext,
+    check::UnsafeOperation,
     mir::{self, Rvalue},
 };
 
@@ -44,11 +45,20 @@ impl<'a> MirContext<'a> {
                 let ty = self.place_type(place_id);
 
                 let base_place = self.lower_place(*inner).pass(is_end);
-                let operand = self.place_to_operand(base_place, ty);
+                let pointer_type = self.tree.places[base_place].ty;
+                if self.is_pointer_type(pointer_type) {
+                    let span = self.place_span(place_id);
+                    self.require_unsafe(UnsafeOperation::PointerDeref, span);
+                }
+                let operand = self.place_to_operand(base_place, pointer_type);
                 self.new_place(mir::Place::new(mir::PlaceKind::Deref(operand), ty))
             }
-            hir::PlaceKind::Index { .. } => {
-                todo!()
+            hir::PlaceKind::Index { base, index } => {
+                let ty = self.place_type(place_id);
+
+                let base = self.lower_place(*base).pass(is_end);
+                let index = self.lower_operand(*index).pass(is_end);
+                self.new_place(mir::Place::new(mir::PlaceKind::Index { base, index }, ty))
             }
             hir::PlaceKind::Field { base, .. } => {
                 let base = self.lower_place(*base).pass(is_end);
@@ -109,7 +119,7 @@ impl<'a> MirContext<'a> {
     pub(crate) fn place_to_operand(&mut self, place_id: mir::PlaceId, ty: TypeId) -> mir::Operand {
         let place = &self.tree.places[place_id];
         match &place.kind {
-            mir::PlaceKind::Field { .. } => {
+            mir::PlaceKind::Field { .. } | mir::PlaceKind::Index { .. } => {
                 let place = place.clone();
                 let field_temp = self.new_temp(ty);
 
@@ -1,8 +1,11 @@
 use hir::{ComplexLiteral, ExpressionKind, LocalInfo, LocalKind};
-use soul_utils::soul_error_internal;
+use soul_utils::{
+    error::{SoulError, SoulErrorKind},
+    soul_error_internal,
+};
 
 use crate::{
-    EndBlock, MirContext,
+    EndBlock, MirContext, ScopeItem,
     mir::{self, OperandKind},
 };
 
@@ -27,6 +30,10 @@ impl<'a> MirContext<'a> {
         let mut last_expression_id = None;
 
         let span = self.statement_span(statement_id);
+        let parent_span = self.current.span;
+        self.current.span = span;
+        let outer_temps = std::mem::take(&mut self.current.statement_temps);
+
         let terminator = match &statement.kind {
             hir::StatementKind::Variable(variable) => {
                 self.lower_variable(variable, is_end);
@@ -51,6 +58,14 @@ impl<'a> MirContext<'a> {
                 last_expression_id = Some(*value);
                 None
             }
+            hir::StatementKind::Return(_) if self.current.in_defer => {
+                self.log_error(SoulError::new(
+                    "can not 'return' out of 'defer'",
+                    SoulErrorKind::InvalidContext,
+                    Some(span),
+                ));
+                None
+            }
             hir::StatementKind::Return(value) => {
                 let operand = match value {
                     Some(val) => {
@@ -64,16 +79,28 @@ impl<'a> MirContext<'a> {
                     None => None,
                 };
 
+                self.drop_statement_temps();
+                let operand = operand.map(|operand| self.move_out_of_scope(operand, 0));
+                self.exit_scopes(0);
+
                 *is_end = true;
                 Some(mir::Terminator::Return(operand))
             }
             hir::StatementKind::Continue => {
-                let current = self.expect_current_block();
                 match self.current.loop_continue {
                     Some(block_id) => {
                         *is_end = true;
+                        self.exit_scopes(self.current.loop_depth);
+                        let current = self.expect_current_block();
                         self.insert_terminator(current, mir::Terminator::Goto(block_id));
                     }
+                    None if self.current.in_defer => {
+                        self.log_error(SoulError::new(
+                            "can not 'continue' out of 'defer'",
+                            SoulErrorKind::InvalidContext,
+                            Some(span),
+                        ));
+                    }
                     _ => {
                         self.log_error(soul_error_internal!(
                             "`self.current.loop_continue` is None",
@@ -85,12 +112,20 @@ impl<'a> MirContext<'a> {
                 None
             }
             hir::StatementKind::Break => {
-                let current = self.expect_current_block();
                 match self.current.loop_finish {
                     Some(bock_id) => {
                         *is_end = true;
+                        self.exit_scopes(self.current.loop_depth);
+                        let current = self.expect_current_block();
                         self.insert_terminator(current, mir::Terminator::Goto(bock_id));
                     }
+                    None if self.current.in_defer => {
+                        self.log_error(SoulError::new(
+                            "can not 'break' out of 'defer'",
+                            SoulErrorKind::InvalidContext,
+                            Some(span),
+                        ));
+                    }
                     _ => {
                         self.log_error(soul_error_internal!(
                             "`self.current.loop_finish` is None",
@@ -101,6 +136,14 @@ impl<'a> MirContext<'a> {
 
                 None
             }
+            hir::StatementKind::Defer(value) => {
+                let in_unsafe = self.current.in_unsafe;
+                self.current.scope.push(ScopeItem::Defer {
+                    value: *value,
+                    in_unsafe,
+                });
+                None
+            }
             hir::StatementKind::Fall(_) => {
                 let span = self.statement_span(statement_id);
                 self.log_error(soul_error_internal!("statement not yet impl", Some(span)));
@@ -108,6 +151,18 @@ impl<'a> MirContext<'a> {
             }
         };
 
+        if !*is_end {
+            match &statement.kind {
+                // the borrow of a temp can be stored in the variable and used after this statement
+                hir::StatementKind::Variable(_) | hir::StatementKind::Assign(_) => {
+                    self.keep_statement_temps()
+                }
+                _ => self.drop_statement_temps(),
+            }
+        }
+        self.current.statement_temps = outer_temps;
+        self.current.span = parent_span;
+
         let response = StatementResponse {
             terminator,
             expression_operand: last_operand,
@@ -124,7 +179,7 @@ impl<'a> MirContext<'a> {
             return;
         }
 
-        if let LocalKind::Variable(Some(value)) = local_info.kind {
+        if let LocalKind::Variable(Some(value)) | LocalKind::Temp(value) = local_info.kind {
             let target_place =
                 self.new_place(mir::Place::new(place_kind, self.local_type(variable.local)));
 
@@ -167,13 +222,15 @@ impl<'a> MirContext<'a> {
         const SHOULD_ASSIGN: bool = true;
 
         if local_info.is_temp() {
-            return (
-                SHOULD_ASSIGN,
-                mir::PlaceKind::Temp(match self.temp_remap.get(variable.local) {
-                    Some(val) => *val,
-                    None => self.new_temp(self.local_type(variable.local)),
-                }),
-            );
+            let temp = match self.temp_remap.get(variable.local) {
+                Some(val) => *val,
+                None => {
+                    let temp = self.new_temp(self.local_type(variable.local));
+                    self.temp_remap.insert(variable.local, temp);
+                    temp
+                }
+            };
+            return (SHOULD_ASSIGN, mir::PlaceKind::Temp(temp));
         }
 
         let local = match self.local_remap.get(variable.local) {
@@ -232,16 +289,22 @@ impl<'a> MirContext<'a> {
     }
 }
 
-fn is_valid_statement_expression(kind: &hir::ExpressionKind) -> bool {
+pub(crate) fn is_valid_statement_expression(kind: &hir::ExpressionKind) -> bool {
     match kind {
         hir::ExpressionKind::Null
         | hir::ExpressionKind::Error
+        | hir::ExpressionKind::Panic(_)
+        | hir::ExpressionKind::Format(_)
         | hir::ExpressionKind::Load(_)
         | hir::ExpressionKind::Local(_)
         | hir::ExpressionKind::DeRef(_)
+        | hir::ExpressionKind::Copy(_)
+        | hir::ExpressionKind::New { .. }
+        | hir::ExpressionKind::NewArray { .. }
         | hir::ExpressionKind::Sizeof(_)
         | hir::ExpressionKind::Literal(_)
         | hir::ExpressionKind::Ref { .. }
+        | hir::ExpressionKind::Slice { .. }
         | hir::ExpressionKind::Cast { .. }
         | hir::ExpressionKind::Unary { .. }
         | hir::ExpressionKind::Binary { .. }
@@ -253,7 +316,6 @@ fn is_valid_statement_expression(kind: &hir::ExpressionKind) -> bool {
         | hir::ExpressionKind::If { .. }
         | hir::ExpressionKind::Call { .. }
         | hir::ExpressionKind::While { .. }
-        | hir::ExpressionKind::ExternalCall { .. }
         | hir::ExpressionKind::EnumVariant { .. } => true,
     }
 }
@@ -0,0 +1,446 @@
+use std::collections::HashSet;
+
+use ast::{ArrayKind, ExternLanguage};
+use hir::{CustomTypeId, StructId, TypeId};
+use run_hir::HirResponse;
+use soul_utils::{
+    ids::{FunctionId, IdAlloc},
+    soul_names::PrimitiveTypes,
+};
+use typed_hir::{ThirTypeKind, ThirTypesMap};
+
+use crate::MirResponse;
+
+/// Writes a C header for the C API of a lib crate: its `extern "C"` functions with a body
+/// and its `#[repr(C)]` structs and enums.
+///
+/// Slices (`[@]T`, `[&]T` and `[*]T`) become `{T* ptr; size_t len;}` structs, structs without
+/// `#[repr(C)]` can only be used behind a pointer. Declarations without a C equivalent are
+/// left out with a comment saying why.
+pub fn generate_c_header(mir: &MirResponse, hir: &HirResponse, crate_name: &str) -> String {
+    let mut writer = CHeaderWriter::new(hir);
+    writer.check_structs();
+
+    let mut structs = String::new();
+    for id in writer.struct_order() {
+        writer.write_struct(id, &mut structs);
+    }
+
+    let mut enums = String::new();
+    for object in hir.typed.types_map.enums.values() {
+        if !object.repr_c {
+            continue;
+        }
+
+        let variants = object
+            .variants
+            .iter()
+            .map(|variant| format!("    {}_{variant},\n", object.name))
+            .collect::<String>();
+        enums.push_str(&format!(
+            "typedef enum {0} {{\n{variants}}} {0};\n\n",
+            object.name
+        ));
+    }
+
+    let mut functions = String::new();
+    let mut written = HashSet::new();
+    for function in mir.tree.functions.values() {
+        let is_c_definition =
+            function.abi == Some(ExternLanguage::C) && function.body.is_internal();
+        if is_c_definition && written.insert(function.id) {
+            writer.write_function(function.id, function.return_type, &mut functions);
+        }
+    }
+
+    let guard = format!(
+        "{}_H",
+        crate_name
+            .chars()
+            .map(|char| match char.is_ascii_alphanumeric() {
+                true => char.to_ascii_uppercase(),
+                false => '_',
+            })
+            .collect::<String>()
+    );
+
+    let mut header = format!(
+        "/* C API of the Soul crate '{crate_name}', generated by the Soul compiler */\n\
+         #ifndef {guard}\n#define {guard}\n\n\
+         #include <stdbool.h>\n#include <stddef.h>\n#include <stdint.h>\n\n\
+         #ifdef __cplusplus\nextern \"C\" {{\n#endif\n\n"
+    );
+
+    if !writer.forward_structs.is_empty() {
+        for name in &writer.forward_structs {
+            header.push_str(&format!("typedef struct {name} {name};\n"));
+        }
+        header.push('\n');
+    }
+    header.push_str(&enums);
+    header.push_str(&writer.slices);
+    header.push_str(&structs);
+    header.push_str(&functions);
+    header.push_str(&format!(
+        "#ifdef __cplusplus\n}}\n#endif\n\n#endif /* {guard} */\n"
+    ));
+    header
+}
+
+struct CHeaderWriter<'a> {
+    hir: &'a HirResponse,
+    types: &'a ThirTypesMap,
+    /// `typedef struct Name Name;` of the `#[repr(C)]` structs and the structs used behind a pointer.
+    forward_structs: Vec<String>,
+    /// The typedefs of the slice structs used so far.
+    slices: String,
+    slice_names: HashSet<String>,
+    /// `#[repr(C)]` structs with a field that has no C type, with the reason.
+    skipped_structs: Vec<(StructId, String)>,
+}
+impl<'a> CHeaderWriter<'a> {
+    fn new(hir: &'a HirResponse) -> Self {
+        let types = &hir.typed.types_map;
+        let forward_structs = types
+            .structs
+            .values()
+            .filter(|object| object.repr_c)
+            .map(|object| object.name.clone())
+            .collect();
+
+        Self {
+            hir,
+            types,
+            forward_structs,
+            slices: String::new(),
+            slice_names: HashSet::new(),
+            skipped_structs: vec![],
+        }
+    }
+
+    /// Skips the `#[repr(C)]` structs with a field without C type, and the structs containing them.
+    fn check_structs(&mut self) {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (id, object) in self.types.structs.entries() {
+                if !object.repr_c || self.is_skipped(id) {
+                    continue;
+                }
+
+                let invalid = object.fields.iter().find_map(|field| {
+                    let reason = self.field_declaration(field.ty, "").err()?;
+                    Some(format!("field '{}': {reason}", self.field_name(field.id)))
+                });
+                if let Some(reason) = invalid {
+                    self.skipped_structs.push((id, reason));
+                    changed = true;
+                }
+            }
+        }
+
+        // generated slice typedefs are written again when the structs are
+        self.slices.clear();
+        self.slice_names.clear();
+    }
+
+    /// The `#[repr(C)]` structs ordered so that structs come after the structs they contain by value.
+    fn struct_order(&self) -> Vec<StructId> {
+        fn visit(
+            writer: &CHeaderWriter,
+            id: StructId,
+            visited: &mut HashSet<StructId>,
+            order: &mut Vec<StructId>,
+        ) {
+            if !visited.insert(id) {
+                return;
+            }
+
+            if let Some(object) = writer.types.id_to_struct(id) {
+                for field in &object.fields {
+                    if let Some(contained) = writer.by_value_struct(field.ty) {
+                        visit(writer, contained, visited, order);
+                    }
+                }
+            }
+            order.push(id);
+        }
+
+        let mut visited = HashSet::new();
+        let mut order = vec![];
+        for (id, object) in self.types.structs.entries() {
+            if object.repr_c {
+                visit(self, id, &mut visited, &mut order);
+            }
+        }
+        order.retain(|id| {
+            self.types
+                .id_to_struct(*id)
+                .is_some_and(|object| object.repr_c)
+        });
+        order
+    }
+
+    fn write_struct(&mut self, id: StructId, out: &mut String) {
+        let Some(object) = self.types.id_to_struct(id) else {
+            return;
+        };
+        if let Some((_, reason)) = self
+            .skipped_structs
+            .iter()
+            .find(|(skipped, _)| *skipped == id)
+        {
+            out.push_str(&format!(
+                "/* skipped struct '{}': {reason} */\n\n",
+                object.name
+            ));
+            return;
+        }
+
+        let mut text = format!("struct {} {{\n", object.name);
+        for field in &object.fields {
+            // checked by `check_structs`
+            let name = self.field_name(field.id);
+            let declaration = self.field_declaration(field.ty, &name).unwrap_or_default();
+            text.push_str(&format!("    {declaration};\n"));
+        }
+        text.push_str("};\n\n");
+        out.push_str(&text);
+    }
+
+    fn write_function(&mut self, id: FunctionId, return_type: TypeId, out: &mut String) {
+        let Some(function) = self.hir.hir.nodes.functions.get(id) else {
+            return;
+        };
+        let name = function.name.as_str();
+
+        let mut parameters = vec![];
+        for parameter in &function.parameters {
+            let parameter_name = parameter.name.as_str();
+            let ty = self
+                .hir
+                .typed
+                .types_table
+                .locals
+                .get(parameter.local)
+                .copied()
+                .unwrap_or(TypeId::error());
+
+            match self.c_type(ty) {
+                Ok(ty) => parameters.push(format!("{ty} {parameter_name}")),
+                Err(reason) => {
+                    out.push_str(&format!(
+                        "/* skipped function '{name}': parameter '{parameter_name}': {reason} */\n\n"
+                    ));
+                    return;
+                }
+            }
+        }
+
+        let return_type = match self.c_type(return_type) {
+            Ok(ty) => ty,
+            Err(reason) => {
+                out.push_str(&format!(
+                    "/* skipped function '{name}': return type: {reason} */\n\n"
+                ));
+                return;
+            }
+        };
+
+        let parameters = match parameters.is_empty() {
+            true => "void".to_string(),
+            false => parameters.join(", "),
+        };
+        out.push_str(&format!("{return_type} {name}({parameters});\n\n"));
+    }
+
+    /// `T name[N]` for a field, the only place C allows arrays by value.
+    fn field_declaration(&mut self, ty: TypeId, name: &str) -> Result<String, String> {
+        let mut dimensions = String::new();
+        let mut element = ty;
+        while let Some(ThirTypeKind::Array {
+            element: inner,
+            kind: ArrayKind::StackArray(size),
+        }) = self.types.id_to_type(element).map(|ty| &ty.kind)
+        {
+            dimensions.push_str(&format!("[{size}]"));
+            element = *inner;
+        }
+
+        let ty = self.c_type(element)?;
+        if ty == "void" {
+            return Err("'none' fields have no C equivalent".into());
+        }
+        Ok(format!("{ty} {name}{dimensions}"))
+    }
+
+    fn c_type(&mut self, ty: TypeId) -> Result<String, String> {
+        let thir = self
+            .types
+            .id_to_type(ty)
+            .ok_or_else(|| format!("type {ty:?} not found"))?;
+
+        Ok(match &thir.kind {
+            ThirTypeKind::None => "void".into(),
+            ThirTypeKind::Primitive(primitive) => c_primitive(*primitive)?.into(),
+            ThirTypeKind::Pointer(inner) => self.c_pointer(*inner, false)?,
+            ThirTypeKind::Ref { of_type, mutable } => self.c_pointer(*of_type, !mutable)?,
+            ThirTypeKind::Array { element, kind } => match kind {
+                ArrayKind::StackArray(_) => {
+                    return Err(
+                        "C only passes arrays inside a struct, wrap it in a #[repr(C)] struct"
+                            .into(),
+                    );
+                }
+                ArrayKind::ConstSlice => self.c_slice("SoulSlice", *element, true)?,
+                ArrayKind::MutSlice => self.c_slice("SoulMutSlice", *element, false)?,
+                ArrayKind::HeapArray => self.c_slice("SoulArray", *element, false)?,
+            },
+            ThirTypeKind::CustomTypes(CustomTypeId::Struct(id)) => {
+                let object = self
+                    .types
+                    .id_to_struct(*id)
+                    .ok_or_else(|| format!("struct {id:?} not found"))?;
+                if !object.repr_c {
+                    return Err(format!(
+                        "struct '{}' is not #[repr(C)], use it behind a pointer",
+                        object.name
+                    ));
+                }
+                if let Some((_, reason)) = self
+                    .skipped_structs
+                    .iter()
+                    .find(|(skipped, _)| skipped == id)
+                {
+                    return Err(format!("struct '{}' is skipped, {reason}", object.name));
+                }
+                object.name.clone()
+            }
+            ThirTypeKind::CustomTypes(CustomTypeId::Enum(id)) => {
+                let object = self
+                    .types
+                    .id_to_enum(*id)
+                    .ok_or_else(|| format!("enum {id:?} not found"))?;
+                if !object.repr_c {
+                    return Err(format!("enum '{}' is not #[repr(C)]", object.name));
+                }
+                object.name.clone()
+            }
+            ThirTypeKind::Optional(_) => return Err("optional types have no C equivalent".into()),
+            ThirTypeKind::Generic(_) => return Err("generic types have no C equivalent".into()),
+            ThirTypeKind::Type | ThirTypeKind::Error => {
+                return Err("type has no C equivalent".into());
+            }
+        })
+    }
+
+    /// `T*`, structs without `#[repr(C)]` are declared as opaque structs.
+    fn c_pointer(&mut self, pointee: TypeId, is_const: bool) -> Result<String, String> {
+        let pointee_kind = self.types.id_to_type(pointee).map(|ty| &ty.kind);
+
+        let pointee = match pointee_kind {
+            Some(ThirTypeKind::CustomTypes(CustomTypeId::Struct(id))) => {
+                let object = self
+                    .types
+                    .id_to_struct(*id)
+                    .ok_or_else(|| format!("struct {id:?} not found"))?;
+                if !object.repr_c && !self.forward_structs.contains(&object.name) {
+                    self.forward_structs.push(object.name.clone());
+                }
+                object.name.clone()
+            }
+            // a pointer to a stack array points to its first element
+            Some(ThirTypeKind::Array {
+                element,
+                kind: ArrayKind::StackArray(_),
+            }) => return self.c_pointer(*element, is_const),
+            _ => self.c_type(pointee)?,
+        };
+
+        Ok(match is_const {
+            // `const char* const*`, the const has to come after a pointer pointee
+            true if pointee.ends_with('*') => format!("{pointee} const*"),
+            true => format!("const {pointee}*"),
+            false => format!("{pointee}*"),
+        })
+    }
+
+    /// The `{T* ptr; size_t len;}` struct a slice is passed as.
+    fn c_slice(&mut self, prefix: &str, element: TypeId, is_const: bool) -> Result<String, String> {
+        let pointer = self.c_pointer(element, is_const)?;
+        let element_name = self
+            .c_pointer(element, false)?
+            .trim_end_matches('*')
+            .replace('*', "_ptr")
+            .replace(' ', "_");
+        let name = format!("{prefix}_{element_name}");
+
+        if self.slice_names.insert(name.clone()) {
+            self.slices.push_str(&format!(
+                "typedef struct {{\n    {pointer} ptr;\n    size_t len;\n}} {name};\n\n"
+            ));
+        }
+        Ok(name)
+    }
+
+    /// The `#[repr(C)]` struct `ty` contains by value, through stack arrays.
+    fn by_value_struct(&self, mut ty: TypeId) -> Option<StructId> {
+        loop {
+            match &self.types.id_to_type(ty)?.kind {
+                ThirTypeKind::Array {
+                    element,
+                    kind: ArrayKind::StackArray(_),
+                } => ty = *element,
+                ThirTypeKind::CustomTypes(CustomTypeId::Struct(id)) => return Some(*id),
+                _ => return None,
+            }
+        }
+    }
+
+    fn is_skipped(&self, id: StructId) -> bool {
+        self.skipped_structs
+            .iter()
+            .any(|(skipped, _)| *skipped == id)
+    }
+
+    fn field_name(&self, id: hir::FieldId) -> String {
+        self.hir
+            .hir
+            .nodes
+            .fields
+            .get(id)
+            .map(|field| field.name.to_string())
+            .unwrap_or_default()
+    }
+}
+
+fn c_primitive(primitive: PrimitiveTypes) -> Result<&'static str, String> {
+    Ok(match primitive {
+        PrimitiveTypes::None | PrimitiveTypes::Never => "void",
+        PrimitiveTypes::Boolean => "bool",
+        PrimitiveTypes::Char => "char",
+        PrimitiveTypes::CStr => "const char*",
+        PrimitiveTypes::Char8 | PrimitiveTypes::Uint8 => "uint8_t",
+        PrimitiveTypes::Char16 | PrimitiveTypes::Uint16 => "uint16_t",
+        PrimitiveTypes::Char32 | PrimitiveTypes::Uint32 => "uint32_t",
+        PrimitiveTypes::Char64 | PrimitiveTypes::Uint64 => "uint64_t",
+        PrimitiveTypes::Int8 => "int8_t",
+        PrimitiveTypes::Int16 => "int16_t",
+        PrimitiveTypes::Int32 => "int32_t",
+        PrimitiveTypes::Int64 => "int64_t",
+        PrimitiveTypes::Int => "ptrdiff_t",
+        PrimitiveTypes::Uint => "size_t",
+        PrimitiveTypes::CInt => "int",
+        PrimitiveTypes::CUint => "unsigned int",
+        PrimitiveTypes::Float32 => "float",
+        PrimitiveTypes::Float64 => "double",
+        PrimitiveTypes::Int128
+        | PrimitiveTypes::Uint128
+        | PrimitiveTypes::Float16
+        | PrimitiveTypes::UntypedInt
+        | PrimitiveTypes::UntypedUint
+        | PrimitiveTypes::UntypedFloat => {
+            return Err(format!("'{}' has no portable C type", primitive.as_str()));
+        }
+    })
+}
@@ -1,12 +1,23 @@
-use ast::AbtractSyntaxTree;
+use ast::{AbtractSyntaxTree, ArrayKind};
+pub use c_header::generate_c_header;
+use hir::TypeId;
 use mir_parser::{mir::MirTree, mir_lower};
 use run_hir::HirResponse;
 use soul_utils::{
     compile_options::CompilerOptions,
-    crate_store::{CrateContext, CrateExports},
-    span::ModuleId,
+    crate_store::{
+        CrateContext, CrateExports, CrateStore, ExportArrayKind, ExportType, ExportedFunction,
+    },
+    ids::FunctionId,
+    soul_names::AttributeKind,
+    span::{Attribute, ModuleId},
     vec_map::VecMap,
 };
+use typed_hir::{ThirTypeKind, ThirTypesMap, display_thir::DisplayThirType};
+
+mod c_header;
+#[cfg(test)]
+mod tests;
 
 pub struct MirResponse {
     pub tree: MirTree,
@@ -16,18 +27,18 @@ pub struct MirResponse {
 pub fn to_mir(
     hir_response: &HirResponse,
     ast: &AbtractSyntaxTree,
-    _options: &CompilerOptions,
+    options: &CompilerOptions,
     context: &mut CrateContext,
-    crate_exports: &CrateExports,
+    crates: &CrateStore,
     root: ModuleId,
 ) -> MirResponse {
     MirResponse {
-        tree: mir_lower(&hir_response, &ast.modules, context, crate_exports, root),
+        tree: mir_lower(&hir_response, &ast.modules, options, context, crates, root),
         root,
     }
 }
 
-pub fn extract_exports(mir: &MirResponse) -> CrateExports {
+pub fn extract_exports(mir: &MirResponse, hir: &HirResponse) -> CrateExports {
     let mut exports = CrateExports::default();
 
     let root = mir.root;
@@ -44,13 +55,82 @@ pub fn extract_exports(mir: &MirResponse) -> CrateExports {
                 format!("{}.{}", module_path, func_name)
             };
 
-            exports.functions.insert(full_name, *func_id);
+            exports
+                .functions
+                .insert(full_name, export_function(mir, hir, *func_id));
+        }
+    }
+
+    for (module_id, module) in mir.tree.modules.entries() {
+        let module_path = get_module_path(module_id, &mir.tree.modules, root);
+        for (name, func_id) in &module.reexports {
+            let full_name = if module_path.is_empty() {
+                name.clone()
+            } else {
+                format!("{}.{}", module_path, name)
+            };
+
+            exports
+                .functions
+                .entry(full_name)
+                .or_insert_with(|| export_function(mir, hir, *func_id));
         }
     }
 
     exports
 }
 
+fn export_function(mir: &MirResponse, hir: &HirResponse, id: FunctionId) -> ExportedFunction {
+    let function = &mir.tree.functions[id];
+    let types = &hir.typed.types_map;
+
+    let parameters = function
+        .parameters
+        .iter()
+        .map(|local| export_type(mir.tree.locals[*local].ty(), types))
+        .collect();
+
+    ExportedFunction {
+        id,
+        name: function.name.to_string(),
+        parameters,
+        return_type: export_type(function.return_type, types),
+        c_abi: function.abi.is_some(),
+        no_mangle: Attribute::contains(&function.attributes, AttributeKind::NoMangle),
+    }
+}
+
+/// Structs, enums and generics only have a meaning in this crate, they are exported by name.
+fn export_type(id: TypeId, types: &ThirTypesMap) -> ExportType {
+    let Some(ty) = types.id_to_type(id) else {
+        return ExportType::Opaque("<unknown>".to_string());
+    };
+
+    match ty.kind {
+        ThirTypeKind::None => ExportType::None,
+        ThirTypeKind::Primitive(prim) => ExportType::Primitive(prim),
+        ThirTypeKind::Array { element, kind } => ExportType::Array {
+            element: Box::new(export_type(element, types)),
+            kind: match kind {
+                ArrayKind::StackArray(size) => ExportArrayKind::StackArray(size),
+                ArrayKind::HeapArray => ExportArrayKind::HeapArray,
+                ArrayKind::MutSlice => ExportArrayKind::MutSlice,
+                ArrayKind::ConstSlice => ExportArrayKind::ConstSlice,
+            },
+        },
+        ThirTypeKind::Ref { of_type, mutable } => ExportType::Ref {
+            of_type: Box::new(export_type(of_type, types)),
+            mutable,
+        },
+        ThirTypeKind::Pointer(inner) => ExportType::Pointer(Box::new(export_type(inner, types))),
+        ThirTypeKind::Optional(inner) => ExportType::Optional(Box::new(export_type(inner, types))),
+        ThirTypeKind::Type
+        | ThirTypeKind::Generic(_)
+        | ThirTypeKind::CustomTypes(_)
+        | ThirTypeKind::Error => ExportType::Opaque(ty.kind.display(types)),
+    }
+}
+
 fn get_module_path(
     module_id: ModuleId,
     mir_modules: &VecMap<ModuleId, mir_parser::mir::Module>,
@@ -0,0 +1,1717 @@
+use std::path::PathBuf;
+
+use ast::Literal;
+use hir::ComplexLiteral;
+use mir_parser::mir::{self, BlockId};
+use run_ast::to_ast;
+use run_hir::{HirResponse, to_hir};
+use soul_tokenizer::to_token_stream;
+use soul_utils::{
+    CrateStore, IdAlloc,
+    compile_options::{Arch, CompilerOptions, Os, TargetInfo},
+    crate_store::CrateContext,
+    sementic_level::{MessageConfig, ModuleStore, SementicFault, SementicLevel},
+    soul_names::{PrimitiveSize, PrimitiveTypes},
+    span::{Attribute, ModuleId},
+};
+use typed_hir::{ThirTypeKind, display_thir::DisplayThirType};
+
+use crate::{MirResponse, extract_exports, generate_c_header, to_mir};
+
+struct Compiled {
+    mir: MirResponse,
+    hir: HirResponse,
+    faults: Vec<SementicFault>,
+}
+
+fn compile(source: &str) -> Compiled {
+    compile_in(source, PathBuf::new())
+}
+
+/// Compiles `source` as the root module of a crate in `folder`, where its modules are loaded from.
+fn compile_in(source: &str, folder: PathBuf) -> Compiled {
+    compile_crate(source, folder, &CrateStore::new(), false)
+}
+
+fn compile_crate(source: &str, folder: PathBuf, crates: &CrateStore, is_lib: bool) -> Compiled {
+    let path = folder.join("main.soul");
+    let options = CompilerOptions::new_default(TargetInfo::new(Arch::X86_64, Os::Windows));
+
+    let mut module_store = ModuleStore::new(path);
+    let mut context = CrateContext::new(is_lib, MessageConfig::default());
+    let root = module_store.get_root_id();
+
+    let tokens = to_token_stream(source, root);
+    let ast = to_ast(
+        tokens,
+        &options,
+        &mut module_store,
+        &mut context,
+        crates,
+        folder,
+    );
+    let hir = to_hir(&ast, &options, &mut context, crates, root);
+    let mir = to_mir(&hir, &ast, &options, &mut context, crates, root);
+
+    Compiled {
+        mir,
+        hir,
+        faults: context.faults.faults,
+    }
+}
+
+/// Errors and notes as `"level line:col-line:col: message"`, warnings are left out.
+fn diagnostics(source: &str) -> Vec<String> {
+    format_faults(&compile(source), |level| level != SementicLevel::Warning)
+}
+
+/// Only the warnings, formatted like `diagnostics`.
+fn warnings(source: &str) -> Vec<String> {
+    format_faults(&compile(source), |level| level == SementicLevel::Warning)
+}
+
+fn format_faults(compiled: &Compiled, keep: impl Fn(SementicLevel) -> bool) -> Vec<String> {
+    compiled
+        .faults
+        .iter()
+        .filter(|fault| keep(fault.get_level()))
+        .map(|fault| {
+            let error = fault.get_soul_error();
+            let span = error
+                .span
+                .map(|span| {
+                    format!(
+                        "{}:{}-{}:{}",
+                        span.start_line, span.start_offset, span.end_line, span.end_offset
+                    )
+                })
+                .unwrap_or_default();
+            format!("{} {span}: {}", fault.get_level().as_str(), error.message)
+        })
+        .collect()
+}
+
+fn function_blocks<'c>(compiled: &'c Compiled, name: &str) -> &'c [BlockId] {
+    let function = compiled
+        .mir
+        .tree
+        .functions
+        .values()
+        .find(|function| function.name.as_str() == name)
+        .expect("function not found");
+
+    match &function.body {
+        mir::FunctionBody::Internal { blocks, .. } => blocks,
+        _ => &[],
+    }
+}
+
+/// The `Drop(&this)` calls in `block`.
+fn drop_calls(compiled: &Compiled, block: BlockId) -> Vec<&mir::Operand> {
+    let tree = &compiled.mir.tree;
+    tree.blocks[block]
+        .statements
+        .iter()
+        .filter_map(|statement| match &tree.statements[*statement].kind {
+            mir::StatementKind::Call { id, arguments, .. }
+                if tree.functions[*id].name.as_str() == "Drop" =>
+            {
+                arguments.first()
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn count_drops(compiled: &Compiled, function: &str) -> usize {
+    function_blocks(compiled, function)
+        .iter()
+        .map(|block| drop_calls(compiled, *block).len())
+        .sum()
+}
+
+/// Drops that only run when a runtime drop flag is set.
+fn count_flagged_drops(compiled: &Compiled, function: &str) -> usize {
+    let tree = &compiled.mir.tree;
+    function_blocks(compiled, function)
+        .iter()
+        .filter(|block| match &tree.blocks[**block].terminator {
+            mir::Terminator::If {
+                condition, then, ..
+            } => {
+                matches!(condition.kind, mir::OperandKind::Local(_))
+                    && !drop_calls(compiled, *then).is_empty()
+            }
+            _ => false,
+        })
+        .count()
+}
+
+const FILE: &str = "struct File {
+    fd: int
+}
+use File {
+    impl Drop {
+        Drop(&this) {
+        }
+    }
+}
+
+take(file: File) {
+}
+";
+
+#[test]
+fn move_check_use_after_move() {
+    let source = "struct Big {
+    a: int
+}
+
+take(big: Big) {
+}
+
+main() {
+    big := Big{a: 1}
+    take(big)
+    take(big)
+}
+";
+
+    assert_eq!(
+        diagnostics(source),
+        [
+            "error 11:9-12:1: use of moved value",
+            "note 10:9-11:1: value moved here",
+        ]
+    );
+}
+
+#[test]
+fn move_check_move_on_one_branch() {
+    let source = "struct Big {
+    a: int
+}
+
+take(big: Big) {
+}
+
+main() {
+    cond := true
+    big := Big{a: 1}
+    if cond == true {
+        take(big)
+    }
+    take(big)
+}
+";
+
+    assert_eq!(
+        diagnostics(source),
+        [
+            "error 14:9-15:1: use of moved value",
+            "note 12:13-13:1: value moved here",
+        ]
+    );
+}
+
+#[test]
+fn move_check_allows_copy_and_reinit() {
+    let source = "struct Big {
+    a: int
+}
+
+struct Small {
+    a: int
+}
+use Small {
+    impl AutoCopy{}
+}
+
+take(big: Big) {
+}
+
+take_small(small: Small) {
+}
+
+main() {
+    small := Small{a: 1}
+    take_small(small)
+    take_small(small)
+
+    mut big := Big{a: 1}
+    copied := copy big
+    take(big)
+    big = Big{a: 2}
+    take(big)
+    take(copied)
+}
+";
+
+    assert_eq!(diagnostics(source), Vec::<String>::new());
+}
+
+#[test]
+fn drops_owned_locals_at_scope_end() {
+    let compiled = compile(&format!(
+        "{FILE}
+scope() {{
+    file := File{{fd: 1}}
+    other := File{{fd: 2}}
+}}
+
+main() {{
+}}
+"
+    ));
+
+    assert_eq!(count_drops(&compiled, "scope"), 2);
+    assert_eq!(count_flagged_drops(&compiled, "scope"), 0);
+}
+
+#[test]
+fn drops_pass_this_as_reference() {
+    let compiled = compile(&format!(
+        "{FILE}
+main() {{
+    file := File{{fd: 1}}
+}}
+"
+    ));
+
+    let types = &compiled.hir.typed.types_map;
+    let blocks = function_blocks(&compiled, "main");
+    let this = blocks
+        .iter()
+        .flat_map(|block| drop_calls(&compiled, *block))
+        .next()
+        .expect("no drop call");
+
+    let this_type = types.id_to_type(this.ty).expect("unknown type");
+    assert!(matches!(
+        this_type.kind,
+        ThirTypeKind::Ref { mutable: true, .. }
+    ));
+}
+
+#[test]
+fn drops_conditionally_moved_local_with_flag() {
+    let compiled = compile(&format!(
+        "{FILE}
+maybe(cond: bool) {{
+    file := File{{fd: 1}}
+    if cond == true {{
+        take(file)
+    }}
+}}
+
+main() {{
+}}
+"
+    ));
+
+    assert_eq!(count_drops(&compiled, "maybe"), 1);
+    assert_eq!(count_flagged_drops(&compiled, "maybe"), 1);
+}
+
+#[test]
+fn drops_nothing_after_move() {
+    let compiled = compile(&format!(
+        "{FILE}
+give(): File {{
+    file := File{{fd: 3}}
+    file
+}}
+
+consume() {{
+    file := File{{fd: 1}}
+    take(file)
+}}
+
+main() {{
+}}
+"
+    ));
+
+    assert_eq!(count_drops(&compiled, "give"), 0);
+    assert_eq!(count_drops(&compiled, "consume"), 0);
+}
+
+#[test]
+fn copy_rejects_dropped_values() {
+    let source = format!(
+        "{FILE}
+struct Big {{
+    a: int
+}}
+
+main() {{
+    file := File{{fd: 1}}
+    copied := copy file
+    big := Big{{a: 1}}
+    copiedBig := copy big
+}}
+"
+    );
+
+    assert_eq!(
+        diagnostics(&source),
+        [
+            "error 20:15-21:1: can not 'copy' a value of type 'const File', it needs to be dropped and is not 'AutoCopy'"
+        ]
+    );
+}
+
+#[test]
+fn borrow_check_shared_and_mutable_conflict() {
+    let source = "read(p: @int) {
+}
+
+main() {
+    mut x := 1
+    shared := @x
+    unique := &x
+    read(shared)
+}
+";
+
+    assert_eq!(
+        diagnostics(source),
+        [
+            "error 7:5-8:1: cannot borrow value as mutable because it is also borrowed",
+            "note 6:5-7:1: value is borrowed here",
+            "note 8:9-9:1: borrow later used here",
+        ]
+    );
+}
+
+#[test]
+fn borrow_check_reference_outlives_local() {
+    let source = "read(p: @int) {
+}
+
+main() {
+    mut r := @1
+    {
+        y := 2
+        r = @y
+    }
+    read(r)
+}
+";
+
+    assert_eq!(
+        diagnostics(source),
+        [
+            "error 8:9-10:0: borrowed value does not live long enough",
+            "note 6:5-10:1: value dropped here while still borrowed",
+            "note 10:9-11:1: borrow later used here",
+        ]
+    );
+}
+
+#[test]
+fn borrow_check_returned_reference_lifetime() {
+    let source = "first(a: @'a int, b: @'b int): @'a int {
+    b
+}
+
+second(a: @'a int, b: @'b int): @'b int {
+    b
+}
+
+main() {
+}
+";
+
+    assert_eq!(
+        diagnostics(source),
+        [
+            "error 2:5-3:0: returned reference borrows from a parameter that does not share the lifetime of the return type",
+        ]
+    );
+}
+
+/// Names of the functions called in each block of `function` that calls any, in block order.
+fn calls_per_block<'c>(compiled: &'c Compiled, function: &str) -> Vec<Vec<&'c str>> {
+    let tree = &compiled.mir.tree;
+    function_blocks(compiled, function)
+        .iter()
+        .map(|block| {
+            tree.blocks[*block]
+                .statements
+                .iter()
+                .filter_map(|statement| match &tree.statements[*statement].kind {
+                    mir::StatementKind::Call { id, .. } => Some(tree.functions[*id].name.as_str()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|calls| !calls.is_empty())
+        .collect()
+}
+
+const DEFERRED: &str = "one() {
+}
+
+two() {
+}
+
+three() {
+}
+";
+
+#[test]
+fn defer_runs_in_reverse_order_at_scope_end() {
+    let source = format!(
+        "{DEFERRED}
+order() {{
+    defer one()
+    defer {{
+        two()
+        three()
+    }}
+    x := 3
+}}
+
+main() {{
+}}
+"
+    );
+
+    let compiled = compile(&source);
+    assert!(compiled.faults.is_empty());
+    assert_eq!(
+        calls_per_block(&compiled, "order"),
+        [["two", "three", "one"]]
+    );
+}
+
+#[test]
+fn defer_runs_on_early_return() {
+    let source = format!(
+        "{DEFERRED}
+early(cond: bool): int {{
+    defer one()
+    if cond == true {{
+        return 1
+    }}
+    defer two()
+    2
+}}
+
+main() {{
+}}
+"
+    );
+
+    let compiled = compile(&source);
+    assert!(compiled.faults.is_empty());
+    assert_eq!(
+        calls_per_block(&compiled, "early"),
+        [vec!["one"], vec!["two", "one"]]
+    );
+}
+
+#[test]
+fn defer_runs_on_break_and_continue() {
+    let source = format!(
+        "{DEFERRED}
+loops(cond: bool) {{
+    defer one()
+    while cond == true {{
+        defer two()
+        if cond == false {{
+            break
+        }}
+        continue
+    }}
+}}
+
+main() {{
+}}
+"
+    );
+
+    let compiled = compile(&source);
+    assert!(compiled.faults.is_empty());
+    // `break`, `continue` and then the end of the function
+    assert_eq!(
+        calls_per_block(&compiled, "loops"),
+        [vec!["one"], vec!["two"], vec!["two"]]
+    );
+}
+
+#[test]
+fn defer_can_not_leave_the_defer() {
+    let source = "leave(cond: bool) {
+    defer {
+        return
+    }
+    while cond == true {
+        defer {
+            continue
+        }
+        defer {
+            break
+        }
+    }
+}
+
+main() {
+}
+";
+
+    assert_eq!(
+        diagnostics(source),
+        [
+            "error 10:13-11:1: can not 'break' out of 'defer'",
+            "error 7:13-8:1: can not 'continue' out of 'defer'",
+            "error 3:9-4:1: can not 'return' out of 'defer'",
+        ]
+    );
+}
+
+/// The mutability of every `array[..]` slice taken in `function`.
+fn whole_slices(compiled: &Compiled, function: &str) -> Vec<bool> {
+    let tree = &compiled.mir.tree;
+    function_blocks(compiled, function)
+        .iter()
+        .flat_map(|block| &tree.blocks[*block].statements)
+        .filter_map(|statement| match &tree.statements[*statement].kind {
+            mir::StatementKind::Assign { value, .. } => match &value.kind {
+                mir::RvalueKind::Slice {
+                    array,
+                    start: None,
+                    end: None,
+                } => match array.kind {
+                    mir::OperandKind::Ref { mutable, .. } => Some(mutable),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// The `Free` statements of heap arrays in `function`.
+fn count_frees(compiled: &Compiled, function: &str) -> usize {
+    let tree = &compiled.mir.tree;
+    function_blocks(compiled, function)
+        .iter()
+        .flat_map(|block| &tree.blocks[*block].statements)
+        .filter(|statement| {
+            matches!(
+                tree.statements[**statement].kind,
+                mir::StatementKind::Free(_)
+            )
+        })
+        .count()
+}
+
+#[test]
+fn heap_array_fill_must_be_auto_copy() {
+    let source = format!(
+        "{FILE}
+main() {{
+    file := File{{fd: 1}}
+    files := new:[for 3 => file]
+}}
+"
+    );
+
+    assert_eq!(
+        diagnostics(&source),
+        [
+            "error 16:28-16:32: heap array element has to be 'AutoCopy' because it is copied into every slot"
+        ]
+    );
+}
+
+#[test]
+fn heap_array_drops_each_element_before_free() {
+    let compiled = compile(&format!(
+        "{FILE}
+consume(files: [*]File) {{
+}}
+
+main() {{
+}}
+"
+    ));
+
+    assert!(compiled.faults.is_empty());
+    assert_eq!(count_drops(&compiled, "consume"), 1);
+    assert_eq!(count_frees(&compiled, "consume"), 1);
+
+    // the element is dropped in the body of a loop over the array and the buffer is
+    // freed once that loop exits
+    let tree = &compiled.mir.tree;
+    let blocks = function_blocks(&compiled, "consume");
+    let body = *blocks
+        .iter()
+        .find(|block| !drop_calls(&compiled, **block).is_empty())
+        .expect("element drop");
+    let mir::Terminator::Goto(cond) = tree.blocks[body].terminator else {
+        panic!("loop body should jump back to its condition");
+    };
+    let mir::Terminator::If { then, arm, .. } = tree.blocks[cond].terminator else {
+        panic!("loop condition should branch");
+    };
+    assert_eq!(then, body);
+    assert!(tree.blocks[arm].statements.iter().any(|statement| matches!(
+        tree.statements[*statement].kind,
+        mir::StatementKind::Free(_)
+    )));
+}
+
+const SLICE_TAKERS: &str = "takeConst(values: [@]int) {
+}
+
+takeMut(values: [&]int) {
+}
+";
+
+#[test]
+fn slice_coercion_of_heap_array() {
+    let compiled = compile(&format!(
+        "{SLICE_TAKERS}
+main() {{
+    mut heap := new:[for 8 => 0]
+    takeConst(heap)
+    takeMut(heap)
+    takeConst(heap)
+}}
+"
+    ));
+
+    assert!(compiled.faults.is_empty());
+    assert_eq!(whole_slices(&compiled, "main"), [false, true, false]);
+
+    // the array is borrowed by the coercion, so it is still freed at the end of `main`
+    assert_eq!(count_frees(&compiled, "main"), 1);
+}
+
+#[test]
+fn slice_coercion_keeps_slice_mutability() {
+    let source = format!(
+        "{SLICE_TAKERS}
+main() {{
+    mut heap := new:[for 8 => 0]
+    shared := @heap
+    takeMut(shared)
+    fixed := new:[for 4 => 1]
+    takeMut(fixed)
+}}
+"
+    );
+
+    assert_eq!(
+        diagnostics(&source),
+        [
+            "error 10:13-10:19: arraykind '[@]' is not compatible with arraykind '[&]'",
+            "error 12:13-12:18: can only take a mutable slice of mutable arrays type 'const [*]int' is not mutable",
+        ]
+    );
+}
+
+#[test]
+fn slice_coercion_only_of_whole_arrays() {
+    let source = "takeOptional(values: ?[@]int) {
+}
+
+pass(values: ?[*]int) {
+    takeOptional(values)
+}
+
+main() {
+}
+";
+
+    assert_eq!(
+        diagnostics(source),
+        [
+            "error 5:18-5:24: only a whole array can be coerced to a slice, not one nested in another type",
+        ]
+    );
+}
+
+#[test]
+fn panic_fits_any_type() {
+    let source = "pick(c: bool): int {
+    if c == true {
+        return 1
+    }
+    value: int = panic(\"no value\")
+    value
+}
+
+main() {
+    picked := pick(true)
+}
+";
+
+    assert!(diagnostics(source).is_empty());
+}
+
+#[test]
+fn format_string_borrowed_by_call_is_freed() {
+    let compiled = compile(
+        "count(text: [@]char): int {
+    return 1
+}
+
+own(text: [*]char) {
+}
+
+main() {
+    x := 1
+    counted := count(f\"x: {x}\")
+    own(f\"owned {x}\")
+}
+",
+    );
+
+    assert!(compiled.faults.is_empty());
+    // only the borrowed text is freed by `main`, the owned one is moved into `own`
+    assert_eq!(count_frees(&compiled, "main"), 1);
+}
+
+#[test]
+fn format_string_borrowed_by_variable_lives_until_scope_end() {
+    let compiled = compile(
+        "show(text: [@]char) {
+}
+
+main() {
+    n := 3
+    x: [@]char = f\"hi {n}\"
+    show(x)
+}
+",
+    );
+
+    assert!(compiled.faults.is_empty());
+    assert_eq!(count_frees(&compiled, "main"), 1);
+
+    // the text is only freed once `x` is no longer used
+    let tree = &compiled.mir.tree;
+    let statements = function_blocks(&compiled, "main")
+        .iter()
+        .flat_map(|block| &tree.blocks[*block].statements)
+        .map(|statement| &tree.statements[*statement].kind)
+        .collect::<Vec<_>>();
+    let show = statements
+        .iter()
+        .position(|kind| {
+            matches!(kind, mir::StatementKind::Call { id, .. } if tree.functions[*id].name.as_str() == "show")
+        })
+        .expect("call to show");
+    let free = statements
+        .iter()
+        .position(|kind| matches!(kind, mir::StatementKind::Free(_)))
+        .expect("free of the text");
+    assert!(show < free);
+}
+
+#[test]
+fn unsafe_check_rejects_raw_pointers_outside_unsafe() {
+    let source = "extern \"C\" abs(x: c_int): c_int
+
+main() {
+    p := new 5
+    offset := p + 1
+    value := *p
+    *p = 4
+    positive := abs(-4)
+}
+";
+
+    assert_eq!(
+        diagnostics(source),
+        [
+            "error 5:15-6:1: pointer arithmetic is only allowed inside an 'unsafe' block",
+            "error 6:14-7:1: dereferencing a raw pointer is only allowed inside an 'unsafe' block",
+            "error 7:5-7:9: dereferencing a raw pointer is only allowed inside an 'unsafe' block",
+            "error 8:17-9:1: calling an extern function is only allowed inside an 'unsafe' block",
+        ]
+    );
+}
+
+#[test]
+fn unsafe_check_allows_raw_pointers_inside_unsafe() {
+    let source = "main() {
+    p := new 5
+    unsafe {
+        offset := p + 1
+        value := *p
+        *p = 4
+    }
+}
+";
+
+    assert!(diagnostics(source).is_empty());
+}
+
+#[test]
+fn unsafe_check_allows_pointer_comparison() {
+    let source = "main() {
+    p := new 5
+    q := new 6
+    same := p == q
+    other := p != q
+}
+";
+
+    assert!(diagnostics(source).is_empty());
+}
+
+/// The types the arguments of calls in `function` are cast to.
+fn cast_types(compiled: &Compiled, function: &str) -> Vec<PrimitiveTypes> {
+    let tree = &compiled.mir.tree;
+    let types = &compiled.hir.typed.types_map;
+    function_blocks(compiled, function)
+        .iter()
+        .flat_map(|block| &tree.blocks[*block].statements)
+        .filter_map(|statement| match &tree.statements[*statement].kind {
+            mir::StatementKind::Assign { value, .. } => match &value.kind {
+                mir::RvalueKind::CastUse { cast_to, .. } => match types.id_to_type(*cast_to) {
+                    Some(ty) => match ty.kind {
+                        ThirTypeKind::Primitive(primitive) => Some(primitive),
+                        _ => None,
+                    },
+                    None => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn variadic_untyped_integers_promote_to_smallest_fitting_type() {
+    let source = "extern \"C\" sumAll(count: c_int, ...): c_int
+
+main() {
+    unsafe {
+        sum := sumAll(3, 42, 5000000000, 10000000000000000000)
+    }
+}
+";
+
+    let compiled = compile(source);
+    assert!(compiled.faults.is_empty());
+    assert_eq!(
+        cast_types(&compiled, "main"),
+        [
+            PrimitiveTypes::CInt,
+            PrimitiveTypes::CInt,
+            PrimitiveTypes::Int64,
+            PrimitiveTypes::Uint64,
+        ]
+    );
+}
+
+#[test]
+fn variadic_untyped_integer_too_large() {
+    let source = "extern \"C\" sumAll(count: c_int, ...): c_int
+
+main() {
+    unsafe {
+        sum := sumAll(1, 100000000000000000000)
+    }
+}
+";
+
+    assert_eq!(
+        diagnostics(source),
+        [
+            "error 5:26-5:47: integer literal does not fit in 'c_int', 'i64' or 'u64' so it can not be passed as a variadic argument"
+        ]
+    );
+}
+
+const C_API: &str = "#[repr(C)]
+struct Vec2 {
+    x: f32
+    y: f32
+}
+
+#[repr(C)]
+enum Color {
+    Red,
+    Green,
+}
+
+extern \"C\" vec2_dot(a: Vec2, b: Vec2): f32 {
+    return a.x * b.x + a.y * b.y
+}
+
+extern \"C\" paint(color: Color, pixels: [&]u8) {
+}
+
+extern \"C\" puts(s: c_str): c_int
+
+main() {
+}
+";
+
+fn c_api_header() -> String {
+    let compiled = compile(C_API);
+    assert!(compiled.faults.is_empty());
+    generate_c_header(&compiled.mir, &compiled.hir, "c-api")
+}
+
+#[test]
+fn c_header_declares_extern_c_functions() {
+    let header = c_api_header();
+
+    assert!(header.contains("#ifndef C_API_H\n#define C_API_H\n"));
+    assert!(header.contains("float vec2_dot(Vec2 a, Vec2 b);\n"));
+    assert!(header.contains("void paint(Color color, SoulMutSlice_uint8_t pixels);\n"));
+    // only declared here, defined in C
+    assert!(!header.contains("puts"));
+}
+
+#[test]
+fn c_header_defines_repr_c_struct() {
+    let header = c_api_header();
+
+    assert!(header.contains("typedef struct Vec2 Vec2;\n"));
+    assert!(header.contains("struct Vec2 {\n    float x;\n    float y;\n};\n"));
+}
+
+#[test]
+fn c_header_defines_repr_c_enum() {
+    let header = c_api_header();
+
+    assert!(header.contains("typedef enum Color {\n    Color_Red,\n    Color_Green,\n} Color;\n"));
+}
+
+#[test]
+fn c_header_passes_mut_slice_as_pointer_and_length() {
+    let header = c_api_header();
+
+    assert!(header.contains(
+        "typedef struct {\n    uint8_t* ptr;\n    size_t len;\n} SoulMutSlice_uint8_t;\n"
+    ));
+}
+
+#[test]
+fn repr_c_types_get_the_c_layout() {
+    let compiled = compile(C_API);
+    assert!(compiled.faults.is_empty());
+    let hir_types = &compiled.hir.hir.info.types;
+    let types = &compiled.hir.typed.types_map;
+
+    let (vec2, _) = hir_types
+        .structs_entries()
+        .find(|(_, object)| object.name.as_str() == "Vec2")
+        .expect("Vec2");
+    let vec2 = types.id_to_struct(vec2).expect("typed Vec2");
+    assert!(vec2.repr_c);
+    assert!(!vec2.packed);
+    assert_eq!(vec2.fields.len(), 2);
+
+    let (color, _) = hir_types
+        .enums_entries()
+        .find(|(_, object)| object.name.as_str() == "Color")
+        .expect("Color");
+    let color = types.id_to_enum(color).expect("typed Color");
+    assert!(color.repr_c);
+    assert!(matches!(color.tag_size(), PrimitiveSize::CIntSize));
+}
+
+#[test]
+fn extern_c_rejects_non_repr_c_struct_by_value() {
+    let source = "struct Plain {
+    x: int
+}
+
+extern \"C\" bad(p: Plain) {
+}
+
+extern \"C\" make(): Plain
+
+extern \"C\" fine(p: *Plain) {
+}
+
+main() {
+}
+";
+
+    assert_eq!(
+        diagnostics(source),
+        [
+            "error 5:12-5:15: struct 'Plain' is not #[repr(C)], use it behind a pointer in extern \"C\" function 'bad'",
+            "error 8:12-8:16: struct 'Plain' is not #[repr(C)], use it behind a pointer in extern \"C\" function 'make'",
+        ]
+    );
+}
+
+#[test]
+fn unmangled_names_must_be_unique() {
+    let other = "#[no_mangle]
+pub add(a: int, b: int): int {
+    return a + b
+}
+";
+    let main = "import (
+    crate.Other
+)
+
+extern \"C\" add(a: int, b: int): int {
+    return a + b
+}
+
+#[no_mangle]
+sub(a: int, b: int): int {
+    return a - b
+}
+
+main() {
+}
+";
+
+    let compiled = compile_with_modules("soul_no_mangle_collision", main, &[("Other.soul", other)]);
+    assert_eq!(
+        format_faults(&compiled, |level| level != SementicLevel::Warning),
+        [
+            "error 5:12-5:15: function: 'add' is not mangeled and name already exists somewhere in crate"
+        ]
+    );
+}
+
+/// Compiles `main` with `modules` as `(file name, source)` next to it in `folder` under the temp dir.
+fn compile_with_modules(folder: &str, main: &str, modules: &[(&str, &str)]) -> Compiled {
+    compile_in(main, write_modules(folder, modules))
+}
+
+fn write_modules(folder: &str, modules: &[(&str, &str)]) -> PathBuf {
+    let folder = std::env::temp_dir().join(folder);
+    std::fs::create_dir_all(&folder).expect("can not create test folder");
+    for (name, source) in modules {
+        std::fs::write(folder.join(name), source).expect("can not write test module");
+    }
+    folder
+}
+
+/// Compiles `lib` with `modules` next to it as the library crate `name` and adds its exports
+/// to `crates`, like a dependency of the crate compiled next.
+fn add_dependency(crates: &mut CrateStore, name: &str, lib: &str, modules: &[(&str, &str)]) {
+    let folder = write_modules(&format!("soul_dependency_{name}"), modules);
+    let compiled = compile_crate(lib, folder.clone(), crates, true);
+    assert!(format_faults(&compiled, |level| level != SementicLevel::Warning).is_empty());
+
+    let exports = extract_exports(&compiled.mir, &compiled.hir);
+    let id = crates.insert(name.to_string(), folder, ModuleId::error());
+    crates.get_mut(id).expect("inserted crate").exports = exports;
+}
+
+#[test]
+fn private_field_access_from_other_module() {
+    let other = "pub struct Point {
+    pub x: int
+    y: int
+}
+
+pub NewPoint(): Point {
+    return Point{x: 1, y: 2}
+}
+";
+    let main = "import (
+    crate.Other.{this, Point, NewPoint}
+)
+
+main() {
+    p: Point = NewPoint()
+    x := p.x
+    y := p.y
+}
+";
+
+    let compiled =
+        compile_with_modules("soul_private_field_access", main, &[("Other.soul", other)]);
+    assert_eq!(
+        format_faults(&compiled, |level| level != SementicLevel::Warning),
+        ["error 8:10-9:0: field 'y' of 'Point' is private"]
+    );
+}
+
+const GLOB_A: &str = "pub Same(): int {
+    return 1
+}
+
+pub OnlyA(): int {
+    return 2
+}
+
+pub struct Point {
+    pub x: int
+}
+
+hidden(): int {
+    return 3
+}
+";
+
+const GLOB_B: &str = "pub Same(): int {
+    return 4
+}
+";
+
+#[test]
+fn glob_import_brings_in_public_items() {
+    let main = "import (
+    crate.A.*
+)
+
+main() {
+    a := OnlyA()
+    p := Point{x: a}
+    h := hidden()
+}
+";
+
+    let compiled = compile_with_modules("soul_glob_import", main, &[("A.soul", GLOB_A)]);
+    assert_eq!(
+        format_faults(&compiled, |level| level != SementicLevel::Warning),
+        ["error 8:10-8:16: 'hidden' is private"]
+    );
+}
+
+#[test]
+fn glob_ambiguity_is_reported_only_when_used() {
+    let modules = [("A.soul", GLOB_A), ("B.soul", GLOB_B)];
+    let unused = "import (
+    crate.A.*
+    crate.B.*
+)
+
+main() {
+    a := OnlyA()
+}
+";
+    let compiled = compile_with_modules("soul_glob_unused", unused, &modules);
+    assert!(format_faults(&compiled, |level| level != SementicLevel::Warning).is_empty());
+
+    let used = "import (
+    crate.A.*
+    crate.B.*
+)
+
+main() {
+    s := Same()
+}
+";
+    let compiled = compile_with_modules("soul_glob_used", used, &modules);
+    assert_eq!(
+        format_faults(&compiled, |level| level != SementicLevel::Warning),
+        ["error 7:10-7:14: 'Same' is ambiguous, it is imported by the globs of 'A', 'B'"]
+    );
+}
+
+#[test]
+fn pub_import_reexports_public_items() {
+    let facade = "pub import (
+    crate.A.{OnlyA, Point}
+)
+
+import (
+    crate.B.{Same}
+)
+";
+    let modules = [
+        ("A.soul", GLOB_A),
+        ("B.soul", GLOB_B),
+        ("Facade.soul", facade),
+    ];
+    let main = "import (
+    crate.Facade.{OnlyA, Point}
+)
+
+main() {
+    p := Point{x: OnlyA()}
+}
+";
+    let compiled = compile_with_modules("soul_pub_import", main, &modules);
+    assert!(format_faults(&compiled, |level| level != SementicLevel::Warning).is_empty());
+
+    // only `pub import` re-exports
+    let main = "import (
+    crate.Facade.{Same}
+)
+
+main() {
+}
+";
+    let compiled = compile_with_modules("soul_plain_import", main, &modules);
+    assert_eq!(
+        format_faults(&compiled, |level| level != SementicLevel::Warning),
+        ["error 1:1-5:1: module 'Facade' does not export 'Same'"]
+    );
+}
+
+const MODULE_A: &str = "pub Real(): int {
+    return 1
+}
+
+pub literal VALUE = 3
+";
+
+#[test]
+fn module_path_resolves_items() {
+    let main = "import (
+    crate.A
+)
+
+main() {
+    c := A.Real()
+    d := A.VALUE
+}
+";
+
+    let compiled = compile_with_modules("soul_module_path", main, &[("A.soul", MODULE_A)]);
+    assert!(format_faults(&compiled, |level| level != SementicLevel::Warning).is_empty());
+}
+
+#[test]
+fn module_path_reports_missing_items() {
+    let main = "import (
+    crate.A
+)
+
+main() {
+    a := A.Missing
+    b := A.Nope()
+}
+";
+
+    let compiled = compile_with_modules("soul_module_path_missing", main, &[("A.soul", MODULE_A)]);
+    assert_eq!(
+        format_faults(&compiled, |level| level != SementicLevel::Warning),
+        [
+            "error 6:12-7:0: no item 'Missing' in module 'A'",
+            "error 7:12-7:16: no item 'Nope' in module 'A'",
+        ]
+    );
+}
+
+const DEPENDENCY_IO: &str = "pub struct Point {
+    pub x: int
+}
+
+pub Add(a: int, b: int): int {
+    return a + b
+}
+
+pub Len(text: [@]char): uint {
+    return 0
+}
+
+pub Norm(p: Point): int {
+    return p.x
+}
+";
+
+/// The crate `Dep` with the module `Io`.
+fn dependency_crates() -> CrateStore {
+    let mut crates = CrateStore::new();
+    let lib = "import (
+    crate.Io
+)
+";
+    add_dependency(&mut crates, "Dep", lib, &[("Io.soul", DEPENDENCY_IO)]);
+    crates
+}
+
+#[test]
+fn dependency_call_imports_the_signature() {
+    let crates = dependency_crates();
+    let main = "import (
+    Dep.Io
+)
+
+main() {
+    x := Io.Add(1, 2)
+    l := Io.Len(\"hi\")
+}
+";
+
+    let compiled = compile_crate(main, PathBuf::new(), &crates, false);
+    assert!(format_faults(&compiled, |level| level != SementicLevel::Warning).is_empty());
+
+    let tree = &compiled.mir.tree;
+    let types = &compiled.hir.typed.types_map;
+    let display = |ty| types.id_to_type(ty).expect("type").display(types);
+    let mut signatures = tree
+        .functions
+        .values()
+        .filter(|function| matches!(function.body, mir::FunctionBody::Dependency(_)))
+        .map(|function| {
+            let parameters = function
+                .parameters
+                .iter()
+                .map(|param| display(tree.locals[*param].ty()))
+                .collect::<Vec<_>>();
+            format!(
+                "{}({}): {}",
+                function.name.as_str(),
+                parameters.join(", "),
+                display(function.return_type)
+            )
+        })
+        .collect::<Vec<_>>();
+    signatures.sort();
+    // parameters are `const` locals in the calling crate too
+    assert_eq!(
+        signatures,
+        ["Add(const int, const int): int", "Len(const [@]char): uint"]
+    );
+
+    let calls = calls_per_block(&compiled, "main").concat();
+    assert!(calls.contains(&"Add") && calls.contains(&"Len"));
+}
+
+#[test]
+fn same_name_functions_of_two_crates_do_not_collide() {
+    let mut crates = dependency_crates();
+    let lib = "import (
+    crate.Math
+)
+";
+    let math = "pub Add(a: int, b: int): int {
+    return a - b
+}
+";
+    add_dependency(&mut crates, "Net", lib, &[("Math.soul", math)]);
+    let main = "import (
+    Dep.Io
+    Net.Math
+)
+
+main() {
+    x := Io.Add(1, 2)
+    y := Math.Add(3, 4)
+}
+";
+
+    let compiled = compile_crate(main, PathBuf::new(), &crates, false);
+    assert!(format_faults(&compiled, |level| level != SementicLevel::Warning).is_empty());
+
+    let tree = &compiled.mir.tree;
+    let defs = function_blocks(&compiled, "main")
+        .iter()
+        .flat_map(|block| &tree.blocks[*block].statements)
+        .filter_map(|statement| match &tree.statements[*statement].kind {
+            mir::StatementKind::Call { id, .. } => Some(&tree.functions[*id]),
+            _ => None,
+        })
+        .filter_map(|function| match function.body {
+            mir::FunctionBody::Dependency(def) => Some((function.id, def)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    // both are local function 1 of their crate, only the crate tells them apart
+    let [(io_add, io_def), (math_add, math_def)] = defs[..] else {
+        panic!("expected two dependency calls, got {defs:?}");
+    };
+    assert_ne!(io_add, math_add);
+    assert_eq!(io_def.local, math_def.local);
+    assert_ne!(io_def.krate, math_def.krate);
+}
+
+#[test]
+fn dependency_call_rejects_named_arguments() {
+    let crates = dependency_crates();
+    let main = "import (
+    Dep.Io
+)
+
+main() {
+    x := Io.Add(a: 1, b: 2)
+}
+";
+
+    let compiled = compile_crate(main, PathBuf::new(), &crates, false);
+    assert_eq!(
+        format_faults(&compiled, |level| level != SementicLevel::Warning),
+        [
+            "error 6:17-6:18: arguments of 'Add' from crate 'Dep' can not be named, pass them in order",
+            "error 6:23-6:24: arguments of 'Add' from crate 'Dep' can not be named, pass them in order",
+        ]
+    );
+}
+
+#[test]
+fn dependency_call_rejects_opaque_types() {
+    let crates = dependency_crates();
+    let main = "import (
+    Dep.Io
+)
+
+main() {
+    n := Io.Norm(1)
+}
+";
+
+    let compiled = compile_crate(main, PathBuf::new(), &crates, false);
+    assert_eq!(
+        format_faults(&compiled, |level| level != SementicLevel::Warning),
+        [
+            "error 6:13-6:17: 'Io.Norm' of crate 'Dep' can not be called from another crate, type 'Point' in its signature is not shared between crates"
+        ]
+    );
+}
+
+#[test]
+fn capitalized_function_without_pub_warns() {
+    let source = "Shout() {
+}
+
+pub Loud() {
+}
+
+quiet() {
+}
+
+main() {
+}
+";
+
+    assert_eq!(
+        warnings(source),
+        [
+            "warning 1:1-1:6: function 'Shout' starts with a capital letter but is not 'pub', it can only be used in this module"
+        ]
+    );
+}
+
+const ATTRIBUTES: &str = "#[packed]
+struct Header {
+    #[custom]
+    tag: u8
+    len: u32
+}
+
+#[inline]
+#[no_mangle]
+add(a: int, b: int): int {
+    return a + b
+}
+
+#[no_mangle]
+counter: int = 0
+
+#[packed]
+helper() {
+}
+
+main() {
+}
+";
+
+fn attribute_names(attributes: &[Attribute]) -> Vec<&str> {
+    attributes
+        .iter()
+        .map(|attribute| attribute.name.as_str())
+        .collect()
+}
+
+#[test]
+fn attributes_reach_hir_and_mir() {
+    let compiled = compile(ATTRIBUTES);
+
+    let add = compiled
+        .hir
+        .hir
+        .nodes
+        .functions
+        .values()
+        .find(|function| function.name.as_str() == "add")
+        .expect("add");
+    assert_eq!(attribute_names(&add.attributes), ["inline", "no_mangle"]);
+
+    let (_, header) = compiled
+        .hir
+        .hir
+        .info
+        .types
+        .structs_entries()
+        .find(|(_, object)| object.name.as_str() == "Header")
+        .expect("Header");
+    assert_eq!(attribute_names(&header.attributes), ["packed"]);
+    let fields = header
+        .fields
+        .iter()
+        .map(|field| (field.name.as_str(), attribute_names(&field.attributes)))
+        .collect::<Vec<_>>();
+    assert_eq!(fields, [("tag", vec!["custom"]), ("len", vec![])]);
+
+    let tree = &compiled.mir.tree;
+    let add = tree
+        .functions
+        .values()
+        .find(|function| function.name.as_str() == "add")
+        .expect("add");
+    assert_eq!(attribute_names(&add.attributes), ["inline", "no_mangle"]);
+
+    let globals = tree
+        .globals
+        .values()
+        .map(|global| attribute_names(&global.attributes))
+        .collect::<Vec<_>>();
+    assert_eq!(globals, [["no_mangle"]]);
+}
+
+#[test]
+fn attributes_warn_when_unknown_or_without_effect() {
+    assert_eq!(
+        warnings(ATTRIBUTES),
+        [
+            "warning 3:7-3:13: unknown attribute 'custom'",
+            "warning 14:3-14:12: attribute 'no_mangle' has no effect on a variable",
+            "warning 17:3-17:9: attribute 'packed' has no effect on a function",
+        ]
+    );
+}
+
+fn function_names(compiled: &Compiled) -> Vec<&str> {
+    compiled
+        .mir
+        .tree
+        .functions
+        .values()
+        .map(|function| function.name.as_str())
+        .collect()
+}
+
+#[test]
+fn cfg_strips_items_that_do_not_match_the_target() {
+    let compiled = compile(
+        "#[cfg(os = \"windows\")]
+onlyWindows() {
+}
+
+#[cfg(os = \"linux\")]
+onlyLinux() {
+}
+
+#[cfg(any(os = \"linux\", arch = \"x86_64\"))]
+either() {
+}
+
+#[cfg(all(os = \"windows\", arch = \"aarch64\"))]
+both() {
+}
+
+#[cfg(not(os = \"linux\"))]
+notLinux() {
+}
+
+#[cfg(os = \"windows\")]
+#[cfg(arch = \"x86\")]
+stacked() {
+}
+
+main() {
+}
+",
+    );
+
+    assert!(compiled.faults.is_empty());
+    let names = function_names(&compiled);
+    for kept in ["onlyWindows", "either", "notLinux"] {
+        assert!(names.contains(&kept), "{kept} should be kept");
+    }
+    for stripped in ["onlyLinux", "both", "stacked"] {
+        assert!(!names.contains(&stripped), "{stripped} should be stripped");
+    }
+}
+
+#[test]
+fn cfg_strips_imports() {
+    let other = "pub Helper() {
+}
+";
+    let main = "#[cfg(os = \"windows\")]
+import (
+    crate.Other.{Helper}
+)
+
+#[cfg(os = \"linux\")]
+import (
+    crate.Missing.{thing}
+)
+
+main() {
+    Helper()
+}
+";
+
+    let compiled = compile_with_modules("soul_cfg_imports", main, &[("Other.soul", other)]);
+    assert!(format_faults(&compiled, |_| true).is_empty());
+}
+
+#[test]
+fn cfg_unknown_value_warns_and_never_matches() {
+    let source = "#[cfg(arch = \"arm\")]
+onArm() {
+}
+
+#[cfg(not(os = \"beos\"))]
+notOnBeos() {
+}
+
+main() {
+}
+";
+
+    assert_eq!(
+        warnings(source),
+        [
+            "warning 1:14-1:19: unknown arch \"arm\", expected one of: x86_64, x86, aarch64, armv7, riscv64",
+            "warning 5:16-5:22: unknown os \"beos\", expected one of: linux, windows, macos",
+        ]
+    );
+
+    let compiled = compile(source);
+    let names = function_names(&compiled);
+    assert!(!names.contains(&"onArm"));
+    assert!(names.contains(&"notOnBeos"));
+}
+
+#[test]
+fn target_intrinsics_give_target_names() {
+    let compiled = compile(
+        "main() {
+    os := target_os()
+    arch := target_arch()
+}
+",
+    );
+
+    assert!(compiled.faults.is_empty());
+    let tree = &compiled.mir.tree;
+    let main = tree
+        .functions
+        .values()
+        .find(|function| function.name.as_str() == "main")
+        .expect("main");
+    let mir::FunctionBody::Internal { locals, .. } = &main.body else {
+        panic!("main should have a body");
+    };
+    let values = locals
+        .iter()
+        .filter_map(|local| match &tree.locals[*local] {
+            mir::Local::Comptime {
+                value: ComplexLiteral::Basic(Literal::Str(value)),
+                ..
+            } => Some(value.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(values, ["windows", "x86_64"]);
+}
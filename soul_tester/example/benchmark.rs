@@ -8,7 +8,7 @@ use run_mir::to_mir;
 use soul_tokenizer::to_token_stream;
 use soul_utils::{
     CrateExports, CrateStore, IdAlloc, ModuleId, SoulToml,
-    compile_options::{Arch, CompilerOptions, Os, TargetInfo},
+    compile_options::{Arch, BuildProfile, CompilerOptions, Os, TargetInfo},
     crate_store::CrateContext,
     sementic_level::ModuleStore,
 };
@@ -30,6 +30,7 @@ fn main() {
     };
     let paths_json: PathsJson = serde_json::from_slice(include_bytes!("../paths.json")).unwrap();
     let project_path = PathBuf::from(&paths_json.project);
+    let options = COMPILER_OPTIONS.with_profile(paths_json.profile);
 
     logger.logln(format!("date: {}", now.format("%Y_%m_%d %H:%M:%S")));
     logger.logln("=== Benchmark ===");
@@ -63,7 +64,7 @@ fn main() {
     let timer = Instant::now();
     let ast = to_ast(
         tokens,
-        &COMPILER_OPTIONS,
+        &options,
         &mut module_store,
         &mut context,
         &crate_store,
@@ -74,19 +75,12 @@ fn main() {
 
     let all_exports = CrateExports::default();
     let timer = Instant::now();
-    let hir = to_hir(&ast, &COMPILER_OPTIONS, &mut context, &all_exports, root);
+    let hir = to_hir(&ast, &options, &mut context, &all_exports, root);
     let hir_time = timer.elapsed();
     logger.logln(format!("HIR:       {:?}", hir_time));
 
     let timer = Instant::now();
-    let mir = to_mir(
-        &hir,
-        &ast,
-        &COMPILER_OPTIONS,
-        &mut context,
-        &all_exports,
-        root,
-    );
+    let mir = to_mir(&hir, &ast, &options, &mut context, &all_exports, root);
     let mir_time = timer.elapsed();
     logger.logln(format!("MIR:       {:?}", mir_time));
 
@@ -100,10 +94,11 @@ fn main() {
             mir: &mir,
             types: &hir.typed,
             context: &Context::create(),
+            module_store: &module_store,
             crate_name: "benchmark".to_string(),
         };
         let mut faults = Vec::new();
-        to_llvm_ir(&request, &COMPILER_OPTIONS, &mut faults);
+        to_llvm_ir(&request, &options, &mut faults);
         timer.elapsed()
     };
 
@@ -181,6 +176,8 @@ fn load_source(path: &PathBuf) -> std::io::Result<String> {
 #[derive(serde::Deserialize)]
 struct PathsJson {
     project: String,
+    #[serde(default)]
+    profile: BuildProfile,
 }
 
 fn write_results(now: chrono::DateTime<Local>, output: &str) {
@@ -506,6 +506,11 @@ impl<'a> AstDisplayer<'a> {
                 self.push(']');
             }
             ast::ExpressionKind::Block(block) => self.display_block(block),
+            ast::ExpressionKind::Unsafe(block) => {
+                self.push_str(KeyWord::Unsafe.as_str());
+                self.push(' ');
+                self.display_block(block);
+            }
             ast::ExpressionKind::Null(_) => self.push_str("null"),
             ast::ExpressionKind::While(r#while) => {
                 self.try_display_node_id(r#while.id);
@@ -525,8 +530,23 @@ impl<'a> AstDisplayer<'a> {
                 self.display_expression(&binary.right);
                 self.push(')');
             }
-            ast::ExpressionKind::Literal((_, literal)) => {
+            ast::ExpressionKind::Range(range) => {
+                self.try_display_node_id(range.id);
+                if let Some(start) = &range.start {
+                    self.display_expression(start);
+                }
+                self.push_str("..");
+                if let Some(end) = &range.end {
+                    self.display_expression(end);
+                }
+            }
+            ast::ExpressionKind::Literal {
+                literal, suffix, ..
+            } => {
                 self.push_str(&literal.value_to_string());
+                if let Some(suffix) = suffix {
+                    self.push_str(suffix.as_str());
+                }
             }
             ast::ExpressionKind::As(cast) => {
                 self.display_expression(&cast.left);
@@ -542,6 +562,52 @@ impl<'a> AstDisplayer<'a> {
                 self.push('*');
                 self.display_expression(inner);
             }
+            ast::ExpressionKind::Copy { inner, .. } => {
+                self.push_str(KeyWord::Copy.as_str());
+                self.push(' ');
+                self.display_expression(inner);
+            }
+            ast::ExpressionKind::New(new) => {
+                self.push_str(KeyWord::New.as_str());
+                if new.fallible {
+                    self.push('?');
+                }
+                match &new.kind {
+                    ast::NewKind::Value(value) => {
+                        self.push(' ');
+                        self.display_expression(value);
+                    }
+                    ast::NewKind::Array(ctor) => {
+                        self.push(':');
+                        self.display_array_contructor(ctor);
+                    }
+                }
+            }
+            ast::ExpressionKind::FormatString(format) => {
+                self.try_display_node_id(format.id);
+                self.push_str("f\"");
+                for piece in &format.pieces {
+                    match piece {
+                        ast::FormatPiece::Text(text) => {
+                            self.push_str(&text.replace('{', "{{").replace('}', "}}"))
+                        }
+                        ast::FormatPiece::Argument { value, width } => {
+                            self.push('{');
+                            self.display_expression(value);
+                            if let Some(width) = width {
+                                self.push_str(&format!(":{width}"));
+                            }
+                            self.push('}');
+                        }
+                    }
+                }
+                self.push('"');
+            }
+            ast::ExpressionKind::Defer { inner, .. } => {
+                self.push_str(KeyWord::Defer.as_str());
+                self.push(' ');
+                self.display_expression(inner);
+            }
             ast::ExpressionKind::ReturnLike(return_like) => {
                 self.push_str(return_like.kind.as_keyword().as_str());
                 if let Some(value) = &return_like.value {
@@ -582,23 +648,7 @@ impl<'a> AstDisplayer<'a> {
                 self.try_display_node_id(*resolved);
                 self.push_str(ident.as_str());
             }
-            ast::ExpressionKind::ArrayContructor(ctor) => {
-                if let Some(collection) = &ctor.collection_type {
-                    self.display_type(collection);
-                    self.push_str(": ");
-                }
-                self.push('[');
-                if let Some(element) = &ctor.element_type {
-                    self.display_type(element);
-                    self.push_str(": ");
-                }
-                self.push_str(KeyWord::For.as_str());
-                self.push(' ');
-                self.display_expression(&ctor.amount);
-                self.push_str(" => ");
-                self.display_expression(&ctor.element);
-                self.push(']');
-            }
+            ast::ExpressionKind::ArrayContructor(ctor) => self.display_array_contructor(ctor),
             ast::ExpressionKind::Ref {
                 expression,
                 is_mutable,
@@ -642,6 +692,24 @@ impl<'a> AstDisplayer<'a> {
         }
     }
 
+    fn display_array_contructor(&mut self, ctor: &ast::ArrayContructor) {
+        if let Some(collection) = &ctor.collection_type {
+            self.display_type(collection);
+            self.push_str(": ");
+        }
+        self.push('[');
+        if let Some(element) = &ctor.element_type {
+            self.display_type(element);
+            self.push_str(": ");
+        }
+        self.push_str(KeyWord::For.as_str());
+        self.push(' ');
+        self.display_expression(&ctor.amount);
+        self.push_str(" => ");
+        self.display_expression(&ctor.element);
+        self.push(']');
+    }
+
     fn display_type(&mut self, ty: &SoulType) {
         if let Some(modifier) = ty.modifier {
             self.push_str(modifier.as_str());
@@ -680,7 +748,7 @@ impl<'a> AstDisplayer<'a> {
                     CONST => self.push('@'),
                 };
                 if let Some(name) = &reference_type.lifetime {
-                    self.push_fmt(format_args!("'{}", name.as_str()));
+                    self.push_fmt(format_args!("'{} ", name.as_str()));
                 }
                 self.display_type(&reference_type.inner);
             }
@@ -137,8 +137,16 @@ impl<'a> HirDisplayer<'a> {
         let function = &self.hir.nodes.functions[function_id];
         self.push('\n');
         self.display_depth();
-        if let hir::FunctionBody::External(id) = function.body {
-            self.push_fmt(format_args!("extern \"{}\"", id.as_str()));
+        match &function.body {
+            hir::FunctionBody::External(id) => {
+                self.push_fmt(format_args!("extern \"{}\"", id.as_str()))
+            }
+            hir::FunctionBody::Dependency(def_id) => self.push_fmt(format_args!(
+                "/*dependency crate {} {}*/ ",
+                def_id.krate.index(),
+                def_id.local.index()
+            )),
+            hir::FunctionBody::Internal(_) => (),
         }
 
         let owner = function.owner_type.to_lazy();
@@ -206,6 +214,10 @@ impl<'a> HirDisplayer<'a> {
         let prev = self.terminate;
         self.terminate = block.terminator.map(|t| t.get_expression_id());
 
+        if block.is_unsafe {
+            self.push_str(KeyWord::Unsafe.as_str());
+            self.push(' ');
+        }
         self.push_fmt(format_args!("{{ /*{}*/\n", id.index()));
         self.push_scope();
         for node in &block.statements {
@@ -241,6 +253,11 @@ impl<'a> HirDisplayer<'a> {
                 }
             }
             hir::StatementKind::Continue => self.push_str(KeyWord::Continue.as_str()),
+            hir::StatementKind::Defer(expression_id) => {
+                self.push_str(KeyWord::Defer.as_str());
+                self.push(' ');
+                self.display_expression(expression_id);
+            }
             hir::StatementKind::Expression {
                 value,
                 ends_semicolon,
@@ -267,6 +284,32 @@ impl<'a> HirDisplayer<'a> {
             hir::ExpressionKind::Error => self.push_str("<error>"),
             hir::ExpressionKind::Block(block_id) => self.display_block(block_id),
             hir::ExpressionKind::Null => self.push_str("null"),
+            hir::ExpressionKind::Panic(message) => {
+                self.push_str("panic(");
+                if let Some(message) = message {
+                    self.display_expression(message);
+                }
+                self.push(')');
+            }
+            hir::ExpressionKind::Format(pieces) => {
+                self.push_str("f\"");
+                for piece in pieces {
+                    match piece {
+                        hir::FormatPiece::Text(text) => {
+                            self.push_str(&text.replace('{', "{{").replace('}', "}}"))
+                        }
+                        hir::FormatPiece::Argument { value, width } => {
+                            self.push('{');
+                            self.display_expression(value);
+                            if let Some(width) = width {
+                                self.push_str(&format!(":{width}"));
+                            }
+                            self.push('}');
+                        }
+                    }
+                }
+                self.push('"');
+            }
             hir::ExpressionKind::Literal(literal) => self.push_str(&literal.value_to_string()),
             hir::ExpressionKind::Local(local_id) => {
                 self.display_local(*local_id);
@@ -300,11 +343,66 @@ impl<'a> HirDisplayer<'a> {
                 self.push(if *mutable { '&' } else { '@' });
                 self.display_place(place);
             }
+            hir::ExpressionKind::Slice {
+                place,
+                start,
+                end,
+                mutable,
+            } => {
+                self.push(if *mutable { '&' } else { '@' });
+                self.display_place(place);
+                self.push('[');
+                if let Some(start) = start {
+                    self.display_expression(start);
+                }
+                self.push_str("..");
+                if let Some(end) = end {
+                    self.display_expression(end);
+                }
+                self.push(']');
+                self.display_expression_astype(*id, value.ty);
+            }
             hir::ExpressionKind::DeRef(expression_id) => {
                 self.push('*');
                 self.display_expression(expression_id);
                 self.display_expression_astype(*id, value.ty);
             }
+            hir::ExpressionKind::Copy(expression_id) => {
+                self.push_str(KeyWord::Copy.as_str());
+                self.push(' ');
+                self.display_expression(expression_id);
+                self.display_expression_astype(*id, value.ty);
+            }
+            hir::ExpressionKind::New {
+                value: inner,
+                fallible,
+            } => {
+                self.push_str(KeyWord::New.as_str());
+                if *fallible {
+                    self.push('?');
+                }
+                self.push(' ');
+                self.display_expression(inner);
+                self.display_expression_astype(*id, value.ty);
+            }
+            hir::ExpressionKind::NewArray {
+                element,
+                len,
+                fallible,
+            } => {
+                self.push_str(KeyWord::New.as_str());
+                if *fallible {
+                    self.push('?');
+                }
+                self.push_str(":[");
+                self.push_str(KeyWord::For.as_str());
+                self.push(' ');
+                self.display_expression(len);
+                self.push_str(" => ");
+                self.display_expression(element);
+                self.push(']');
+                self.display_expression_astype(*id, value.ty);
+            }
             hir::ExpressionKind::Unary(Unary {
                 operator,
                 expression,
@@ -397,17 +495,6 @@ impl<'a> HirDisplayer<'a> {
                 self.display_expression_astype(*id, *ty);
                 self.push_str("*/");
             }
-            hir::ExpressionKind::ExternalCall {
-                crate_name,
-                function_name,
-                ..
-            } => {
-                self.push_str("/*external ");
-                self.push_str(crate_name);
-                self.push_str(".");
-                self.push_str(function_name);
-                self.push_str("*/");
-            }
             hir::ExpressionKind::EnumVariant {
                 enum_id,
                 variant_name,
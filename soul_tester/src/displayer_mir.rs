@@ -7,7 +7,7 @@ use mir_parser::mir::{
 use run_hir::HirResponse;
 use soul_utils::{
     ids::{FunctionId, IdAlloc},
-    soul_names::{TypeModifier, TypeWrapper},
+    soul_names::{KeyWord, TypeModifier, TypeWrapper},
     span::ModuleId,
     vec_map::VecMapIndex,
 };
@@ -122,10 +122,20 @@ impl<'a> MirDisplayer<'a> {
     fn display_function(&mut self, function_id: FunctionId) {
         let function = &self.mir.functions[function_id];
 
-        if let FunctionBody::External(language) = function.body {
-            self.push_str("extern \"");
-            self.push_str(language.as_str());
-            self.push_str("\" ");
+        match &function.body {
+            FunctionBody::External(language) => {
+                self.push_str("extern \"");
+                self.push_str(language.as_str());
+                self.push_str("\" ");
+            }
+            FunctionBody::Dependency(def_id) => write!(
+                self.sb,
+                "/*dependency crate {} {}*/ ",
+                def_id.krate.index(),
+                def_id.local.index()
+            )
+            .expect("no fmt error"),
+            FunctionBody::Internal { .. } => (),
         }
 
         let owner = function.owner_type;
@@ -152,7 +162,7 @@ impl<'a> MirDisplayer<'a> {
         self.push(' ');
 
         let (entry_block, locals, blocks) = match &function.body {
-            FunctionBody::External(_) => {
+            FunctionBody::External(_) | FunctionBody::Dependency { .. } => {
                 write!(self.sb, "/*{}*/", function_id.index()).expect("no fmt error");
                 return;
             }
@@ -249,6 +259,13 @@ impl<'a> MirDisplayer<'a> {
                 self.push_str("else ");
                 self.display_goto(*arm);
             }
+            mir::Terminator::Panic(message) => {
+                self.push_str("panic(");
+                if let Some(value) = message {
+                    self.display_operand(value);
+                }
+                self.push(')');
+            }
             mir::Terminator::Unreachable => self.push_str("// unreachable"),
         }
         self.push('\n');
@@ -313,6 +330,11 @@ impl<'a> MirDisplayer<'a> {
                 self.display_local_name(*local_id);
                 self.push(')');
             }
+            mir::StatementKind::Free(place) => {
+                self.push_str("Free(");
+                self.display_place(place);
+                self.push(')');
+            }
         }
     }
 
@@ -367,6 +389,67 @@ impl<'a> MirDisplayer<'a> {
                 self.push_str("*/");
             }
             mir::RvalueKind::Operand(operand) => self.display_operand(operand),
+            mir::RvalueKind::New { value, fallible } => {
+                self.push_str(KeyWord::New.as_str());
+                if *fallible {
+                    self.push('?');
+                }
+                self.push(' ');
+                self.display_operand(value);
+            }
+            mir::RvalueKind::NewArray {
+                element,
+                len,
+                fallible,
+            } => {
+                self.push_str(KeyWord::New.as_str());
+                if *fallible {
+                    self.push('?');
+                }
+                self.push_str(":[");
+                self.push_str(KeyWord::For.as_str());
+                self.push(' ');
+                self.display_operand(len);
+                self.push_str(" => ");
+                self.display_operand(element);
+                self.push(']');
+            }
+            mir::RvalueKind::Slice { array, start, end } => {
+                self.display_operand(array);
+                self.push('[');
+                if let Some(start) = start {
+                    self.display_operand(start);
+                }
+                self.push_str("..");
+                if let Some(end) = end {
+                    self.display_operand(end);
+                }
+                self.push(']');
+            }
+            mir::RvalueKind::Format(pieces) => {
+                self.push_str("f\"");
+                for piece in pieces {
+                    match piece {
+                        mir::FormatPiece::Text(text) => {
+                            self.push_str(&text.replace('{', "{{").replace('}', "}}"))
+                        }
+                        mir::FormatPiece::Argument { value, width } => {
+                            self.push('{');
+                            self.display_operand(value);
+                            if let Some(width) = width {
+                                self.push_str(&format!(":{width}"));
+                            }
+                            self.push('}');
+                        }
+                    }
+                }
+                self.push('"');
+            }
+            mir::RvalueKind::Copy(operand) => {
+                self.push_str(KeyWord::Copy.as_str());
+                self.push(' ');
+                self.display_operand(operand);
+            }
             mir::RvalueKind::Binary {
                 left,
                 operator,
@@ -436,6 +519,12 @@ impl<'a> MirDisplayer<'a> {
             } => {
                 self.display_field(base, *field_id);
             }
+            PlaceKind::Index { base, index } => {
+                self.display_place(base);
+                self.push('[');
+                self.display_operand(index);
+                self.push(']');
+            }
             PlaceKind::Temp(temp_id) => {
                 self.display_temp_name(*temp_id);
             }
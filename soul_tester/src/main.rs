@@ -8,16 +8,16 @@ use std::{
 use anyhow::{Error, Result};
 use ast::AbtractSyntaxTree;
 use fern::Dispatch;
-use inkwell::context::Context;
+use inkwell::{context::Context, module::Module};
 use log::{error, info};
 use paths::Paths;
 use run_ast::to_ast;
 use run_hir::{HirResponse, to_hir};
-use run_mir::{MirResponse, extract_exports, to_mir};
+use run_mir::{MirResponse, extract_exports, generate_c_header, to_mir};
 use soul_ir::{IrRequest, to_llvm_ir};
 use soul_tokenizer::to_token_stream;
 use soul_utils::{
-    CrateExports, CrateId, CrateStore, ModuleId, SoulToml,
+    CrateId, CrateStore, ModuleId, SoulToml,
     char_colors::{DEFAULT, GREEN},
     compile_options::{Arch, CompilerOptions, Os, TargetInfo},
     crate_store::CrateContext,
@@ -52,17 +52,12 @@ struct Output {
     hir_response: HirResponse,
 }
 
-fn collect_all_crate_exports(crate_store: &CrateStore) -> CrateExports {
-    let mut all_exports = CrateExports::default();
-    for krate in crate_store.values() {
-        for (name, id) in &krate.exports.functions {
-            all_exports.functions.insert(name.clone(), *id);
-        }
-        for (name, id) in &krate.exports.types {
-            all_exports.types.insert(name.clone(), *id);
-        }
-    }
-    all_exports
+/// A lib crate that passed its frontend, lowered to llvm after the main crate.
+struct LibOutput {
+    id: CrateId,
+    name: String,
+    output: Output,
+    module_store: ModuleStore,
 }
 
 fn main() -> Result<()> {
@@ -71,10 +66,15 @@ fn main() -> Result<()> {
 
     let (manifest, mut crate_store) = paths.load_crates()?;
     let mut benchmarks = Benchmarks::default();
+    let options = COMPILER_OPTIONS.with_profile(paths.profile);
 
-    compile_all_libs(&paths, &mut crate_store, &manifest, &mut benchmarks)?;
-
-    let all_exports = collect_all_crate_exports(&crate_store);
+    let libs = compile_all_libs(
+        &paths,
+        &options,
+        &mut crate_store,
+        &manifest,
+        &mut benchmarks,
+    )?;
 
     let root_lib = &manifest.package.name;
     let source_path = Paths::to_source_path(paths.project_path())?;
@@ -86,26 +86,39 @@ fn main() -> Result<()> {
     let mut output = run_crate_frontend(
         crate_store.main_crate(),
         &paths,
+        &options,
         paths.project_path(),
         source_path,
         &entry_file,
         &mut module_store,
         &crate_store,
         &mut context,
-        &all_exports,
         &mut benchmarks,
     )?;
 
     log_faults(&context.faults, &module_store);
-    if is_fatal(&context.faults, COMPILER_OPTIONS.fatal_level()) {
+    if is_fatal(&context.faults, options.fatal_level()) {
         return Ok(());
     }
 
     info!("{GREEN}frontend success{DEFAULT}",);
 
+    if entry_file.is_lib {
+        write_c_header(&output, paths.project_path(), root_lib)?;
+    }
+
+    let llvm_context = Context::create();
+    let Some(lib_modules) = lower_libs(&libs, &options, &llvm_context, &mut benchmarks) else {
+        return Ok(());
+    };
     if run_llvm(
         &mut output,
+        &options,
+        crate_store.main_crate(),
+        &llvm_context,
+        lib_modules,
         paths.project_path(),
+        &module_store,
         &mut context.faults,
         root_lib,
         &mut benchmarks,
@@ -121,10 +134,12 @@ fn main() -> Result<()> {
 
 fn compile_all_libs(
     paths: &Paths,
+    options: &CompilerOptions,
     crate_store: &mut CrateStore,
     manifest: &SoulToml,
     benchmarks: &mut Benchmarks,
-) -> Result<()> {
+) -> Result<Vec<LibOutput>> {
+    let mut libs = vec![];
     let crate_info_list: Vec<_> = crate_store
         .entries()
         .map(|(id, data)| (id, data.name.clone(), data.project_path.clone()))
@@ -144,41 +159,96 @@ fn compile_all_libs(
         let output = run_crate_frontend(
             crate_id,
             paths,
+            options,
             &project_path,
             source_path,
             &entry_path,
             &mut module_store,
             crate_store,
             &mut context,
-            &CrateExports::default(),
             benchmarks,
         )?;
 
         log_faults(&context.faults, &module_store);
-        if is_fatal(&context.faults, COMPILER_OPTIONS.fatal_level()) {
+        if is_fatal(&context.faults, options.fatal_level()) {
             continue;
         }
 
-        let exports = extract_exports(&output.mir_response);
+        let exports = extract_exports(&output.mir_response, &output.hir_response);
+        write_c_header(&output, &project_path, &lib_name)?;
 
         if let Some(crate_mut) = crate_store.get_mut_by_name(&lib_name) {
             crate_mut.exports = exports;
         }
+
+        libs.push(LibOutput {
+            id: crate_id,
+            name: lib_name,
+            output,
+            module_store,
+        });
     }
 
-    Ok(())
+    Ok(libs)
+}
+
+/// Lowers every lib crate into its own llvm module, these get linked into the main crate.
+/// `None` if any lib failed, the main crate can not be linked without it.
+fn lower_libs<'a>(
+    libs: &'a [LibOutput],
+    options: &CompilerOptions,
+    context: &'a Context,
+    benchmarks: &mut Benchmarks,
+) -> Option<Vec<Module<'a>>> {
+    let mut modules = vec![];
+    for lib in libs {
+        let request = IrRequest::new(
+            &lib.output.mir_response,
+            &lib.output.hir_response.typed,
+            context,
+            &lib.module_store,
+            lib.id,
+            lib.name.clone(),
+        );
+
+        let mut faults = FaultCollector::new(MESSAGE_CONFIG);
+        let timer = Instant::now();
+        let ir = to_llvm_ir(&request, options, &mut faults.faults);
+        benchmarks.ir += timer.elapsed();
+        log_faults(&faults, &lib.module_store);
+
+        if ir.is_fatal {
+            error!("lib '{}' failed to lower to llvm ir", lib.name);
+            modules.push(None);
+            continue;
+        }
+        modules.push(Some(ir.module));
+    }
+
+    linkable(modules)
+}
+
+/// Every lib is lowered so all faults get logged, but one failed lib means nothing gets linked.
+fn linkable<T>(lowered: Vec<Option<T>>) -> Option<Vec<T>> {
+    lowered.into_iter().collect()
+}
+
+/// Writes `output/<lib>.h` declaring the `extern "C"` API of a lib crate for C consumers.
+fn write_c_header(output: &Output, project: &Path, lib_name: &str) -> Result<()> {
+    let header = generate_c_header(&output.mir_response, &output.hir_response, lib_name);
+    Paths::write_to_output(&header, project, Path::new(&format!("{lib_name}.h")))
 }
 
 fn run_crate_frontend(
     crate_id: CrateId,
     paths: &Paths,
+    options: &CompilerOptions,
     manifest: &Path,
     source: PathBuf,
     entry: &EntryFile,
     module_store: &mut ModuleStore,
     crate_store: &CrateStore,
     context: &mut CrateContext,
-    crate_exports: &CrateExports,
     benchmarks: &mut Benchmarks,
 ) -> Result<Output> {
     let timer = Instant::now();
@@ -195,7 +265,7 @@ fn run_crate_frontend(
     let timer = Instant::now();
     let ast = to_ast(
         tokens,
-        &COMPILER_OPTIONS,
+        options,
         module_store,
         context,
         crate_store,
@@ -205,13 +275,13 @@ fn run_crate_frontend(
     display_ast(manifest, module_store, &ast)?;
 
     let timer = Instant::now();
-    let mut hir = to_hir(&ast, &COMPILER_OPTIONS, context, crate_exports, root);
+    let mut hir = to_hir(&ast, options, context, crate_store, root);
     display_hir(manifest, &hir, &ast)?;
     benchmarks.hir(crate_id, timer.elapsed());
     clear_hir_type_map(&mut hir);
 
     let timer = Instant::now();
-    let mir = to_mir(&hir, &ast, &COMPILER_OPTIONS, context, crate_exports, root);
+    let mir = to_mir(&hir, &ast, options, context, crate_store, root);
     benchmarks.mir(crate_id, timer.elapsed());
     display_mir(manifest, &mir, &hir, &ast)?;
 
@@ -221,9 +291,14 @@ fn run_crate_frontend(
     })
 }
 
-fn run_llvm(
-    output: &mut Output,
+fn run_llvm<'a>(
+    output: &'a mut Output,
+    options: &CompilerOptions,
+    crate_id: CrateId,
+    context: &'a Context,
+    lib_modules: Vec<Module<'a>>,
     manifest: &Path,
+    module_store: &'a ModuleStore,
     faults: &mut FaultCollector,
     lib_name: &str,
     benchmarks: &mut Benchmarks,
@@ -231,16 +306,18 @@ fn run_llvm(
     let request = IrRequest {
         mir: &output.mir_response,
         types: &output.hir_response.typed,
-        context: &Context::create(),
+        context,
+        module_store,
+        crate_id,
         crate_name: lib_name.to_string(),
     };
 
     faults.faults.clear();
 
     let timer = Instant::now();
-    let ir = to_llvm_ir(&request, &COMPILER_OPTIONS, &mut faults.faults);
-    benchmarks.ir = timer.elapsed();
-    log_faults(faults, &ModuleStore::new(PathBuf::new()));
+    let ir = to_llvm_ir(&request, options, &mut faults.faults);
+    benchmarks.ir += timer.elapsed();
+    log_faults(faults, module_store);
 
     #[cfg(not(debug_assertions))]
     if ir.is_fatal {
@@ -257,6 +334,13 @@ fn run_llvm(
         return false;
     }
 
+    for lib_module in lib_modules {
+        if let Err(err) = ir.module.link_in_module(lib_module) {
+            error!("{err}");
+            return false;
+        }
+    }
+
     let llvm_code = ir.module.to_string();
 
     if let Err(err) = Paths::write_to_output(&llvm_code, manifest, Path::new("out.ll")) {
@@ -391,3 +475,20 @@ fn init_logger(log_file: &str) -> Result<()> {
 fn is_fatal(faults: &FaultCollector, fatal_level: SementicLevel) -> bool {
     faults.faults.iter().any(|f| f.is_fatal(fatal_level))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::linkable;
+
+    #[test]
+    fn failed_lib_stops_before_linking() {
+        assert_eq!(linkable(vec![Some(1), None, Some(3)]), None);
+        assert_eq!(linkable(vec![None::<u8>]), None);
+    }
+
+    #[test]
+    fn lowered_libs_are_linked_in_order() {
+        assert_eq!(linkable(vec![Some(1), Some(2)]), Some(vec![1, 2]));
+        assert_eq!(linkable(Vec::<Option<u8>>::new()), Some(vec![]));
+    }
+}
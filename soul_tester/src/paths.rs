@@ -3,13 +3,16 @@ use std::fs::File;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
-use soul_utils::{CrateStore, IdAlloc, ModuleId, SoulToml};
+use soul_utils::{CrateStore, IdAlloc, ModuleId, SoulToml, compile_options::BuildProfile};
 
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Paths {
     pub log_file: String,
     pub project: String,
+    /// `"debug"` or `"release"`, debug builds get runtime checks.
+    #[serde(default)]
+    pub profile: BuildProfile,
 }
 
 #[derive(Debug, Clone, serde::Deserialize)]
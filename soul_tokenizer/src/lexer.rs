@@ -1,8 +1,9 @@
 use std::{iter::Peekable, str::Chars};
 
 use soul_utils::{
-    StringLiteral, StringTag,
+    FmtArgument, FmtPiece, StringLiteral, StringTag,
     error::{SoulError, SoulErrorKind, SoulResult},
+    soul_names::{PrimitiveSize, PrimitiveTypes},
     span::{ModuleId, Span},
     symbool_kind::SymbolKind,
 };
@@ -40,6 +41,21 @@ impl<'a> Lexer<'a> {
         lexer
     }
 
+    /// Lexer for `source` that starts at `line` and `offset` of an outer source, used to
+    /// tokenize the values embedded in a format string.
+    pub(crate) fn new_at(source: &'a str, module: ModuleId, line: usize, offset: usize) -> Self {
+        let mut lexer = Lexer {
+            module,
+            line,
+            offset: offset.saturating_sub(1),
+            token_index: 0,
+            current_char: None,
+            input: source.chars().peekable(),
+        };
+        lexer.next_char();
+        lexer
+    }
+
     pub(crate) fn current_token_index(&self) -> usize {
         self.token_index
     }
@@ -82,7 +98,8 @@ impl<'a> Lexer<'a> {
 
         if let Some(symbol) = SymbolKind::from_lexer(self) {
             let kind = if self.is_negative_number(symbol) {
-                TokenKind::Number(self.get_number(start_line, start_offset)?)
+                let (number, suffix) = self.get_number(start_line, start_offset)?;
+                TokenKind::Number(number, suffix)
             } else {
                 self.next_char();
                 TokenKind::Symbol(symbol)
@@ -113,14 +130,17 @@ impl<'a> Lexer<'a> {
 
     /// Advances to the next character, updating line/offset tracking.
     pub(crate) fn next_char(&mut self) {
+        let previous = self.current_char;
         self.current_char = self.input.next();
-        if let Some(char) = self.current_char {
-            if char == '\n' {
+        match self.current_char {
+            Some('\n') => {
                 self.line += 1;
                 self.offset = 0;
-            } else {
-                self.offset += 1;
             }
+            Some(_) => self.offset += 1,
+            // the last token ends after its last char, also when no newline follows it
+            None if previous.is_some_and(|char| char != '\n') => self.offset += 1,
+            None => (),
         }
     }
 
@@ -148,13 +168,23 @@ impl<'a> Lexer<'a> {
             '"' => {
                 TokenKind::StringLiteral(self.get_string_literal(start_line, start_offset, None)?)
             }
+            '\'' if self.is_lifetime_start() => {
+                self.next_char();
+                TokenKind::Lifetime(self.get_ident())
+            }
             '\'' => match self.try_get_char_literal(start_line, start_offset)? {
                 Some(char) => TokenKind::CharLiteral(char),
                 None => TokenKind::Unknown('\''),
             },
+            'r' if self.is_raw_string_start() => TokenKind::StringLiteral(StringLiteral::Normal(
+                self.get_raw_string(start_line, start_offset)?,
+            )),
             ch if is_ident(ch) => TokenKind::Ident(self.get_ident()),
 
-            ch if is_number(ch) => TokenKind::Number(self.get_number(start_line, start_offset)?),
+            ch if is_number(ch) => {
+                let (number, suffix) = self.get_number(start_line, start_offset)?;
+                TokenKind::Number(number, suffix)
+            }
             _ => {
                 self.next_char();
                 TokenKind::Unknown(char)
@@ -203,6 +233,12 @@ impl<'a> Lexer<'a> {
         }
     }
 
+    /// `'a` and `'name` start a lifetime, `'a'` is a char literal.
+    fn is_lifetime_start(&self) -> bool {
+        let mut chars = self.input.clone();
+        chars.next().is_some_and(is_ident) && chars.next() != Some('\'')
+    }
+
     fn try_get_char_literal(
         &mut self,
         start_line: usize,
@@ -211,19 +247,15 @@ impl<'a> Lexer<'a> {
         self.next_char();
 
         let char = if self.current_char == Some('\\') {
+            let (line, offset) = (self.line, self.offset);
             self.next_char();
-            match self.current_char {
-                Some('n') => '\n',
-                Some('r') => '\r',
-                Some('t') => '\t',
-                Some('\'') => '\'',
-                Some('\\') => '\\',
-                Some(other) => other,
+            match self.lex_escape(line, offset, '\'')? {
+                Some(char) => char,
                 None => {
                     return Err(SoulError::new(
-                        "Unclosed char literal escape sequence",
+                        "char literal can not contain a line continuation",
                         SoulErrorKind::InvalidEscapeSequence,
-                        Some(self.new_span(start_line, start_offset)),
+                        Some(self.new_span(line, offset)),
                     ));
                 }
             }
@@ -252,6 +284,12 @@ impl<'a> Lexer<'a> {
         start_offset: usize,
         possible_tag: Option<StringTag>,
     ) -> SoulResult<StringLiteral> {
+        if possible_tag == Some(StringTag::Fmt) {
+            return self
+                .get_fmt_pieces(start_line, start_offset)
+                .map(StringLiteral::Fmt);
+        }
+
         let string = self.get_string(start_line, start_offset)?;
         let tag = match possible_tag {
             Some(val) => val,
@@ -260,28 +298,181 @@ impl<'a> Lexer<'a> {
 
         Ok(match tag {
             StringTag::CStr => StringLiteral::CStr(string),
+            StringTag::Fmt => unreachable!("format strings are split into pieces above"),
+        })
+    }
+
+    /// Splits `f"text {value:width} text"` into text and argument pieces, `{{` and `}}` are
+    /// literal braces.
+    fn get_fmt_pieces(
+        &mut self,
+        start_line: usize,
+        start_offset: usize,
+    ) -> SoulResult<Vec<FmtPiece>> {
+        let mut pieces = vec![];
+        let mut text = String::new();
+
+        self.next_char();
+        while let Some(ch) = self.current_char {
+            if ch == '\\' {
+                let (line, offset) = (self.line, self.offset);
+                self.next_char();
+                text.extend(self.lex_escape(line, offset, '"')?);
+            } else if ch == '"' {
+                self.next_char();
+                if !text.is_empty() {
+                    pieces.push(FmtPiece::Text(text));
+                }
+                return Ok(pieces);
+            } else if ch == '{' && self.peek_char() == Some('{') {
+                self.next_char();
+                text.push('{');
+            } else if ch == '}' && self.peek_char() == Some('}') {
+                self.next_char();
+                text.push('}');
+            } else if ch == '{' {
+                if !text.is_empty() {
+                    pieces.push(FmtPiece::Text(std::mem::take(&mut text)));
+                }
+                pieces.push(FmtPiece::Argument(
+                    self.get_fmt_argument(start_line, start_offset)?,
+                ));
+                continue;
+            } else if ch == '}' {
+                return Err(SoulError::new(
+                    "unmatched '}' in format string, use '}}' for a literal '}'",
+                    SoulErrorKind::UnexpectedCharacter,
+                    Some(self.new_span(self.line, self.offset)),
+                ));
+            } else {
+                text.push(ch);
+            }
+            self.next_char();
+        }
+
+        Err(SoulError::new(
+            "StringLiteral does not have an end qoute",
+            SoulErrorKind::InvalidEscapeSequence,
+            Some(self.new_span(start_line, start_offset)),
+        ))
+    }
+
+    /// Lexes `{value}` or `{value:width}` starting at `{`, stops after the closing `}`.
+    fn get_fmt_argument(
+        &mut self,
+        start_line: usize,
+        start_offset: usize,
+    ) -> SoulResult<FmtArgument> {
+        self.next_char();
+        let (line, offset) = (self.line, self.offset);
+
+        let mut source = String::new();
+        let mut depth = 0usize;
+        let mut quote = None;
+        let mut backslash = false;
+        let mut end_span = None;
+        while let Some(ch) = self.current_char {
+            if let Some(open) = quote {
+                if backslash {
+                    backslash = false;
+                } else if ch == '\\' {
+                    backslash = true;
+                } else if ch == open {
+                    quote = None;
+                }
+            } else {
+                match ch {
+                    '"' | '\'' => quote = Some(ch),
+                    '(' | '[' | '{' => depth += 1,
+                    ')' | ']' if depth > 0 => depth -= 1,
+                    '}' | ':' if depth == 0 => {
+                        end_span = Some(Span {
+                            module: self.module,
+                            start_line: line,
+                            start_offset: offset,
+                            end_line: self.line,
+                            end_offset: self.offset,
+                        });
+                        break;
+                    }
+                    '}' => depth -= 1,
+                    _ => (),
+                }
+            }
+            source.push(ch);
+            self.next_char();
+        }
+
+        let Some(span) = end_span else {
+            return Err(SoulError::new(
+                "format string argument does not have a closing '}'",
+                SoulErrorKind::InvalidEscapeSequence,
+                Some(self.new_span(start_line, start_offset)),
+            ));
+        };
+
+        if source.trim().is_empty() {
+            return Err(SoulError::new(
+                "format string argument is empty, use '{{' for a literal '{'",
+                SoulErrorKind::UnexpectedCharacter,
+                Some(span),
+            ));
+        }
+
+        let width = if self.current_char == Some(':') {
+            self.next_char();
+            Some(self.get_fmt_width()?)
+        } else {
+            None
+        };
+
+        // skip the closing '}'
+        self.next_char();
+        Ok(FmtArgument {
+            source,
+            span,
+            width,
+        })
+    }
+
+    fn get_fmt_width(&mut self) -> SoulResult<usize> {
+        let (line, offset) = (self.line, self.offset);
+
+        let mut spec = String::new();
+        while let Some(ch) = self.current_char {
+            if ch == '}' || ch == '"' {
+                break;
+            }
+            spec.push(ch);
+            self.next_char();
+        }
+
+        if self.current_char != Some('}') {
+            return Err(SoulError::new(
+                "format string argument does not have a closing '}'",
+                SoulErrorKind::InvalidEscapeSequence,
+                Some(self.new_span(line, offset)),
+            ));
+        }
+
+        spec.trim().parse::<usize>().map_err(|_| {
+            SoulError::new(
+                format!("format spec '{spec}' should be a width e.g. '{{value:8}}'"),
+                SoulErrorKind::InvalidNumber,
+                Some(self.new_span(line, offset)),
+            )
         })
     }
 
     fn get_string(&mut self, start_line: usize, start_offset: usize) -> SoulResult<String> {
         let mut cstring = String::new();
-        let mut backslash = false;
 
         self.next_char();
         while let Some(ch) = self.current_char {
-            if backslash {
-                let escaped_char = match ch {
-                    'n' => '\n',
-                    'r' => '\r',
-                    't' => '\t',
-                    '\\' => '\\',
-                    '"' => '"',
-                    other => other,
-                };
-                cstring.push(escaped_char);
-                backslash = false;
-            } else if ch == '\\' {
-                backslash = true;
+            if ch == '\\' {
+                let (line, offset) = (self.line, self.offset);
+                self.next_char();
+                cstring.extend(self.lex_escape(line, offset, '"')?);
             } else if ch == '"' {
                 self.next_char();
                 return Ok(cstring);
@@ -298,61 +489,362 @@ impl<'a> Lexer<'a> {
         ))
     }
 
-    fn get_number(&mut self, start_line: usize, start_offset: usize) -> SoulResult<Number> {
+    /// Lexes the escape sequence after a `\` at `line`/`offset`, starts on the char after the
+    /// backslash and stops on the last char of the sequence. Gives `None` for a line
+    /// continuation, a `\` at the end of a line skips the newline and leading whitespace.
+    /// `close` is the quote that ends the literal the escape is in.
+    fn lex_escape(&mut self, line: usize, offset: usize, close: char) -> SoulResult<Option<char>> {
+        let escape_error = |lexer: &mut Self, message: String| {
+            Err(lexer.escape_error(message, line, offset, close))
+        };
+
+        let char = match self.current_char {
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('0') => '\0',
+            Some(ch @ ('\\' | '"' | '\'')) => ch,
+            Some('\n' | '\r') => {
+                while matches!(self.peek_char(), Some(' ' | '\t' | '\n' | '\r')) {
+                    self.next_char();
+                }
+                return Ok(None);
+            }
+            Some('x') => {
+                let mut value = 0;
+                for _ in 0..2 {
+                    match self.peek_char().and_then(|ch| ch.to_digit(16)) {
+                        Some(digit) => value = value * 16 + digit,
+                        None => {
+                            self.next_char();
+                            return escape_error(
+                                self,
+                                "'\\x' escape needs two hex digits e.g. '\\x7F'".to_string(),
+                            );
+                        }
+                    }
+                    self.next_char();
+                }
+                if value > 0x7F {
+                    return escape_error(
+                        self,
+                        format!("'\\x{value:02X}' is not ascii, use '\\u{{{value:X}}}' instead"),
+                    );
+                }
+                value as u8 as char
+            }
+            Some('u') => self.lex_unicode_escape(line, offset, close)?,
+            Some(other) => {
+                return escape_error(self, format!("unknown escape sequence '\\{other}'"));
+            }
+            None => return escape_error(self, "unclosed escape sequence".to_string()),
+        };
+        Ok(Some(char))
+    }
+
+    /// Lexes the `{1F600}` of `\u{1F600}`, starts on the `u` and stops on the `}`.
+    fn lex_unicode_escape(&mut self, line: usize, offset: usize, close: char) -> SoulResult<char> {
+        let unicode_error = |lexer: &mut Self, message: &str| {
+            lexer.escape_error(message.to_string(), line, offset, close)
+        };
+
+        self.next_char();
+        if self.current_char != Some('{') {
+            return Err(unicode_error(
+                self,
+                "'\\u' escape needs braces e.g. '\\u{1F600}'",
+            ));
+        }
+
+        let mut digits = String::new();
+        loop {
+            self.next_char();
+            match self.current_char {
+                Some('}') => break,
+                Some(ch) if ch.is_ascii_hexdigit() && digits.len() < 6 => digits.push(ch),
+                _ => {
+                    return Err(unicode_error(
+                        self,
+                        "'\\u{..}' escape needs 1 to 6 hex digits and a closing '}'",
+                    ));
+                }
+            }
+        }
+
+        let char = u32::from_str_radix(&digits, 16)
+            .ok()
+            .and_then(char::from_u32);
+        match char {
+            Some(char) => Ok(char),
+            None => Err(unicode_error(
+                self,
+                "'\\u{..}' escape is not a valid unicode scalar value",
+            )),
+        }
+    }
+
+    /// Error for the escape sequence at `line`/`offset` that ends on the current char. Skips
+    /// the rest of the literal up to and past its `close` quote, or to the end of the line,
+    /// so lexing resumes after the literal and reports the bad escape only once.
+    fn escape_error(
+        &mut self,
+        message: String,
+        line: usize,
+        offset: usize,
+        close: char,
+    ) -> SoulError {
+        if self
+            .current_char
+            .is_some_and(|ch| ch != close && ch != '\n')
+        {
+            self.next_char();
+        }
+        let span = self.new_span(line, offset);
+
+        while let Some(ch) = self.current_char {
+            if ch == '\n' {
+                break;
+            }
+            if ch == '\\' {
+                self.next_char();
+            }
+            self.next_char();
+            if ch == close {
+                break;
+            }
+        }
+
+        SoulError::new(message, SoulErrorKind::InvalidEscapeSequence, Some(span))
+    }
+
+    /// Whether the `r` at the current char starts `r"..."` or `r#"..."#`.
+    fn is_raw_string_start(&self) -> bool {
+        let mut chars = self.input.clone().skip_while(|ch| *ch == '#');
+        chars.next() == Some('"')
+    }
+
+    /// Lexes `r"..."` or `r#"..."#` without escapes, the string ends at a `"` followed by as
+    /// many `#` as it started with.
+    fn get_raw_string(&mut self, start_line: usize, start_offset: usize) -> SoulResult<String> {
+        let mut hashes = 0;
+        self.next_char();
+        while self.current_char == Some('#') {
+            hashes += 1;
+            self.next_char();
+        }
+
+        let mut text = String::new();
+        self.next_char();
+        while let Some(ch) = self.current_char {
+            if ch == '"'
+                && self
+                    .input
+                    .clone()
+                    .take(hashes)
+                    .filter(|ch| *ch == '#')
+                    .count()
+                    == hashes
+            {
+                for _ in 0..=hashes {
+                    self.next_char();
+                }
+                return Ok(text);
+            }
+            text.push(ch);
+            self.next_char();
+        }
+
+        Err(SoulError::new(
+            format!(
+                "raw string does not have an end quote '\"{}'",
+                "#".repeat(hashes)
+            ),
+            SoulErrorKind::InvalidEscapeSequence,
+            Some(self.new_span(start_line, start_offset)),
+        ))
+    }
+
+    /// Lexes `42`, `-1.5e3`, `0xFF`, `0b1010`, `0o755` or `1_000_000` with an optional type
+    /// suffix e.g. `10u8`, `1.5f32`.
+    fn get_number(
+        &mut self,
+        start_line: usize,
+        start_offset: usize,
+    ) -> SoulResult<(Number, Option<PrimitiveTypes>)> {
         let mut num_str = String::new();
         let mut is_float = false;
         let mut has_minus = false;
 
         if self.current_char == Some('-') {
             has_minus = true;
-            num_str.push('-');
             self.next_char();
         }
 
+        let radix = self.lex_radix_prefix();
+        self.lex_digits(radix, &mut num_str);
+
+        if radix != 10 {
+            self.check_radix_digits(radix, &num_str, start_line, start_offset)?;
+        } else {
+            if self.current_char == Some('.') && self.peek_char() != Some('.') {
+                is_float = self.lex_float(&mut num_str)
+            }
+
+            if let Some(ch) = self.current_char
+                && (ch == 'e' || ch == 'E')
+            {
+                is_float = true;
+                self.lex_exponextion_number(ch, &mut num_str, start_line, start_offset)?;
+            }
+        }
+
+        let suffix = self.lex_number_suffix()?;
+        let number_error = |lexer: &Self, message: String| {
+            SoulError::new(
+                message,
+                SoulErrorKind::InvalidNumber,
+                Some(lexer.new_span(start_line, start_offset)),
+            )
+        };
+
+        let suffix_type = suffix.map(|(ty, _)| ty);
+        if let Some((suffix, span)) = suffix {
+            if is_float && !suffix.is_float() {
+                return Err(SoulError::new(
+                    format!("float literal can not have suffix '{}'", suffix.as_str()),
+                    SoulErrorKind::InvalidNumber,
+                    Some(span),
+                ));
+            }
+            if radix != 10 && suffix.is_float() {
+                return Err(SoulError::new(
+                    format!(
+                        "{} literal can not have suffix '{}'",
+                        radix_name(radix),
+                        suffix.as_str()
+                    ),
+                    SoulErrorKind::InvalidNumber,
+                    Some(span),
+                ));
+            }
+            is_float |= suffix.is_float();
+        }
+
+        if is_float {
+            if has_minus {
+                num_str.insert(0, '-');
+            }
+            return num_str
+                .parse::<f64>()
+                .map(|float| (Number::Float(float), suffix_type))
+                .map_err(|err| number_error(self, err.to_string()));
+        }
+
+        let magnitude = u128::from_str_radix(&num_str, radix)
+            .map_err(|err| number_error(self, err.to_string()))?;
+        let number = if has_minus {
+            match 0i128.checked_sub_unsigned(magnitude) {
+                Some(value) => Number::Int(value),
+                None => {
+                    return Err(number_error(
+                        self,
+                        "number too small to fit in target type".to_string(),
+                    ));
+                }
+            }
+        } else {
+            Number::Uint(magnitude)
+        };
+
+        if let Some(suffix) = suffix_type
+            && !fits_integer_suffix(number, suffix)
+        {
+            return Err(number_error(
+                self,
+                format!("literal is out of range for '{}'", suffix.as_str()),
+            ));
+        }
+
+        Ok((number, suffix_type))
+    }
+
+    /// Consumes `0x`, `0b` or `0o` and returns the radix of the digits that follow.
+    fn lex_radix_prefix(&mut self) -> u32 {
+        if self.current_char != Some('0') {
+            return 10;
+        }
+
+        let radix = match self.peek_char() {
+            Some('x') => 16,
+            Some('b') => 2,
+            Some('o') => 8,
+            _ => return 10,
+        };
+        self.next_char();
+        self.next_char();
+        radix
+    }
+
+    /// Pushes the digits of `radix` into `num_str`, skipping `_` separators.
+    fn lex_digits(&mut self, radix: u32, num_str: &mut String) {
         while let Some(ch) = self.current_char {
-            if ch.is_ascii_digit() {
+            if ch.is_digit(radix) {
                 num_str.push(ch);
-                self.next_char();
-            } else {
+            } else if ch != '_' {
                 break;
             }
+            self.next_char();
         }
+    }
 
-        if self.current_char == Some('.') && self.peek_char() != Some('.') {
-            is_float = self.lex_float(&mut num_str)
-        }
-
+    /// Errors when a prefixed literal has no digits or stops at a decimal digit outside its
+    /// radix e.g. the `2` in `0b102`.
+    fn check_radix_digits(
+        &mut self,
+        radix: u32,
+        num_str: &str,
+        start_line: usize,
+        start_offset: usize,
+    ) -> SoulResult<()> {
         if let Some(ch) = self.current_char
-            && (ch == 'e' || ch == 'E')
+            && ch.is_ascii_digit()
         {
-            is_float = true;
-            self.lex_exponextion_number(ch, &mut num_str, start_line, start_offset)?;
+            let (line, offset) = (self.line, self.offset);
+            self.next_char();
+            return Err(SoulError::new(
+                format!("invalid digit '{ch}' in {} literal", radix_name(radix)),
+                SoulErrorKind::InvalidNumber,
+                Some(self.new_span(line, offset)),
+            ));
         }
 
-        if is_float {
-            num_str.parse::<f64>().map(Number::Float).map_err(|err| {
-                SoulError::new(
-                    err.to_string(),
-                    SoulErrorKind::InvalidNumber,
-                    Some(self.new_span(start_line, start_offset)),
-                )
-            })
-        } else if has_minus {
-            num_str.parse::<i64>().map(Number::Int).map_err(|err| {
-                SoulError::new(
-                    err.to_string(),
-                    SoulErrorKind::InvalidNumber,
-                    Some(self.new_span(start_line, start_offset)),
-                )
-            })
-        } else {
-            num_str.parse::<u64>().map(Number::Uint).map_err(|err| {
-                SoulError::new(
-                    err.to_string(),
-                    SoulErrorKind::InvalidNumber,
-                    Some(self.new_span(start_line, start_offset)),
-                )
-            })
+        if num_str.is_empty() {
+            return Err(SoulError::new(
+                format!("{} literal has no digits", radix_name(radix)),
+                SoulErrorKind::InvalidNumber,
+                Some(self.new_span(start_line, start_offset)),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Lexes the type suffix directly after a number e.g. the `u8` of `10u8`.
+    fn lex_number_suffix(&mut self) -> SoulResult<Option<(PrimitiveTypes, Span)>> {
+        match self.current_char {
+            Some(ch) if is_ident(ch) => (),
+            _ => return Ok(None),
+        }
+
+        let (line, offset) = (self.line, self.offset);
+        let suffix = self.get_ident();
+        let span = self.new_span(line, offset);
+        match PrimitiveTypes::try_from_str(&suffix) {
+            Some(ty) if ty.is_numeric() && !ty.is_untyped_numeric() => Ok(Some((ty, span))),
+            _ => Err(SoulError::new(
+                format!("invalid suffix '{suffix}' for number literal"),
+                SoulErrorKind::InvalidNumber,
+                Some(span),
+            )),
         }
     }
 
@@ -398,16 +890,7 @@ impl<'a> Lexer<'a> {
     fn lex_float(&mut self, num_str: &mut String) -> bool {
         num_str.push('.');
         self.next_char();
-
-        while let Some(ch) = self.current_char {
-            if ch.is_ascii_digit() {
-                num_str.push(ch);
-                self.next_char();
-            } else {
-                break;
-            }
-        }
-
+        self.lex_digits(10, num_str);
         true
     }
 
@@ -450,3 +933,37 @@ fn is_ident(ch: char) -> bool {
 fn is_number(ch: char) -> bool {
     ch.is_ascii_digit()
 }
+
+fn radix_name(radix: u32) -> &'static str {
+    match radix {
+        2 => "binary",
+        8 => "octal",
+        16 => "hexadecimal",
+        _ => "decimal",
+    }
+}
+
+/// Whether an integer literal fits its suffix, platform sized suffixes (`int`, `c_int`, ..)
+/// are checked once the target is known.
+fn fits_integer_suffix(number: Number, suffix: PrimitiveTypes) -> bool {
+    let bits = match suffix.to_primitive_size() {
+        PrimitiveSize::Bit8 => 8,
+        PrimitiveSize::Bit16 => 16,
+        PrimitiveSize::Bit32 => 32,
+        PrimitiveSize::Bit64 => 64,
+        PrimitiveSize::Bit128 => 128,
+        PrimitiveSize::CharSize | PrimitiveSize::IntAndPtrSize | PrimitiveSize::CIntSize => {
+            return !(suffix.is_unsigned_interger() && matches!(number, Number::Int(_)));
+        }
+    };
+
+    match number {
+        Number::Float(_) => true,
+        Number::Int(_) if suffix.is_unsigned_interger() => false,
+        Number::Int(value) => bits >= 128 || value >= -(1i128 << (bits - 1)),
+        Number::Uint(value) if suffix.is_unsigned_interger() => {
+            bits >= 128 || value < (1u128 << bits)
+        }
+        Number::Uint(value) => value < (1u128 << (bits - 1)),
+    }
+}
@@ -16,6 +16,7 @@ impl FromLexer for SymbolKind {
         match current {
             '@' => Some(SymbolKind::ConstRef),
             '$' => Some(SymbolKind::Money),
+            '#' => Some(SymbolKind::Hash),
             '+' => {
                 let peek = lexer.peek_char();
                 if peek == Some('=') {
@@ -167,7 +168,12 @@ impl FromLexer for SymbolKind {
                 let peek = lexer.peek_char();
                 if peek == Some('.') {
                     lexer.next_char();
-                    Some(SymbolKind::DoubleDot)
+                    if lexer.peek_char() == Some('.') {
+                        lexer.next_char();
+                        Some(SymbolKind::Ellipsis)
+                    } else {
+                        Some(SymbolKind::DoubleDot)
+                    }
                 } else {
                     Some(SymbolKind::Dot)
                 }
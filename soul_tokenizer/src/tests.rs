@@ -1,4 +1,7 @@
-use soul_utils::{IdAlloc, ModuleId, symbool_kind::SymbolKind};
+use soul_utils::{
+    FmtPiece, IdAlloc, ModuleId, StringLiteral, soul_names::PrimitiveTypes,
+    symbool_kind::SymbolKind,
+};
 
 use crate::{TokenKind, lexer::Lexer, token::Number};
 
@@ -44,7 +47,7 @@ fn lex_positive_integer_number() {
     let tokens = lexer_to_vec("123");
 
     assert_eq!(tokens.len(), 1);
-    assert_eq!(tokens[0], TokenKind::Number(Number::Uint(123)));
+    assert_eq!(tokens[0], TokenKind::Number(Number::Uint(123), None));
 }
 
 #[test]
@@ -52,7 +55,70 @@ fn lex_float_number() {
     let tokens = lexer_to_vec("12.34");
 
     assert_eq!(tokens.len(), 1);
-    assert_eq!(tokens[0], TokenKind::Number(Number::Float(12.34)));
+    assert_eq!(tokens[0], TokenKind::Number(Number::Float(12.34), None));
+}
+
+#[test]
+fn lex_radix_prefixed_numbers() {
+    let tokens = lexer_to_vec("0xFF 0b1010 0o755 -0x10");
+
+    let expected = vec![
+        TokenKind::Number(Number::Uint(0xFF), None),
+        TokenKind::Number(Number::Uint(0b1010), None),
+        TokenKind::Number(Number::Uint(0o755), None),
+        TokenKind::Number(Number::Int(-0x10), None),
+    ];
+
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn lex_number_separators_and_suffixes() {
+    let tokens = lexer_to_vec("1_000_000 10u8 0xFF_FFi64 1.5f32 3f64");
+
+    let expected = vec![
+        TokenKind::Number(Number::Uint(1_000_000), None),
+        TokenKind::Number(Number::Uint(10), Some(PrimitiveTypes::Uint8)),
+        TokenKind::Number(Number::Uint(0xFFFF), Some(PrimitiveTypes::Int64)),
+        TokenKind::Number(Number::Float(1.5), Some(PrimitiveTypes::Float32)),
+        TokenKind::Number(Number::Float(3.0), Some(PrimitiveTypes::Float64)),
+    ];
+
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn lex_128_bit_numbers() {
+    let tokens = lexer_to_vec(
+        "340282366920938463463374607431768211455 -170141183460469231731687303715884105728i128",
+    );
+
+    let expected = vec![
+        TokenKind::Number(Number::Uint(u128::MAX), None),
+        TokenKind::Number(Number::Int(i128::MIN), Some(PrimitiveTypes::Int128)),
+    ];
+    assert_eq!(tokens, expected);
+
+    let mut lexer = Lexer::new("340282366920938463463374607431768211456", module_id());
+    assert!(lexer.next_token().is_err());
+}
+
+#[test]
+fn lex_invalid_numbers_error_with_span() {
+    let mut lexer = Lexer::new("0b102", module_id());
+    let err = lexer.next_token().unwrap_err();
+    let span = err.span.expect("error should have a span");
+    assert_eq!((span.start_offset, span.end_offset), (5, 6));
+
+    let mut lexer = Lexer::new("x := 256u8", module_id());
+    let err = (0..3).find_map(|_| lexer.next_token().err()).unwrap();
+    let span = err.span.expect("error should have a span");
+    assert_eq!((span.start_offset, span.end_offset), (6, 11));
+
+    for input in ["0x", "10abc", "-1u32", "1.5i32", "128i8"] {
+        let mut lexer = Lexer::new(input, module_id());
+        assert!(lexer.next_token().is_err(), "'{input}' should not lex");
+    }
 }
 
 #[test]
@@ -62,7 +128,7 @@ fn lex_identifier_and_number() {
     let expected = vec![
         TokenKind::Ident("x".to_string()),
         TokenKind::Symbol(SymbolKind::Assign),
-        TokenKind::Number(Number::Uint(42)),
+        TokenKind::Number(Number::Uint(42), None),
     ];
 
     assert_eq!(tokens, expected);
@@ -89,6 +155,28 @@ fn lex_symbols() {
     assert_eq!(tokens, expected);
 }
 
+#[test]
+fn lex_attribute() {
+    let tokens = lexer_to_vec("#[repr(C)]");
+
+    assert_eq!(tokens[0], TokenKind::Symbol(SymbolKind::Hash));
+    assert_eq!(tokens[1], TokenKind::Symbol(SymbolKind::SquareOpen));
+    assert!(matches!(tokens[2], TokenKind::Ident(ref s) if s == "repr"));
+    assert_eq!(tokens[3], TokenKind::Symbol(SymbolKind::RoundOpen));
+    assert!(matches!(tokens[4], TokenKind::Ident(ref s) if s == "C"));
+    assert_eq!(tokens[5], TokenKind::Symbol(SymbolKind::RoundClose));
+    assert_eq!(tokens[6], TokenKind::Symbol(SymbolKind::SquareClose));
+}
+
+#[test]
+fn lex_ellipsis() {
+    let tokens = lexer_to_vec("(fmt: c_str, ...) 0..3");
+
+    assert_eq!(tokens[5], TokenKind::Symbol(SymbolKind::Ellipsis));
+    assert_eq!(tokens[6], TokenKind::Symbol(SymbolKind::RoundClose));
+    assert_eq!(tokens[8], TokenKind::Symbol(SymbolKind::DoubleDot));
+}
+
 #[test]
 fn skip_line_comments() {
     let tokens = lexer_to_vec(
@@ -119,3 +207,175 @@ fn span_tracking_advances_lines() {
     assert_eq!(foo.span.start_line, 1);
     assert_eq!(bar.span.start_line, 2);
 }
+
+#[test]
+fn span_of_last_token_ends_after_it() {
+    let mut lexer = Lexer::new("foo bar", module_id());
+
+    let _ = lexer.next_token().unwrap();
+    let bar = lexer.next_token().unwrap();
+
+    assert_eq!(bar.span.start_offset, 5);
+    assert_eq!(bar.span.end_offset, 8);
+}
+
+fn lex_fmt_pieces(input: &str) -> Vec<FmtPiece> {
+    let mut lexer = Lexer::new(input, module_id());
+    match lexer.next_token().expect("lexer error").kind {
+        TokenKind::StringLiteral(StringLiteral::Fmt(pieces)) => pieces,
+        other => panic!("expected format string got {other:?}"),
+    }
+}
+
+#[test]
+fn lex_fmt_string_pieces() {
+    let pieces = lex_fmt_pieces(r#"f"index: {this.index:4} of {{len}}""#);
+
+    assert_eq!(pieces.len(), 3);
+    assert_eq!(pieces[0], FmtPiece::Text("index: ".to_string()));
+    let FmtPiece::Argument(argument) = &pieces[1] else {
+        panic!("expected argument got {:?}", pieces[1]);
+    };
+    assert_eq!(argument.source, "this.index");
+    assert_eq!(argument.width, Some(4));
+    assert_eq!(argument.span.start_offset, 11);
+    assert_eq!(pieces[2], FmtPiece::Text(" of {len}".to_string()));
+}
+
+#[test]
+fn lex_fmt_string_nested_braces_and_quotes() {
+    let pieces = lex_fmt_pieces(r#"f"{Point{x: 1}.Len()}{Name("}")}""#);
+
+    assert_eq!(pieces.len(), 2);
+    assert!(matches!(&pieces[0], FmtPiece::Argument(arg) if arg.source == "Point{x: 1}.Len()"));
+    assert!(matches!(&pieces[1], FmtPiece::Argument(arg) if arg.source == r#"Name("}")"#));
+}
+
+#[test]
+fn lex_fmt_string_unmatched_brace_errors() {
+    let mut lexer = Lexer::new(r#"f"oops }""#, module_id());
+    assert!(lexer.next_token().is_err());
+
+    let mut lexer = Lexer::new(r#"f"{}""#, module_id());
+    assert!(lexer.next_token().is_err());
+}
+
+fn lex_string(input: &str) -> String {
+    let mut lexer = Lexer::new(input, module_id());
+    match lexer.next_token().expect("lexer error").kind {
+        TokenKind::StringLiteral(StringLiteral::Normal(text)) => text,
+        other => panic!("expected string got {other:?}"),
+    }
+}
+
+#[test]
+fn lex_string_escape_sequences() {
+    assert_eq!(
+        lex_string(r#""a\0b\r\n\t\"\'\\\x41\u{E9}\u{1F600}""#),
+        "a\0b\r\n\t\"'\\A\u{E9}\u{1F600}"
+    );
+    assert_eq!(lex_string("\"one \\\n    two\""), "one two");
+
+    let tokens = lexer_to_vec(r"'\x7F' '\u{41}' '\''");
+    assert_eq!(
+        tokens,
+        vec![
+            TokenKind::CharLiteral('\x7F'),
+            TokenKind::CharLiteral('A'),
+            TokenKind::CharLiteral('\''),
+        ]
+    );
+}
+
+#[test]
+fn lex_lifetimes() {
+    let tokens = lexer_to_vec("@'a int &'name T 'a' 'b");
+
+    assert_eq!(
+        tokens,
+        vec![
+            TokenKind::Symbol(SymbolKind::ConstRef),
+            TokenKind::Lifetime("a".to_string()),
+            TokenKind::Ident("int".to_string()),
+            TokenKind::Symbol(SymbolKind::And),
+            TokenKind::Lifetime("name".to_string()),
+            TokenKind::Ident("T".to_string()),
+            TokenKind::CharLiteral('a'),
+            TokenKind::Lifetime("b".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn lex_invalid_escape_errors_at_column() {
+    let mut lexer = Lexer::new(r#"x := "ab\qc""#, module_id());
+    let err = (0..3).find_map(|_| lexer.next_token().err()).unwrap();
+    let span = err.span.expect("error should have a span");
+    assert_eq!((span.start_offset, span.end_offset), (9, 11));
+
+    for input in [
+        r#""\x80""#,
+        r#""\x4""#,
+        r#""\u41""#,
+        r#""\u{D800}""#,
+        r#""\u{1234567}""#,
+    ] {
+        let mut lexer = Lexer::new(input, module_id());
+        assert!(lexer.next_token().is_err(), "{input} should not lex");
+    }
+}
+
+#[test]
+fn lex_continues_after_invalid_escape() {
+    for input in [
+        r#"x := "bad \q here" y"#,
+        r#"x := "bad \x" y"#,
+        r#"x := "bad \u{41" y"#,
+        r#"x := f"bad \q {here}" y"#,
+        r#"x := '\q' y"#,
+    ] {
+        let mut lexer = Lexer::new(input, module_id());
+        let mut errors = 0;
+        let mut tokens = vec![];
+        loop {
+            match lexer.next_token() {
+                Ok(token) if matches!(token.kind, TokenKind::EndFile) => break,
+                Ok(token) => tokens.push(token.kind),
+                Err(_) => errors += 1,
+            }
+        }
+
+        assert_eq!(errors, 1, "{input} should report the escape once");
+        assert_eq!(
+            tokens.last(),
+            Some(&TokenKind::Ident("y".to_string())),
+            "{input} should lex on after the literal"
+        );
+    }
+}
+
+#[test]
+fn lex_raw_strings() {
+    assert_eq!(lex_string(r#"r"C:\path\n""#), r"C:\path\n");
+    assert_eq!(lex_string(r###"r#"say "hi""#"###), r#"say "hi""#);
+
+    let tokens = lexer_to_vec("r x");
+    assert!(matches!(tokens[0], TokenKind::Ident(ref s) if s == "r"));
+
+    let mut lexer = Lexer::new(r##"r#"open"##, module_id());
+    assert!(lexer.next_token().is_err());
+}
+
+#[test]
+fn multi_line_string_span_ends_on_last_line() {
+    let mut lexer = Lexer::new("\"first\nsecond\" x", module_id());
+
+    let string = lexer.next_token().unwrap();
+    assert_eq!(
+        string.kind,
+        TokenKind::StringLiteral(StringLiteral::Normal("first\nsecond".into()))
+    );
+    assert_eq!(string.span.start_line, 1);
+    assert_eq!(string.span.end_line, 2);
+    assert_eq!(string.span.end_offset, 8);
+}
@@ -22,10 +22,12 @@ pub enum TokenKind {
     Ident(String),
     /// Single unrecognized character.
     Unknown(char),
-    /// Numeric literal of any supported kind.
-    Number(Number),
+    /// Numeric literal of any supported kind, with its type suffix e.g. `10u8`.
+    Number(Number, Option<PrimitiveTypes>),
     /// Character literal, e.g. `'a'`.
     CharLiteral(char),
+    /// Lifetime of a reference, e.g. `'a` in `@'a int`.
+    Lifetime(String),
     /// Symbol/operator token with associated kind.
     Symbol(SymbolKind),
     /// String literal, e.g. `"hello"`.
@@ -47,9 +49,9 @@ impl TokenKind {
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Number {
     /// Signed integer literal, e.g. `-42`.
-    Int(i64),
+    Int(i128),
     /// Unsigned integer literal, e.g. `42u32`.
-    Uint(u64),
+    Uint(u128),
     /// Floating point literal, e.g. `3.14`.
     Float(f64),
 }
@@ -74,12 +76,13 @@ impl TokenKind {
     pub fn inner_display(&self, sb: &mut String) -> Result<usize> {
         let old = sb.len();
         match self {
-            TokenKind::Number(number) => _ = number.inner_display(sb)?,
+            TokenKind::Number(number, suffix) => _ = number.inner_display(*suffix, sb)?,
             TokenKind::EndFile => sb.push_str(Self::END_FILE_STR),
             TokenKind::EndLine => sb.push_str(Self::END_LINE_STR),
             TokenKind::Unknown(char) => write!(sb, "Unknown({char:?})")?,
             TokenKind::Ident(ident) => write!(sb, "{ident:?}")?,
             TokenKind::CharLiteral(char) => write!(sb, "char({char:?})")?,
+            TokenKind::Lifetime(name) => write!(sb, "'{name}")?,
             TokenKind::StringLiteral(str) => write!(sb, "str({str:?})")?,
             TokenKind::Symbol(symbool_kind) => write!(sb, "'{}'", symbool_kind.as_str())?,
         };
@@ -93,10 +96,11 @@ impl TokenKind {
             TokenKind::EndLine => Self::END_LINE_STR.len(),
             TokenKind::Ident(ident) => "\"".len() + ident.len() + "\"".len(),
             TokenKind::Unknown(_) => "Unknown('".len() + 1 + "')".len(),
-            TokenKind::Number(number) => number
-                .inner_display(&mut String::new())
+            TokenKind::Number(number, suffix) => number
+                .inner_display(*suffix, &mut String::new())
                 .expect("no write error"),
             TokenKind::CharLiteral(_) => "char(".len() + 3 + "')".len(),
+            TokenKind::Lifetime(name) => "'".len() + name.len(),
             TokenKind::Symbol(symbol_kind) => "\"".len() + symbol_kind.as_str().len() + "\"".len(),
             TokenKind::StringLiteral(str) => "str(\"".len() + str.display_len() + "\")".len(),
         }
@@ -127,26 +131,30 @@ impl Number {
     /// Returns a display string with type annotation (e.g., "42: untypedInt").
     pub fn display(&self) -> String {
         let mut sb = String::new();
-        self.inner_display(&mut sb).expect("write should not fail");
+        self.inner_display(None, &mut sb)
+            .expect("write should not fail");
         sb
     }
 
     /// Writes a display string to the given buffer.
     pub fn write_display(&self, sb: &mut String) {
-        self.inner_display(sb).expect("write should not fail");
+        self.inner_display(None, sb).expect("write should not fail");
     }
 
     #[doc(hidden)]
-    fn inner_display(&self, sb: &mut String) -> Result<usize> {
-        const INT_STR: &str = PrimitiveTypes::UntypedInt.as_str();
-        const UINT_STR: &str = PrimitiveTypes::UntypedUint.as_str();
-        const FLOAT_STR: &str = PrimitiveTypes::UntypedFloat.as_str();
+    fn inner_display(&self, suffix: Option<PrimitiveTypes>, sb: &mut String) -> Result<usize> {
+        let untyped = match self {
+            Number::Int(_) => PrimitiveTypes::UntypedInt,
+            Number::Uint(_) => PrimitiveTypes::UntypedUint,
+            Number::Float(_) => PrimitiveTypes::UntypedFloat,
+        };
+        let type_str = suffix.unwrap_or(untyped).as_str();
 
         let len = sb.len();
         match self {
-            Number::Int(num) => write!(sb, "{num}: {INT_STR}"),
-            Number::Uint(num) => write!(sb, "{num}: {UINT_STR}"),
-            Number::Float(num) => write!(sb, "{num}: {FLOAT_STR}"),
+            Number::Int(num) => write!(sb, "{num}: {type_str}"),
+            Number::Uint(num) => write!(sb, "{num}: {type_str}"),
+            Number::Float(num) => write!(sb, "{num}: {type_str}"),
         }?;
         Ok(sb.len().saturating_sub(len))
     }
@@ -30,6 +30,15 @@ impl<'a> TokenStream<'a> {
         }
     }
 
+    /// Creates a token stream for `source` that was taken out of an outer source at `span`,
+    /// so its tokens keep the spans of the outer source.
+    pub fn new_at(source: &'a str, span: Span) -> Self {
+        Self {
+            lexer: Lexer::new_at(source, span.module, span.start_line, span.start_offset),
+            current: Token::new(TokenKind::EndLine, span),
+        }
+    }
+
     /// Initializes the token stream. Call this before using the token stream.
     pub fn initialize(&mut self) -> SoulResult<()> {
         self.advance()
@@ -1,4 +1,4 @@
-use crate::sementic_level::SementicLevel;
+use crate::{define_str_enum, sementic_level::SementicLevel};
 
 /// Compiler configuration options.
 #[derive(Debug, Clone)]
@@ -11,6 +11,8 @@ pub struct CompilerOptions {
     target_info: TargetInfo,
     /// Whether structs are packed by default.
     default_packed: bool,
+    /// Insert runtime bounds, null, overflow and division checks.
+    debug_checks: bool,
 }
 impl CompilerOptions {
     /// Creates a new `CompilerOptions` with all options specified.
@@ -19,10 +21,12 @@ impl CompilerOptions {
         fatal_level: SementicLevel,
         target_info: TargetInfo,
         default_packed: bool,
+        debug_checks: bool,
     ) -> Self {
         Self {
             fatal_level,
             target_info,
+            debug_checks,
             default_packed,
             debug_view_literal_resolve,
         }
@@ -34,10 +38,17 @@ impl CompilerOptions {
             debug_view_literal_resolve: false,
             fatal_level: SementicLevel::Error,
             default_packed: false,
+            debug_checks: false,
             target_info,
         }
     }
 
+    /// Returns these options for a build with `profile`, only debug builds insert runtime checks.
+    pub const fn with_profile(mut self, profile: BuildProfile) -> Self {
+        self.debug_checks = matches!(profile, BuildProfile::Debug);
+        self
+    }
+
     /// Returns whether debug output for literal resolution is enabled.
     pub const fn debug_view_literal_resolve(&self) -> bool {
         self.debug_view_literal_resolve
@@ -48,6 +59,11 @@ impl CompilerOptions {
         self.default_packed
     }
 
+    /// Returns whether runtime checks are inserted.
+    pub const fn debug_checks(&self) -> bool {
+        self.debug_checks
+    }
+
     /// Returns the minimum severity level that causes compilation to fail.
     pub const fn fatal_level(&self) -> SementicLevel {
         self.fatal_level
@@ -59,30 +75,70 @@ impl CompilerOptions {
     }
 }
 
-/// Target CPU architecture.
-#[derive(Debug, Clone, Copy)]
-pub enum Arch {
-    /// 64-bit x86 architecture.
-    X86_64,
-    /// 32-bit x86 architecture.
-    X86,
-    /// 64-bit ARM architecture.
-    AArch64,
-    /// 32-bit ARM architecture.
-    Armv7,
-    /// 64-bit RISC-V architecture.
-    Riscv64,
+/// Whether the user builds their crate for debugging or for release.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BuildProfile {
+    /// Runtime bounds, null, overflow and division checks are inserted.
+    #[default]
+    Debug,
+    /// Runtime checks are left out.
+    Release,
 }
 
-/// Target operating system.
-#[derive(Debug, Clone, Copy)]
-pub enum Os {
-    /// Linux.
-    Linux,
-    /// Windows.
-    Windows,
-    /// macOS.
-    Macos,
+define_str_enum!(
+    /// Target CPU architecture.
+    pub enum Arch {
+        /// 64-bit x86 architecture.
+        X86_64 => "x86_64",
+        /// 32-bit x86 architecture.
+        X86 => "x86",
+        /// 64-bit ARM architecture.
+        AArch64 => "aarch64",
+        /// 32-bit ARM architecture.
+        Armv7 => "armv7",
+        /// 64-bit RISC-V architecture.
+        Riscv64 => "riscv64",
+    }
+);
+
+define_str_enum!(
+    /// Target operating system.
+    pub enum Os {
+        /// Linux.
+        Linux => "linux",
+        /// Windows.
+        Windows => "windows",
+        /// macOS.
+        Macos => "macos",
+    }
+);
+
+/// A `#[cfg(...)]` predicate, the item it is on is only compiled when it matches the target.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum CfgPredicate {
+    /// `os = "linux"`
+    Os(Os),
+    /// `arch = "x86_64"`
+    Arch(Arch),
+    /// `any(a, b)`, matches when one of the predicates matches.
+    Any(Vec<CfgPredicate>),
+    /// `all(a, b)`, matches when every predicate matches.
+    All(Vec<CfgPredicate>),
+    /// `not(a)`
+    Not(Box<CfgPredicate>),
+}
+impl CfgPredicate {
+    /// Whether `target` satisfies this predicate.
+    pub fn matches(&self, target: &TargetInfo) -> bool {
+        match self {
+            CfgPredicate::Os(os) => *os == target.os,
+            CfgPredicate::Arch(arch) => *arch == target.arch,
+            CfgPredicate::Any(predicates) => predicates.iter().any(|p| p.matches(target)),
+            CfgPredicate::All(predicates) => predicates.iter().all(|p| p.matches(target)),
+            CfgPredicate::Not(predicate) => !predicate.matches(target),
+        }
+    }
 }
 
 /// Target platform information.
@@ -0,0 +1,53 @@
+use crate::compile_options::{Arch, BuildProfile, CompilerOptions, Os, TargetInfo};
+use crate::sementic_level::SementicLevel;
+
+fn target() -> TargetInfo {
+    TargetInfo::new(Arch::X86_64, Os::Linux)
+}
+
+#[test]
+fn test_default_options_have_no_debug_checks() {
+    let options = CompilerOptions::new_default(target());
+    assert!(!options.debug_checks());
+}
+
+#[test]
+fn test_debug_profile_enables_debug_checks() {
+    let options = CompilerOptions::new_default(target()).with_profile(BuildProfile::Debug);
+    assert!(options.debug_checks());
+}
+
+#[test]
+fn test_release_profile_disables_debug_checks() {
+    let options = CompilerOptions::new(false, SementicLevel::Error, target(), false, true)
+        .with_profile(BuildProfile::Release);
+    assert!(!options.debug_checks());
+}
+
+#[test]
+fn test_with_profile_keeps_other_options() {
+    let options = CompilerOptions::new(true, SementicLevel::Warning, target(), true, false)
+        .with_profile(BuildProfile::Debug);
+
+    assert!(options.debug_view_literal_resolve());
+    assert!(options.default_packed());
+    assert_eq!(options.fatal_level(), SementicLevel::Warning);
+    assert_eq!(options.target_info().arch, Arch::X86_64);
+}
+
+#[test]
+fn test_build_profile_defaults_to_debug() {
+    assert_eq!(BuildProfile::default(), BuildProfile::Debug);
+}
+
+#[test]
+fn test_build_profile_serializes_lowercase() {
+    assert_eq!(
+        serde_json::to_string(&BuildProfile::Release).unwrap(),
+        "\"release\""
+    );
+    assert_eq!(
+        serde_json::from_str::<BuildProfile>("\"debug\"").unwrap(),
+        BuildProfile::Debug
+    );
+}
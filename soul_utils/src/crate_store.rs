@@ -6,18 +6,92 @@ use crate::{
     ids::{FunctionId, IdGenerator},
     impl_soul_ids,
     sementic_level::{FaultCollector, MessageConfig},
+    soul_names::PrimitiveTypes,
     span::{CrateId, ModuleId},
     vec_map::VecMap,
 };
 
 impl_soul_ids!(TypeId);
 
+/// An item of a crate, ids are counted per crate so `local` only means something
+/// together with `krate`.
+#[derive(
+    Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+pub struct DefId<T> {
+    pub krate: CrateId,
+    pub local: T,
+}
+impl<T> DefId<T> {
+    pub const fn new(krate: CrateId, local: T) -> Self {
+        Self { krate, local }
+    }
+}
+
 #[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct CrateExports {
-    pub functions: HashMap<String, FunctionId>,
+    pub functions: HashMap<String, ExportedFunction>,
     pub types: HashMap<String, TypeId>,
 }
 
+/// A public function of a lib crate, with everything another crate needs to call it.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ExportedFunction {
+    /// Id of the function inside of its own crate, part of its mangled symbol.
+    pub id: FunctionId,
+    /// Source-level name, re-exports can export a function under another name.
+    pub name: String,
+    pub parameters: Vec<ExportType>,
+    pub return_type: ExportType,
+    /// `extern "C"` definition, called with the C calling convention.
+    pub c_abi: bool,
+    /// `#[no_mangle]`, linked under `name` instead of a mangled symbol.
+    pub no_mangle: bool,
+}
+
+/// A type in the signature of an [`ExportedFunction`], spelled out because `TypeId`s
+/// only mean something inside of the crate that made them.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum ExportType {
+    None,
+    Primitive(PrimitiveTypes),
+    Array {
+        element: Box<ExportType>,
+        kind: ExportArrayKind,
+    },
+    Ref {
+        of_type: Box<ExportType>,
+        mutable: bool,
+    },
+    Pointer(Box<ExportType>),
+    Optional(Box<ExportType>),
+    /// A struct, enum or generic of the exporting crate, these can not be shared between
+    /// crates yet. Holds the name of the type for error messages.
+    Opaque(String),
+}
+impl ExportType {
+    /// The first type in `self` that can not be used from another crate.
+    pub fn find_opaque(&self) -> Option<&str> {
+        match self {
+            ExportType::None | ExportType::Primitive(_) => None,
+            ExportType::Array { element: inner, .. }
+            | ExportType::Ref { of_type: inner, .. }
+            | ExportType::Pointer(inner)
+            | ExportType::Optional(inner) => inner.find_opaque(),
+            ExportType::Opaque(name) => Some(name),
+        }
+    }
+}
+
+/// Mirrors the array kinds of the ast, `[N]T`, `[*]T`, `[&]T` and `[@]T`.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum ExportArrayKind {
+    StackArray(u64),
+    HeapArray,
+    MutSlice,
+    ConstSlice,
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct CrateContext {
     pub is_lib: bool,
@@ -84,14 +158,31 @@ impl CrateStore {
         id
     }
 
-    pub fn resolve_function(&self, crate_name: &str, function_name: &str) -> Option<FunctionId> {
-        self.name_to_crate(&crate_name.to_string())
-            .and_then(|c| c.exports.functions.get(function_name).copied())
+    pub fn resolve_function(
+        &self,
+        crate_name: &str,
+        function_name: &str,
+    ) -> Option<DefId<FunctionId>> {
+        let krate = self.name_to_crate(&crate_name.to_string())?;
+        let function = krate.exports.functions.get(function_name)?;
+        Some(DefId::new(krate.id, function.id))
+    }
+
+    pub fn resolve_type(&self, crate_name: &str, type_name: &str) -> Option<DefId<TypeId>> {
+        let krate = self.name_to_crate(&crate_name.to_string())?;
+        let id = krate.exports.types.get(type_name)?;
+        Some(DefId::new(krate.id, *id))
     }
 
-    pub fn resolve_type(&self, crate_name: &str, type_name: &str) -> Option<TypeId> {
-        self.name_to_crate(&crate_name.to_string())
-            .and_then(|c| c.exports.types.get(type_name).copied())
+    /// The exported function `function_name` of `crate_name` together with its [`DefId`].
+    pub fn get_export_function(
+        &self,
+        crate_name: &str,
+        function_name: &str,
+    ) -> Option<(DefId<FunctionId>, &ExportedFunction)> {
+        let krate = self.name_to_crate(&crate_name.to_string())?;
+        let function = krate.exports.functions.get(function_name)?;
+        Some((DefId::new(krate.id, function.id), function))
     }
 
     pub fn get(&self, id: CrateId) -> Option<&Crate> {
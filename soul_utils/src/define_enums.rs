@@ -3,7 +3,7 @@
 ///
 /// This macro creates an enum where each variant maps to a static string value.
 /// It also generates constant slices of all variants (`NAMES`) and all string values (`VALUES`),
-/// as well as methods for bidirectional conversion (`as_str`, `from_str`), and optionally,
+/// as well as methods for bidirectional conversion (`as_str`, `try_from_str`), and optionally,
 /// a precedence value.
 ///
 /// # Features
@@ -32,10 +32,10 @@
 /// const MY_NAME_STR: &str = Names::MyName.as_str(); // const-time
 /// assert_eq!(MY_NAME_STR, "tim");
 ///
-/// let best_language = Names::from_str("soul"); // Runtime only
+/// let best_language = Names::try_from_str("soul"); // Runtime only
 /// assert_eq!(best_language, Some(Names::BestLanguage));
 ///
-/// let none_variant = Names::from_str("none");
+/// let none_variant = Names::try_from_str("none");
 /// assert_eq!(none_variant, None);
 /// ```
 ///
@@ -84,7 +84,7 @@ macro_rules! define_str_enum {
             }
 
             /// tries to converts a string into a variant.
-            pub fn from_str(s: &str) -> Option<Self> {
+            pub fn try_from_str(s: &str) -> Option<Self> {
                 match s {
                     $( $symbol => Some($enum_name::$name), )*
                     _ => None,
@@ -124,7 +124,7 @@ macro_rules! define_str_enum {
             }
 
             /// tries to converts a string into a variant.
-            pub fn from_str(s: &str) -> Option<Self> {
+            pub fn try_from_str(s: &str) -> Option<Self> {
                 match s {
                     $( $symbol => Some($enum_name::$name), )*
                     _ => None,
@@ -181,10 +181,10 @@ macro_rules! define_str_enum {
 /// const CONST_REF: Option<Refs> = Refs::from_symbool(SymboolKind::ConstRef); // const-time
 /// assert_eq!(CONST_REF, Some(Refs::ConstRef));
 ///
-/// let mut_ref = Refs::from_str("&");
+/// let mut_ref = Refs::try_from_str("&");
 /// assert_eq!(mut_ref, Some(Refs::MutRef));
 ///
-/// let none_variant = Refs::from_str("none");
+/// let none_variant = Refs::try_from_str("none");
 /// assert_eq!(none_variant, None);
 /// ```
 ///
@@ -246,7 +246,7 @@ macro_rules! define_symbols {
                 }
             }
 
-            pub fn from_str(s: &str) -> Option<Self> {
+            pub fn try_from_str(s: &str) -> Option<Self> {
                 match s {
                     $( $symbol => Some($enum_name::$name), )*
                     _ => None,
@@ -297,7 +297,7 @@ macro_rules! define_symbols {
                 }
             }
 
-            pub fn from_str(s: &str) -> Option<Self> {
+            pub fn try_from_str(s: &str) -> Option<Self> {
                 match s {
                     $( $symbol => Some($enum_name::$name), )*
                     _ => None,
@@ -106,12 +106,20 @@ pub enum SoulErrorKind {
     InvalidFunctionName,
     InvalidContext,
     InvalidType,
+    InvalidLifetime,
     InvalidOperator,
     InvalidTokenKind,
     UnexpecedFileEnd,
     ScopeOverride(Span),
     UnexpectedCharacter,
     InvalidEscapeSequence,
+    InvalidAttribute,
+
+    UseAfterMove,
+    MoveOutOfBorrow,
+    BorrowConflict,
+    BorrowOutlivesValue,
+    UnsafeOutsideUnsafeBlock,
 }
 
 impl SoulErrorKind {
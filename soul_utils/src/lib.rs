@@ -1,4 +1,4 @@
-pub use crate::crate_store::{Crate, CrateContext, CrateExports, CrateStore, TypeId};
+pub use crate::crate_store::{Crate, CrateContext, CrateExports, CrateStore, DefId, TypeId};
 pub use crate::ids::IdAlloc;
 pub use crate::soul_manifest::SoulToml;
 pub use crate::span::{CrateId, ModuleId, Span};
@@ -22,6 +22,8 @@ pub mod try_result;
 pub mod vec_map;
 pub mod vec_set;
 
+#[cfg(test)]
+mod compile_options_tests;
 #[cfg(test)]
 mod vec_map_tests;
 #[cfg(test)]
@@ -31,12 +33,15 @@ mod vec_set_tests;
 pub enum StringLiteral {
     Normal(String),
     CStr(String),
+    /// `f"text {value} text"`, split into text and the source of the embedded values.
+    Fmt(Vec<FmtPiece>),
 }
 impl std::fmt::Debug for StringLiteral {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             StringLiteral::Normal(str) => f.write_fmt(format_args!("{:?}", str)),
             StringLiteral::CStr(str) => f.write_fmt(format_args!("c{:?}", str)),
+            StringLiteral::Fmt(_) => f.write_fmt(format_args!("f{:?}", self.to_string())),
         }
     }
 }
@@ -45,6 +50,23 @@ impl std::fmt::Display for StringLiteral {
         match self {
             StringLiteral::Normal(str) => f.write_str(str),
             StringLiteral::CStr(str) => f.write_str(str),
+            StringLiteral::Fmt(pieces) => {
+                for piece in pieces {
+                    match piece {
+                        FmtPiece::Text(text) => {
+                            f.write_str(&text.replace('{', "{{").replace('}', "}}"))?
+                        }
+                        FmtPiece::Argument(argument) => {
+                            f.write_fmt(format_args!("{{{}", argument.source))?;
+                            if let Some(width) = argument.width {
+                                f.write_fmt(format_args!(":{width}"))?;
+                            }
+                            f.write_str("}")?;
+                        }
+                    }
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -52,6 +74,7 @@ impl StringLiteral {
     pub fn display_len(&self) -> usize {
         match self {
             StringLiteral::CStr(str) | StringLiteral::Normal(str) => str.len(),
+            StringLiteral::Fmt(_) => self.to_string().len(),
         }
     }
 
@@ -59,18 +82,40 @@ impl StringLiteral {
         match self {
             StringLiteral::Normal(_) => None,
             StringLiteral::CStr(_) => Some(StringTag::CStr),
+            StringLiteral::Fmt(_) => Some(StringTag::Fmt),
         }
     }
 }
 
+/// A piece of a format string literal.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FmtPiece {
+    /// Plain text with `{{` and `}}` already unescaped.
+    Text(String),
+    /// An embedded `{value}` or `{value:width}`.
+    Argument(FmtArgument),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FmtArgument {
+    /// Source of the value expression, parsed by the ast parser.
+    pub source: String,
+    /// Span of `source` so the parsed expression points back into the literal.
+    pub span: Span,
+    /// Minimal amount of chars the value is padded to.
+    pub width: Option<usize>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum StringTag {
     CStr, // c
+    Fmt,  // f
 }
 impl StringTag {
     pub fn from_char(ch: char) -> Option<Self> {
         match ch {
             'c' => Some(Self::CStr),
+            'f' => Some(Self::Fmt),
             _ => None,
         }
     }
@@ -116,6 +116,20 @@ impl SementicFault {
         }
     }
 
+    pub const fn warning(err: SoulError) -> Self {
+        Self {
+            message: err,
+            level: SementicLevel::Warning,
+        }
+    }
+
+    pub const fn note(err: SoulError) -> Self {
+        Self {
+            message: err,
+            level: SementicLevel::Note,
+        }
+    }
+
     pub const fn debug(err: SoulError) -> Self {
         Self {
             message: err,
@@ -2,6 +2,13 @@ use crate::{define_str_enum, define_symbols, symbool_kind::SymbolKind};
 
 pub const MAIN_FUNCTION_NAME: &str = "main";
 pub const INIT_GLOBALS_FUNCTION_NAME: &str = "___init_global";
+/// Allocator runtime behind `new`, link a strong symbol with this name to replace the default.
+pub const ALLOC_FUNCTION_NAME: &str = "__soul_alloc";
+/// Allocator runtime that releases heap arrays, paired with [`ALLOC_FUNCTION_NAME`].
+pub const FREE_FUNCTION_NAME: &str = "__soul_free";
+/// Runtime called with `(file, line, column, message, message_len)` on a `panic` or failed
+/// runtime check, never returns.
+pub const PANIC_FUNCTION_NAME: &str = "__soul_panic";
 
 define_symbols!(
     /// Type wrapper symbols that modify how types are referenced or stored.
@@ -82,6 +89,8 @@ define_str_enum!(
 
         /// empty type (also known as `void` in c like languages)
         None => "none", 8,
+        /// type of expressions that never finish (e.g. `panic`), fits wherever a value is expected
+        Never => "never", 0,
         /// boolean (`true` or `false`) type
         Boolean => "bool", 8,
 
@@ -341,6 +350,8 @@ define_str_enum!(
         Break => "break", 0,
         Return => "return", 0,
         Continue => "continue", 0,
+        Defer => "defer", 0,
+        Unsafe => "unsafe", 0,
 
         Struct => "struct", 0,
         Class => "class", 0,
@@ -368,8 +379,66 @@ define_str_enum!(
         Import => "import", 0,
         Extern => "extern", 0,
         Crate => "crate", 0,
+        Pub => "pub", 0,
     }
 );
+
+define_str_enum!(
+    /// Traits known by the compiler that change how a type is lowered.
+    pub enum BuiltinTrait {
+        /// Values of this type are copied instead of moved when used by value.
+        AutoCopy => "AutoCopy",
+        /// `Drop(&this)` is called when an owned value of this type goes out of scope.
+        Drop => "Drop",
+        /// `Display(@this): [*]char` gives the text of a value of this type in `f"{value}"`.
+        Display => "Display",
+    }
+);
+
+define_str_enum!(
+    /// Attributes known by the compiler, written as `#[name]` or `#[name(values)]` before an item.
+    pub enum AttributeKind {
+        /// Hints the backend to inline calls to this function.
+        Inline => "inline",
+        /// Emits this function under its source name instead of a mangled one.
+        NoMangle => "no_mangle",
+        /// Lays the fields of this struct out without padding.
+        Packed => "packed",
+        /// Picks the layout of this struct or the tag size of this enum, `#[repr(C)]` matches the C compiler.
+        Repr => "repr",
+        /// Only compiles this item when the target matches, like `#[cfg(os = "linux")]`.
+        Cfg => "cfg",
+    }
+);
+
+define_str_enum!(
+    /// The kinds of items an attribute can be placed on.
+    pub enum AttributeTarget {
+        Function => "function",
+        Struct => "struct",
+        Enum => "enum",
+        Field => "field",
+        Variable => "variable",
+        Statement => "statement",
+    }
+);
+
+impl AttributeKind {
+    /// Whether this attribute has an effect on `target`.
+    pub const fn applies_to(&self, target: AttributeTarget) -> bool {
+        match self {
+            AttributeKind::Inline | AttributeKind::NoMangle => {
+                matches!(target, AttributeTarget::Function)
+            }
+            AttributeKind::Packed => matches!(target, AttributeTarget::Struct),
+            AttributeKind::Repr => {
+                matches!(target, AttributeTarget::Struct | AttributeTarget::Enum)
+            }
+            AttributeKind::Cfg => true,
+        }
+    }
+}
+
 impl KeyWord {
     pub fn is_operator_keyword(&self) -> bool {
         match self {
@@ -1,4 +1,10 @@
-use crate::{Ident, ids::IdAlloc, impl_soul_ids};
+use crate::{
+    Ident,
+    compile_options::{CfgPredicate, TargetInfo},
+    ids::IdAlloc,
+    impl_soul_ids,
+    soul_names::AttributeKind,
+};
 use std::hash::Hash;
 
 impl_soul_ids!(ModuleId, CrateId);
@@ -40,6 +46,28 @@ pub struct Attribute {
     pub values: Vec<Ident>,
 }
 
+impl Attribute {
+    /// The compiler known attribute this is, `None` for unknown names.
+    pub fn kind(&self) -> Option<AttributeKind> {
+        AttributeKind::try_from_str(self.name.as_str())
+    }
+
+    /// Whether any of `attributes` is `kind`.
+    pub fn contains(attributes: &[Attribute], kind: AttributeKind) -> bool {
+        attributes
+            .iter()
+            .any(|attribute| attribute.kind() == Some(kind))
+    }
+
+    /// Whether `attributes` contains `#[repr(C)]`.
+    pub fn is_repr_c(attributes: &[Attribute]) -> bool {
+        attributes.iter().any(|attribute| {
+            attribute.kind() == Some(AttributeKind::Repr)
+                && matches!(attribute.values.as_slice(), [value] if value.as_str() == "C")
+        })
+    }
+}
+
 /// All AST nodes are wrapped in `Spanned` to track their location in the
 /// source code for error reporting and debugging.
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -50,11 +78,39 @@ pub struct Spanned<T> {
     pub span: Span,
 }
 
+/// Where an item can be used from.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize,
+)]
+pub enum Visibility {
+    /// `pub`, usable from other modules and crates.
+    Public,
+    /// Only usable inside the module that declares it.
+    #[default]
+    Private,
+}
+
+impl Visibility {
+    pub const fn is_public(self) -> bool {
+        matches!(self, Visibility::Public)
+    }
+
+    /// Modules have no declaration to write `pub` on, so a capitalized module name is public.
+    pub fn of_module_name(name: &str) -> Self {
+        match name.chars().next().is_some_and(char::is_uppercase) {
+            true => Visibility::Public,
+            false => Visibility::Private,
+        }
+    }
+}
+
 /// Metadata associated with an AST item.
 #[derive(Debug, Clone, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
 pub struct ItemMetaData {
     /// Additional attributes associated with this node.
     pub attributes: Vec<Attribute>,
+    /// The `#[cfg(...)]` predicate this node is compiled under, `None` means always.
+    pub cfg: Option<CfgPredicate>,
 }
 
 impl ItemMetaData {
@@ -65,7 +121,15 @@ impl ItemMetaData {
 
     /// Creates a new `ItemMetaData` with the given attributes.
     pub const fn new(attributes: Vec<Attribute>) -> Self {
-        Self { attributes }
+        Self {
+            attributes,
+            cfg: None,
+        }
+    }
+
+    /// Whether this node is compiled for `target`.
+    pub fn is_cfg_enabled(&self, target: &TargetInfo) -> bool {
+        self.cfg.as_ref().is_none_or(|cfg| cfg.matches(target))
     }
 }
 
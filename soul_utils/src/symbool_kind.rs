@@ -28,6 +28,8 @@ define_str_enum!(
         ConstRef => "@",
         /// `$`
         Money => "$",
+        /// `#`
+        Hash => "#",
         /// `|`
         Or => "|",
         /// `^`
@@ -86,6 +88,8 @@ define_str_enum!(
         Comma => ",",
         /// `..`
         DoubleDot => "..",
+        /// `...`
+        Ellipsis => "...",
         /// `[]`
         Array => "[]",
         /// `(`